@@ -1,8 +1,11 @@
 use std::{error::Error, fmt, io};
 
+use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, IntoStaticStr};
 
+use super::types::{BitcoinNetwork, EventIdString};
+
 pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 #[derive(Debug)]
@@ -19,6 +22,19 @@ pub enum N3xbError {
     Io(io::Error),
     JoinError(tokio::task::JoinError),
     OneshotRecv(tokio::sync::oneshot::error::RecvError),
+    DuplicateTradeUuid(uuid::Uuid),
+    EventRejected(String),
+    EventSendFailed(String),
+    RelayUnreachable(String),
+    UnregisteredTradeEngineSpecifics(String),
+    InvalidTradeEngineSpecifics(String),
+    Timeout(String),
+    TradeAlreadyRegistered(uuid::Uuid),
+    TooManyRelays(usize),
+    BitcoinNetworkMismatch(BitcoinNetwork, BitcoinNetwork), // Expected, Actual
+    InvalidEventId(String),
+    CounterpartyDecryptFailed(EventIdString, String), // Event ID, Reason
+    DisputeUndelivered(Vec<XOnlyPublicKey>), // Recipients the Dispute Request failed to send to
 }
 
 impl Error for N3xbError {}
@@ -58,6 +74,88 @@ impl fmt::Display for N3xbError {
             N3xbError::OneshotRecv(err) => {
                 format!("n3xB-Error | RecvError - {}", err.to_string())
             }
+            N3xbError::DuplicateTradeUuid(trade_uuid) => {
+                format!(
+                    "n3xB-Error | DuplicateTradeUuid - TradeUUID {} already in use by an active trade",
+                    trade_uuid
+                )
+            }
+            N3xbError::EventRejected(relay_errors) => {
+                format!(
+                    "n3xB-Error | EventRejected - Event not accepted by any relay. Relay errors: {}",
+                    relay_errors
+                )
+            }
+            N3xbError::EventSendFailed(relay_errors) => {
+                format!(
+                    "n3xB-Error | EventSendFailed - Event failed to send to any relay. Relay errors: {}",
+                    relay_errors
+                )
+            }
+            N3xbError::RelayUnreachable(relay_errors) => {
+                format!(
+                    "n3xB-Error | RelayUnreachable - No relay could be reached. Relay errors: {}",
+                    relay_errors
+                )
+            }
+            N3xbError::UnregisteredTradeEngineSpecifics(type_name) => {
+                format!(
+                    "n3xB-Error | UnregisteredTradeEngineSpecifics - Trade Engine specifics of type {} not registered",
+                    type_name
+                )
+            }
+            N3xbError::InvalidTradeEngineSpecifics(reason) => {
+                format!(
+                    "n3xB-Error | InvalidTradeEngineSpecifics - Trade Engine specifics refused by the Trade Engine - {}",
+                    reason
+                )
+            }
+            N3xbError::TradeAlreadyRegistered(trade_uuid) => {
+                format!(
+                    "n3xB-Error | TradeAlreadyRegistered - TradeUUID {} already has a peer message channel registered",
+                    trade_uuid
+                )
+            }
+            N3xbError::TooManyRelays(max_relays) => {
+                format!(
+                    "n3xB-Error | TooManyRelays - Adding the relays would exceed the maximum of {} relays",
+                    max_relays
+                )
+            }
+            N3xbError::BitcoinNetworkMismatch(expected, actual) => {
+                format!(
+                    "n3xB-Error | BitcoinNetworkMismatch - Expected Bitcoin network {} but got {}",
+                    expected, actual
+                )
+            }
+            N3xbError::InvalidEventId(event_id) => {
+                format!(
+                    "n3xB-Error | InvalidEventId - {} is not a 64 character lowercase hex Event ID",
+                    event_id
+                )
+            }
+            N3xbError::CounterpartyDecryptFailed(event_id, reason) => {
+                format!(
+                    "n3xB-Error | CounterpartyDecryptFailed - DM w/ Event ID {} from the trade counterparty could not be decrypted - {}",
+                    event_id, reason
+                )
+            }
+            N3xbError::DisputeUndelivered(pubkeys) => {
+                format!(
+                    "n3xB-Error | DisputeUndelivered - Dispute Request failed to send to pubkeys {}",
+                    pubkeys
+                        .iter()
+                        .map(|pubkey| pubkey.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
+            N3xbError::Timeout(operation) => {
+                format!(
+                    "n3xB-Error | Timeout - {} did not complete in time",
+                    operation
+                )
+            }
         };
         write!(f, "{}", error_string)
     }
@@ -139,6 +237,13 @@ pub enum OfferInvalidReason {
     MarketOracleInvalid,
     TradeEngineSpecific,
     PowTooHigh,
+    Expired,
+    UntrustedTaker,
+    Blocked,
+    OfferLimitReached,
+    StaleTerms,
+    PartialTakeAmountInvalid,
+    NoBitcoinObligation,
 }
 
 impl fmt::Debug for OfferInvalidReason {
@@ -185,6 +290,30 @@ impl fmt::Debug for OfferInvalidReason {
             OfferInvalidReason::PowTooHigh => {
                 write!(f, "The Taker desired minimum PoW is too high for the Maker")
             }
+            OfferInvalidReason::UntrustedTaker => {
+                write!(f, "Taker pubkey is not in the Maker's trusted set")
+            }
+            OfferInvalidReason::Expired => {
+                write!(f, "Offer was not accepted before the Maker acceptance deadline")
+            }
+            OfferInvalidReason::Blocked => {
+                write!(f, "Taker pubkey is blocked by the Maker")
+            }
+            OfferInvalidReason::OfferLimitReached => {
+                write!(f, "Maker is holding its maximum number of Offers")
+            }
+            OfferInvalidReason::StaleTerms => {
+                write!(
+                    f,
+                    "Offer was made against Order terms the Maker has since changed"
+                )
+            }
+            OfferInvalidReason::PartialTakeAmountInvalid => {
+                write!(f, "Partial take amount is outside of the Maker's bounds")
+            }
+            OfferInvalidReason::NoBitcoinObligation => {
+                write!(f, "Neither Maker nor Taker has a Bitcoin obligation")
+            }
         }
     }
 }
@@ -1,3 +1,5 @@
 pub mod error;
 pub mod persist;
+pub mod store;
 pub mod types;
+pub(crate) mod utils;
@@ -1,15 +1,20 @@
 use ::tracing::{error, trace};
-use std::{
-    fs,
-    path::Path,
-    sync::{
-        mpsc::{self, TrySendError},
-        Arc, RwLock, RwLockReadGuard,
-    },
+use serde_json::{Map, Value};
+use std::sync::{
+    mpsc::{self, TrySendError},
+    Arc, RwLock, RwLockReadGuard,
 };
 use tracing::debug;
 
-use crate::common::{error::N3xbError, types::SerdeGenericTrait};
+use crate::common::{error::N3xbError, store::N3xbStore, types::SerdeGenericTrait};
+
+// Data persisted before versioning was introduced carries no version, and is taken as version 1
+const PERSIST_VERSION_KEY: &str = "version";
+const PERSIST_UNVERSIONED: u32 = 1;
+
+// Migrates a persisted data blob from the version given to the version right after.
+// The version field itself is updated by the Persister
+pub(crate) type PersistMigrateFn = fn(u32, &mut Map<String, Value>) -> Result<(), N3xbError>;
 
 enum PersisterMsg {
     Persist,
@@ -22,21 +27,88 @@ pub(crate) struct Persister {
 }
 
 impl Persister {
-    pub(crate) fn restore(data_path: impl AsRef<Path>) -> Result<String, N3xbError> {
-        let json: String = std::fs::read_to_string(data_path.as_ref())?;
-        debug!(
-            "Restored crusty-n3xB JSON from path: {} - {}",
-            data_path.as_ref().display().to_string(),
-            json
-        );
+    pub(crate) fn restore(backend: &dyn N3xbStore, key: &str) -> Result<String, N3xbError> {
+        let Some(blob) = backend.load(key)? else {
+            return Err(N3xbError::Simple(format!(
+                "No persisted crusty-n3xB JSON under key {}",
+                key
+            )));
+        };
+        let json = String::from_utf8(blob).map_err(|error| {
+            N3xbError::Simple(format!(
+                "Persisted crusty-n3xB JSON under key {} is not UTF-8 - {}",
+                key, error
+            ))
+        })?;
+        debug!("Restored crusty-n3xB JSON from key: {} - {}", key, json);
         Ok(json)
     }
 
+    // Restores the JSON and migrates it one version at a time up to the current version
+    pub(crate) fn restore_migrated(
+        backend: &dyn N3xbStore,
+        key: &str,
+        current_version: u32,
+        migrate: PersistMigrateFn,
+    ) -> Result<String, N3xbError> {
+        let json = Self::restore(backend, key)?;
+        Self::migrate(json, current_version, migrate)
+    }
+
+    fn migrate(
+        json: String,
+        current_version: u32,
+        migrate: PersistMigrateFn,
+    ) -> Result<String, N3xbError> {
+        let mut value: Value = serde_json::from_str(&json)?;
+        let Some(store) = value.as_object_mut() else {
+            return Err(N3xbError::Simple(format!(
+                "Persisted crusty-n3xB JSON is not an object"
+            )));
+        };
+
+        let version = match store.get(PERSIST_VERSION_KEY) {
+            Some(version) => match version.as_u64() {
+                Some(version) => version as u32,
+                None => {
+                    return Err(N3xbError::Simple(format!(
+                        "Persisted crusty-n3xB JSON has invalid version {}",
+                        version
+                    )));
+                }
+            },
+            None => PERSIST_UNVERSIONED,
+        };
+
+        if version > current_version {
+            return Err(N3xbError::Simple(format!(
+                "Persisted crusty-n3xB JSON is of version {}, newer than the supported version {}",
+                version, current_version
+            )));
+        }
+
+        if version == current_version {
+            return Ok(json);
+        }
+
+        for from_version in version..current_version {
+            debug!(
+                "Migrating persisted crusty-n3xB JSON from version {} to {}",
+                from_version,
+                from_version + 1
+            );
+            migrate(from_version, store)?;
+        }
+        store.insert(PERSIST_VERSION_KEY.to_string(), current_version.into());
+        Ok(serde_json::to_string(&value)?)
+    }
+
     pub(crate) fn new(
         store: Arc<RwLock<dyn SerdeGenericTrait>>,
-        data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        key: impl Into<String>,
     ) -> Self {
-        let (persist_tx, task_handle) = Self::setup_persistence(store, data_path);
+        let (persist_tx, task_handle) = Self::setup_persistence(store, backend, key.into());
 
         Self {
             persist_tx,
@@ -46,13 +118,11 @@ impl Persister {
 
     fn setup_persistence(
         store: Arc<RwLock<dyn SerdeGenericTrait>>,
-        data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        key: String,
     ) -> (mpsc::SyncSender<PersisterMsg>, std::thread::JoinHandle<()>) {
-        let data_path_buf = data_path.as_ref().to_path_buf();
-
         let (persist_tx, persist_rx) = mpsc::sync_channel(1);
         let task_handle = std::thread::spawn(move || {
-            let data_path = data_path_buf.clone();
             loop {
                 match persist_rx.recv() {
                     Ok(msg) => match msg {
@@ -64,12 +134,9 @@ impl Persister {
                                     continue;
                                 }
                             };
-                            if let Some(error) = Self::persist(store, &data_path).err() {
-                                error!(
-                                    "Error persisting data to path {} - {}",
-                                    data_path.display().to_string(),
-                                    error
-                                );
+                            if let Some(error) = Self::persist(store, backend.as_ref(), &key).err()
+                            {
+                                error!("Error persisting data to key {} - {}", key, error);
                             }
                         }
                         PersisterMsg::Close => {
@@ -82,17 +149,15 @@ impl Persister {
                     }
                 }
             }
-            debug!(
-                "Persistence thread for {} exiting",
-                data_path.display().to_string()
-            );
+            debug!("Persistence thread for {} exiting", key);
         });
         (persist_tx, task_handle)
     }
 
     fn persist(
         store: RwLockReadGuard<'_, dyn SerdeGenericTrait>,
-        data_path: impl AsRef<Path>,
+        backend: &dyn N3xbStore,
+        key: &str,
     ) -> Result<(), N3xbError> {
         let json = serde_json::to_string(&*store)?;
         let contains_type = json.contains("type");
@@ -103,15 +168,12 @@ impl Persister {
         };
 
         debug!(
-            "Persisting crusty-n3xB JSON {} to path: {} - {}",
-            contains_type_string,
-            data_path.as_ref().display().to_string(),
-            json
+            "Persisting crusty-n3xB JSON {} to key: {} - {}",
+            contains_type_string, key, json
         );
 
         assert!(contains_type);
-        fs::write(data_path.as_ref(), json)?;
-        Ok(())
+        backend.save(key, json.as_bytes())
     }
 
     pub(crate) fn queue(&self) {
@@ -135,3 +197,44 @@ impl Persister {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some_migrate(version: u32, store: &mut Map<String, Value>) -> Result<(), N3xbError> {
+        match version {
+            1 => {
+                store.insert("added_in_v2".to_string(), Value::Bool(true));
+            }
+            2 => {
+                store.insert("added_in_v3".to_string(), Value::Bool(true));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_persist_migrate_unversioned() {
+        let json = Persister::migrate("{}".to_string(), 3, some_migrate).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[PERSIST_VERSION_KEY], 3);
+        assert_eq!(value["added_in_v2"], true);
+        assert_eq!(value["added_in_v3"], true);
+    }
+
+    #[test]
+    fn test_persist_migrate_from_intermediate_version() {
+        let json = Persister::migrate(r#"{"version":2}"#.to_string(), 3, some_migrate).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[PERSIST_VERSION_KEY], 3);
+        assert!(value.get("added_in_v2").is_none());
+        assert_eq!(value["added_in_v3"], true);
+    }
+
+    #[test]
+    fn test_persist_migrate_rejects_newer_version() {
+        assert!(Persister::migrate(r#"{"version":4}"#.to_string(), 3, some_migrate).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::common::error::N3xbError;
+
+// Storage backend everything crusty-n3xB persists goes through, as blobs under '/' separated keys
+// such as "<pubkey>/<trade-engine-name>/<network>/makers/<trade-uuid>-maker.json". Implement for
+// anything that can hold keyed blobs, such as an embedded database or browser storage
+pub trait N3xbStore: Send + Sync {
+    // None if nothing is stored under the key
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>, N3xbError>;
+
+    // Replaces whatever is stored under the key
+    fn save(&self, key: &str, blob: &[u8]) -> Result<(), N3xbError>;
+
+    // Deleting a key with nothing stored is not an error
+    fn delete(&self, key: &str) -> Result<(), N3xbError>;
+
+    // Keys of the blobs right under the prefix, as if it were a directory. Deeper keys are left out
+    fn list(&self, prefix: &str) -> Result<Vec<String>, N3xbError>;
+
+    fn exists(&self, key: &str) -> Result<bool, N3xbError> {
+        Ok(self.load(key)?.is_some())
+    }
+}
+
+// Default storage backend, with each key as a file path relative to the root directory
+pub struct FsStore {
+    root_dir_path: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root_dir_path: impl AsRef<Path>) -> Self {
+        Self {
+            root_dir_path: root_dir_path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root_dir_path.join(key)
+    }
+}
+
+impl N3xbStore for FsStore {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>, N3xbError> {
+        match std::fs::read(self.path(key)) {
+            Ok(blob) => Ok(Some(blob)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn save(&self, key: &str, blob: &[u8]) -> Result<(), N3xbError> {
+        let path = self.path(key);
+        if let Some(dir_path) = path.parent() {
+            std::fs::create_dir_all(dir_path)?;
+        }
+        std::fs::write(path, blob)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), N3xbError> {
+        match std::fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, N3xbError> {
+        let entries = match std::fs::read_dir(self.path(prefix)) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut keys = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            keys.push(format!("{}/{}", prefix, file_name));
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, N3xbError> {
+        Ok(self.path(key).is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fs_store_save_load_list_delete() {
+        let root_dir_path = "test_fs_store";
+        let _ = std::fs::remove_dir_all(root_dir_path);
+        let store = FsStore::new(root_dir_path);
+
+        assert!(store.load("some/dir/blob-a.json").unwrap().is_none());
+        assert!(store.list("some/dir").unwrap().is_empty());
+
+        store.save("some/dir/blob-a.json", b"a").unwrap();
+        store.save("some/dir/blob-b.json", b"b").unwrap();
+        store.save("some/dir/deeper/blob-c.json", b"c").unwrap();
+        assert_eq!(
+            store.load("some/dir/blob-a.json").unwrap(),
+            Some(b"a".to_vec())
+        );
+        assert!(store.exists("some/dir/blob-b.json").unwrap());
+        assert_eq!(
+            store.list("some/dir").unwrap(),
+            vec!["some/dir/blob-a.json", "some/dir/blob-b.json"]
+        );
+
+        store.delete("some/dir/blob-a.json").unwrap();
+        store.delete("some/dir/blob-a.json").unwrap();
+        assert!(!store.exists("some/dir/blob-a.json").unwrap());
+        assert_eq!(
+            store.list("some/dir").unwrap(),
+            vec!["some/dir/blob-b.json"]
+        );
+
+        std::fs::remove_dir_all(root_dir_path).unwrap();
+    }
+}
@@ -1,15 +1,93 @@
 use dyn_clone::DynClone;
 use iso_currency::Currency;
+use nostr_sdk::prelude::EventId;
 use serde::{Deserialize, Serialize};
-use strum_macros::{Display, EnumString, IntoStaticStr};
+use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
 
 use std::any::Any;
+use std::borrow::Borrow;
 use std::hash::Hash;
+use std::sync::Arc;
 use std::{collections::HashSet, fmt::Debug, str::FromStr};
 
 use crate::common::error::N3xbError;
 
-pub type EventIdString = String;
+// Nostr Event ID as 64 lowercase hex characters. Validated when parsed and when deserialized
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EventIdString(String);
+
+impl EventIdString {
+    const HEX_LENGTH: usize = 64;
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // Fails instead of panicking, so a bad ID only fails the one operation it was passed to
+    pub(crate) fn to_event_id(&self) -> Result<EventId, N3xbError> {
+        EventId::from_str(&self.0).map_err(|_| N3xbError::InvalidEventId(self.0.clone()))
+    }
+
+    // Skips validation, to stand in for IDs from corrupted data or buggy callers
+    #[cfg(test)]
+    pub(crate) fn new_unchecked(event_id: impl Into<String>) -> Self {
+        Self(event_id.into())
+    }
+}
+
+impl FromStr for EventIdString {
+    type Err = N3xbError;
+
+    fn from_str(event_id: &str) -> Result<Self, Self::Err> {
+        let is_hex = event_id
+            .chars()
+            .all(|char| matches!(char, '0'..='9' | 'a'..='f'));
+        if event_id.len() != Self::HEX_LENGTH || !is_hex {
+            return Err(N3xbError::InvalidEventId(event_id.to_string()));
+        }
+        Ok(Self(event_id.to_string()))
+    }
+}
+
+impl TryFrom<String> for EventIdString {
+    type Error = N3xbError;
+
+    fn try_from(event_id: String) -> Result<Self, Self::Error> {
+        Self::from_str(&event_id)
+    }
+}
+
+impl From<EventId> for EventIdString {
+    fn from(event_id: EventId) -> Self {
+        Self(event_id.to_string())
+    }
+}
+
+impl From<EventIdString> for String {
+    fn from(event_id: EventIdString) -> Self {
+        event_id.0
+    }
+}
+
+impl std::fmt::Display for EventIdString {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for EventIdString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Hashes the same as the underlying str, so maps keyed by EventIdString can be looked up by &str
+impl Borrow<str> for EventIdString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
 
 pub enum BuySell {
     Buy,
@@ -21,6 +99,10 @@ pub(crate) enum SerdeGenericType {
     TakerOffer,
     TradeResponse,
     TradeEngineSpecific,
+    SettlementInfo,
+    TradeComplete,
+    DisputeRequest,
+    OfferWithdrawal,
 }
 
 #[typetag::serde(tag = "type")]
@@ -34,8 +116,29 @@ impl dyn SerdeGenericTrait {
     pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
         self.any_ref().downcast_ref()
     }
+
+    // The typetag name, as in the type field when serialized
+    pub fn type_name(&self) -> Result<String, N3xbError> {
+        let value = serde_json::to_value(self)?;
+        match value.get("type").and_then(|type_name| type_name.as_str()) {
+            Some(type_name) => Ok(type_name.to_string()),
+            None => Err(N3xbError::Simple(format!(
+                "Serde generic {:?} serialized without a type",
+                self
+            ))),
+        }
+    }
+
+    pub(crate) fn is_placeholder(&self) -> bool {
+        self.downcast_ref::<SerdeGenericsPlaceholder>().is_some()
+    }
 }
 
+// Registered by the Trade Engine to check its own specifics on Orders and Offers received.
+// An Err carries the Trade Engine defined reason for refusing them
+pub type TradeEngineSpecificsValidator =
+    Arc<dyn Fn(&dyn SerdeGenericTrait) -> Result<(), String> + Send + Sync>;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct SerdeGenericsPlaceholder {}
 
@@ -47,8 +150,19 @@ impl SerdeGenericTrait for SerdeGenericsPlaceholder {
 }
 
 #[derive(
-    PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize, EnumString, Display, IntoStaticStr,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumIter,
+    Display,
+    IntoStaticStr,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum BitcoinNetwork {
     Mainnet,
     Testnet,
@@ -57,8 +171,19 @@ pub enum BitcoinNetwork {
 }
 
 #[derive(
-    PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize, EnumString, Display, IntoStaticStr,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumIter,
+    Display,
+    IntoStaticStr,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum BitcoinSettlementMethod {
     Onchain,
     Lightning,
@@ -69,8 +194,19 @@ pub enum BitcoinSettlementMethod {
 // We are not implementing trade limits and risk association here. This should be for the higher level to determine.
 
 #[derive(
-    PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize, EnumString, Display, IntoStaticStr,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumIter,
+    Display,
+    IntoStaticStr,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum FiatPaymentMethod {
     Uphold,
     MoneyBeam,
@@ -97,6 +233,7 @@ pub enum FiatPaymentMethod {
     MoneyGram,
     WesternUnion,
     FaceToFace,
+    Cash, // In-person cash. The meeting area goes in the Order's geohash
     HalCash,
     // Blockchains, ??
     PromptPay,
@@ -169,6 +306,8 @@ impl ObligationKind {
         }
     }
 
+    // Canonical tag strings are Bitcoin[-<Network>][-<SettlementMethod>], Fiat[-<Currency>][-<PaymentMethod>]
+    // and Custom[-<Custom>]. Mainnet is implied when no Bitcoin network is present
     pub fn to_tag_strings(&self) -> HashSet<String> {
         let mut tag_string_set: HashSet<String>;
         let obligation_kind_prefix_bitcoin =
@@ -241,6 +380,8 @@ impl ObligationKind {
         tag_string_set
     }
 
+    // Other n3xB implementations might order the components after the prefix differently, or use a
+    // different letter case. So each component is recognized by what it parses into instead of by position
     pub fn from_tag_strings(tags: HashSet<String>) -> Result<HashSet<ObligationKind>, N3xbError> {
         let obligation_kind_prefix_bitcoin =
             ObligationKind::Bitcoin(BitcoinNetwork::Mainnet, None).to_string();
@@ -254,20 +395,25 @@ impl ObligationKind {
         ]);
 
         let mut kind_prefix: Option<String> = None;
-        let mut currency: Option<Currency> = None;
         let mut obligation_kinds: HashSet<ObligationKind> = HashSet::new();
 
         for tag in tags.clone() {
-            let splits_set: Vec<&str> = tag.split(OBLIGATION_KIND_SPLIT_CHAR).collect();
-
-            let splits_prefix = splits_set[0];
-            if !obligation_kind_prefix_set.contains(splits_prefix) {
+            let splits_set: Vec<&str> = tag
+                .split(OBLIGATION_KIND_SPLIT_CHAR)
+                .map(|split| split.trim())
+                .collect();
+
+            let Some(splits_prefix) = obligation_kind_prefix_set
+                .iter()
+                .find(|prefix| prefix.eq_ignore_ascii_case(splits_set[0]))
+            else {
                 return Err(N3xbError::Simple(
                     "Unrecgonized Obligation Kind Prefix".to_string(),
                 ));
+            };
 
             // Checking if all obligations are of the same kind is actually optional after refactor
-            } else if let Some(kind_prefix_unwrapped) = &kind_prefix {
+            if let Some(kind_prefix_unwrapped) = &kind_prefix {
                 if kind_prefix_unwrapped != splits_prefix {
                     let err_string = format!(
                         "Obligation tag set contains contradictory prefixes\n {:?}",
@@ -279,36 +425,24 @@ impl ObligationKind {
                 kind_prefix = Some(splits_prefix.to_string());
             }
 
+            let components: Vec<&str> = splits_set[1..]
+                .iter()
+                .cloned()
+                .filter(|split| !split.is_empty())
+                .collect();
+
             if &obligation_kind_prefix_bitcoin == kind_prefix.as_ref().unwrap() {
-                if splits_set.len() > 2 {
-                    let network = BitcoinNetwork::from_str(splits_set[1])?;
-                    let method = BitcoinSettlementMethod::from_str(splits_set[2])?;
-                    obligation_kinds.insert(ObligationKind::Bitcoin(network, Some(method)));
-                } else if splits_set.len() > 1 {
-                    match BitcoinSettlementMethod::from_str(splits_set[1]) {
-                        Ok(method) => {
-                            obligation_kinds.insert(ObligationKind::Bitcoin(
-                                BitcoinNetwork::Mainnet,
-                                Some(method),
-                            ));
-                        }
-                        Err(_) => {
-                            continue;
-                        }
-                    }
+                if let Some(kind) = Self::bitcoin_kind_from_tag_components(&tag, components)? {
+                    obligation_kinds.insert(kind);
                 }
             } else if &obligation_kind_prefix_fiat == kind_prefix.as_ref().unwrap() {
-                if splits_set.len() > 1 {
-                    currency = Some(Currency::from_str(splits_set[1])?);
-                }
-                if splits_set.len() > 2 {
-                    let fiat_method = FiatPaymentMethod::from_str(splits_set[2])?;
-                    obligation_kinds
-                        .insert(ObligationKind::Fiat(currency.unwrap(), Some(fiat_method)));
+                if let Some(kind) = Self::fiat_kind_from_tag_components(&tag, components)? {
+                    obligation_kinds.insert(kind);
                 }
             } else if &obligation_kind_prefix_custom == kind_prefix.as_ref().unwrap() {
-                if splits_set.len() > 1 {
-                    obligation_kinds.insert(ObligationKind::Custom(splits_set[1].to_string()));
+                if !components.is_empty() {
+                    let custom = components.join(OBLIGATION_KIND_SPLIT_CHAR);
+                    obligation_kinds.insert(ObligationKind::Custom(custom));
                 }
             } else {
                 panic!("Unexpected Obligation Kind Prefix");
@@ -316,12 +450,178 @@ impl ObligationKind {
         }
         Ok(obligation_kinds)
     }
+
+    fn bitcoin_kind_from_tag_components(
+        tag: &str,
+        components: Vec<&str>,
+    ) -> Result<Option<ObligationKind>, N3xbError> {
+        let mut network: Option<BitcoinNetwork> = None;
+        let mut method: Option<BitcoinSettlementMethod> = None;
+
+        for component in components {
+            if let Ok(parsed_network) = BitcoinNetwork::from_str(component) {
+                if network.replace(parsed_network).is_some() {
+                    return Err(Self::contradictory_tag_error(tag));
+                }
+            } else if let Ok(parsed_method) = BitcoinSettlementMethod::from_str(component) {
+                if method.replace(parsed_method).is_some() {
+                    return Err(Self::contradictory_tag_error(tag));
+                }
+            } else {
+                return Err(N3xbError::TagParsing(format!(
+                    "Unrecognized component {} in Bitcoin Obligation tag {}",
+                    component, tag
+                )));
+            }
+        }
+
+        // Tags with only a network specified carries no Obligation Kind by itself
+        Ok(method.map(|method| {
+            ObligationKind::Bitcoin(network.unwrap_or(BitcoinNetwork::Mainnet), Some(method))
+        }))
+    }
+
+    fn fiat_kind_from_tag_components(
+        tag: &str,
+        components: Vec<&str>,
+    ) -> Result<Option<ObligationKind>, N3xbError> {
+        let mut currency: Option<Currency> = None;
+        let mut method: Option<FiatPaymentMethod> = None;
+
+        for component in components {
+            if let Ok(parsed_currency) = Currency::from_str(&component.to_uppercase()) {
+                if currency.replace(parsed_currency).is_some() {
+                    return Err(Self::contradictory_tag_error(tag));
+                }
+            } else if let Ok(parsed_method) = FiatPaymentMethod::from_str(component) {
+                if method.replace(parsed_method).is_some() {
+                    return Err(Self::contradictory_tag_error(tag));
+                }
+            } else {
+                return Err(N3xbError::TagParsing(format!(
+                    "Unrecognized component {} in Fiat Obligation tag {}",
+                    component, tag
+                )));
+            }
+        }
+
+        match (currency, method) {
+            (Some(currency), Some(method)) => Ok(Some(ObligationKind::Fiat(currency, Some(method)))),
+            (None, Some(_)) => Err(N3xbError::TagParsing(format!(
+                "Fiat Obligation tag {} has payment method but no currency",
+                tag
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn contradictory_tag_error(tag: &str) -> N3xbError {
+        N3xbError::TagParsing(format!(
+            "Obligation tag {} contains contradictory components",
+            tag
+        ))
+    }
+
+    pub fn format_amount(&self, amount: f64) -> String {
+        match self {
+            ObligationKind::Bitcoin(_, _) => {
+                format!("{} sats", Self::format_with_thousands(amount, 0))
+            }
+            ObligationKind::Fiat(currency, _) => {
+                let decimals = currency.exponent().unwrap_or(2) as usize;
+                format!(
+                    "{} {}",
+                    Self::format_with_thousands(amount, decimals),
+                    currency.code()
+                )
+            }
+            ObligationKind::Custom(custom) => format!("{} {}", amount, custom),
+        }
+    }
+
+    pub fn format_amount_btc(&self, sats: f64) -> Option<String> {
+        match self {
+            ObligationKind::Bitcoin(_, _) => Some(format!(
+                "{} BTC",
+                Self::format_with_thousands(sats / Self::SATS_PER_BTC, 8)
+            )),
+            ObligationKind::Fiat(_, _) | ObligationKind::Custom(_) => None,
+        }
+    }
+
+    const SATS_PER_BTC: f64 = 100_000_000.0;
+
+    fn format_with_thousands(amount: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, amount.abs());
+        let (integer_part, fraction_part) = match formatted.split_once('.') {
+            Some((integer_part, fraction_part)) => (integer_part, Some(fraction_part)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut grouped = String::new();
+        for (index, digit) in integer_part.chars().enumerate() {
+            if index > 0 && (integer_part.len() - index) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+
+        if let Some(fraction_part) = fraction_part {
+            grouped = format!("{}.{}", grouped, fraction_part);
+        }
+
+        if amount < 0.0 {
+            format!("-{}", grouped)
+        } else {
+            grouped
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn event_id_string_parses_only_64_lowercase_hex() {
+        let hex = "a".repeat(64);
+        let event_id = EventIdString::from_str(&hex).unwrap();
+        assert_eq!(event_id.to_string(), hex);
+        assert_eq!(
+            EventIdString::from(event_id.to_event_id().unwrap()),
+            event_id
+        );
+
+        assert!(EventIdString::from_str("").is_err());
+        assert!(EventIdString::from_str(&"a".repeat(63)).is_err());
+        assert!(EventIdString::from_str(&"A".repeat(64)).is_err());
+        assert!(EventIdString::from_str(&"g".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn event_id_string_unchecked_fails_conversion() {
+        let event_id = EventIdString::new_unchecked("not-an-event-id");
+        assert!(matches!(
+            event_id.to_event_id(),
+            Err(N3xbError::InvalidEventId(_))
+        ));
+    }
+
+    #[test]
+    fn event_id_string_serde_rejects_malformed() {
+        let json = format!("\"{}\"", "0".repeat(64));
+        let event_id: EventIdString = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&event_id).unwrap(), json);
+        assert!(serde_json::from_str::<EventIdString>("\"not-an-event-id\"").is_err());
+    }
+
+    #[test]
+    fn placeholder_type_name() {
+        let placeholder: Box<dyn SerdeGenericTrait> = Box::new(SerdeGenericsPlaceholder {});
+        assert_eq!(placeholder.type_name().unwrap(), "n3xB-placeholder");
+        assert!(placeholder.is_placeholder());
+    }
+
     #[test]
     fn usd_venmo_is_same_currency_usd_cashapp() {
         let kind1 = ObligationKind::Fiat(Currency::USD, Some(FiatPaymentMethod::Venmo));
@@ -482,4 +782,85 @@ mod tests {
         );
         assert_eq!(obligation_kinds, expected_kinds);
     }
+
+    #[test]
+    fn bitcoin_obligation_kind_from_reordered_tags() {
+        let obligation_tags = HashSet::from([
+            "Bitcoin-Lightning-Testnet".to_string(),
+            "bitcoin-testnet".to_string(),
+        ]);
+        let obligation_kinds = ObligationKind::from_tag_strings(obligation_tags).unwrap();
+        let expected_kinds = HashSet::from([ObligationKind::Bitcoin(
+            BitcoinNetwork::Testnet,
+            Some(BitcoinSettlementMethod::Lightning),
+        )]);
+        print!(
+            "Obligation Kind: {:?} Expected: {:?}",
+            obligation_kinds, expected_kinds
+        );
+        assert_eq!(obligation_kinds, expected_kinds);
+    }
+
+    #[test]
+    fn fiat_obligation_kind_from_reordered_tags() {
+        let obligation_tags = HashSet::from([
+            "Fiat-Venmo-USD".to_string(),
+            "fiat-usd-cashapp".to_string(),
+            "FIAT-EUR-SEPA".to_string(),
+            "Fiat-USD".to_string(),
+            "Fiat".to_string(),
+        ]);
+        let obligation_kinds = ObligationKind::from_tag_strings(obligation_tags).unwrap();
+        let expected_kinds = HashSet::from([
+            ObligationKind::Fiat(Currency::USD, Some(FiatPaymentMethod::Venmo)),
+            ObligationKind::Fiat(Currency::USD, Some(FiatPaymentMethod::CashApp)),
+            ObligationKind::Fiat(Currency::EUR, Some(FiatPaymentMethod::Sepa)),
+        ]);
+        print!(
+            "Obligation Kind: {:?} Expected: {:?}",
+            obligation_kinds, expected_kinds
+        );
+        assert_eq!(obligation_kinds, expected_kinds);
+    }
+
+    #[test]
+    fn fiat_obligation_kind_from_contradictory_tag() {
+        let obligation_tags = HashSet::from(["Fiat-USD-EUR-Venmo".to_string()]);
+        assert!(ObligationKind::from_tag_strings(obligation_tags).is_err());
+    }
+
+    #[test]
+    fn fiat_obligation_kind_from_tag_without_currency() {
+        let obligation_tags = HashSet::from(["Fiat-Venmo".to_string()]);
+        assert!(ObligationKind::from_tag_strings(obligation_tags).is_err());
+    }
+
+    #[test]
+    fn fiat_usd_format_amount() {
+        let obligation_kind = ObligationKind::Fiat(Currency::USD, None);
+        assert_eq!(obligation_kind.format_amount(1234.56), "1,234.56 USD");
+        assert_eq!(obligation_kind.format_amount(1234567.0), "1,234,567.00 USD");
+    }
+
+    #[test]
+    fn fiat_jpy_format_amount() {
+        let obligation_kind = ObligationKind::Fiat(Currency::JPY, None);
+        assert_eq!(obligation_kind.format_amount(123456.0), "123,456 JPY");
+    }
+
+    #[test]
+    fn bitcoin_format_amount() {
+        let obligation_kind = ObligationKind::Bitcoin(BitcoinNetwork::Mainnet, None);
+        assert_eq!(obligation_kind.format_amount(1234567.0), "1,234,567 sats");
+        assert_eq!(
+            obligation_kind.format_amount_btc(1234567.0).unwrap(),
+            "0.01234567 BTC"
+        );
+    }
+
+    #[test]
+    fn fiat_format_amount_btc_is_none() {
+        let obligation_kind = ObligationKind::Fiat(Currency::EUR, None);
+        assert!(obligation_kind.format_amount_btc(100.0).is_none());
+    }
 }
@@ -0,0 +1,38 @@
+use std::any::type_name;
+
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use super::error::N3xbError;
+
+// Responding to a caller whose future was cancelled should not panic the actor
+pub(crate) fn send_rsp<T>(rsp_tx: oneshot::Sender<T>, rsp: T) {
+    if rsp_tx.send(rsp).is_err() {
+        debug!(
+            "Oneshot receiver dropped before response of type {} could be sent",
+            type_name::<T>()
+        );
+    }
+}
+
+// Nostr NIP-13 PoW, counted as the leading zero bits of the Event ID
+pub(crate) fn event_id_leading_zero_bits(event_id: &str) -> Result<u64, N3xbError> {
+    if event_id.len() != 64 || !event_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(N3xbError::Simple(format!(
+            "Event ID {} is not a 32-byte hex string",
+            event_id
+        )));
+    }
+
+    let mut leading_zero_bits: u64 = 0;
+    for c in event_id.chars() {
+        let nibble = c.to_digit(16).unwrap() as u8;
+        if nibble == 0 {
+            leading_zero_bits += 4;
+        } else {
+            leading_zero_bits += (nibble.leading_zeros() - 4) as u64;
+            break;
+        }
+    }
+    Ok(leading_zero_bits)
+}
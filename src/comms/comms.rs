@@ -1,40 +1,301 @@
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::path::Path;
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, trace, warn};
 
+use nostr_sdk::nostr::nips::nip05;
+use nostr_sdk::nostr::nips::nip26::{DelegationTag, EventProperties};
 use nostr_sdk::prelude::*;
-pub use nostr_sdk::prelude::{RelayInformationDocument, RelayStatus};
+pub use nostr_sdk::prelude::{Metadata, RelayInformationDocument, RelayStatus};
 
 use secp256k1::{rand::rngs::OsRng, Secp256k1, SecretKey, XOnlyPublicKey};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::select;
 use tokio::sync::{mpsc, oneshot};
 use uuid::Uuid;
 
 use crate::common::error::N3xbError;
+use crate::common::store::N3xbStore;
 use crate::common::types::{
     BitcoinNetwork, EventIdString, ObligationKind, SerdeGenericTrait, SerdeGenericType,
+    TradeEngineSpecificsValidator,
 };
+use crate::common::utils::{event_id_leading_zero_bits, send_rsp};
+use crate::dispute::DisputeRequest;
 use crate::offer::Offer;
 use crate::order::{
-    EventKind, FilterTag, MakerObligation, Order, OrderEnvelope, OrderTag, TakerObligation,
-    TradeDetails, TradeParameter, N3XB_APPLICATION_TAG,
+    custom_tags_to_strings, geohash_prefixes, EventKind, FilterTag, MakerObligation, Order,
+    OrderEnvelope, OrderTag, TakerObligation, TradeDetails, TradeDirection, TradeParameter,
+    N3XB_APPLICATION_TAG,
 };
-use crate::peer_msg::{PeerEnvelope, PeerMessage};
+use crate::peer_msg::{OfferWithdrawal, PeerEnvelope, PeerMessage, TradeComplete};
+use crate::settlement::SettlementInfo;
 use crate::trade_rsp::TradeResponse;
 
 use super::data::CommsData;
+use super::event_cache::EventCache;
 use super::maker_order_note::MakerOrderNote;
+use super::order_cache::OrderCache;
+use super::profile_cache::ProfileCache;
+use super::relay_stats::RelayParseStats;
+use super::relay_url::{normalize_relay_addrs, normalize_relay_url};
 use super::router::Router;
 
 #[derive(Clone, Debug)]
 pub struct RelayInfo {
     pub url: url::Url,
     pub status: RelayStatus,
+    pub capability: RelayCapability,
     pub document: RelayInformationDocument,
+    pub parse_stats: RelayParseStats,
+}
+
+// Full relay setup as persisted, for carrying it over to another device
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RelayConfig {
+    pub relays: Vec<RelayConfigEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RelayConfigEntry {
+    pub url: url::Url,
+    #[serde(default)]
+    pub proxy: Option<SocketAddr>,
+    #[serde(default)]
+    pub capability: RelayCapability,
+}
+
+impl RelayConfig {
+    pub fn to_json(&self) -> Result<String, N3xbError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: impl AsRef<str>) -> Result<Self, N3xbError> {
+        Ok(serde_json::from_str(json.as_ref())?)
+    }
+}
+
+// Impact of swapping the identity keypair on the trades in flight, see Manager::rotate_identity
+#[derive(Clone, Debug)]
+pub struct IdentityRotation {
+    pub old_pubkey: XOnlyPublicKey,
+    pub new_pubkey: XOnlyPublicKey,
+    pub continuing_trades: Vec<Uuid>, // Have per-trade keys, so stay on the old identity till done
+    pub disrupted_trades: Vec<Uuid>,  // Peers still DM the old pubkey, which can no longer be read
+    pub undeletable_order_notes: Vec<EventIdString>, // Signed by the old pubkey, left to expire
+}
+
+// Pushed to a watcher of a specific Maker Order, see Manager::watch_order
+#[derive(Clone, Debug)]
+pub enum OrderUpdate {
+    Updated(OrderEnvelope), // Republished, such as with amended Trade Details
+    Deleted,                // Cancelled or taken down by the Maker. The watch ends here
+}
+
+struct OrderWatch {
+    pubkey: XOnlyPublicKey,
+    event_id: Option<EventIdString>, // Latest Maker Order Note seen
+    created_at: u64,
+    since: u64, // Notes older than the watch itself are only recorded, not pushed
+    tx: mpsc::Sender<OrderUpdate>,
+}
+
+// Own Maker Order Note, tracked across relays to confirm it propagated
+struct OrderPropagationWatch {
+    event_id: EventIdString,
+    seen_on: HashSet<url::Url>,
+    tx: mpsc::Sender<url::Url>,
+}
+
+// Relays found supporting the required NIPs off the actor, for it to go on adding
+struct RelayNipsCheck {
+    relay_addrs: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>,
+    connect: bool,
+    skipped_relays: Vec<SkippedRelay>,
+    rsp_tx: oneshot::Sender<Result<Vec<SkippedRelay>, N3xbError>>,
+}
+
+// Outcome of a NIP-05 verification spawned off the actor, for it to cache and respond with
+struct Nip05Verification {
+    pubkey: XOnlyPublicKey,
+    nip05: String,
+    verified: bool,
+    metadata: Metadata,
+    rsp_tx: oneshot::Sender<Result<Option<Metadata>, N3xbError>>,
+}
+
+// Peer message mined with PoW off the actor, for it to send on
+struct MinedPeerMessage {
+    event: Result<Event, N3xbError>,
+    relay_hints: HashSet<Url>,
+    rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+}
+
+// Outcome of sending to hinted relays off the actor, for it to fall back to its own relays on
+struct HintedSend {
+    event: Event,
+    event_id: Option<EventId>,
+    temporary_urls: Vec<url::Url>,
+    rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+}
+
+// Established counterparty of a trade, whose DMs failing to decrypt are reported rather than dropped
+struct CounterpartyWatch {
+    pubkey: XOnlyPublicKey,
+    tx: mpsc::Sender<N3xbError>,
+}
+
+// Keys for encrypting peer messages of a trade in place of the identity keys, for forward secrecy
+struct TradeEncryptionKeys {
+    secret_key: SecretKey,
+    peer_pubkey: XOnlyPublicKey,
+}
+
+// Relay errors from sending an event no relay accepted, by how each relay failed
+#[derive(Default)]
+struct EventSendFailures {
+    rejected: Vec<String>,
+    failed: Vec<String>,
+    unreachable: Vec<String>,
+}
+
+impl EventSendFailures {
+    // Rejected only if every relay rejected, as retrying may still get it out to the others
+    fn into_error(self) -> N3xbError {
+        let all_rejected = self.failed.is_empty() && self.unreachable.is_empty();
+        let any_failed = !self.failed.is_empty();
+        let relay_errors = [self.rejected, self.failed, self.unreachable]
+            .concat()
+            .join(", ");
+
+        if all_rejected && !relay_errors.is_empty() {
+            N3xbError::EventRejected(relay_errors)
+        } else if any_failed {
+            N3xbError::EventSendFailed(relay_errors)
+        } else {
+            N3xbError::RelayUnreachable(relay_errors)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QueryOpts {
+    pub use_cache: bool,
+    pub max_age: Duration, // Cached results older than this are refetched from relays
+    pub relays: Option<Vec<url::Url>>, // Only query these relays, instead of all readable relays
+    pub max_relay_failure_rate: Option<f64>, // Skip relays serving a larger share of malformed events
+
+    // Window of Order creation in Unix seconds. The later of since & created_within applies
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub created_within: Option<Duration>, // Only Orders created this recently, as of the query
+    pub limit: Option<usize>,             // Most recent Orders per relay
+}
+
+impl QueryOpts {
+    // Most restrictive of since and the created_within window, as of now in Unix seconds
+    pub(crate) fn effective_since(&self, now: u64) -> Option<u64> {
+        let window_start = self
+            .created_within
+            .map(|created_within| now.saturating_sub(created_within.as_secs()));
+        self.since.max(window_start)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AddRelayOpts {
+    pub connect: bool,
+    pub require_nips: Vec<u16>, // Relays not advertising all of these in their NIP-11 document are skipped
+}
+
+impl Default for AddRelayOpts {
+    fn default() -> Self {
+        Self {
+            connect: true,
+            require_nips: Vec::new(),
+        }
+    }
+}
+
+// Relay left out of an add for lacking required NIPs. All required NIPs are reported missing if
+// the relay's NIP-11 document couldn't be fetched
+#[derive(Clone, Debug)]
+pub struct SkippedRelay {
+    pub url: url::Url,
+    pub missing_nips: Vec<u16>,
+}
+
+// A page of Orders from a single relay, walking backwards in time. The oldest created_at is of all
+// events in the page, including the ones that didn't make it to Orders, to seed the next page with
+pub(crate) struct OrderPage {
+    pub(crate) order_envelopes: Vec<OrderEnvelope>,
+    pub(crate) event_count: usize,
+    pub(crate) oldest_created_at: Option<u64>,
+}
+
+impl Default for QueryOpts {
+    fn default() -> Self {
+        Self {
+            use_cache: false,
+            max_age: Duration::from_secs(30),
+            relays: None,
+            max_relay_failure_rate: None,
+            since: None,
+            until: None,
+            created_within: None,
+            limit: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelayCapability {
+    #[default]
+    ReadWrite,
+    ReadOnly,  // Subscribed & queried from, never sent to
+    WriteOnly, // Sent to, never subscribed to
+}
+
+impl RelayCapability {
+    pub fn can_read(&self) -> bool {
+        *self != RelayCapability::WriteOnly
+    }
+
+    pub fn can_write(&self) -> bool {
+        *self != RelayCapability::ReadOnly
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CommsOptions {
+    pub wait_for_connection: bool,
+    pub wait_for_send: bool,
+    pub connect_on_start: bool, // Connect to restored relays on construction, otherwise stay lazy until connect is requested
+    pub event_cache_size: usize, // Parsed Maker Order Notes kept across queries. 0 disables the cache
+    pub max_relays: usize,       // Relays added beyond this are refused, bounding connections held
+    pub min_peer_pow: u8, // Peer messages received with less PoW are dropped as spam. 0 accepts all
+    pub peer_pow: u8,     // PoW mined into peer messages sent, for peers requiring a minimum
+    pub hint_relay_nips: Vec<u16>, // Relays hinted by peers must advertise these NIPs to be sent to
+    pub peer_allowlist: Option<HashSet<XOnlyPublicKey>>, // Replaces the persisted allowlist if set
+}
+
+impl Default for CommsOptions {
+    fn default() -> Self {
+        Self {
+            wait_for_connection: true,
+            wait_for_send: true,
+            connect_on_start: false,
+            event_cache_size: 1000,
+            max_relays: 100,
+            min_peer_pow: 0,
+            peer_pow: 0,
+            hint_relay_nips: Vec::new(),
+            peer_allowlist: None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -54,15 +315,37 @@ impl CommsAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub(crate) async fn rotate_identity(
+        &self,
+        new_secret_key: SecretKey,
+    ) -> Result<IdentityRotation, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<IdentityRotation, N3xbError>>();
+        let request = CommsRequest::RotateIdentity {
+            new_secret_key,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn get_npub(&self) -> String {
+        let pubkey = self.get_pubkey().await;
+        pubkey
+            .to_bech32()
+            .expect("XOnlyPublicKey should always be encodable as NIP-19 npub")
+    }
+
     pub(crate) async fn add_relays(
         &self,
-        relay_addrs: Vec<(url::Url, Option<SocketAddr>)>,
+        relay_addrs: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>,
         connect: bool,
-    ) -> Result<(), N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        require_nips: Vec<u16>,
+    ) -> Result<Vec<SkippedRelay>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Vec<SkippedRelay>, N3xbError>>();
         let request = CommsRequest::AddRelays {
             relay_addrs,
             connect,
+            require_nips,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
@@ -83,6 +366,40 @@ impl CommsAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub(crate) async fn export_relay_config(&self) -> RelayConfig {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<RelayConfig>();
+        let request = CommsRequest::ExportRelayConfig { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Merges into the relays already added, connecting to the imported ones
+    pub(crate) async fn import_relay_config(&self, config: RelayConfig) -> Result<(), N3xbError> {
+        let relay_addrs = config
+            .relays
+            .into_iter()
+            .map(|entry| (entry.url, entry.proxy, entry.capability))
+            .collect();
+        self.add_relays(relay_addrs, true, Vec::new()).await?;
+        Ok(())
+    }
+
+    // JSON of the filters currently subscribed with, for diagnosing missing events
+    pub(crate) async fn get_active_filters(&self) -> Vec<String> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Vec<String>>();
+        let request = CommsRequest::GetActiveFilters { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // True if at least one Relay is currently connected
+    pub(crate) async fn is_connected(&self) -> bool {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<bool>();
+        let request = CommsRequest::IsConnected { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub(crate) async fn connect_relay(&self, relay_url: url::Url) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
         let request = CommsRequest::ConnectRelay { relay_url, rsp_tx };
@@ -97,206 +414,574 @@ impl CommsAccess {
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn register_peer_message_tx(
+    // Only DMs from these pubkeys are processed, such as for a private n3xB network. None allows all
+    pub(crate) async fn set_peer_allowlist(
         &self,
-        trade_uuid: Uuid,
-        tx: mpsc::Sender<PeerEnvelope>,
+        allowlist: Option<HashSet<XOnlyPublicKey>>,
     ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::RegisterTradeTx {
-            trade_uuid,
-            tx,
+        let request = CommsRequest::SetPeerAllowlist { allowlist, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn set_delegation_tag(
+        &self,
+        delegation_tag: Option<String>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::SetDelegationTag {
+            delegation_tag,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    #[allow(dead_code)]
-    pub(crate) async fn unregister_peer_message_tx(
+    pub(crate) async fn register_trade_engine_specifics_type(
         &self,
-        trade_uuid: Uuid,
+        type_name: impl Into<String>,
     ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::UnregisterTradeTx { trade_uuid, rsp_tx };
+        let request = CommsRequest::RegisterTradeEngineSpecificsType {
+            type_name: type_name.into(),
+            rsp_tx,
+        };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn register_peer_message_fallback_tx(
+    pub(crate) async fn register_trade_engine_specifics_validator(
         &self,
-        tx: mpsc::Sender<PeerEnvelope>,
+        validator: Option<TradeEngineSpecificsValidator>,
     ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::RegisterFallbackTx { tx, rsp_tx };
+        let request = CommsRequest::RegisterTradeEngineSpecificsValidator { validator, rsp_tx };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn unregister_peer_message_fallback_tx(&self) -> Result<(), N3xbError> {
+    pub(crate) async fn register_default_trade_engine_specifics(
+        &self,
+        default_order_specifics: Option<Box<dyn SerdeGenericTrait>>,
+        default_offer_specifics: Option<Box<dyn SerdeGenericTrait>>,
+    ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::UnregisterFallbackTx { rsp_tx };
+        let request = CommsRequest::RegisterDefaultTradeEngineSpecifics {
+            default_order_specifics,
+            default_offer_specifics,
+            rsp_tx,
+        };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn send_maker_order_note(
+    pub(crate) async fn validate_trade_engine_specifics(
         &self,
-        order: Order,
-    ) -> Result<OrderEnvelope, N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<OrderEnvelope, N3xbError>>();
-        let request = CommsRequest::SendMakerOrderNote { order, rsp_tx };
+        trade_engine_specifics: Box<dyn SerdeGenericTrait>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::ValidateTradeEngineSpecifics {
+            trade_engine_specifics,
+            rsp_tx,
+        };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn query_orders(
+    pub(crate) async fn register_peer_message_tx(
         &self,
-        filter_tags: Vec<FilterTag>,
-    ) -> Result<Vec<OrderEnvelope>, N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Vec<OrderEnvelope>, N3xbError>>();
-        let request = CommsRequest::QueryOrders {
-            filter_tags,
+        trade_uuid: Uuid,
+        tx: mpsc::Sender<PeerEnvelope>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::RegisterTradeTx {
+            trade_uuid,
+            tx,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn send_taker_offer_message(
+    #[allow(dead_code)]
+    pub(crate) async fn unregister_peer_message_tx(
         &self,
-        pubkey: XOnlyPublicKey, // Pubkey of destination receipient (Maker)
-        responding_to_id: Option<EventIdString>,
-        maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
-        offer: Offer,
-    ) -> Result<EventIdString, N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
-        let request = CommsRequest::SendTakerOfferMessage {
-            pubkey,
-            responding_to_id,
-            maker_order_note_id,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnregisterTradeTx { trade_uuid, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn register_trade_filter(
+        &self,
+        trade_uuid: Uuid,
+        filter: Filter,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::RegisterTradeFilter {
             trade_uuid,
-            offer,
+            filter,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn send_trade_response(
+    pub(crate) async fn unregister_trade_filter(&self, trade_uuid: Uuid) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnregisterTradeFilter { trade_uuid, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Peer messages of the trade are encrypted between secret_key and the peer's per-trade pubkey
+    // from here on. Peer messages of trades without are encrypted with the identity keys
+    pub(crate) async fn register_trade_encryption(
         &self,
-        pubkey: XOnlyPublicKey,
-        responding_to_id: Option<EventIdString>,
-        maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
-        trade_rsp: TradeResponse,
-    ) -> Result<EventIdString, N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
-        let request = CommsRequest::SendTradeResponse {
-            pubkey,
-            responding_to_id,
-            maker_order_note_id,
+        secret_key: SecretKey,
+        peer_pubkey: XOnlyPublicKey,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::RegisterTradeEncryption {
             trade_uuid,
-            trade_rsp,
+            secret_key,
+            peer_pubkey,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn send_trade_engine_specific_message(
+    pub(crate) async fn unregister_trade_encryption(
+        &self,
+        trade_uuid: Uuid,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnregisterTradeEncryption { trade_uuid, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn watch_order(
         &self,
         pubkey: XOnlyPublicKey,
-        responding_to_id: Option<EventIdString>,
-        maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
-        message: Box<dyn SerdeGenericTrait>,
-    ) -> Result<EventIdString, N3xbError> {
-        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
-        let request = CommsRequest::SendTradeEngineSpecificMessage {
+        tx: mpsc::Sender<OrderUpdate>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::WatchOrder {
             pubkey,
-            responding_to_id,
-            maker_order_note_id,
             trade_uuid,
-            message,
+            tx,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn delete_maker_order_note(
+    pub(crate) async fn unwatch_order(&self, trade_uuid: Uuid) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnwatchOrder { trade_uuid, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Replaces any prior watch of the trade, such as of an Order Note since republished
+    pub(crate) async fn watch_order_propagation(
         &self,
+        trade_uuid: Uuid,
         event_id: EventIdString,
-        reason: impl Into<String>,
+        tx: mpsc::Sender<url::Url>,
     ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::DeletMakerOrderNote {
+        let request = CommsRequest::WatchOrderPropagation {
+            trade_uuid,
             event_id,
-            reason: reason.into(),
+            tx,
             rsp_tx,
         };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
 
-    pub(crate) async fn shutdown(&self) -> Result<(), N3xbError> {
+    pub(crate) async fn unwatch_order_propagation(
+        &self,
+        trade_uuid: Uuid,
+    ) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
-        let request = CommsRequest::Shutdown { rsp_tx };
+        let request = CommsRequest::UnwatchOrderPropagation { trade_uuid, rsp_tx };
         self.tx.send(request).await.unwrap();
         rsp_rx.await.unwrap()
     }
-}
-
-pub(crate) struct Comms {
-    tx: mpsc::Sender<CommsRequest>,
-    pub task_handle: tokio::task::JoinHandle<()>,
-}
 
-impl Comms {
-    const INTEFACER_REQUEST_CHANNEL_SIZE: usize = 100;
-    const NOSTR_EVENT_DEFAULT_POW_DIFFICULTY: u8 = 8;
-
-    // Constructors
-
-    pub(crate) async fn new(
-        trade_engine_name: impl AsRef<str>,
+    // Replaces any prior watch of the trade. DMs from the pubkey that fail to decrypt are sent to tx
+    pub(crate) async fn watch_counterparty(
+        &self,
+        trade_uuid: Uuid,
+        pubkey: XOnlyPublicKey,
+        tx: mpsc::Sender<N3xbError>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::WatchCounterparty {
+            trade_uuid,
+            pubkey,
+            tx,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn unwatch_counterparty(&self, trade_uuid: Uuid) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnwatchCounterparty { trade_uuid, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn register_peer_message_fallback_tx(
+        &self,
+        tx: mpsc::Sender<PeerEnvelope>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::RegisterFallbackTx { tx, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn unregister_peer_message_fallback_tx(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::UnregisterFallbackTx { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_maker_order_note(
+        &self,
+        order: Order,
+    ) -> Result<OrderEnvelope, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<OrderEnvelope, N3xbError>>();
+        let request = CommsRequest::SendMakerOrderNote { order, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn query_orders(
+        &self,
+        filter_tags: Vec<FilterTag>,
+        query_opts: QueryOpts,
+    ) -> Result<Vec<OrderEnvelope>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Vec<OrderEnvelope>, N3xbError>>();
+        let request = CommsRequest::QueryOrders {
+            filter_tags,
+            query_opts,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn query_orders_page(
+        &self,
+        filter_tags: Vec<FilterTag>,
+        relay_url: url::Url,
+        until: Option<u64>,
+        limit: usize,
+    ) -> Result<OrderPage, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<OrderPage, N3xbError>>();
+        let request = CommsRequest::QueryOrdersPage {
+            filter_tags,
+            relay_url,
+            until,
+            limit,
+            rsp_tx,
+        };
+        // Called from a background walk that can outlive Comms. Fails instead of panicking then
+        self.tx.send(request).await?;
+        rsp_rx.await?
+    }
+
+    // NIP-65 relays the pubkey reads from, for where to send peer messages to
+    pub(crate) async fn query_relay_list(
+        &self,
+        pubkey: XOnlyPublicKey,
+    ) -> Result<HashSet<Url>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<HashSet<Url>, N3xbError>>();
+        let request = CommsRequest::QueryRelayList { pubkey, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Peer messages of the trade DMed to us since the given Unix seconds, as refetched from relays.
+    // For picking up messages missed across connectivity gaps
+    pub(crate) async fn query_peer_messages(
+        &self,
+        trade_uuid: Uuid,
+        since: Option<u64>,
+    ) -> Result<Vec<PeerEnvelope>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Vec<PeerEnvelope>, N3xbError>>();
+        let request = CommsRequest::QueryPeerMessages {
+            trade_uuid,
+            since,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Kind-0 Metadata of the pubkey, if any. With verify_nip05, a NIP-05 identifier that fails
+    // verification is cleared, so whatever identifier is left can be shown as verified
+    pub(crate) async fn get_maker_profile(
+        &self,
+        pubkey: XOnlyPublicKey,
+        verify_nip05: bool,
+    ) -> Result<Option<Metadata>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Option<Metadata>, N3xbError>>();
+        let request = CommsRequest::GetMakerProfile {
+            pubkey,
+            verify_nip05,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_taker_offer_message(
+        &self,
+        pubkey: XOnlyPublicKey, // Pubkey of destination receipient (Maker)
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        offer: Offer,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendTakerOfferMessage {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            offer,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_trade_response(
+        &self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        trade_rsp: TradeResponse,
+        relay_hints: HashSet<Url>,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendTradeResponse {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            trade_rsp,
+            relay_hints,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_trade_engine_specific_message(
+        &self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        message: Box<dyn SerdeGenericTrait>,
+        sequence: u64,
+        message_id: Uuid,
+        relay_hints: HashSet<Url>,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendTradeEngineSpecificMessage {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            message,
+            sequence,
+            message_id,
+            relay_hints,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_settlement_info(
+        &self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        settlement_info: SettlementInfo,
+        relay_hints: HashSet<Url>,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendSettlementInfo {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            settlement_info,
+            relay_hints,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_dispute_request(
+        &self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        dispute_request: DisputeRequest,
+        relay_hints: HashSet<Url>,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendDisputeRequest {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            dispute_request,
+            relay_hints,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_trade_complete(
+        &self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        relay_hints: HashSet<Url>,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendTradeComplete {
+            pubkey,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            relay_hints,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn send_offer_withdrawal(
+        &self,
+        pubkey: XOnlyPublicKey,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        offer_event_id: EventIdString,
+    ) -> Result<EventIdString, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<EventIdString, N3xbError>>();
+        let request = CommsRequest::SendOfferWithdrawal {
+            pubkey,
+            maker_order_note_id,
+            trade_uuid,
+            offer_event_id,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn delete_maker_order_note(
+        &self,
+        event_id: EventIdString,
+        reason: impl Into<String>,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::DeletMakerOrderNote {
+            event_id,
+            reason: reason.into(),
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub(crate) async fn shutdown(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = CommsRequest::Shutdown { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+}
+
+pub(crate) struct Comms {
+    tx: mpsc::Sender<CommsRequest>,
+    pub task_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Comms {
+    const INTEFACER_REQUEST_CHANNEL_SIZE: usize = 100;
+    const NOSTR_EVENT_DEFAULT_POW_DIFFICULTY: u8 = 8;
+
+    // Constructors
+
+    pub(crate) async fn new(
+        trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
-        data_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        comms_opts: CommsOptions,
     ) -> Self {
         let secp = Secp256k1::new();
         let (secret_key, _) = secp.generate_keypair(&mut OsRng);
-        Self::new_with_key(secret_key, trade_engine_name, network, data_dir_path).await
+        Self::new_with_key(secret_key, trade_engine_name, network, backend, comms_opts).await
     }
 
     pub(crate) async fn new_with_key(
         secret_key: SecretKey,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
-        data_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        comms_opts: CommsOptions,
     ) -> Self {
-        let client = Self::new_nostr_client(secret_key).await;
-        Self::new_with_nostr_client(client, trade_engine_name, network, data_dir_path).await
+        let client = Self::new_nostr_client(secret_key, &comms_opts).await;
+        Self::new_with_nostr_client(client, trade_engine_name, network, backend, &comms_opts).await
     }
 
     pub(super) async fn new_with_nostr_client(
         client: Client,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
-        data_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        comms_opts: &CommsOptions,
     ) -> Self {
         let (tx, rx) = mpsc::channel::<CommsRequest>(Self::INTEFACER_REQUEST_CHANNEL_SIZE);
-        let actor = CommsActor::new(rx, trade_engine_name, network, client, data_dir_path).await;
+        let actor =
+            CommsActor::new(rx, trade_engine_name, network, client, backend, comms_opts).await;
         let task_handle = tokio::spawn(async move { actor.run().await });
         Self { tx, task_handle }
     }
 
-    async fn new_nostr_client(secret_key: SecretKey) -> Client {
+    async fn new_nostr_client(secret_key: SecretKey, comms_opts: &CommsOptions) -> Client {
         let keys = Keys::new(secret_key);
         let opts = Options::new()
-            .wait_for_connection(true)
-            .wait_for_send(true)
+            .wait_for_connection(comms_opts.wait_for_connection)
+            .wait_for_send(comms_opts.wait_for_send)
             .difficulty(Self::NOSTR_EVENT_DEFAULT_POW_DIFFICULTY);
         Client::with_opts(&keys, opts)
     }
@@ -304,6 +989,52 @@ impl Comms {
     pub(crate) fn new_accessor(&self) -> CommsAccess {
         CommsAccess::new(self.tx.clone())
     }
+
+    // PoW Estimation
+
+    const POW_BENCHMARK_DIFFICULTY: u8 = 8;
+    const POW_BENCHMARK_ITERATIONS: u32 = 8;
+
+    pub(crate) async fn estimate_pow_time(difficulty: u8) -> Duration {
+        // Mine a few throw-away events at a low difficulty, then extrapolate.
+        // Expected attempts doubles with each additional leading zero bit.
+        let benchmark = tokio::task::spawn_blocking(|| {
+            let keys = Keys::generate();
+            let start = std::time::Instant::now();
+            for iteration in 0..Self::POW_BENCHMARK_ITERATIONS {
+                let builder = EventBuilder::new(
+                    CommsActor::MAKER_ORDER_NOTE_KIND,
+                    format!("n3xb-pow-benchmark-{}", iteration),
+                    &[],
+                );
+                if let Some(error) = builder
+                    .to_pow_event(&keys, Self::POW_BENCHMARK_DIFFICULTY)
+                    .err()
+                {
+                    warn!("estimate_pow_time() benchmark failed - {}", error);
+                }
+            }
+            start.elapsed() / Self::POW_BENCHMARK_ITERATIONS
+        })
+        .await;
+
+        let time_per_benchmark = match benchmark {
+            Ok(duration) => duration,
+            Err(error) => {
+                warn!("estimate_pow_time() benchmark task failed - {}", error);
+                return Duration::ZERO;
+            }
+        };
+
+        if difficulty <= Self::POW_BENCHMARK_DIFFICULTY {
+            let shift = Self::POW_BENCHMARK_DIFFICULTY - difficulty;
+            time_per_benchmark / 2u32.saturating_pow(shift as u32)
+        } else {
+            let shift = (difficulty - Self::POW_BENCHMARK_DIFFICULTY) as i32;
+            let estimate_secs = time_per_benchmark.as_secs_f64() * 2f64.powi(shift);
+            Duration::try_from_secs_f64(estimate_secs).unwrap_or(Duration::MAX)
+        }
+    }
 }
 
 pub(super) enum CommsRequest {
@@ -311,10 +1042,15 @@ pub(super) enum CommsRequest {
     GetPublicKey {
         rsp_tx: oneshot::Sender<XOnlyPublicKey>,
     },
+    RotateIdentity {
+        new_secret_key: SecretKey,
+        rsp_tx: oneshot::Sender<Result<IdentityRotation, N3xbError>>,
+    },
     AddRelays {
-        relay_addrs: Vec<(url::Url, Option<SocketAddr>)>,
+        relay_addrs: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>,
         connect: bool,
-        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+        require_nips: Vec<u16>,
+        rsp_tx: oneshot::Sender<Result<Vec<SkippedRelay>, N3xbError>>,
     },
     RemoveRelay {
         relay_url: url::Url,
@@ -323,6 +1059,15 @@ pub(super) enum CommsRequest {
     GetRelays {
         rsp_tx: oneshot::Sender<Vec<RelayInfo>>,
     },
+    ExportRelayConfig {
+        rsp_tx: oneshot::Sender<RelayConfig>,
+    },
+    GetActiveFilters {
+        rsp_tx: oneshot::Sender<Vec<String>>,
+    },
+    IsConnected {
+        rsp_tx: oneshot::Sender<bool>,
+    },
     ConnectRelay {
         relay_url: url::Url,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
@@ -330,6 +1075,31 @@ pub(super) enum CommsRequest {
     ConnectAllRelays {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    SetPeerAllowlist {
+        allowlist: Option<HashSet<XOnlyPublicKey>>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    SetDelegationTag {
+        delegation_tag: Option<String>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RegisterTradeEngineSpecificsType {
+        type_name: String,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RegisterTradeEngineSpecificsValidator {
+        validator: Option<TradeEngineSpecificsValidator>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RegisterDefaultTradeEngineSpecifics {
+        default_order_specifics: Option<Box<dyn SerdeGenericTrait>>,
+        default_offer_specifics: Option<Box<dyn SerdeGenericTrait>>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    ValidateTradeEngineSpecifics {
+        trade_engine_specifics: Box<dyn SerdeGenericTrait>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     RegisterTradeTx {
         trade_uuid: Uuid,
         tx: mpsc::Sender<PeerEnvelope>,
@@ -339,6 +1109,55 @@ pub(super) enum CommsRequest {
         trade_uuid: Uuid,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    RegisterTradeFilter {
+        trade_uuid: Uuid,
+        filter: Filter,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    UnregisterTradeFilter {
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    UnregisterTradeEncryption {
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RegisterTradeEncryption {
+        trade_uuid: Uuid,
+        secret_key: SecretKey,
+        peer_pubkey: XOnlyPublicKey,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    WatchOrder {
+        pubkey: XOnlyPublicKey,
+        trade_uuid: Uuid,
+        tx: mpsc::Sender<OrderUpdate>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    UnwatchOrder {
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    WatchOrderPropagation {
+        trade_uuid: Uuid,
+        event_id: EventIdString,
+        tx: mpsc::Sender<url::Url>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    UnwatchOrderPropagation {
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    WatchCounterparty {
+        trade_uuid: Uuid,
+        pubkey: XOnlyPublicKey,
+        tx: mpsc::Sender<N3xbError>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    UnwatchCounterparty {
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     #[allow(dead_code)]
     RegisterFallbackTx {
         tx: mpsc::Sender<PeerEnvelope>,
@@ -354,8 +1173,30 @@ pub(super) enum CommsRequest {
     },
     QueryOrders {
         filter_tags: Vec<FilterTag>,
+        query_opts: QueryOpts,
         rsp_tx: oneshot::Sender<Result<Vec<OrderEnvelope>, N3xbError>>,
     },
+    QueryOrdersPage {
+        filter_tags: Vec<FilterTag>,
+        relay_url: url::Url,
+        until: Option<u64>,
+        limit: usize,
+        rsp_tx: oneshot::Sender<Result<OrderPage, N3xbError>>,
+    },
+    QueryRelayList {
+        pubkey: XOnlyPublicKey,
+        rsp_tx: oneshot::Sender<Result<HashSet<Url>, N3xbError>>,
+    },
+    QueryPeerMessages {
+        trade_uuid: Uuid,
+        since: Option<u64>,
+        rsp_tx: oneshot::Sender<Result<Vec<PeerEnvelope>, N3xbError>>,
+    },
+    GetMakerProfile {
+        pubkey: XOnlyPublicKey,
+        verify_nip05: bool,
+        rsp_tx: oneshot::Sender<Result<Option<Metadata>, N3xbError>>,
+    },
     SendTakerOfferMessage {
         pubkey: XOnlyPublicKey, // Pubkey of destination receipient (Maker)
         responding_to_id: Option<EventIdString>,
@@ -370,6 +1211,7 @@ pub(super) enum CommsRequest {
         maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
         trade_rsp: TradeResponse,
+        relay_hints: HashSet<Url>,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     },
     SendTradeEngineSpecificMessage {
@@ -378,6 +1220,42 @@ pub(super) enum CommsRequest {
         maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
         message: Box<dyn SerdeGenericTrait>,
+        sequence: u64,
+        message_id: Uuid, // Same across resends, for the recipient to deduplicate
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    },
+    SendSettlementInfo {
+        pubkey: XOnlyPublicKey, // Pubkey of destination receipient
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        settlement_info: SettlementInfo,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    },
+    SendDisputeRequest {
+        pubkey: XOnlyPublicKey, // Pubkey of destination receipient
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        dispute_request: DisputeRequest,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    },
+    SendTradeComplete {
+        pubkey: XOnlyPublicKey, // Pubkey of destination receipient
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    },
+    SendOfferWithdrawal {
+        pubkey: XOnlyPublicKey, // Pubkey of destination receipient (Maker)
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        offer_event_id: EventIdString,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     },
     DeletMakerOrderNote {
@@ -395,44 +1273,118 @@ pub(super) struct CommsActor {
     trade_engine_name: String,
     network: BitcoinNetwork,
     pubkey: XOnlyPublicKey,
+    backend: Arc<dyn N3xbStore>,
     data: CommsData,
     client: Client,
     router: Router,
+    trade_filters: HashMap<Uuid, Filter>,
+    trade_encryption_keys: HashMap<Uuid, TradeEncryptionKeys>,
+    trade_identity_keys: HashMap<Uuid, Keys>, // Trades kept on the identity they started with
+    order_watches: HashMap<Uuid, OrderWatch>,
+    order_propagation_watches: HashMap<Uuid, OrderPropagationWatch>,
+    counterparty_watches: HashMap<Uuid, CounterpartyWatch>,
+    delegation_tag_values: Option<Vec<String>>,
+    trade_engine_specifics_types: HashSet<String>, // Any type is accepted if none are registered
+    trade_engine_specifics_validator: Option<TradeEngineSpecificsValidator>,
+    // For when absent or malformed on Orders and Offers received
+    default_order_trade_engine_specifics: Option<Box<dyn SerdeGenericTrait>>,
+    default_offer_trade_engine_specifics: Option<Box<dyn SerdeGenericTrait>>,
+    order_cache: OrderCache,
+    event_cache: EventCache,
+    profile_cache: ProfileCache,
+    nip05_verification_tx: Option<mpsc::Sender<Nip05Verification>>,
+    relay_nips_check_tx: Option<mpsc::Sender<RelayNipsCheck>>,
+    mined_peer_message_tx: Option<mpsc::Sender<MinedPeerMessage>>,
+    hinted_send_tx: Option<mpsc::Sender<HintedSend>>,
+    temporary_hint_relays: HashSet<url::Url>, // Connected to by hinted sends still in flight
+    relay_parse_stats: HashMap<url::Url, RelayParseStats>,
+    max_relays: usize,
+    min_peer_pow: u8,
+    peer_pow: u8,
+    hint_relay_nips: Vec<u16>,
 }
 
 impl CommsActor {
     const MAKER_ORDER_NOTE_KIND: Kind = Kind::Custom(61021);
+    const PROFILE_CACHE_MAX_AGE: Duration = Duration::from_secs(600);
+    const PROFILE_CACHE_MAX_SIZE: usize = 1000;
+    const NIP05_VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+    const NIP11_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+    const MAX_RELAY_HINTS: usize = 3;
+    const HINT_RELAY_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+    const PEER_MESSAGE_QUERY_PAGE_SIZE: usize = 200;
+    const PEER_MESSAGE_QUERY_MAX_PAGES: usize = 10;
+    const MAX_PEER_POW: u8 = 24; // Mining time doubles with each bit
 
     pub(super) async fn new(
         rx: mpsc::Receiver<CommsRequest>,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
         client: Client,
-        data_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        comms_opts: &CommsOptions,
     ) -> Self {
         let pubkey = client.keys().await.public_key();
-        let data =
-            match CommsData::new(&data_dir_path, pubkey, &trade_engine_name, network.borrow()) {
-                Ok(data) => data,
-                Err(error) => {
-                    panic!(
-                        "Comms w/ pubkey {} failed to initialize CommsData with path {} - {}",
-                        pubkey,
-                        data_dir_path.as_ref().display().to_string(),
-                        error
-                    );
-                }
-            };
-        let relays = data.relays();
+        let data = match CommsData::new(
+            backend.clone(),
+            pubkey,
+            &trade_engine_name,
+            network.borrow(),
+        ) {
+            Ok(data) => data,
+            Err(error) => {
+                panic!(
+                    "Comms w/ pubkey {} failed to initialize CommsData - {}",
+                    pubkey, error
+                );
+            }
+        };
+        if let Some(allowlist) = &comms_opts.peer_allowlist {
+            data.set_peer_allowlist(Some(allowlist.clone()));
+        }
+        if comms_opts.peer_pow > Self::MAX_PEER_POW {
+            warn!(
+                "Comms w/ pubkey {} capping peer message PoW of {} to {}",
+                pubkey,
+                comms_opts.peer_pow,
+                Self::MAX_PEER_POW
+            );
+        }
+        let relays = normalize_relay_addrs(data.relays());
 
         let actor = CommsActor {
             rx,
             trade_engine_name: trade_engine_name.as_ref().to_string(),
             network: network.borrow().to_owned(),
             pubkey,
+            backend,
             data,
             client,
             router: Router::new(),
+            trade_filters: HashMap::new(),
+            trade_encryption_keys: HashMap::new(),
+            trade_identity_keys: HashMap::new(),
+            order_watches: HashMap::new(),
+            order_propagation_watches: HashMap::new(),
+            counterparty_watches: HashMap::new(),
+            delegation_tag_values: None,
+            trade_engine_specifics_types: HashSet::new(),
+            trade_engine_specifics_validator: None,
+            default_order_trade_engine_specifics: None,
+            default_offer_trade_engine_specifics: None,
+            order_cache: OrderCache::new(),
+            event_cache: EventCache::new(comms_opts.event_cache_size),
+            profile_cache: ProfileCache::new(Self::PROFILE_CACHE_MAX_SIZE),
+            nip05_verification_tx: None,
+            relay_nips_check_tx: None,
+            mined_peer_message_tx: None,
+            hinted_send_tx: None,
+            temporary_hint_relays: HashSet::new(),
+            relay_parse_stats: HashMap::new(),
+            max_relays: comms_opts.max_relays,
+            min_peer_pow: comms_opts.min_peer_pow,
+            peer_pow: comms_opts.peer_pow.min(Self::MAX_PEER_POW),
+            hint_relay_nips: comms_opts.hint_relay_nips.clone(),
         };
         if let Some(error) = actor.add_relays_to_client(relays.clone()).await.err() {
             error!(
@@ -440,17 +1392,38 @@ impl CommsActor {
                 pubkey, relays, error
             );
         }
+
+        if comms_opts.connect_on_start && !relays.is_empty() {
+            debug!(
+                "Comms w/ pubkey {} connecting to {} restored relays on start",
+                pubkey,
+                relays.len()
+            );
+            actor.client.connect().await;
+        }
         actor
     }
 
     async fn run(mut self) {
         // Nostr client initializaiton
-        self.client
-            .subscribe(self.subscription_filters(self.pubkey))
-            .await;
+        self.resubscribe().await;
 
         let mut event_rx = self.client.notifications();
 
+        let (nip05_verification_tx, mut nip05_verification_rx) =
+            mpsc::channel::<Nip05Verification>(20);
+        self.nip05_verification_tx = Some(nip05_verification_tx);
+
+        let (relay_nips_check_tx, mut relay_nips_check_rx) = mpsc::channel::<RelayNipsCheck>(20);
+        self.relay_nips_check_tx = Some(relay_nips_check_tx);
+
+        let (mined_peer_message_tx, mut mined_peer_message_rx) =
+            mpsc::channel::<MinedPeerMessage>(20);
+        self.mined_peer_message_tx = Some(mined_peer_message_tx);
+
+        let (hinted_send_tx, mut hinted_send_rx) = mpsc::channel::<HintedSend>(20);
+        self.hinted_send_tx = Some(hinted_send_tx);
+
         // Request handling main event loop
         // !!! This function will end if no Sender remains for the Receiver
         loop {
@@ -466,6 +1439,18 @@ impl CommsActor {
                         Err(error) => error!("Comms event RX receive error - {}", error),
                     }
                 },
+                Some(verification) = nip05_verification_rx.recv() => {
+                    self.handle_nip05_verification(verification);
+                },
+                Some(relay_nips_check) = relay_nips_check_rx.recv() => {
+                    self.handle_relay_nips_check(relay_nips_check).await;
+                },
+                Some(mined_peer_message) = mined_peer_message_rx.recv() => {
+                    self.handle_mined_peer_message(mined_peer_message).await;
+                },
+                Some(hinted_send) = hinted_send_rx.recv() => {
+                    self.handle_hinted_send(hinted_send).await;
+                },
                 else => break,
             }
         }
@@ -480,13 +1465,21 @@ impl CommsActor {
 
         match request {
             CommsRequest::GetPublicKey { rsp_tx } => self.get_pubkey(rsp_tx).await,
+            CommsRequest::RotateIdentity {
+                new_secret_key,
+                rsp_tx,
+            } => self.rotate_identity(new_secret_key, rsp_tx).await,
 
             // Relays Management
             CommsRequest::AddRelays {
                 relay_addrs,
                 connect,
+                require_nips,
                 rsp_tx,
-            } => self.add_relays(relay_addrs, connect, rsp_tx).await,
+            } => {
+                self.add_relays(relay_addrs, connect, require_nips, rsp_tx)
+                    .await
+            }
 
             CommsRequest::RemoveRelay { relay_url, rsp_tx } => {
                 self.remove_relay(relay_url, rsp_tx).await
@@ -494,13 +1487,126 @@ impl CommsActor {
 
             CommsRequest::GetRelays { rsp_tx } => self.get_relays(rsp_tx).await,
 
+            CommsRequest::ExportRelayConfig { rsp_tx } => self.export_relay_config(rsp_tx),
+            CommsRequest::GetActiveFilters { rsp_tx } => self.get_active_filters(rsp_tx),
+
+            CommsRequest::IsConnected { rsp_tx } => self.is_connected(rsp_tx).await,
+
             CommsRequest::ConnectRelay { relay_url, rsp_tx } => {
                 self.connect_relay(relay_url, rsp_tx).await
             }
 
             CommsRequest::ConnectAllRelays { rsp_tx } => self.connect_all_relays(rsp_tx).await,
 
+            CommsRequest::SetPeerAllowlist { allowlist, rsp_tx } => {
+                self.data.set_peer_allowlist(allowlist);
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            // NIP-26 Delegation
+            CommsRequest::SetDelegationTag {
+                delegation_tag,
+                rsp_tx,
+            } => self.set_delegation_tag(delegation_tag, rsp_tx),
+
             // Change subscription filters
+            CommsRequest::RegisterTradeFilter {
+                trade_uuid,
+                filter,
+                rsp_tx,
+            } => self.register_trade_filter(trade_uuid, filter, rsp_tx).await,
+
+            CommsRequest::UnregisterTradeFilter { trade_uuid, rsp_tx } => {
+                self.unregister_trade_filter(trade_uuid, rsp_tx).await
+            }
+
+            CommsRequest::RegisterTradeEncryption {
+                trade_uuid,
+                secret_key,
+                peer_pubkey,
+                rsp_tx,
+            } => self.register_trade_encryption(trade_uuid, secret_key, peer_pubkey, rsp_tx),
+
+            CommsRequest::UnregisterTradeEncryption { trade_uuid, rsp_tx } => {
+                self.unregister_trade_encryption(trade_uuid, rsp_tx)
+            }
+
+            CommsRequest::WatchOrder {
+                pubkey,
+                trade_uuid,
+                tx,
+                rsp_tx,
+            } => self.watch_order(pubkey, trade_uuid, tx, rsp_tx).await,
+
+            CommsRequest::UnwatchOrder { trade_uuid, rsp_tx } => {
+                self.unwatch_order(trade_uuid, rsp_tx).await
+            }
+
+            CommsRequest::WatchOrderPropagation {
+                trade_uuid,
+                event_id,
+                tx,
+                rsp_tx,
+            } => {
+                self.watch_order_propagation(trade_uuid, event_id, tx, rsp_tx)
+                    .await
+            }
+
+            CommsRequest::UnwatchOrderPropagation { trade_uuid, rsp_tx } => {
+                self.unwatch_order_propagation(trade_uuid, rsp_tx).await
+            }
+
+            CommsRequest::WatchCounterparty {
+                trade_uuid,
+                pubkey,
+                tx,
+                rsp_tx,
+            } => {
+                let counterparty_watch = CounterpartyWatch { pubkey, tx };
+                self.counterparty_watches
+                    .insert(trade_uuid, counterparty_watch);
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            CommsRequest::UnwatchCounterparty { trade_uuid, rsp_tx } => {
+                self.counterparty_watches.remove(&trade_uuid);
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            // Trade Engine specifics
+            CommsRequest::RegisterTradeEngineSpecificsType { type_name, rsp_tx } => {
+                self.trade_engine_specifics_types.insert(type_name);
+                self.order_cache = OrderCache::new();
+                self.event_cache.clear();
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            CommsRequest::RegisterTradeEngineSpecificsValidator { validator, rsp_tx } => {
+                self.trade_engine_specifics_validator = validator;
+                self.order_cache = OrderCache::new();
+                self.event_cache.clear();
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            CommsRequest::RegisterDefaultTradeEngineSpecifics {
+                default_order_specifics,
+                default_offer_specifics,
+                rsp_tx,
+            } => {
+                self.default_order_trade_engine_specifics = default_order_specifics;
+                self.default_offer_trade_engine_specifics = default_offer_specifics;
+                self.order_cache = OrderCache::new();
+                self.event_cache.clear();
+                send_rsp(rsp_tx, Ok(()));
+            }
+
+            CommsRequest::ValidateTradeEngineSpecifics {
+                trade_engine_specifics,
+                rsp_tx,
+            } => {
+                let result = self.validate_trade_engine_specifics(trade_engine_specifics.as_ref());
+                send_rsp(rsp_tx, result);
+            }
 
             // Router management
             CommsRequest::RegisterTradeTx {
@@ -508,23 +1614,46 @@ impl CommsActor {
                 tx,
                 rsp_tx,
             } => {
+                let dm_catch_all_needed = self.dm_catch_all_needed();
                 let result = self.router.register_peer_message_tx(trade_uuid, tx);
-                rsp_tx.send(result).unwrap(); // oneshot should never fail
+                if self.dm_catch_all_needed() != dm_catch_all_needed {
+                    self.resubscribe().await;
+                }
+                send_rsp(rsp_tx, result);
             }
 
             CommsRequest::UnregisterTradeTx { trade_uuid, rsp_tx } => {
+                let dm_catch_all_needed = self.dm_catch_all_needed();
                 let result = self.router.unregister_peer_message_tx(trade_uuid);
-                rsp_tx.send(result).unwrap(); // oneshot should never fail
+                self.counterparty_watches.remove(&trade_uuid);
+                self.trade_encryption_keys.remove(&trade_uuid);
+                let retired_identity = self.trade_identity_keys.remove(&trade_uuid).is_some();
+                let trade_filter_removed = self.trade_filters.remove(&trade_uuid).is_some();
+                if trade_filter_removed
+                    || retired_identity
+                    || self.dm_catch_all_needed() != dm_catch_all_needed
+                {
+                    self.resubscribe().await;
+                }
+                send_rsp(rsp_tx, result);
             }
 
             CommsRequest::RegisterFallbackTx { tx, rsp_tx } => {
+                let dm_catch_all_needed = self.dm_catch_all_needed();
                 let result = self.router.register_peer_message_fallback_tx(tx);
-                rsp_tx.send(result).unwrap(); // oneshot should never fail
+                if self.dm_catch_all_needed() != dm_catch_all_needed {
+                    self.resubscribe().await;
+                }
+                send_rsp(rsp_tx, result);
             }
 
             CommsRequest::UnregisterFallbackTx { rsp_tx } => {
+                let dm_catch_all_needed = self.dm_catch_all_needed();
                 let result = self.router.unregister_peer_message_fallback_tx();
-                rsp_tx.send(result).unwrap(); // oneshot should never fail
+                if self.dm_catch_all_needed() != dm_catch_all_needed {
+                    self.resubscribe().await;
+                }
+                send_rsp(rsp_tx, result);
             }
 
             // Send Maker Order Notes
@@ -535,8 +1664,39 @@ impl CommsActor {
             // Query Order Notes
             CommsRequest::QueryOrders {
                 filter_tags,
+                query_opts,
+                rsp_tx,
+            } => self.query_orders(filter_tags, query_opts, rsp_tx).await,
+
+            CommsRequest::QueryOrdersPage {
+                filter_tags,
+                relay_url,
+                until,
+                limit,
+                rsp_tx,
+            } => {
+                self.query_orders_page(filter_tags, relay_url, until, limit, rsp_tx)
+                    .await
+            }
+
+            // Query Relay List
+            CommsRequest::QueryRelayList { pubkey, rsp_tx } => {
+                self.query_relay_list(pubkey, rsp_tx).await
+            }
+
+            // Query Peer Messages
+            CommsRequest::QueryPeerMessages {
+                trade_uuid,
+                since,
+                rsp_tx,
+            } => self.query_peer_messages(trade_uuid, since, rsp_tx).await,
+
+            // Get Maker Profile
+            CommsRequest::GetMakerProfile {
+                pubkey,
+                verify_nip05,
                 rsp_tx,
-            } => self.query_orders(filter_tags, rsp_tx).await,
+            } => self.get_maker_profile(pubkey, verify_nip05, rsp_tx).await,
 
             // Send Taker Offer Message
             CommsRequest::SendTakerOfferMessage {
@@ -565,6 +1725,7 @@ impl CommsActor {
                 maker_order_note_id,
                 trade_uuid,
                 trade_rsp,
+                relay_hints,
                 rsp_tx,
             } => {
                 self.send_trade_response(
@@ -573,6 +1734,7 @@ impl CommsActor {
                     maker_order_note_id,
                     trade_uuid,
                     trade_rsp,
+                    relay_hints,
                     rsp_tx,
                 )
                 .await;
@@ -585,6 +1747,9 @@ impl CommsActor {
                 maker_order_note_id,
                 trade_uuid,
                 message,
+                sequence,
+                message_id,
+                relay_hints,
                 rsp_tx,
             } => {
                 self.send_trade_engine_specific_message(
@@ -593,35 +1758,123 @@ impl CommsActor {
                     maker_order_note_id,
                     trade_uuid,
                     message,
+                    sequence,
+                    message_id,
+                    relay_hints,
                     rsp_tx,
                 )
                 .await;
             }
-            // Delete an Maker Order Note
-            CommsRequest::DeletMakerOrderNote {
-                event_id,
-                reason,
+            // Send Settlement Info
+            CommsRequest::SendSettlementInfo {
+                pubkey,
+                responding_to_id,
+                maker_order_note_id,
+                trade_uuid,
+                settlement_info,
+                relay_hints,
                 rsp_tx,
             } => {
-                self.delete_maker_order_note(event_id, reason, rsp_tx).await;
+                self.send_settlement_info(
+                    pubkey,
+                    responding_to_id,
+                    maker_order_note_id,
+                    trade_uuid,
+                    settlement_info,
+                    relay_hints,
+                    rsp_tx,
+                )
+                .await;
             }
-
-            // Shutdown
-            CommsRequest::Shutdown { rsp_tx } => {
-                self.shutdown(rsp_tx).await;
-                terminate = true;
+            // Send Dispute Request
+            CommsRequest::SendDisputeRequest {
+                pubkey,
+                responding_to_id,
+                maker_order_note_id,
+                trade_uuid,
+                dispute_request,
+                relay_hints,
+                rsp_tx,
+            } => {
+                self.send_dispute_request(
+                    pubkey,
+                    responding_to_id,
+                    maker_order_note_id,
+                    trade_uuid,
+                    dispute_request,
+                    relay_hints,
+                    rsp_tx,
+                )
+                .await;
+            }
+            // Send Trade Complete
+            CommsRequest::SendTradeComplete {
+                pubkey,
+                responding_to_id,
+                maker_order_note_id,
+                trade_uuid,
+                relay_hints,
+                rsp_tx,
+            } => {
+                self.send_trade_complete(
+                    pubkey,
+                    responding_to_id,
+                    maker_order_note_id,
+                    trade_uuid,
+                    relay_hints,
+                    rsp_tx,
+                )
+                .await;
             }
-        }
-        terminate
-    }
 
-    async fn handle_notification(&mut self, notification: RelayPoolNotification) {
+            // Send Offer Withdrawal
+            CommsRequest::SendOfferWithdrawal {
+                pubkey,
+                maker_order_note_id,
+                trade_uuid,
+                offer_event_id,
+                rsp_tx,
+            } => {
+                self.send_offer_withdrawal(
+                    pubkey,
+                    maker_order_note_id,
+                    trade_uuid,
+                    offer_event_id,
+                    rsp_tx,
+                )
+                .await;
+            }
+
+            // Delete an Maker Order Note
+            CommsRequest::DeletMakerOrderNote {
+                event_id,
+                reason,
+                rsp_tx,
+            } => {
+                self.delete_maker_order_note(event_id, reason, rsp_tx).await;
+            }
+
+            // Shutdown
+            CommsRequest::Shutdown { rsp_tx } => {
+                self.shutdown(rsp_tx).await;
+                terminate = true;
+            }
+        }
+        terminate
+    }
+
+    async fn handle_notification(&mut self, notification: RelayPoolNotification) {
         match notification {
             RelayPoolNotification::Event(url, event) => {
                 self.handle_notification_event(url::Url::from_str(url.as_str()).unwrap(), event)
                     .await;
             }
             RelayPoolNotification::Message(url, relay_message) => {
+                // Event notifications only come from the first relay delivering an Event
+                if let RelayMessage::Event { event, .. } = &relay_message {
+                    let relay_url = url::Url::from_str(url.as_str()).unwrap();
+                    self.handle_order_propagation(relay_url, event).await;
+                }
                 trace!(
                     "Comms w/ pubkey {} handle_notification(), dropping Relay Message {:?} from url {}",
                     self.pubkey,
@@ -656,386 +1909,2028 @@ impl CommsActor {
             );
             return;
         }
-        self.data.store_event_id(&event_id);
+        self.data.store_event_id(&event_id);
+
+        if let Kind::EncryptedDirectMessage = event.kind {
+            self.handle_direct_message(url, event).await;
+        } else if event.kind == Self::MAKER_ORDER_NOTE_KIND {
+            self.handle_watched_order_note(url, event).await;
+        } else if let Kind::EventDeletion = event.kind {
+            self.handle_watched_order_deletion(event).await;
+        } else {
+            debug!(
+                "Comms w/ pubkey {} handle_notification_event() Event kind Fallthrough",
+                self.pubkey
+            );
+        }
+    }
+
+    async fn handle_direct_message(&mut self, url: url::Url, event: Event) {
+        let Some(peer_message) = self.open_direct_message(&event, true).await else {
+            return;
+        };
+
+        if let Some(error) = self
+            .router
+            .handle_peer_message(
+                event.pubkey,
+                url,
+                event.id.into(),
+                event.created_at.as_u64(),
+                peer_message,
+            )
+            .await
+            .err()
+        {
+            error!(
+                "Comms w/ pubkey {} handle_direct_message() failed in router.handle_peer_message() - {}",
+                self.pubkey,
+                error
+            );
+        } else {
+            debug!(
+                "Comms w/ pubkey {} handle_direct_message() handled PeerMessage w/ EventID {}",
+                self.pubkey, event.id
+            );
+        }
+    }
+
+    // Decrypts a DM into a Peer Message, if it passes all the checks. None if it is to be dropped.
+    // Decrypt failures are only reported to the trade's watcher for DMs received live
+    async fn open_direct_message(
+        &self,
+        event: &Event,
+        report_decrypt_failure: bool,
+    ) -> Option<PeerMessage> {
+        if !self.data.peer_allowed(&event.pubkey) {
+            debug!(
+                "Comms w/ pubkey {} open_direct_message() dropping EventID {} from pubkey {} not in the allowlist",
+                self.pubkey, event.id, event.pubkey
+            );
+            return None;
+        }
+
+        let secret_key = self.client.keys().await.secret_key().unwrap();
+        let content = match decrypt(&secret_key, &event.pubkey, &event.content) {
+            Ok(content) => content,
+            Err(error) => match self.decrypt_with_trade_keys(event) {
+                Some(content) => content,
+                None if report_decrypt_failure => {
+                    self.report_decrypt_failure(event, error.to_string());
+                    return None;
+                }
+                None => {
+                    debug!(
+                        "Comms w/ pubkey {} open_direct_message() failed to decrypt replayed EventID {} - {}",
+                        self.pubkey, event.id, error
+                    );
+                    return None;
+                }
+            },
+        };
+
+        match event_id_leading_zero_bits(&event.id.to_string()) {
+            Ok(pow) if pow >= u64::from(self.min_peer_pow) => {}
+            Ok(pow) => {
+                debug!(
+                    "Comms w/ pubkey {} open_direct_message() dropping EventID {} w/ PoW of {}, less than the minimum of {}",
+                    self.pubkey, event.id, pow, self.min_peer_pow
+                );
+                return None;
+            }
+            Err(error) => {
+                warn!(
+                    "Comms w/ pubkey {} open_direct_message() ignoring EventID {} - {}",
+                    self.pubkey, event.id, error
+                );
+                return None;
+            }
+        }
+
+        // Check type & version first, so messages of an unsupported version are told apart from malformed ones
+        if let Some(error) = PeerMessage::check_header(content.as_str()).err() {
+            warn!(
+                "Comms w/ pubkey {} open_direct_message() ignoring EventID {} - {}",
+                self.pubkey, event.id, error
+            );
+            return None;
+        }
+
+        let mut peer_message = match Self::deserialize_with_default_specifics::<PeerMessage>(
+            content.as_str(),
+            PeerMessage::offer_specifics_pointer,
+            self.default_offer_trade_engine_specifics.as_deref(),
+        ) {
+            Ok((mut peer_message, defaulted)) => {
+                peer_message.trade_engine_specifics_defaulted = defaulted;
+                peer_message
+            }
+            Err(error) => {
+                error!(
+                    "Comms w/ pubkey {} open_direct_message() failed to deserialize content as PeerMessage - {}",
+                    self.pubkey,
+                    error
+                );
+                return None;
+            }
+        };
+
+        if let Some(error) = self
+            .check_peer_message_trade_engine_specifics(&peer_message)
+            .err()
+        {
+            warn!(
+                "Comms w/ pubkey {} open_direct_message() ignoring EventID {} - {}",
+                self.pubkey, event.id, error
+            );
+            return None;
+        }
+        Some(peer_message)
+    }
+
+    // Undecryptable DMs from anyone but an established counterparty are taken as spam
+    fn report_decrypt_failure(&self, event: &Event, reason: String) {
+        let mut counterparty = false;
+        for (trade_uuid, counterparty_watch) in self.counterparty_watches.iter() {
+            if counterparty_watch.pubkey != event.pubkey {
+                continue;
+            }
+            counterparty = true;
+            error!(
+                "Comms w/ pubkey {} open_direct_message() failed to decrypt EventID {} from counterparty of TradeUUID {} - {}",
+                self.pubkey, event.id, trade_uuid, reason
+            );
+            let error = N3xbError::CounterpartyDecryptFailed(event.id.into(), reason.clone());
+            if let Some(error) = counterparty_watch.tx.try_send(error).err() {
+                warn!(
+                    "Comms w/ pubkey {} report_decrypt_failure() failed to notify TradeUUID {} - {}",
+                    self.pubkey, trade_uuid, error
+                );
+            }
+        }
+
+        if !counterparty {
+            debug!(
+                "Comms w/ pubkey {} open_direct_message() failed to decrypt EventID {} from non-counterparty {} - {}",
+                self.pubkey, event.id, event.pubkey, reason
+            );
+        }
+    }
+
+    // There's no telling which trade a DM is for before decrypting, so each set of per-trade keys is
+    // tried. Only content that parses as a Peer Message of the same trade is taken as decrypted
+    fn decrypt_with_trade_keys(&self, event: &Event) -> Option<String> {
+        self.trade_encryption_keys
+            .iter()
+            .find_map(|(trade_uuid, encryption_keys)| {
+                let content = decrypt(
+                    &encryption_keys.secret_key,
+                    &encryption_keys.peer_pubkey,
+                    &event.content,
+                )
+                .ok()?;
+                let peer_message = serde_json::from_str::<PeerMessage>(&content).ok()?;
+                (peer_message.trade_uuid == *trade_uuid).then_some(content)
+            })
+    }
+
+    // Nostr Client Management
+
+    async fn get_pubkey(&self, rsp_tx: oneshot::Sender<XOnlyPublicKey>) {
+        send_rsp(rsp_tx, self.pubkey);
+    }
+
+    // Relays carry over to the new identity. Trades with per-trade keys keep signing with, and
+    // reading DMs to, the old identity, as their peers can't be told of the new pubkey
+    async fn rotate_identity(
+        &mut self,
+        new_secret_key: SecretKey,
+        rsp_tx: oneshot::Sender<Result<IdentityRotation, N3xbError>>,
+    ) {
+        let old_keys = self.client.keys().await;
+        let new_keys = Keys::new(new_secret_key);
+        let old_pubkey = self.pubkey;
+        let new_pubkey = new_keys.public_key();
+        if new_pubkey == old_pubkey {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} rotate_identity() to the same identity",
+                self.pubkey
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let new_data = match CommsData::new(
+            self.backend.clone(),
+            new_pubkey,
+            &self.trade_engine_name,
+            &self.network,
+        ) {
+            Ok(data) => data,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+        let relays = self
+            .data
+            .relays()
+            .into_iter()
+            .map(|(url, addr)| {
+                let capability = self.data.relay_capability(&url);
+                (url, addr, capability)
+            })
+            .collect();
+        new_data.add_relays(relays);
+        let old_data = std::mem::replace(&mut self.data, new_data);
+
+        // Deletions are only honored if signed by the same pubkey as the Maker Order Note
+        let undeletable_order_notes: Vec<EventIdString> = old_data
+            .order_note_ids()
+            .into_iter()
+            .filter_map(|event_id| EventIdString::from_str(&event_id).ok())
+            .collect();
+        if !undeletable_order_notes.is_empty() {
+            warn!(
+                "Comms rotating identity from pubkey {} can no longer delete Maker Order Notes {:?}",
+                old_pubkey, undeletable_order_notes
+            );
+        }
+        old_data.terminate();
+
+        let mut continuing_trades: Vec<Uuid> = Vec::new();
+        let mut disrupted_trades: Vec<Uuid> = Vec::new();
+        for trade_uuid in self.router.trade_uuids() {
+            if self.trade_encryption_keys.contains_key(&trade_uuid) {
+                self.trade_identity_keys
+                    .entry(trade_uuid)
+                    .or_insert_with(|| old_keys.clone());
+                continuing_trades.push(trade_uuid);
+            } else {
+                disrupted_trades.push(trade_uuid);
+            }
+        }
+
+        // Delegation was signed for the old pubkey, and can't vouch for the new one
+        self.delegation_tag_values = None;
+        self.client.set_keys(&new_keys).await;
+        self.pubkey = new_pubkey;
+        self.resubscribe().await;
+
+        info!(
+            "Comms rotated identity from pubkey {} to {}, {} trades continuing, {} disrupted",
+            old_pubkey,
+            new_pubkey,
+            continuing_trades.len(),
+            disrupted_trades.len()
+        );
+        let identity_rotation = IdentityRotation {
+            old_pubkey,
+            new_pubkey,
+            continuing_trades,
+            disrupted_trades,
+            undeletable_order_notes,
+        };
+        send_rsp(rsp_tx, Ok(identity_rotation));
+    }
+
+    async fn add_relays_to_client(
+        &self,
+        relays: Vec<(url::Url, Option<SocketAddr>)>,
+    ) -> Result<(), N3xbError> {
+        let into_relays: Vec<(String, Option<SocketAddr>)> = relays
+            .clone()
+            .into_iter()
+            .map(|(url, addr)| {
+                let url = url.into();
+                (url, addr)
+            })
+            .collect();
+        self.client.add_relays(into_relays).await?;
+        debug!("Comms w/ pubkey {} added relays {:?}", self.pubkey, relays);
+        Ok(())
+    }
+
+    // Fetches the NIP-11 document straight from the relay, before ever connecting to it
+    async fn missing_relay_nips(
+        pubkey: XOnlyPublicKey,
+        relay_url: url::Url,
+        proxy: Option<SocketAddr>,
+        require_nips: Vec<u16>,
+    ) -> Vec<u16> {
+        let result = tokio::time::timeout(
+            Self::NIP11_FETCH_TIMEOUT,
+            RelayInformationDocument::get(relay_url.clone(), proxy),
+        )
+        .await;
+        let document = match result {
+            Ok(Ok(document)) => document,
+            Ok(Err(error)) => {
+                warn!(
+                    "Comms w/ pubkey {} failed to fetch NIP-11 document of relay {} - {}",
+                    pubkey, relay_url, error
+                );
+                return require_nips;
+            }
+            Err(_) => {
+                warn!(
+                    "Comms w/ pubkey {} timed out fetching NIP-11 document of relay {}",
+                    pubkey, relay_url
+                );
+                return require_nips;
+            }
+        };
+
+        let supported_nips = document.supported_nips.unwrap_or_default();
+        require_nips
+            .iter()
+            .filter(|nip| !supported_nips.contains(nip))
+            .copied()
+            .collect()
+    }
+
+    async fn add_relays(
+        &self,
+        relay_addrs: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>,
+        connect: bool,
+        require_nips: Vec<u16>,
+        rsp_tx: oneshot::Sender<Result<Vec<SkippedRelay>, N3xbError>>,
+    ) {
+        let relay_caps: HashMap<url::Url, RelayCapability> = relay_addrs
+            .iter()
+            .map(|(url, _, capability)| (normalize_relay_url(url), *capability))
+            .collect();
+        let relay_addrs = normalize_relay_addrs(
+            relay_addrs
+                .into_iter()
+                .map(|(url, addr, _)| (url, addr))
+                .collect(),
+        );
+        let with_caps = |relay_addrs: Vec<(url::Url, Option<SocketAddr>)>| {
+            relay_addrs
+                .into_iter()
+                .map(|(url, addr)| {
+                    let capability = relay_caps.get(&url).copied().unwrap_or_default();
+                    (url, addr, capability)
+                })
+                .collect::<Vec<(url::Url, Option<SocketAddr>, RelayCapability)>>()
+        };
+
+        if relay_addrs.is_empty() {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} add_relays() called with no relays",
+                self.pubkey
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        if require_nips.is_empty() {
+            self.add_supporting_relays(with_caps(relay_addrs), connect, Vec::new(), rsp_tx)
+                .await;
+            return;
+        }
+
+        let Some(relay_nips_check_tx) = self.relay_nips_check_tx.clone() else {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} not running to check NIPs of relays",
+                self.pubkey
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        };
+
+        // NIP-11 documents are fetched over HTTP, all at once and off the actor loop
+        let own_pubkey = self.pubkey;
+        let relay_addrs = with_caps(relay_addrs);
+        tokio::spawn(async move {
+            let fetches: Vec<_> = relay_addrs
+                .iter()
+                .map(|(url, addr, _)| {
+                    tokio::spawn(Self::missing_relay_nips(
+                        own_pubkey,
+                        url.clone(),
+                        *addr,
+                        require_nips.clone(),
+                    ))
+                })
+                .collect();
+
+            let mut skipped_relays: Vec<SkippedRelay> = Vec::new();
+            let mut supporting_relay_addrs = Vec::new();
+            for ((url, addr, capability), fetch) in relay_addrs.into_iter().zip(fetches) {
+                let missing_nips = fetch.await.unwrap_or_else(|_| require_nips.clone());
+                if missing_nips.is_empty() {
+                    supporting_relay_addrs.push((url, addr, capability));
+                } else {
+                    warn!(
+                        "Comms w/ pubkey {} skipping relay {} missing required NIPs {:?}",
+                        own_pubkey, url, missing_nips
+                    );
+                    skipped_relays.push(SkippedRelay { url, missing_nips });
+                }
+            }
+
+            let relay_nips_check = RelayNipsCheck {
+                relay_addrs: supporting_relay_addrs,
+                connect,
+                skipped_relays,
+                rsp_tx,
+            };
+            if let Err(error) = relay_nips_check_tx.send(relay_nips_check).await {
+                warn!(
+                    "Comms w/ pubkey {} dropped relay NIPs check as the actor has terminated",
+                    own_pubkey
+                );
+                send_rsp(
+                    error.0.rsp_tx,
+                    Err(N3xbError::Simple("Comms terminated".to_string())),
+                );
+            }
+        });
+    }
+
+    // Relays added, and any hinted relay the client failed to let go of, as counted toward max_relays
+    async fn held_relay_urls(&self) -> HashSet<url::Url> {
+        let mut held_relays: HashSet<url::Url> = self
+            .data
+            .relays()
+            .into_iter()
+            .map(|(url, _)| normalize_relay_url(&url))
+            .collect();
+        held_relays.extend(
+            self.client
+                .relays()
+                .await
+                .into_keys()
+                .map(|url| normalize_relay_url(&url)),
+        );
+        held_relays.extend(self.temporary_hint_relays.iter().cloned());
+        held_relays
+    }
+
+    async fn handle_relay_nips_check(&self, relay_nips_check: RelayNipsCheck) {
+        let RelayNipsCheck {
+            relay_addrs,
+            connect,
+            skipped_relays,
+            rsp_tx,
+        } = relay_nips_check;
+        self.add_supporting_relays(relay_addrs, connect, skipped_relays, rsp_tx)
+            .await;
+    }
+
+    async fn add_supporting_relays(
+        &self,
+        relay_addrs: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>,
+        connect: bool,
+        skipped_relays: Vec<SkippedRelay>,
+        rsp_tx: oneshot::Sender<Result<Vec<SkippedRelay>, N3xbError>>,
+    ) {
+        if relay_addrs.is_empty() {
+            send_rsp(rsp_tx, Ok(skipped_relays));
+            return;
+        }
+        let relay_caps: HashMap<url::Url, RelayCapability> = relay_addrs
+            .iter()
+            .map(|(url, _, capability)| (url.clone(), *capability))
+            .collect();
+        let with_caps = |relay_addrs: Vec<(url::Url, Option<SocketAddr>)>| {
+            relay_addrs
+                .into_iter()
+                .map(|(url, addr)| {
+                    let capability = relay_caps.get(&url).copied().unwrap_or_default();
+                    (url, addr, capability)
+                })
+                .collect::<Vec<(url::Url, Option<SocketAddr>, RelayCapability)>>()
+        };
+        let relay_addrs: Vec<(url::Url, Option<SocketAddr>)> = relay_addrs
+            .into_iter()
+            .map(|(url, addr, _)| (url, addr))
+            .collect();
+
+        let held_relays = self.held_relay_urls().await;
+        let new_relay_count = relay_addrs
+            .iter()
+            .filter(|(url, _)| !held_relays.contains(url))
+            .count();
+        if held_relays.len() + new_relay_count > self.max_relays {
+            send_rsp(rsp_tx, Err(N3xbError::TooManyRelays(self.max_relays)));
+            return;
+        }
+
+        if let Some(error) = self.add_relays_to_client(relay_addrs.clone()).await.err() {
+            send_rsp(rsp_tx, Err(error.into()));
+            return;
+        }
+
+        if connect {
+            let mut relay_error_strings = HashMap::<url::Url, String>::new();
+
+            for relay_addr in relay_addrs.clone() {
+                let relay_url = relay_addr.0.clone();
+                let capability = relay_caps.get(&relay_url).copied().unwrap_or_default();
+                let relay = self.client.relay(relay_url.to_string()).await;
+
+                match relay {
+                    Ok(relay) => {
+                        if capability.can_read() {
+                            if let Some(error) = relay
+                                .subscribe(self.subscription_filters(self.pubkey), None)
+                                .await
+                                .err()
+                            {
+                                relay_error_strings.insert(relay_url.clone(), error.to_string());
+                                continue;
+                            }
+                        }
+                        relay.connect(true).await;
+
+                        let relay_status = relay.status().await;
+                        match relay_status {
+                            RelayStatus::Connected => {
+                                self.data.add_relays(with_caps(vec![relay_addr]));
+                            }
+                            _ => {
+                                relay_error_strings
+                                    .insert(relay_url.clone(), relay_status.to_string());
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        relay_error_strings.insert(relay_url.clone(), error.to_string());
+                    }
+                }
+
+                if relay_error_strings.contains_key(&relay_url) {
+                    if let Some(error) = self.client.remove_relay(relay_url.as_str()).await.err() {
+                        error!(
+                            "Comms w/ pubkey {} failed to remove relay {} - {}",
+                            self.pubkey, relay_url, error
+                        )
+                    }
+                }
+            }
+
+            let relay_errors_string = relay_error_strings
+                .iter()
+                .map(|(url, error)| format!("{} - {}", url.to_string(), error.to_string()))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            if relay_error_strings.len() == relay_addrs.len() {
+                let error = N3xbError::Simple(format!(
+                    "Comms w/ pubkey {} failed to connect to all relays - {}",
+                    self.pubkey, relay_errors_string
+                ));
+                send_rsp(rsp_tx, Err(error));
+            } else if relay_error_strings.is_empty() {
+                send_rsp(rsp_tx, Ok(skipped_relays));
+            } else {
+                let error = N3xbError::Simple(format!(
+                    "Comms w/ pubkey {} failed to connect to relays: {}",
+                    self.pubkey, relay_errors_string
+                ));
+                send_rsp(rsp_tx, Err(error));
+            }
+        } else {
+            self.data.add_relays(with_caps(relay_addrs));
+
+            send_rsp(rsp_tx, Ok(skipped_relays));
+        }
+    }
+
+    async fn disconnect_remove_relay(&mut self, relay_url: url::Url) -> Result<(), N3xbError> {
+        let relay_url = normalize_relay_url(&relay_url);
+        self.data.remove_relay(&relay_url);
+
+        let relay_string: String = relay_url.clone().into();
+        self.client.disconnect_relay(relay_string.clone()).await?;
+        self.client.remove_relay(relay_string).await?;
+        Ok(())
+    }
+
+    async fn remove_relay(
+        &mut self,
+        relay_url: url::Url,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        match self.disconnect_remove_relay(relay_url).await {
+            Ok(_) => {
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(error) => send_rsp(rsp_tx, Err(error.into())),
+        };
+    }
+
+    fn get_active_filters(&self, rsp_tx: oneshot::Sender<Vec<String>>) {
+        let filters = self
+            .subscription_filters(self.pubkey)
+            .iter()
+            .filter_map(|filter| match serde_json::to_string(filter) {
+                Ok(json) => Some(json),
+                Err(error) => {
+                    warn!(
+                        "Comms w/ pubkey {} failed to serialize filter {:?} - {}",
+                        self.pubkey, filter, error
+                    );
+                    None
+                }
+            })
+            .collect();
+        send_rsp(rsp_tx, filters);
+    }
+
+    async fn is_connected(&self, rsp_tx: oneshot::Sender<bool>) {
+        let mut connected = false;
+        for relay in self.client.relays().await.values() {
+            if relay.status().await == RelayStatus::Connected {
+                connected = true;
+                break;
+            }
+        }
+        send_rsp(rsp_tx, connected);
+    }
+
+    async fn get_relays(&self, rsp_tx: oneshot::Sender<Vec<RelayInfo>>) {
+        let relays = self.client.relays().await;
+        let mut relays_info = Vec::<RelayInfo>::new();
+
+        for (url, relay) in relays {
+            let url = normalize_relay_url(&url::Url::from_str(url.as_str()).unwrap());
+            let status = relay.status().await;
+            let capability = self.data.relay_capability(&url);
+            let document = relay.document().await;
+            let mut parse_stats = self
+                .relay_parse_stats
+                .get(&url)
+                .copied()
+                .unwrap_or_default();
+            parse_stats.decay(Instant::now());
+
+            relays_info.push(RelayInfo {
+                url,
+                status,
+                capability,
+                document,
+                parse_stats,
+            });
+        }
+        send_rsp(rsp_tx, relays_info);
+    }
+
+    fn export_relay_config(&self, rsp_tx: oneshot::Sender<RelayConfig>) {
+        let mut relays: Vec<RelayConfigEntry> = self
+            .data
+            .relays()
+            .into_iter()
+            .map(|(url, proxy)| {
+                let capability = self.data.relay_capability(&url);
+                RelayConfigEntry {
+                    url,
+                    proxy,
+                    capability,
+                }
+            })
+            .collect();
+        relays.sort_by(|a, b| a.url.cmp(&b.url));
+        send_rsp(rsp_tx, RelayConfig { relays });
+    }
+
+    async fn connect_relay(
+        &self,
+        relay_url: url::Url,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        let relay_string = normalize_relay_url(&relay_url).to_string();
+        let result = self.subscribe_connect_relay(relay_string).await;
+        match result {
+            Ok(_) => send_rsp(rsp_tx, Ok(())),
+            Err(error) => send_rsp(rsp_tx, Err(error)),
+        };
+    }
+
+    async fn subscribe_connect_relay(&self, relay_string: String) -> Result<(), N3xbError> {
+        // Relays added without connecting never had the DM subscription applied
+        let relay = self.client.relay(relay_string.clone()).await?;
+        if self.relay_url_capability(&relay_string).can_read() {
+            if let Some(error) = relay
+                .subscribe(self.subscription_filters(self.pubkey), None)
+                .await
+                .err()
+            {
+                return Err(N3xbError::Simple(format!(
+                    "Comms w/ pubkey {} failed to subscribe relay {} - {}",
+                    self.pubkey, relay_string, error
+                )));
+            }
+        }
+        self.client.connect_relay(relay_string).await?;
+        Ok(())
+    }
+
+    async fn connect_all_relays(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        // (Re)apply subscription filters so DMs also arrive on relays added mid-session
+        self.resubscribe().await;
+        self.client.connect().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // Trade filters narrow down DMs only for the trades registering one. Makers still need all DMs
+    // to own pubkey to receive Offers from not yet known Takers, as does any fallback registrant
+    fn dm_catch_all_needed(&self) -> bool {
+        self.trade_filters.is_empty()
+            || self.router.has_peer_message_fallback_tx()
+            || self
+                .router
+                .trade_uuids()
+                .iter()
+                .any(|trade_uuid| !self.trade_filters.contains_key(trade_uuid))
+    }
+
+    fn subscription_filters(&self, pubkey: XOnlyPublicKey) -> Vec<Filter> {
+        // Subscribe to all DM to own pubkey only while needed. Filter unrecognized DM out some
+        // other way. Can be spam prone
+        let mut filters = Vec::new();
+        if self.dm_catch_all_needed() {
+            filters.push(Filter::new().pubkey(pubkey));
+        }
+
+        // Trade specific filters registered against their TradeUUIDs, removed as trades end
+        filters.extend(self.trade_filters.values().cloned());
+
+        // DMs to identities rotated away from, for the trades still on them
+        let retired_pubkeys: HashSet<XOnlyPublicKey> = self
+            .trade_identity_keys
+            .values()
+            .map(|keys| keys.public_key())
+            .collect();
+        for retired_pubkey in retired_pubkeys {
+            filters.push(
+                Filter::new()
+                    .pubkey(retired_pubkey)
+                    .kind(Kind::EncryptedDirectMessage),
+            );
+        }
+
+        // Own Maker Order Notes, to see which relays they made it to
+        for trade_uuid in self.order_propagation_watches.keys() {
+            let uuid_tag = OrderTag::TradeUUID(trade_uuid.to_owned());
+            filters.push(
+                Filter::new()
+                    .author(pubkey)
+                    .kind(Self::MAKER_ORDER_NOTE_KIND)
+                    .custom_tag(
+                        Alphabet::try_from(uuid_tag.key()).unwrap(),
+                        [trade_uuid.to_string()].to_vec(),
+                    ),
+            );
+        }
+
+        // Republished and deleted Maker Order Notes of watched Orders
+        for (trade_uuid, order_watch) in &self.order_watches {
+            let uuid_tag = OrderTag::TradeUUID(trade_uuid.to_owned());
+            filters.push(
+                Filter::new()
+                    .author(order_watch.pubkey)
+                    .kind(Self::MAKER_ORDER_NOTE_KIND)
+                    .custom_tag(
+                        Alphabet::try_from(uuid_tag.key()).unwrap(),
+                        [trade_uuid.to_string()].to_vec(),
+                    ),
+            );
+            // Deletions can't predate the Note. Till one is seen, only those since the watch started
+            let deletions_since = if order_watch.created_at > 0 {
+                order_watch.created_at.min(order_watch.since)
+            } else {
+                order_watch.since
+            };
+            filters.push(
+                Filter::new()
+                    .author(order_watch.pubkey)
+                    .kind(Kind::EventDeletion)
+                    .since(Timestamp::from(deletions_since)),
+            );
+        }
+        filters
+    }
+
+    // Trade Engine specifics
+
+    fn check_trade_engine_specifics(
+        &self,
+        trade_engine_specifics: &dyn SerdeGenericTrait,
+    ) -> Result<(), N3xbError> {
+        if self.trade_engine_specifics_types.is_empty() || trade_engine_specifics.is_placeholder() {
+            return Ok(());
+        }
+
+        let type_name = trade_engine_specifics.type_name()?;
+        if self.trade_engine_specifics_types.contains(&type_name) {
+            Ok(())
+        } else {
+            Err(N3xbError::UnregisteredTradeEngineSpecifics(type_name))
+        }
+    }
+
+    fn validate_trade_engine_specifics(
+        &self,
+        trade_engine_specifics: &dyn SerdeGenericTrait,
+    ) -> Result<(), N3xbError> {
+        let Some(validator) = &self.trade_engine_specifics_validator else {
+            return Ok(());
+        };
+        if trade_engine_specifics.is_placeholder() {
+            return Ok(());
+        }
+        validator(trade_engine_specifics).map_err(N3xbError::InvalidTradeEngineSpecifics)
+    }
+
+    // Content that doesn't deserialize as is gets retried with the Trade Engine specifics at the
+    // JSON pointer replaced by the default, or added if absent. True if the default was used
+    fn deserialize_with_default_specifics<T: DeserializeOwned>(
+        content: &str,
+        specifics_pointer: fn(&serde_json::Value) -> Option<&'static str>,
+        default_specifics: Option<&dyn SerdeGenericTrait>,
+    ) -> Result<(T, bool), N3xbError> {
+        let error = match serde_json::from_str::<T>(content) {
+            Ok(deserialized) => return Ok((deserialized, false)),
+            Err(error) => error,
+        };
+        let Some(default_specifics) = default_specifics else {
+            return Err(error.into());
+        };
+        let Ok(mut value) = serde_json::from_str::<serde_json::Value>(content) else {
+            return Err(error.into());
+        };
+        let Some(pointer) = specifics_pointer(&value) else {
+            return Err(error.into());
+        };
+        let Some((parent_pointer, key)) = pointer.rsplit_once('/') else {
+            return Err(error.into());
+        };
+        let Some(parent) = value
+            .pointer_mut(parent_pointer)
+            .and_then(|parent| parent.as_object_mut())
+        else {
+            return Err(error.into());
+        };
+        parent.insert(key.to_string(), serde_json::to_value(default_specifics)?);
+
+        // Anything else malformed still fails the same as before
+        match serde_json::from_value::<T>(value) {
+            Ok(deserialized) => Ok((deserialized, true)),
+            Err(_) => Err(error.into()),
+        }
+    }
+
+    fn check_peer_message_trade_engine_specifics(
+        &self,
+        peer_message: &PeerMessage,
+    ) -> Result<(), N3xbError> {
+        match peer_message.message_type {
+            SerdeGenericType::TakerOffer => match peer_message.message.downcast_ref::<Offer>() {
+                Some(offer) => {
+                    self.check_trade_engine_specifics(offer.trade_engine_specifics.as_ref())
+                }
+                None => Ok(()), // Left for the Maker to report
+            },
+            SerdeGenericType::TradeResponse => {
+                match peer_message.message.downcast_ref::<TradeResponse>() {
+                    Some(trade_rsp) => {
+                        self.check_trade_engine_specifics(trade_rsp.trade_engine_specifics.as_ref())
+                    }
+                    None => Ok(()), // Left for the Taker to report
+                }
+            }
+            SerdeGenericType::TradeEngineSpecific => {
+                self.check_trade_engine_specifics(peer_message.message.as_ref())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // NIP-26 Delegation
+
+    fn set_delegation_tag(
+        &mut self,
+        delegation_tag: Option<String>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        let Some(delegation_tag) = delegation_tag else {
+            self.delegation_tag_values = None;
+            send_rsp(rsp_tx, Ok(()));
+            return;
+        };
+
+        // Delegation must be for own pubkey, and must currently allow for Maker Order Notes
+        let event_properties = EventProperties::new(
+            Self::MAKER_ORDER_NOTE_KIND.as_u64(),
+            Timestamp::now().as_u64(),
+        );
+        let result = DelegationTag::from_str(&delegation_tag)
+            .map_err(|error| N3xbError::Simple(error.to_string()))
+            .and_then(|tag| {
+                tag.validate(self.pubkey, &event_properties)
+                    .map_err(|error| N3xbError::Simple(error.to_string()))
+            })
+            .and_then(|_| {
+                serde_json::from_str::<Vec<String>>(&delegation_tag).map_err(N3xbError::from)
+            });
+
+        match result {
+            Ok(mut tag_vec) => {
+                tag_vec.remove(0);
+                self.delegation_tag_values = Some(tag_vec);
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(error) => {
+                warn!(
+                    "Comms w/ pubkey {} set_delegation_tag() with invalid delegation tag - {}",
+                    self.pubkey, error
+                );
+                send_rsp(rsp_tx, Err(error));
+            }
+        }
+    }
+
+    fn relay_url_capability(&self, relay_string: impl AsRef<str>) -> RelayCapability {
+        match url::Url::parse(relay_string.as_ref()) {
+            Ok(url) => self.data.relay_capability(&normalize_relay_url(&url)),
+            Err(_) => RelayCapability::default(),
+        }
+    }
+
+    async fn resubscribe(&self) {
+        // Write-only relays are never subscribed to
+        let filters = self.subscription_filters(self.pubkey);
+        for (url, relay) in self.client.relays().await {
+            if !self.relay_url_capability(url.as_str()).can_read() {
+                continue;
+            }
+            if let Some(error) = relay.subscribe(filters.clone(), None).await.err() {
+                warn!(
+                    "Comms w/ pubkey {} failed to subscribe relay {} - {}",
+                    self.pubkey, url, error
+                );
+            }
+        }
+    }
+
+    async fn register_trade_filter(
+        &mut self,
+        trade_uuid: Uuid,
+        filter: Filter,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        // Trade filters are always narrowed to DMs destined for own pubkey. Re-registering, such as
+        // by a restored Taker, replaces the filter in place
+        let filter = filter.pubkey(self.pubkey);
+        if self.trade_filters.insert(trade_uuid, filter).is_some() {
+            debug!(
+                "Comms w/ pubkey {} register_trade_filter() for TradeUUID {} replaced existing filter",
+                self.pubkey, trade_uuid
+            );
+        }
+        self.resubscribe().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn unregister_trade_filter(
+        &mut self,
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if self.trade_filters.remove(&trade_uuid).is_none() {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} unregister_trade_filter() for TradeUUID {} expected to already be registered",
+                self.pubkey, trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+        self.resubscribe().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // Per-trade Encryption
+
+    fn register_trade_encryption(
+        &mut self,
+        trade_uuid: Uuid,
+        secret_key: SecretKey,
+        peer_pubkey: XOnlyPublicKey,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        debug!(
+            "Comms w/ pubkey {} encrypting peer messages of TradeUUID {} to per-trade pubkey {}",
+            self.pubkey, trade_uuid, peer_pubkey
+        );
+        let encryption_keys = TradeEncryptionKeys {
+            secret_key,
+            peer_pubkey,
+        };
+        self.trade_encryption_keys
+            .insert(trade_uuid, encryption_keys);
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    fn unregister_trade_encryption(
+        &mut self,
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if self.trade_encryption_keys.remove(&trade_uuid).is_some() {
+            debug!(
+                "Comms w/ pubkey {} dropped per-trade encryption keys of TradeUUID {}",
+                self.pubkey, trade_uuid
+            );
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // Watch Maker Order Notes
+
+    async fn watch_order(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        trade_uuid: Uuid,
+        tx: mpsc::Sender<OrderUpdate>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if self.order_watches.contains_key(&trade_uuid) {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} watch_order() for TradeUUID {} already watched",
+                self.pubkey, trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let order_watch = OrderWatch {
+            pubkey,
+            event_id: None,
+            created_at: 0,
+            since: Timestamp::now().as_u64(),
+            tx,
+        };
+        self.order_watches.insert(trade_uuid, order_watch);
+        self.resubscribe().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn unwatch_order(
+        &mut self,
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if self.order_watches.remove(&trade_uuid).is_none() {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} unwatch_order() for TradeUUID {} expected to already be watched",
+                self.pubkey, trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+        self.resubscribe().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn handle_watched_order_note(&mut self, url: url::Url, event: Event) {
+        let pubkey = event.pubkey;
+        let event_id = EventIdString::from(event.id);
+        let created_at = event.created_at.as_u64();
+
+        let order_envelope = match self
+            .extract_order_envelope_from_event(event, HashSet::from([url]))
+            .await
+        {
+            Ok(order_envelope) => order_envelope,
+            Err(error) => {
+                debug!(
+                    "Comms w/ pubkey {} handle_watched_order_note() failed to extract Order from EventID {} - {}",
+                    self.pubkey, event_id, error
+                );
+                return;
+            }
+        };
+
+        let trade_uuid = order_envelope.order.trade_uuid;
+        let Some(order_watch) = self.order_watches.get_mut(&trade_uuid) else {
+            return;
+        };
+        if order_watch.pubkey != pubkey || created_at < order_watch.created_at {
+            return;
+        }
+        let first_seen = order_watch.event_id.is_none();
+        order_watch.event_id = Some(event_id);
+        order_watch.created_at = created_at;
+
+        // Don't block the Comms actor on a watcher busy with something else
+        let mut unwatch = false;
+        if created_at >= order_watch.since {
+            match order_watch
+                .tx
+                .try_send(OrderUpdate::Updated(order_envelope))
+            {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!(
+                        "Comms w/ pubkey {} handle_watched_order_note() dropped update to busy watcher of TradeUUID {}",
+                        self.pubkey, trade_uuid
+                    );
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    warn!(
+                        "Comms w/ pubkey {} handle_watched_order_note() watcher of TradeUUID {} closed, unwatching",
+                        self.pubkey, trade_uuid
+                    );
+                    unwatch = true;
+                }
+            }
+        }
+
+        if unwatch {
+            self.order_watches.remove(&trade_uuid);
+        }
+        // Deletions are watched for back to the first Note seen. See subscription_filters()
+        if unwatch || first_seen {
+            self.resubscribe().await;
+        }
+    }
+
+    async fn handle_watched_order_deletion(&mut self, event: Event) {
+        let deleted_event_ids: HashSet<String> = event
+            .tags
+            .iter()
+            .map(|tag| tag.as_vec())
+            .filter(|tag_vec| tag_vec.first().map(|key| key.as_str()) == Some("e"))
+            .filter_map(|tag_vec| tag_vec.get(1).cloned())
+            .collect();
+
+        let deleted_trade_uuids: Vec<Uuid> = self
+            .order_watches
+            .iter()
+            .filter(|(_, order_watch)| {
+                order_watch.pubkey == event.pubkey
+                    && order_watch.event_id.as_ref().map_or(false, |event_id| {
+                        deleted_event_ids.contains(event_id.as_str())
+                    })
+            })
+            .map(|(trade_uuid, _)| trade_uuid.to_owned())
+            .collect();
+
+        if deleted_trade_uuids.is_empty() {
+            return;
+        }
+
+        for trade_uuid in deleted_trade_uuids {
+            let Some(order_watch) = self.order_watches.remove(&trade_uuid) else {
+                continue;
+            };
+            if let Some(error) = order_watch.tx.try_send(OrderUpdate::Deleted).err() {
+                warn!(
+                    "Comms w/ pubkey {} handle_watched_order_deletion() failed to notify watcher of TradeUUID {} - {}",
+                    self.pubkey, trade_uuid, error
+                );
+            }
+        }
+        self.resubscribe().await;
+    }
+
+    // Watch Own Maker Order Note Propagation
+
+    async fn watch_order_propagation(
+        &mut self,
+        trade_uuid: Uuid,
+        event_id: EventIdString,
+        tx: mpsc::Sender<url::Url>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        let order_propagation_watch = OrderPropagationWatch {
+            event_id,
+            seen_on: HashSet::new(),
+            tx,
+        };
+        let replaced = self
+            .order_propagation_watches
+            .insert(trade_uuid, order_propagation_watch);
+
+        // Same filter regardless of the Event ID, so only a new watch needs the filters updated
+        if replaced.is_none() {
+            self.resubscribe().await;
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // Not watched is not an error, so Makers can unwatch whenever their Order Note goes down
+    async fn unwatch_order_propagation(
+        &mut self,
+        trade_uuid: Uuid,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if self.order_propagation_watches.remove(&trade_uuid).is_some() {
+            self.resubscribe().await;
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn handle_order_propagation(&mut self, url: url::Url, event: &Event) {
+        if event.pubkey != self.pubkey || event.kind != Self::MAKER_ORDER_NOTE_KIND {
+            return;
+        }
+        let event_id = EventIdString::from(event.id);
+        let url = normalize_relay_url(&url);
+
+        let mut closed_trade_uuids: Vec<Uuid> = Vec::new();
+        for (trade_uuid, order_propagation_watch) in self.order_propagation_watches.iter_mut() {
+            if order_propagation_watch.event_id != event_id
+                || !order_propagation_watch.seen_on.insert(url.clone())
+            {
+                continue;
+            }
+            // Don't block the Comms actor on a Maker busy with something else
+            match order_propagation_watch.tx.try_send(url.clone()) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!(
+                        "Comms w/ pubkey {} handle_order_propagation() dropped notification of relay {} to busy watcher of TradeUUID {}",
+                        self.pubkey, url, trade_uuid
+                    );
+                    // Notified again if seen on the relay again
+                    order_propagation_watch.seen_on.remove(&url);
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    warn!(
+                        "Comms w/ pubkey {} handle_order_propagation() watcher of TradeUUID {} closed, unwatching",
+                        self.pubkey, trade_uuid
+                    );
+                    closed_trade_uuids.push(trade_uuid.to_owned());
+                }
+            }
+        }
+
+        if closed_trade_uuids.is_empty() {
+            return;
+        }
+        for trade_uuid in closed_trade_uuids {
+            self.order_propagation_watches.remove(&trade_uuid);
+        }
+        self.resubscribe().await;
+    }
+
+    // Send Maker Order Note
+
+    async fn send_maker_order_note(
+        &self,
+        order: Order,
+        rsp_tx: oneshot::Sender<Result<OrderEnvelope, N3xbError>>,
+    ) {
+        // Create Note Content
+        let maker_order_note = MakerOrderNote {
+            maker_obligation: order.maker_obligation.content.clone(),
+            taker_obligation: order.taker_obligation.content.clone(),
+            trade_details: order.trade_details.content.clone(),
+            trade_engine_specifics: order.trade_engine_specifics.clone(),
+            pow_difficulty: order.pow_difficulty.clone(),
+        };
+
+        let content_string = match serde_json::to_string(&maker_order_note) {
+            Ok(string) => string,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error.into()));
+                return;
+            }
+        };
+
+        let order_tags = OrderTag::from_order(order.clone(), &self.trade_engine_name);
+        let expiration = order.expiration_from(Timestamp::now().as_u64());
+
+        let mut tags = Self::create_event_tags(order_tags, expiration);
+        if let Some(delegation_tag_values) = &self.delegation_tag_values {
+            tags.push(Tag::Generic(
+                TagKind::Custom(MakerOrderNote::DELEGATION_TAG_KEY.to_string()),
+                delegation_tag_values.to_owned(),
+            ));
+        }
+
+        let builder = EventBuilder::new(Self::MAKER_ORDER_NOTE_KIND, content_string, &tags);
+
+        let keys = self.client.keys().await;
+
+        let event = match builder.to_event(&keys) {
+            Ok(event) => event,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(N3xbError::Simple(error.to_string())));
+                return;
+            }
+        };
+
+        let created_at = event.created_at.as_u64();
+        let result = self.send_event_to_each_relay(event.clone()).await;
+
+        match result {
+            Ok((event_id, urls)) => {
+                let delegator_pubkey = self
+                    .delegation_tag_values
+                    .as_ref()
+                    .and_then(|values| values.first())
+                    .and_then(|delegator| XOnlyPublicKey::from_str(delegator).ok());
+
+                self.data.insert_order_note_id(event_id.to_string());
+                let order_envelope = OrderEnvelope {
+                    pubkey: keys.public_key(),
+                    event_id: event_id.into(),
+                    urls,
+                    order,
+                    delegator_pubkey,
+                    created_at,
+                    expiration,
+                    signed_event: Some(event),
+                    trade_engine_specifics_defaulted: false,
+                    _private: (),
+                };
+                send_rsp(rsp_tx, Ok(order_envelope));
+            }
+            Err(error) => send_rsp(rsp_tx, Err(error)),
+        }
+    }
+
+    // Sends to writable relays one at a time so failures can be told apart. Only a relay replying
+    // to the event with an OK false has rejected it. Any other failure is a send failure, or the
+    // relay being unreachable if it wasn't connected to begin with
+    async fn send_event_to_each_relay(
+        &self,
+        event: Event,
+    ) -> Result<(EventId, HashSet<Url>), N3xbError> {
+        let mut accepted_urls = HashSet::<Url>::new();
+        let mut send_failures = EventSendFailures::default();
+
+        for (url, relay) in self.client.relays().await {
+            if !self.relay_url_capability(url.as_str()).can_write() {
+                continue;
+            }
+            let relay_connected = relay.status().await == RelayStatus::Connected;
+
+            match self.client.send_event_to(url.clone(), event.clone()).await {
+                Ok(_) => {
+                    accepted_urls.insert(url::Url::parse(url.as_str()).unwrap());
+                }
+                Err(error) => {
+                    warn!(
+                        "Comms w/ pubkey {} failed to send Event {} to relay {} - {}",
+                        self.pubkey,
+                        event.id.to_string(),
+                        url,
+                        error
+                    );
+                    let relay_error = format!("{} - {}", url, error);
+                    if Self::relay_rejected_event(&error) {
+                        send_failures.rejected.push(relay_error);
+                    } else if relay_connected {
+                        send_failures.failed.push(relay_error);
+                    } else {
+                        send_failures.unreachable.push(relay_error);
+                    }
+                }
+            }
+        }
+
+        if !accepted_urls.is_empty() {
+            Ok((event.id, accepted_urls))
+        } else {
+            Err(send_failures.into_error())
+        }
+    }
+
+    // nostr-sdk surfaces a relay's OK false reply to an event as it not being published
+    fn relay_rejected_event(error: &nostr_sdk::client::Error) -> bool {
+        error.to_string().contains("event not published")
+    }
+
+    fn create_event_tags(tags: Vec<OrderTag>, expiration: Option<u64>) -> Vec<Tag> {
+        // Relays only index the first value of a tag, so each prefix of a geohash gets its own tag
+        let tags: Vec<OrderTag> = tags
+            .into_iter()
+            .flat_map(|order_tag| match order_tag {
+                OrderTag::Geohash(geohash) => geohash_prefixes(&geohash)
+                    .into_iter()
+                    .map(OrderTag::Geohash)
+                    .collect(),
+                order_tag => vec![order_tag],
+            })
+            .collect();
+
+        let mut event_tags: Vec<Tag> = tags
+            .iter()
+            .map(|event_tag| match event_tag {
+                OrderTag::TradeUUID(trade_uuid) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![trade_uuid.to_string()],
+                ),
+                OrderTag::MakerObligations(obligation_kinds) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    obligation_kinds
+                        .to_owned()
+                        .iter()
+                        .flat_map(|kind| kind.to_tag_strings())
+                        .collect(),
+                ),
+                OrderTag::TakerObligations(obligations_kinds) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    obligations_kinds
+                        .to_owned()
+                        .iter()
+                        .flat_map(|kind| kind.to_tag_strings())
+                        .collect(),
+                ),
+                OrderTag::TradeDetailParameters(parameters) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    TradeDetails::parameters_to_tags(parameters.clone())
+                        .into_iter()
+                        .collect(),
+                ),
+                OrderTag::TradeEngineName(name) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![name.to_owned()],
+                ),
+                OrderTag::EventKind(kind) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![kind.to_string()],
+                ),
+                OrderTag::ApplicationTag(app_tag) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![app_tag.to_owned()],
+                ),
+                OrderTag::CustomTags(custom_tags) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    custom_tags_to_strings(custom_tags),
+                ),
+                OrderTag::TradeDirection(direction) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![direction.to_string()],
+                ),
+                OrderTag::Geohash(geohash_prefix) => Tag::Generic(
+                    TagKind::Custom(event_tag.key().to_string()),
+                    vec![geohash_prefix.to_owned()],
+                ),
+            })
+            .collect();
+
+        // NIP-40 Expiration
+        if let Some(expiration) = expiration {
+            event_tags.push(Tag::Generic(
+                TagKind::Custom(MakerOrderNote::EXPIRATION_TAG_KEY.to_string()),
+                vec![expiration.to_string()],
+            ));
+        }
+        event_tags
+    }
+
+    // Query Order Notes
+
+    async fn query_orders(
+        &mut self,
+        filter_tags: Vec<FilterTag>,
+        query_opts: QueryOpts,
+        rsp_tx: oneshot::Sender<Result<Vec<OrderEnvelope>, N3xbError>>,
+    ) {
+        let (mut filter, custom_tags) = self.order_query_filter(filter_tags);
+        if let Some(since) = query_opts.since {
+            filter = filter.since(Timestamp::from(since));
+        }
+        if let Some(until) = query_opts.until {
+            filter = filter.until(Timestamp::from(until));
+        }
+        if let Some(limit) = query_opts.limit {
+            filter = filter.limit(limit);
+        }
 
-        if let Kind::EncryptedDirectMessage = event.kind {
-            self.handle_direct_message(url, event).await;
-        } else {
-            debug!(
-                "Comms w/ pubkey {} handle_notification_event() Event kind Fallthrough",
-                self.pubkey
-            );
+        // Custom Tags aren't part of the filter, and are keyed on sorted for a stable key. The
+        // created_within window moves with time, so it is keyed on as is and applied afterwards
+        let mut custom_tag_strings: Vec<String> = custom_tags
+            .iter()
+            .map(|(key, values)| {
+                let mut values: Vec<&String> = values.iter().collect();
+                values.sort();
+                format!("{}={:?}", key, values)
+            })
+            .collect();
+        custom_tag_strings.sort();
+        let mut query_key = format!("{}{:?}", filter.as_json(), custom_tag_strings);
+        if let Some(created_within) = query_opts.created_within {
+            query_key = format!("{}{:?}", query_key, created_within);
+        }
+        let effective_since = query_opts.effective_since(Timestamp::now().as_u64());
+        if let Some(since) = effective_since {
+            filter = filter.since(Timestamp::from(since));
         }
-    }
 
-    async fn handle_direct_message(&mut self, url: url::Url, event: Event) {
-        let secret_key = self.client.keys().await.secret_key().unwrap();
-        let content = match decrypt(&secret_key, &event.pubkey, &event.content) {
-            Ok(content) => content,
-            Err(error) => {
-                error!(
-                    "Comms w/ pubkey {} handle_direct_message() failed to decrypt - {}",
-                    self.pubkey, error
-                );
-                return;
-            }
-        };
+        if let Some(relays) = &query_opts.relays {
+            let relay_strings: Vec<String> = relays
+                .iter()
+                .map(|url| normalize_relay_url(url).to_string())
+                .collect();
+            query_key = format!("{}{:?}", query_key, relay_strings);
+        }
+        // Relays skipped for malformed events differ by the rate, and so might the Orders
+        if let Some(max_relay_failure_rate) = query_opts.max_relay_failure_rate {
+            query_key = format!("{}{:?}", query_key, max_relay_failure_rate);
+        }
 
-        match serde_json::from_str::<PeerMessage>(content.as_str()) {
-            Ok(peer_message) => {
-                if let Some(error) = self
-                    .router
-                    .handle_peer_message(event.pubkey, url, event.id.to_string(), peer_message)
-                    .await
-                    .err()
-                {
-                    error!(
-                        "Comms w/ pubkey {} handle_direct_message() failed in router.handle_peer_message() - {}",
-                        self.pubkey,
-                        error
-                    );
-                    return;
-                } else {
-                    debug!(
-                        "Comms w/ pubkey {} handle_direct_message() handled PeerMessage w/ EventID {}",
-                        self.pubkey, event.id
-                    );
-                }
-            }
-            Err(error) => {
-                error!(
-                    "Comms w/ pubkey {} handle_direct_message() failed to deserialize content as PeerMessage - {}",
+        if query_opts.use_cache {
+            if let Some(order_envelopes) = self.order_cache.get(&query_key, query_opts.max_age) {
+                trace!(
+                    "Comms w/ pubkey {} serving {} Orders from cache",
                     self.pubkey,
-                    error
+                    order_envelopes.len()
                 );
+                let order_envelopes = Self::unexpired_order_envelopes(order_envelopes)
+                    .into_iter()
+                    .filter(|order_envelope| {
+                        order_envelope.created_at >= effective_since.unwrap_or_default()
+                    })
+                    .collect();
+                send_rsp(rsp_tx, Ok(order_envelopes));
                 return;
             }
         }
-    }
 
-    // Nostr Client Management
+        let timeout = Duration::from_secs(1);
+        let relays = match self.relays_to_query(&query_opts).await {
+            Some(relays) => relays,
+            None => self.readable_relay_urls().await,
+        };
+        let result = self.get_events_from_relays(&relays, filter, timeout).await;
+        let events = match result {
+            Ok(events) => events,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
 
-    async fn get_pubkey(&self, rsp_tx: oneshot::Sender<XOnlyPublicKey>) {
-        rsp_tx.send(self.pubkey).unwrap(); // Oneshot should not fail
-    }
+        let order_envelopes = self.matching_order_envelopes(events, &custom_tags).await;
 
-    async fn add_relays_to_client(
-        &self,
-        relays: Vec<(url::Url, Option<SocketAddr>)>,
-    ) -> Result<(), N3xbError> {
-        let into_relays: Vec<(String, Option<SocketAddr>)> = relays
-            .clone()
+        // Fresh results always refresh the cache, so a later cached query can be served from it
+        let order_envelopes = order_envelopes
             .into_iter()
-            .map(|(url, addr)| {
-                let url = url.into();
-                (url, addr)
+            .map(|order_envelope| {
+                let created_at = order_envelope.created_at;
+                (order_envelope, created_at)
             })
             .collect();
-        self.client.add_relays(into_relays).await?;
-        debug!("Comms w/ pubkey {} added relays {:?}", self.pubkey, relays);
-        Ok(())
+        let order_envelopes = self.order_cache.insert(query_key, order_envelopes);
+        send_rsp(rsp_tx, Ok(Self::unexpired_order_envelopes(order_envelopes)));
     }
 
-    async fn add_relays(
-        &self,
-        relay_addrs: Vec<(url::Url, Option<SocketAddr>)>,
-        connect: bool,
-        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    // Nostr until is inclusive, so the next page repeats events at the oldest created_at of this one
+    async fn query_orders_page(
+        &mut self,
+        filter_tags: Vec<FilterTag>,
+        relay_url: url::Url,
+        until: Option<u64>,
+        limit: usize,
+        rsp_tx: oneshot::Sender<Result<OrderPage, N3xbError>>,
     ) {
-        if relay_addrs.is_empty() {
-            let error = N3xbError::Simple(format!(
-                "Comms w/ pubkey {} add_relays() called with no relays",
-                self.pubkey
-            ));
-            rsp_tx.send(Err(error)).unwrap(); // Oneshot should not fail
-            return;
+        let (mut filter, custom_tags) = self.order_query_filter(filter_tags);
+        filter = filter.limit(limit);
+        if let Some(until) = until {
+            filter = filter.until(Timestamp::from(until));
         }
 
-        if let Some(error) = self.add_relays_to_client(relay_addrs.clone()).await.err() {
-            rsp_tx.send(Err(error.into())).unwrap(); // Oneshot should not fail
-            return;
-        }
+        let timeout = Duration::from_secs(1);
+        let events = match self
+            .get_events_from_relays(&[relay_url], filter, timeout)
+            .await
+        {
+            Ok(events) => events,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
 
-        if connect {
-            let mut relay_error_strings = HashMap::<url::Url, String>::new();
+        let event_count = events.len();
+        let oldest_created_at = events.iter().map(|event| event.created_at.as_u64()).min();
+        let order_envelopes = self.matching_order_envelopes(events, &custom_tags).await;
+        let order_page = OrderPage {
+            order_envelopes: Self::unexpired_order_envelopes(order_envelopes),
+            event_count,
+            oldest_created_at,
+        };
+        send_rsp(rsp_tx, Ok(order_page));
+    }
 
-            for relay_addr in relay_addrs.clone() {
-                let relay_url = relay_addr.0.clone();
-                let relay = self.client.relay(relay_url.to_string()).await;
+    // Custom tags are not indexed by relays, and are matched against the Orders afterwards instead
+    fn order_query_filter(
+        &self,
+        filter_tags: Vec<FilterTag>,
+    ) -> (Filter, HashMap<String, HashSet<String>>) {
+        let mut custom_tags: HashMap<String, HashSet<String>> = HashMap::new();
+        for filter_tag in &filter_tags {
+            if let FilterTag::CustomTag(key, values) = filter_tag {
+                custom_tags
+                    .entry(key.to_owned())
+                    .or_default()
+                    .extend(values.to_owned());
+            }
+        }
+        let order_tags = OrderTag::from_filter_tags(filter_tags, &self.trade_engine_name);
 
-                match relay {
-                    Ok(relay) => {
-                        if let Some(error) = relay
-                            .subscribe(self.subscription_filters(self.pubkey), None)
-                            .await
-                            .err()
-                        {
-                            relay_error_strings.insert(relay_url.clone(), error.to_string());
-                            continue;
-                        }
-                        relay.connect(true).await;
+        let filter = Self::create_event_tag_filter(order_tags, &self.network);
+        (filter, custom_tags)
+    }
 
-                        let relay_status = relay.status().await;
-                        match relay_status {
-                            RelayStatus::Connected => {
-                                self.data.add_relays(vec![relay_addr]);
-                            }
-                            _ => {
-                                relay_error_strings
-                                    .insert(relay_url.clone(), relay_status.to_string());
-                            }
-                        }
-                    }
-                    Err(error) => {
-                        relay_error_strings.insert(relay_url.clone(), error.to_string());
+    async fn matching_order_envelopes(
+        &mut self,
+        events: Vec<Event>,
+        custom_tags: &HashMap<String, HashSet<String>>,
+    ) -> Vec<OrderEnvelope> {
+        let maybe_order_envelopes = self.extract_order_envelopes_from_events(events).await;
+        let mut order_envelopes: Vec<OrderEnvelope> = Vec::new();
+        for maybe_order_envelope in maybe_order_envelopes {
+            match maybe_order_envelope {
+                Ok(order_envelope) => {
+                    if order_envelope.order.matches_custom_tags(custom_tags) {
+                        order_envelopes.push(order_envelope);
                     }
                 }
-
-                if relay_error_strings.contains_key(&relay_url) {
-                    if let Some(error) = self.client.remove_relay(relay_url.as_str()).await.err() {
-                        error!(
-                            "Comms w/ pubkey {} failed to remove relay {} - {}",
-                            self.pubkey, relay_url, error
-                        )
-                    }
+                Err(error) => {
+                    warn!(
+                        "Order extraction from Nostr event failed - {}",
+                        error.to_string()
+                    );
                 }
             }
+        }
+        order_envelopes
+    }
 
-            let relay_errors_string = relay_error_strings
-                .iter()
-                .map(|(url, error)| format!("{} - {}", url.to_string(), error.to_string()))
-                .collect::<Vec<String>>()
-                .join(", ");
+    // Relays serving too many malformed events are skipped, unless that would leave none to query
+    async fn relays_to_query(&mut self, query_opts: &QueryOpts) -> Option<Vec<url::Url>> {
+        let Some(max_failure_rate) = query_opts.max_relay_failure_rate else {
+            return query_opts.relays.clone();
+        };
 
-            if relay_error_strings.len() == relay_addrs.len() {
-                let error = N3xbError::Simple(format!(
-                    "Comms w/ pubkey {} failed to connect to all relays - {}",
-                    self.pubkey, relay_errors_string
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // Oneshot should not fail
-            } else if relay_error_strings.is_empty() {
-                rsp_tx.send(Ok(())).unwrap(); // Oneshot should not fail
-            } else {
-                let error = N3xbError::Simple(format!(
-                    "Comms w/ pubkey {} failed to connect to relays: {}",
-                    self.pubkey, relay_errors_string
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // Oneshot should not fail
+        let now = Instant::now();
+        self.relay_parse_stats
+            .values_mut()
+            .for_each(|stats| stats.decay(now));
+
+        let candidates: Vec<url::Url> = match &query_opts.relays {
+            Some(relays) => relays.iter().map(normalize_relay_url).collect(),
+            None => self
+                .client
+                .relays()
+                .await
+                .keys()
+                .filter_map(|url| url::Url::parse(url.as_str()).ok())
+                .map(|url| normalize_relay_url(&url))
+                .collect(),
+        };
+
+        let (healthy, skipped): (Vec<url::Url>, Vec<url::Url>) =
+            candidates.into_iter().partition(|url| {
+                self.relay_parse_stats
+                    .get(url)
+                    .map_or(true, |stats| !stats.exceeds(max_failure_rate))
+            });
+
+        if skipped.is_empty() {
+            return query_opts.relays.clone();
+        }
+        if healthy.is_empty() {
+            warn!(
+                "Comms w/ pubkey {} has all relays exceeding malformed event rate of {}. Querying all regardless",
+                self.pubkey, max_failure_rate
+            );
+            return query_opts.relays.clone();
+        }
+
+        debug!(
+            "Comms w/ pubkey {} skipping relays {:?} exceeding malformed event rate of {}",
+            self.pubkey, skipped, max_failure_rate
+        );
+        Some(healthy)
+    }
+
+    // Write-only relays are never queried, even if asked for
+    async fn get_events_from_relays(
+        &self,
+        relays: &[url::Url],
+        filter: Filter,
+        timeout: Duration,
+    ) -> Result<Vec<Event>, N3xbError> {
+        let relay_urls: HashSet<url::Url> = relays.iter().map(normalize_relay_url).collect();
+        let mut events_by_id = HashMap::<EventId, Event>::new();
+        let mut queried_any = false;
+
+        for (url, relay) in self.client.relays().await {
+            let Ok(relay_url) = url::Url::parse(url.as_str()) else {
+                continue;
+            };
+            let relay_url = normalize_relay_url(&relay_url);
+            if !relay_urls.contains(&relay_url)
+                || !self.data.relay_capability(&relay_url).can_read()
+            {
+                continue;
             }
-        } else {
-            self.data.add_relays(relay_addrs.clone());
+            queried_any = true;
+
+            match relay
+                .get_events_of(vec![filter.clone()], timeout, FilterOptions::ExitOnEOSE)
+                .await
+            {
+                Ok(events) => {
+                    for event in events {
+                        events_by_id.insert(event.id, event);
+                    }
+                }
+                Err(error) => {
+                    warn!(
+                        "Comms w/ pubkey {} failed to query relay {} - {}",
+                        self.pubkey, url, error
+                    );
+                }
+            }
+        }
 
-            rsp_tx.send(Ok(())).unwrap(); // Oneshot should not fail
+        if !queried_any {
+            return Err(N3xbError::Simple(format!(
+                "Comms w/ pubkey {} has none of the relays {:?} to query from",
+                self.pubkey, relays
+            )));
         }
+        Ok(events_by_id.into_values().collect())
     }
 
-    async fn disconnect_remove_relay(&mut self, relay_url: url::Url) -> Result<(), N3xbError> {
-        self.data.remove_relay(&relay_url);
+    async fn readable_relay_urls(&self) -> Vec<url::Url> {
+        self.client
+            .relays()
+            .await
+            .keys()
+            .filter_map(|url| url::Url::parse(url.as_str()).ok())
+            .map(|url| normalize_relay_url(&url))
+            .filter(|url| self.data.relay_capability(url).can_read())
+            .collect()
+    }
 
-        let relay_string: String = relay_url.clone().into();
-        self.client.disconnect_relay(relay_string.clone()).await?;
-        self.client.remove_relay(relay_string).await?;
-        Ok(())
+    // Not all relays support NIP-40, so expired Orders are also dropped here
+    fn unexpired_order_envelopes(order_envelopes: Vec<OrderEnvelope>) -> Vec<OrderEnvelope> {
+        let now = Timestamp::now().as_u64();
+        order_envelopes
+            .into_iter()
+            .filter(|order_envelope| !order_envelope.is_expired(now))
+            .collect()
     }
 
-    async fn remove_relay(
-        &mut self,
-        relay_url: url::Url,
-        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    // Query NIP-65 Relay List
+
+    async fn query_relay_list(
+        &self,
+        pubkey: XOnlyPublicKey,
+        rsp_tx: oneshot::Sender<Result<HashSet<Url>, N3xbError>>,
     ) {
-        match self.disconnect_remove_relay(relay_url).await {
-            Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap();
+        let filter = Filter::new().author(pubkey).kind(Kind::RelayList);
+        let timeout = Duration::from_secs(1);
+        let relays = self.readable_relay_urls().await;
+        let events = match self.get_events_from_relays(&relays, filter, timeout).await {
+            Ok(events) => events,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
             }
-            Err(error) => rsp_tx.send(Err(error.into())).unwrap(),
         };
-    }
-
-    async fn get_relays(&self, rsp_tx: oneshot::Sender<Vec<RelayInfo>>) {
-        let relays = self.client.relays().await;
-        let mut relays_info = Vec::<RelayInfo>::new();
-
-        for (url, relay) in relays {
-            let status = relay.status().await;
-            let document = relay.document().await;
 
-            relays_info.push(RelayInfo {
-                url: url::Url::from_str(url.as_str()).unwrap(),
-                status,
-                document,
-            });
-        }
-        rsp_tx.send(relays_info).unwrap(); // Oneshot should not fail
+        // Relay List is replaceable, only the latest one counts
+        let Some(event) = events.into_iter().max_by_key(|event| event.created_at) else {
+            send_rsp(rsp_tx, Ok(HashSet::new()));
+            return;
+        };
+        send_rsp(rsp_tx, Ok(Self::extract_read_relays(&event)));
     }
 
-    async fn connect_relay(
+    // Query Peer Messages
+
+    // Paged from the newest back, up to a maximum number of pages. Nostr until is inclusive, so
+    // events at the oldest created_at of a page are repeated in the next
+    async fn query_peer_messages(
         &self,
-        relay_url: url::Url,
-        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+        trade_uuid: Uuid,
+        since: Option<u64>,
+        rsp_tx: oneshot::Sender<Result<Vec<PeerEnvelope>, N3xbError>>,
     ) {
-        let relay_string = relay_url.to_string();
-        let result = self.client.connect_relay(relay_string).await;
-        match result {
-            Ok(_) => rsp_tx.send(Ok(())).unwrap(),
-            Err(error) => rsp_tx.send(Err(error.into())).unwrap(),
-        };
-    }
+        let timeout = Duration::from_secs(1);
+        let relays = self.readable_relay_urls().await;
+        let mut events: Vec<Event> = Vec::new();
+        let mut event_ids: HashSet<EventId> = HashSet::new();
+        let mut until: Option<Timestamp> = None;
+
+        for _ in 0..Self::PEER_MESSAGE_QUERY_MAX_PAGES {
+            let mut filter = Filter::new()
+                .pubkey(self.pubkey)
+                .kind(Kind::EncryptedDirectMessage)
+                .limit(Self::PEER_MESSAGE_QUERY_PAGE_SIZE);
+            if let Some(since) = since {
+                filter = filter.since(Timestamp::from(since));
+            }
+            if let Some(until) = until {
+                filter = filter.until(until);
+            }
 
-    async fn connect_all_relays(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
-        self.client.connect().await;
-        rsp_tx.send(Ok(())).unwrap();
-    }
+            let page = match self.get_events_from_relays(&relays, filter, timeout).await {
+                Ok(page) => page,
+                Err(error) => {
+                    send_rsp(rsp_tx, Err(error));
+                    return;
+                }
+            };
 
-    fn subscription_filters(&self, pubkey: XOnlyPublicKey) -> Vec<Filter> {
-        // Need a way to track existing Filters
-        // Need a way to correlate State Machines to Subscriptions as to remove filters as necessary
+            let page_len = page.len();
+            let oldest_created_at = page.iter().map(|event| event.created_at).min();
+            for event in page {
+                if event_ids.insert(event.id) {
+                    events.push(event);
+                }
+            }
+
+            match oldest_created_at {
+                Some(oldest_created_at) if page_len >= Self::PEER_MESSAGE_QUERY_PAGE_SIZE => {
+                    until = Some(oldest_created_at);
+                }
+                _ => break,
+            }
+        }
+        events.sort_by_key(|event| event.created_at);
 
-        // Subscribe to all DM to own pubkey. Filter unrecognized DM out some other way. Can be spam prone
-        let dm_filter = Filter::new().pubkey(pubkey);
-        vec![dm_filter]
+        // Which relays the DMs were refetched from isn't known
+        let mut peer_envelopes = Vec::new();
+        for event in events {
+            // Old DMs may be under keys since rotated or unregistered, so are not reported on
+            let Some(peer_message) = self.open_direct_message(&event, false).await else {
+                continue;
+            };
+            if peer_message.trade_uuid != trade_uuid {
+                continue;
+            }
+            peer_envelopes.push(PeerEnvelope::new(
+                event.pubkey,
+                HashSet::new(),
+                event.id.into(),
+                event.created_at.as_u64(),
+                peer_message,
+            ));
+        }
+        send_rsp(rsp_tx, Ok(peer_envelopes));
     }
 
-    // Send Maker Order Note
+    // Query Profile Metadata
 
-    async fn send_maker_order_note(
-        &self,
-        order: Order,
-        rsp_tx: oneshot::Sender<Result<OrderEnvelope, N3xbError>>,
+    async fn get_maker_profile(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        verify_nip05: bool,
+        rsp_tx: oneshot::Sender<Result<Option<Metadata>, N3xbError>>,
     ) {
-        // Create Note Content
-        let maker_order_note = MakerOrderNote {
-            maker_obligation: order.maker_obligation.content.clone(),
-            taker_obligation: order.taker_obligation.content.clone(),
-            trade_details: order.trade_details.content.clone(),
-            trade_engine_specifics: order.trade_engine_specifics.clone(),
-            pow_difficulty: order.pow_difficulty.clone(),
+        let metadata = match self.profile_cache.get(&pubkey, Self::PROFILE_CACHE_MAX_AGE) {
+            Some(metadata) => metadata,
+            None => match self.fetch_metadata(pubkey).await {
+                Ok(metadata) => {
+                    self.profile_cache.insert(pubkey, metadata.clone());
+                    metadata
+                }
+                Err(error) => {
+                    send_rsp(rsp_tx, Err(error));
+                    return;
+                }
+            },
         };
 
-        let content_string = match serde_json::to_string(&maker_order_note) {
-            Ok(string) => string,
-            Err(error) => {
-                rsp_tx.send(Err(error.into())).unwrap();
-                return;
-            }
+        let Some(mut metadata) = metadata else {
+            send_rsp(rsp_tx, Ok(None));
+            return;
         };
 
-        let order_tags = OrderTag::from_order(order.clone(), &self.trade_engine_name);
+        let Some(nip05) = metadata.nip05.clone().filter(|_| verify_nip05) else {
+            send_rsp(rsp_tx, Ok(Some(metadata)));
+            return;
+        };
 
-        let builder = EventBuilder::new(
-            Self::MAKER_ORDER_NOTE_KIND,
-            content_string,
-            &Self::create_event_tags(order_tags),
-        );
+        if let Some(verified) = self.profile_cache.nip05_verified(&pubkey, &nip05) {
+            if !verified {
+                metadata.nip05 = None;
+            }
+            send_rsp(rsp_tx, Ok(Some(metadata)));
+            return;
+        }
 
-        let keys = self.client.keys().await;
+        let Some(nip05_verification_tx) = self.nip05_verification_tx.clone() else {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} not running to verify NIP-05 {}",
+                self.pubkey, nip05
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        };
 
-        let urls = self
-            .client
-            .relays()
+        // Verification is an HTTP request to the NIP-05 domain, so is kept off the actor loop
+        let own_pubkey = self.pubkey;
+        tokio::spawn(async move {
+            let verified = match tokio::time::timeout(
+                Self::NIP05_VERIFY_TIMEOUT,
+                nip05::verify(pubkey, &nip05, None),
+            )
             .await
-            .keys()
-            .cloned()
-            .map(|url| url::Url::parse(url.as_str()).unwrap())
-            .collect();
-
-        let result = self
-            .client
-            .send_event(builder.to_event(&keys).unwrap())
-            .await;
-
-        match result {
-            Ok(event_id) => {
-                let order_envelope = OrderEnvelope {
-                    pubkey: keys.public_key(),
-                    event_id: event_id.to_string(),
-                    urls,
-                    order,
-                    _private: (),
-                };
-                rsp_tx.send(Ok(order_envelope)).unwrap();
+            {
+                Ok(Ok(())) => true,
+                Ok(Err(error)) => {
+                    debug!(
+                        "Comms w/ pubkey {} failed to verify NIP-05 {} of pubkey {} - {}",
+                        own_pubkey, nip05, pubkey, error
+                    );
+                    false
+                }
+                Err(_) => {
+                    debug!(
+                        "Comms w/ pubkey {} timed out verifying NIP-05 {} of pubkey {}",
+                        own_pubkey, nip05, pubkey
+                    );
+                    false
+                }
+            };
+            let verification = Nip05Verification {
+                pubkey,
+                nip05,
+                verified,
+                metadata,
+                rsp_tx,
+            };
+            if let Err(error) = nip05_verification_tx.send(verification).await {
+                warn!(
+                    "Comms w/ pubkey {} dropped NIP-05 verification as the actor has terminated",
+                    own_pubkey
+                );
+                send_rsp(
+                    error.0.rsp_tx,
+                    Err(N3xbError::Simple("Comms terminated".to_string())),
+                );
             }
-            Err(error) => rsp_tx.send(Err(error.into())).unwrap(),
-        }
-    }
-
-    fn create_event_tags(tags: Vec<OrderTag>) -> Vec<Tag> {
-        tags.iter()
-            .map(|event_tag| match event_tag {
-                OrderTag::TradeUUID(trade_uuid) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    vec![trade_uuid.to_string()],
-                ),
-                OrderTag::MakerObligations(obligation_kinds) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    obligation_kinds
-                        .to_owned()
-                        .iter()
-                        .flat_map(|kind| kind.to_tag_strings())
-                        .collect(),
-                ),
-                OrderTag::TakerObligations(obligations_kinds) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    obligations_kinds
-                        .to_owned()
-                        .iter()
-                        .flat_map(|kind| kind.to_tag_strings())
-                        .collect(),
-                ),
-                OrderTag::TradeDetailParameters(parameters) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    TradeDetails::parameters_to_tags(parameters.clone())
-                        .into_iter()
-                        .collect(),
-                ),
-                OrderTag::TradeEngineName(name) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    vec![name.to_owned()],
-                ),
-                OrderTag::EventKind(kind) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    vec![kind.to_string()],
-                ),
-                OrderTag::ApplicationTag(app_tag) => Tag::Generic(
-                    TagKind::Custom(event_tag.key().to_string()),
-                    vec![app_tag.to_owned()],
-                ),
-            })
-            .collect()
+        });
     }
 
-    // Query Order Notes
+    fn handle_nip05_verification(&mut self, verification: Nip05Verification) {
+        let Nip05Verification {
+            pubkey,
+            nip05,
+            verified,
+            mut metadata,
+            rsp_tx,
+        } = verification;
 
-    async fn query_orders(
-        &self,
-        filter_tags: Vec<FilterTag>,
-        rsp_tx: oneshot::Sender<Result<Vec<OrderEnvelope>, N3xbError>>,
-    ) {
-        let order_tags = OrderTag::from_filter_tags(filter_tags, &self.trade_engine_name);
+        self.profile_cache
+            .set_nip05_verified(&pubkey, nip05, verified);
+        if !verified {
+            metadata.nip05 = None;
+        }
+        send_rsp(rsp_tx, Ok(Some(metadata)));
+    }
 
-        let filter = Self::create_event_tag_filter(order_tags, &self.network);
+    async fn fetch_metadata(&self, pubkey: XOnlyPublicKey) -> Result<Option<Metadata>, N3xbError> {
+        let filter = Filter::new().author(pubkey).kind(Kind::Metadata);
         let timeout = Duration::from_secs(1);
-        let events = match self.client.get_events_of(vec![filter], Some(timeout)).await {
-            Ok(events) => events,
-            Err(error) => {
-                rsp_tx.send(Err(error.into())).unwrap();
-                return;
-            }
+        let relays = self.readable_relay_urls().await;
+        let events = self
+            .get_events_from_relays(&relays, filter, timeout)
+            .await?;
+
+        // Metadata is replaceable, only the latest one counts
+        let Some(event) = events.into_iter().max_by_key(|event| event.created_at) else {
+            return Ok(None);
         };
-
-        let maybe_order_envelopes = self.extract_order_envelopes_from_events(events).await;
-        let mut order_envelopes: Vec<OrderEnvelope> = Vec::new();
-        for maybe_order_envelope in maybe_order_envelopes {
-            match maybe_order_envelope {
-                Ok(order_envelope) => order_envelopes.push(order_envelope),
-                Err(error) => {
-                    warn!(
-                        "Order extraction from Nostr event failed - {}",
-                        error.to_string()
-                    );
-                }
+        match serde_json::from_str::<Metadata>(&event.content) {
+            Ok(metadata) => Ok(Some(metadata)),
+            Err(error) => {
+                warn!(
+                    "Comms w/ pubkey {} failed to parse Metadata of pubkey {} - {}",
+                    self.pubkey, pubkey, error
+                );
+                Ok(None)
             }
         }
-        rsp_tx.send(Ok(order_envelopes)).unwrap();
+    }
+
+    // Relays marked as write are only where the pubkey posts to, and are not read from
+    fn extract_read_relays(event: &Event) -> HashSet<Url> {
+        event
+            .tags
+            .iter()
+            .map(|tag| tag.as_vec())
+            .filter(|tag_vec| tag_vec.first().map(|key| key.as_str()) == Some("r"))
+            .filter(|tag_vec| tag_vec.get(2).map(|marker| marker.as_str()) != Some("write"))
+            .filter_map(|tag_vec| tag_vec.get(1).and_then(|url| Url::parse(url).ok()))
+            .map(|url| normalize_relay_url(&url))
+            .collect()
     }
 
     fn extract_order_tags_from_tags(&self, tags: Vec<Tag>) -> Vec<OrderTag> {
@@ -1044,7 +3939,11 @@ impl CommsActor {
             let mut tag_vec = tag.as_vec();
             let tag_key = tag_vec.remove(0);
 
-            if let Ok(order_tag) = OrderTag::from_key_value(&tag_key, tag_vec) {
+            if tag_key == MakerOrderNote::DELEGATION_TAG_KEY
+                || tag_key == MakerOrderNote::EXPIRATION_TAG_KEY
+            {
+                continue;
+            } else if let Ok(order_tag) = OrderTag::from_key_value(&tag_key, tag_vec) {
                 order_tags.push(order_tag);
             } else {
                 warn!("Unrecognized Tag with key: {}", tag_key);
@@ -1056,14 +3955,25 @@ impl CommsActor {
     async fn extract_order_envelope_from_event(
         &self,
         event: Event,
+        urls: HashSet<url::Url>,
     ) -> Result<OrderEnvelope, N3xbError> {
-        let maker_order_note: MakerOrderNote = serde_json::from_str(event.content.as_str())?;
-        let order_tags = self.extract_order_tags_from_tags(event.tags);
+        let (maker_order_note, trade_engine_specifics_defaulted) =
+            Self::deserialize_with_default_specifics::<MakerOrderNote>(
+                event.content.as_str(),
+                |_| Some("/trade_engine_specifics"),
+                self.default_order_trade_engine_specifics.as_deref(),
+            )?;
+        let delegator_pubkey = MakerOrderNote::extract_delegator_pubkey(&event)?;
+        let expiration = MakerOrderNote::extract_expiration(&event);
+        let order_tags = self.extract_order_tags_from_tags(event.tags.clone());
 
         let mut some_trade_uuid: Option<Uuid> = None;
         let mut some_maker_obligation_kinds: Option<HashSet<ObligationKind>> = None;
         let mut some_taker_obligation_kinds: Option<HashSet<ObligationKind>> = None;
         let mut trade_parameters: HashSet<TradeParameter> = HashSet::new();
+        let mut custom_tags: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut some_direction: Option<TradeDirection> = None;
+        let mut some_geohash: Option<String> = None;
 
         for order_tag in order_tags {
             match order_tag {
@@ -1075,6 +3985,21 @@ impl CommsActor {
                     some_taker_obligation_kinds = Some(obligations);
                 }
                 OrderTag::TradeDetailParameters(parameters) => trade_parameters = parameters,
+                OrderTag::CustomTags(tags) => {
+                    for (key, values) in tags {
+                        custom_tags.entry(key).or_default().extend(values);
+                    }
+                }
+                OrderTag::TradeDirection(direction) => some_direction = Some(direction),
+                // The longest of the prefixes tagged is the full geohash
+                OrderTag::Geohash(geohash) => {
+                    if some_geohash
+                        .as_ref()
+                        .map_or(true, |longest| geohash.len() > longest.len())
+                    {
+                        some_geohash = Some(geohash);
+                    }
+                }
 
                 // Sanity Checks. Abort order parsing if fails
                 OrderTag::TradeEngineName(name) => {
@@ -1136,6 +4061,28 @@ impl CommsActor {
             return Err(N3xbError::Simple(message));
         };
 
+        if let Some(error) = self
+            .check_trade_engine_specifics(maker_order_note.trade_engine_specifics.as_ref())
+            .err()
+        {
+            warn!(
+                "Maker Order Note with Trade UUID {} - {}",
+                trade_uuid, error
+            );
+            return Err(error);
+        }
+
+        if let Some(error) = self
+            .validate_trade_engine_specifics(maker_order_note.trade_engine_specifics.as_ref())
+            .err()
+        {
+            warn!(
+                "Maker Order Note with Trade UUID {} - {}",
+                trade_uuid, error
+            );
+            return Err(error);
+        }
+
         let order = Order {
             trade_uuid,
             maker_obligation,
@@ -1143,34 +4090,41 @@ impl CommsActor {
             trade_details,
             trade_engine_specifics: maker_order_note.trade_engine_specifics,
             pow_difficulty: maker_order_note.pow_difficulty,
+            expiration,
+            custom_tags,
+            geohash: some_geohash,
             _private: (),
         };
 
-        // Is this order seen from other relays?
-        let relay_urls = self
-            .client
-            .database()
-            .event_recently_seen_on_relays(event.id)
-            .await
-            .unwrap()
-            .unwrap();
-
-        let urls = relay_urls
-            .iter()
-            .map(|url| url::Url::parse(url.as_str()).unwrap())
-            .collect();
+        // The direction tag is only for relay side filtering. It must agree with the Obligations
+        if let Some(direction) = some_direction {
+            if direction != order.direction() {
+                let message = format!(
+                    "Trade Direction {} mismatch on Maker Order Note deserialization. {} expected.",
+                    direction,
+                    order.direction()
+                );
+                warn!("{}", message);
+                return Err(N3xbError::Simple(message));
+            }
+        }
 
         Ok(OrderEnvelope {
             pubkey: event.pubkey,
             urls,
-            event_id: event.id.to_string(),
+            event_id: event.id.into(),
             order: order,
+            delegator_pubkey,
+            created_at: event.created_at.as_u64(),
+            expiration,
+            signed_event: Some(event),
+            trade_engine_specifics_defaulted,
             _private: (),
         })
     }
 
     async fn extract_order_envelopes_from_events(
-        &self,
+        &mut self,
         events: Vec<Event>,
     ) -> Vec<Result<OrderEnvelope, N3xbError>> {
         let mut order_envelopes: Vec<Result<OrderEnvelope, N3xbError>> = Vec::new();
@@ -1185,12 +4139,51 @@ impl CommsActor {
                 event_ids.insert(event.id);
             }
 
-            let order_envelope = self.extract_order_envelope_from_event(event).await;
+            // Is this order seen from other relays?
+            let urls = self.event_seen_on_relays(event.id).await;
+
+            // Parsed before by an earlier query
+            let order_envelope = match self.event_cache.get(&EventIdString::from(event.id)) {
+                Some(mut order_envelope) => {
+                    order_envelope.urls = urls.clone();
+                    Ok(order_envelope)
+                }
+                None => {
+                    let order_envelope = self
+                        .extract_order_envelope_from_event(event, urls.clone())
+                        .await;
+                    if let Ok(order_envelope) = &order_envelope {
+                        self.event_cache.insert(order_envelope.clone());
+                    }
+                    order_envelope
+                }
+            };
+            for url in urls.iter() {
+                self.relay_parse_stats
+                    .entry(normalize_relay_url(url))
+                    .or_default()
+                    .record(order_envelope.is_ok());
+            }
             order_envelopes.push(order_envelope);
         }
         order_envelopes
     }
 
+    async fn event_seen_on_relays(&self, event_id: EventId) -> HashSet<url::Url> {
+        let relay_urls = self
+            .client
+            .database()
+            .event_recently_seen_on_relays(event_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        relay_urls
+            .iter()
+            .map(|url| url::Url::parse(url.as_str()).unwrap())
+            .collect()
+    }
+
     fn consume_tags_for_filter(
         tags: Vec<OrderTag>,
         filter: Filter,
@@ -1257,54 +4250,441 @@ impl CommsActor {
                     );
                     Self::consume_tags_for_filter(tags[1..].to_vec(), filter, network)
                 }
+                OrderTag::CustomTags(custom_tags) => {
+                    let filter = filter.custom_tag(
+                        Alphabet::try_from(tag.key()).unwrap(),
+                        custom_tags_to_strings(custom_tags),
+                    );
+                    Self::consume_tags_for_filter(tags[1..].to_vec(), filter, network)
+                }
+                OrderTag::TradeDirection(direction) => {
+                    let filter = filter.custom_tag(
+                        Alphabet::try_from(tag.key()).unwrap(),
+                        [direction.to_string()].to_vec(),
+                    );
+                    Self::consume_tags_for_filter(tags[1..].to_vec(), filter, network)
+                }
+                OrderTag::Geohash(geohash_prefix) => {
+                    let filter = filter.custom_tag(
+                        Alphabet::try_from(tag.key()).unwrap(),
+                        [geohash_prefix.to_owned()].to_vec(),
+                    );
+                    Self::consume_tags_for_filter(tags[1..].to_vec(), filter, network)
+                }
+            }
+        } else {
+            filter
+        }
+    }
+
+    fn create_event_tag_filter(
+        tags: Vec<OrderTag>,
+        network: impl Borrow<BitcoinNetwork>,
+    ) -> Filter {
+        let filter = Filter::new().kind(Self::MAKER_ORDER_NOTE_KIND);
+        Self::consume_tags_for_filter(tags, filter, network)
+    }
+
+    async fn send_peer_message(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        peer_message: PeerMessage,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let content_string = match serde_json::to_string(&peer_message) {
+            Ok(string) => string,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error.into()));
+                return;
+            }
+        };
+
+        let responding_to_event_id = match peer_message
+            .responding_to_id
+            .as_ref()
+            .map(|responding_to_id| responding_to_id.to_event_id())
+            .transpose()
+        {
+            Ok(responding_to_event_id) => responding_to_event_id,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        let keys = match self.trade_identity_keys.get(&peer_message.trade_uuid) {
+            Some(keys) => keys.clone(),
+            None => self.client.keys().await,
+        };
+        let builder = match self.trade_encryption_keys.get(&peer_message.trade_uuid) {
+            Some(encryption_keys) => Self::new_trade_encrypted_direct_msg(
+                encryption_keys,
+                pubkey,
+                content_string,
+                responding_to_event_id,
+            ),
+            None => EventBuilder::new_encrypted_direct_msg(
+                &keys,
+                pubkey,
+                content_string,
+                responding_to_event_id,
+            )
+            .map_err(|error| N3xbError::Simple(error.to_string())),
+        };
+        let event = match builder {
+            Ok(builder) if self.peer_pow > 0 => {
+                self.mine_peer_message(builder, keys, relay_hints, rsp_tx);
+                return;
+            }
+            Ok(builder) => match builder.to_event(&keys) {
+                Ok(event) => event,
+                Err(error) => {
+                    send_rsp(rsp_tx, Err(N3xbError::Simple(error.to_string())));
+                    return;
+                }
+            },
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+        self.send_peer_message_event(event, relay_hints, rsp_tx)
+            .await;
+    }
+
+    // Mining can take seconds, so is done on a blocking thread and handed back to the actor to
+    // send. The actor loop carries on serving other trades meanwhile
+    fn mine_peer_message(
+        &self,
+        builder: EventBuilder,
+        keys: Keys,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let Some(mined_peer_message_tx) = self.mined_peer_message_tx.clone() else {
+            let error = N3xbError::Simple(format!(
+                "Comms w/ pubkey {} not running to mine peer message PoW",
+                self.pubkey
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        };
+
+        let own_pubkey = self.pubkey;
+        let difficulty = self.peer_pow;
+        tokio::spawn(async move {
+            let mined =
+                tokio::task::spawn_blocking(move || builder.to_pow_event(&keys, difficulty)).await;
+            let event = match mined {
+                Ok(Ok(event)) => Ok(event),
+                Ok(Err(error)) => Err(N3xbError::Simple(error.to_string())),
+                Err(error) => Err(N3xbError::Simple(error.to_string())),
+            };
+            let mined_peer_message = MinedPeerMessage {
+                event,
+                relay_hints,
+                rsp_tx,
+            };
+            if let Err(error) = mined_peer_message_tx.send(mined_peer_message).await {
+                warn!(
+                    "Comms w/ pubkey {} dropped mined peer message as the actor has terminated",
+                    own_pubkey
+                );
+                send_rsp(
+                    error.0.rsp_tx,
+                    Err(N3xbError::Simple("Comms terminated".to_string())),
+                );
             }
-        } else {
-            filter
+        });
+    }
+
+    async fn handle_mined_peer_message(&mut self, mined_peer_message: MinedPeerMessage) {
+        let MinedPeerMessage {
+            event,
+            relay_hints,
+            rsp_tx,
+        } = mined_peer_message;
+        match event {
+            Ok(event) => {
+                self.send_peer_message_event(event, relay_hints, rsp_tx)
+                    .await
+            }
+            Err(error) => send_rsp(rsp_tx, Err(error)),
         }
     }
 
-    fn create_event_tag_filter(
-        tags: Vec<OrderTag>,
-        network: impl Borrow<BitcoinNetwork>,
-    ) -> Filter {
-        let filter = Filter::new().kind(Self::MAKER_ORDER_NOTE_KIND);
-        Self::consume_tags_for_filter(tags, filter, network)
+    async fn send_peer_message_event(
+        &mut self,
+        event: Event,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        // Prefer the relays the recipient hinted at, fallback to all of our writable relays
+        if !relay_hints.is_empty() {
+            if let Some(hinted_send_tx) = self.hinted_send_tx.clone() {
+                self.send_event_to_relay_hints(event, relay_hints, hinted_send_tx, rsp_tx)
+                    .await;
+                return;
+            }
+        }
+        self.send_event_to_own_relays(event, rsp_tx).await;
     }
 
-    async fn send_peer_message(
+    async fn handle_hinted_send(&mut self, hinted_send: HintedSend) {
+        let HintedSend {
+            event,
+            event_id,
+            temporary_urls,
+            rsp_tx,
+        } = hinted_send;
+        for url in temporary_urls {
+            self.temporary_hint_relays.remove(&url);
+        }
+
+        match event_id {
+            Some(event_id) => send_rsp(rsp_tx, Ok(event_id.into())),
+            None => self.send_event_to_own_relays(event, rsp_tx).await,
+        }
+    }
+
+    async fn send_event_to_own_relays(
         &self,
-        pubkey: XOnlyPublicKey,
-        peer_message: PeerMessage,
+        event: Event,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     ) {
-        let content_string = match serde_json::to_string(&peer_message) {
-            Ok(string) => string,
-            Err(error) => {
-                rsp_tx.send(Err(error.into())).unwrap();
-                return;
+        match self.send_event_to_each_relay(event).await {
+            Ok((event_id, _)) => send_rsp(rsp_tx, Ok(event_id.into())),
+            Err(error) => send_rsp(rsp_tx, Err(error)),
+        }
+    }
+
+    // Same as a NIP-04 DM, except the content is encrypted with the per-trade keys. Still signed by
+    // and addressed to the identity keys, so the DM reaches the recipient's subscription as usual
+    fn new_trade_encrypted_direct_msg(
+        encryption_keys: &TradeEncryptionKeys,
+        receiver_pubkey: XOnlyPublicKey,
+        content: String,
+        reply_to: Option<EventId>,
+    ) -> Result<EventBuilder, N3xbError> {
+        let encrypted_content = encrypt(
+            &encryption_keys.secret_key,
+            &encryption_keys.peer_pubkey,
+            content,
+        )
+        .map_err(|error| N3xbError::Simple(error.to_string()))?;
+
+        let mut tags = vec![Tag::Generic(TagKind::P, vec![receiver_pubkey.to_string()])];
+        if let Some(reply_to) = reply_to {
+            tags.push(Tag::Generic(TagKind::E, vec![reply_to.to_string()]));
+        }
+        Ok(EventBuilder::new(
+            Kind::EncryptedDirectMessage,
+            encrypted_content,
+            &tags,
+        ))
+    }
+
+    // Hinted relays not already added are only connected to for the duration of the send. They
+    // count toward max_relays while connected, and must advertise the NIPs required of hint relays.
+    // The Taker picks the hints, so sending to them is kept off the actor loop, with a timeout on
+    // each relay. The outcome comes back to the actor as a HintedSend
+    async fn send_event_to_relay_hints(
+        &mut self,
+        event: Event,
+        relay_hints: HashSet<Url>,
+        hinted_send_tx: mpsc::Sender<HintedSend>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let existing_relays: HashSet<url::Url> =
+            self.data.relays().into_iter().map(|(url, _)| url).collect();
+        let mut relay_urls: Vec<url::Url> = normalize_relay_addrs(
+            relay_hints
+                .into_iter()
+                .map(|relay_hint| (relay_hint, None))
+                .collect(),
+        )
+        .into_iter()
+        .map(|(url, _)| url)
+        .filter(|url| self.data.relay_capability(url).can_write())
+        .filter(|url| !self.temporary_hint_relays.contains(url))
+        .collect();
+        relay_urls.sort_by_key(|url| !existing_relays.contains(url));
+        relay_urls.truncate(Self::MAX_RELAY_HINTS);
+
+        let (existing_urls, mut temporary_urls): (Vec<url::Url>, Vec<url::Url>) = relay_urls
+            .into_iter()
+            .partition(|url| existing_relays.contains(url));
+        let temporary_capacity = self
+            .max_relays
+            .saturating_sub(self.held_relay_urls().await.len());
+        if temporary_urls.len() > temporary_capacity {
+            warn!(
+                "Comms w/ pubkey {} only has room for {} of {} hinted relays under max relays of {}",
+                self.pubkey,
+                temporary_capacity,
+                temporary_urls.len(),
+                self.max_relays
+            );
+            temporary_urls.truncate(temporary_capacity);
+        }
+        self.temporary_hint_relays
+            .extend(temporary_urls.iter().cloned());
+
+        let own_pubkey = self.pubkey;
+        let client = self.client.clone();
+        let hint_relay_nips = self.hint_relay_nips.clone();
+        tokio::spawn(async move {
+            let mut sent_event_id: Option<EventId> = None;
+
+            for url in existing_urls {
+                match tokio::time::timeout(
+                    Self::HINT_RELAY_SEND_TIMEOUT,
+                    client.send_event_to(url.as_str(), event.clone()),
+                )
+                .await
+                {
+                    Ok(Ok(event_id)) => sent_event_id = Some(event_id),
+                    Ok(Err(error)) => {
+                        warn!(
+                            "Comms w/ pubkey {} failed to send Event {} to hinted relay {} - {}",
+                            own_pubkey,
+                            event.id.to_string(),
+                            url,
+                            error
+                        );
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Comms w/ pubkey {} timed out sending Event {} to hinted relay {}",
+                            own_pubkey,
+                            event.id.to_string(),
+                            url
+                        );
+                    }
+                }
             }
-        };
 
-        let responding_to_event_id: Option<EventId> =
-            if let Some(responding_to_id) = peer_message.responding_to_id {
-                Some(EventId::from_str(responding_to_id.as_str()).unwrap())
+            let supporting_urls = if hint_relay_nips.is_empty() {
+                temporary_urls.clone()
             } else {
-                None
+                Self::relays_supporting_nips(own_pubkey, temporary_urls.clone(), hint_relay_nips)
+                    .await
             };
+            for url in supporting_urls {
+                match tokio::time::timeout(
+                    Self::HINT_RELAY_SEND_TIMEOUT,
+                    Self::send_event_to_temporary_relay(own_pubkey, &client, &event, &url),
+                )
+                .await
+                {
+                    Ok(Some(event_id)) => sent_event_id = Some(event_id),
+                    Ok(None) => {}
+                    Err(_) => {
+                        warn!(
+                            "Comms w/ pubkey {} timed out sending Event {} to hinted relay {}",
+                            own_pubkey,
+                            event.id.to_string(),
+                            url
+                        );
+                    }
+                }
+                if let Some(error) = client.remove_relay(url.as_str()).await.err() {
+                    warn!(
+                        "Comms w/ pubkey {} failed to remove temporary hinted relay {} - {}",
+                        own_pubkey, url, error
+                    );
+                }
+            }
 
-        let result = self
-            .client
-            .send_direct_msg(pubkey, content_string, responding_to_event_id)
-            .await;
+            let hinted_send = HintedSend {
+                event,
+                event_id: sent_event_id,
+                temporary_urls,
+                rsp_tx,
+            };
+            if let Err(error) = hinted_send_tx.send(hinted_send).await {
+                warn!(
+                    "Comms w/ pubkey {} dropped hinted send as the actor has terminated",
+                    own_pubkey
+                );
+                send_rsp(
+                    error.0.rsp_tx,
+                    Err(N3xbError::Simple("Comms terminated".to_string())),
+                );
+            }
+        });
+    }
 
-        match result {
-            Ok(event_id) => rsp_tx.send(Ok(event_id.to_string())).unwrap(),
-            Err(error) => rsp_tx.send(Err(error.into())).unwrap(),
+    async fn send_event_to_temporary_relay(
+        own_pubkey: XOnlyPublicKey,
+        client: &Client,
+        event: &Event,
+        url: &url::Url,
+    ) -> Option<EventId> {
+        if let Some(error) = client.add_relay(url.as_str(), None).await.err() {
+            warn!(
+                "Comms w/ pubkey {} failed to add hinted relay {} - {}",
+                own_pubkey, url, error
+            );
+            return None;
+        }
+
+        if let Some(error) = client.connect_relay(url.as_str()).await.err() {
+            warn!(
+                "Comms w/ pubkey {} failed to connect to hinted relay {} - {}",
+                own_pubkey, url, error
+            );
+            return None;
+        }
+
+        match client.send_event_to(url.as_str(), event.clone()).await {
+            Ok(event_id) => Some(event_id),
+            Err(error) => {
+                warn!(
+                    "Comms w/ pubkey {} failed to send Event {} to hinted relay {} - {}",
+                    own_pubkey,
+                    event.id.to_string(),
+                    url,
+                    error
+                );
+                None
+            }
+        }
+    }
+
+    // NIP-11 documents are fetched concurrently, so a slow relay only costs one fetch timeout
+    async fn relays_supporting_nips(
+        own_pubkey: XOnlyPublicKey,
+        relay_urls: Vec<url::Url>,
+        required_nips: Vec<u16>,
+    ) -> Vec<url::Url> {
+        let fetches: Vec<(url::Url, tokio::task::JoinHandle<Vec<u16>>)> = relay_urls
+            .into_iter()
+            .map(|url| {
+                let fetch =
+                    Self::missing_relay_nips(own_pubkey, url.clone(), None, required_nips.clone());
+                (url, tokio::spawn(fetch))
+            })
+            .collect();
+
+        let mut supporting_urls: Vec<url::Url> = Vec::new();
+        for (url, fetch) in fetches {
+            let missing_nips = fetch.await.unwrap_or_else(|_| required_nips.clone());
+            if missing_nips.is_empty() {
+                supporting_urls.push(url);
+            } else {
+                warn!(
+                    "Comms w/ pubkey {} skipping hinted relay {} missing required NIPs {:?}",
+                    own_pubkey, url, missing_nips
+                );
+            }
         }
+        supporting_urls
     }
 
     async fn send_taker_offer_message(
-        &self,
+        &mut self,
         pubkey: XOnlyPublicKey,
         responding_to_id: Option<EventIdString>,
         maker_order_note_id: EventIdString,
@@ -1312,58 +4692,161 @@ impl CommsActor {
         offer: Offer,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     ) {
-        let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+        let peer_message = PeerMessage::new(
             responding_to_id,
             maker_order_note_id,
             trade_uuid,
-            message_type: SerdeGenericType::TakerOffer,
-            message: Box::new(offer),
-        };
+            SerdeGenericType::TakerOffer,
+            Box::new(offer),
+            None,
+            None,
+        );
 
-        self.send_peer_message(pubkey, peer_message, rsp_tx).await;
+        self.send_peer_message(pubkey, peer_message, HashSet::new(), rsp_tx)
+            .await;
     }
 
     async fn send_trade_engine_specific_message(
-        &self,
+        &mut self,
         pubkey: XOnlyPublicKey,
         responding_to_id: Option<EventIdString>,
         maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
         message: Box<dyn SerdeGenericTrait>,
+        sequence: u64,
+        message_id: Uuid,
+        relay_hints: HashSet<Url>,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     ) {
-        let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+        let peer_message = PeerMessage::new(
             responding_to_id,
             maker_order_note_id,
             trade_uuid,
-            message_type: SerdeGenericType::TradeEngineSpecific,
+            SerdeGenericType::TradeEngineSpecific,
             message,
-        };
+            Some(sequence),
+            Some(message_id),
+        );
 
-        self.send_peer_message(pubkey, peer_message, rsp_tx).await;
+        self.send_peer_message(pubkey, peer_message, relay_hints, rsp_tx)
+            .await;
     }
 
     async fn send_trade_response(
-        &self,
+        &mut self,
         pubkey: XOnlyPublicKey,
         responding_to_id: Option<EventIdString>,
         maker_order_note_id: EventIdString,
         trade_uuid: Uuid,
         trade_rsp: TradeResponse,
+        relay_hints: HashSet<Url>,
         rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
     ) {
-        let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+        let peer_message = PeerMessage::new(
             responding_to_id,
             maker_order_note_id,
             trade_uuid,
-            message_type: SerdeGenericType::TradeResponse,
-            message: Box::new(trade_rsp),
-        };
+            SerdeGenericType::TradeResponse,
+            Box::new(trade_rsp),
+            None,
+            None,
+        );
+
+        self.send_peer_message(pubkey, peer_message, relay_hints, rsp_tx)
+            .await;
+    }
+
+    async fn send_settlement_info(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        settlement_info: SettlementInfo,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let peer_message = PeerMessage::new(
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            SerdeGenericType::SettlementInfo,
+            Box::new(settlement_info),
+            None,
+            None,
+        );
+
+        self.send_peer_message(pubkey, peer_message, relay_hints, rsp_tx)
+            .await;
+    }
+
+    async fn send_dispute_request(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        dispute_request: DisputeRequest,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let peer_message = PeerMessage::new(
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            SerdeGenericType::DisputeRequest,
+            Box::new(dispute_request),
+            None,
+            None,
+        );
+
+        self.send_peer_message(pubkey, peer_message, relay_hints, rsp_tx)
+            .await;
+    }
+
+    async fn send_trade_complete(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        relay_hints: HashSet<Url>,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let peer_message = PeerMessage::new(
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            SerdeGenericType::TradeComplete,
+            Box::new(TradeComplete {}),
+            None,
+            None,
+        );
+
+        self.send_peer_message(pubkey, peer_message, relay_hints, rsp_tx)
+            .await;
+    }
+
+    async fn send_offer_withdrawal(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        offer_event_id: EventIdString,
+        rsp_tx: oneshot::Sender<Result<EventIdString, N3xbError>>,
+    ) {
+        let peer_message = PeerMessage::new(
+            Some(offer_event_id.clone()),
+            maker_order_note_id,
+            trade_uuid,
+            SerdeGenericType::OfferWithdrawal,
+            Box::new(OfferWithdrawal { offer_event_id }),
+            None,
+            None,
+        );
 
-        self.send_peer_message(pubkey, peer_message, rsp_tx).await;
+        self.send_peer_message(pubkey, peer_message, HashSet::new(), rsp_tx)
+            .await;
     }
 
     async fn delete_maker_order_note(
@@ -1372,19 +4855,327 @@ impl CommsActor {
         reason: String,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
-        let result = self
-            .client
-            .delete_event(EventId::from_str(&event_id).unwrap(), Some(reason))
-            .await;
-        match result {
-            Ok(_) => rsp_tx.send(Ok(())).unwrap(),
-            Err(error) => rsp_tx.send(Err(error.into())).unwrap(),
+        let event_id = match event_id.to_event_id() {
+            Ok(event_id) => event_id,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        // Read-only relays are never sent to, deletions included
+        let keys = self.client.keys().await;
+        let event = match EventBuilder::delete(vec![event_id], Some(reason)).to_event(&keys) {
+            Ok(event) => event,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(N3xbError::Simple(error.to_string())));
+                return;
+            }
+        };
+        match self.send_event_to_each_relay(event).await {
+            Ok(_) => {
+                self.data.remove_order_note_id(event_id.to_string());
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(error) => send_rsp(rsp_tx, Err(error)),
         }
     }
 
     async fn shutdown(&self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
         info!("Comms w/ pubkey {} Shutdown", self.pubkey);
         // TODO: Any other shutdown logic needed?
-        rsp_tx.send(Ok(())).unwrap();
+        send_rsp(rsp_tx, Ok(()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::fs;
+
+    use super::*;
+    use crate::testing::{
+        SomeTestOfferParams, SomeTestOrderParams, SomeTestParams,
+        SomeTradeEngineMakerOrderSpecifics,
+    };
+
+    #[tokio::test]
+    async fn test_malformed_event_ids_fail_only_the_operation() {
+        let data_dir_path = "test_malformed_event_ids";
+        let _ = fs::remove_dir_all(data_dir_path).await;
+
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::maker_private_key(), data_dir_path).await;
+        let comms_accessor = comms.new_accessor();
+        let malformed_event_id = EventIdString::new_unchecked("not-an-event-id");
+
+        let result = comms_accessor
+            .delete_maker_order_note(malformed_event_id.clone(), "Malformed")
+            .await;
+        assert!(matches!(result, Err(N3xbError::InvalidEventId(_))));
+
+        let taker_pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        let result = comms_accessor
+            .send_trade_complete(
+                taker_pubkey,
+                Some(malformed_event_id),
+                SomeTestParams::some_event_id(1),
+                SomeTestOrderParams::some_uuid(),
+                HashSet::new(),
+            )
+            .await;
+        assert!(matches!(result, Err(N3xbError::InvalidEventId(_))));
+
+        // Comms actor is still around for everything else
+        assert!(comms_accessor.get_relays().await.is_empty());
+
+        comms_accessor.shutdown().await.unwrap();
+        let _ = fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_trade_filter_reregistration_replaces() {
+        let data_dir_path = "test_trade_filter_reregistration";
+        let _ = fs::remove_dir_all(data_dir_path).await;
+
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::taker_private_key(), data_dir_path).await;
+        let comms_accessor = comms.new_accessor();
+        let maker_pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        let maker_dm_filter = Filter::new()
+            .author(maker_pubkey)
+            .kind(Kind::EncryptedDirectMessage);
+
+        // A restored Taker registers its filter again
+        let trade_uuid = SomeTestOrderParams::some_uuid();
+        for _ in 0..2 {
+            comms_accessor
+                .register_trade_filter(trade_uuid, maker_dm_filter.clone())
+                .await
+                .unwrap();
+        }
+        comms_accessor
+            .unregister_trade_filter(trade_uuid)
+            .await
+            .unwrap();
+        assert!(comms_accessor
+            .unregister_trade_filter(trade_uuid)
+            .await
+            .is_err());
+
+        comms_accessor.shutdown().await.unwrap();
+        let _ = fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_query_orders_page_after_shutdown_fails() {
+        let data_dir_path = "test_query_orders_page_after_shutdown";
+        let _ = fs::remove_dir_all(data_dir_path).await;
+
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::taker_private_key(), data_dir_path).await;
+        let comms_accessor = comms.new_accessor();
+        comms_accessor.shutdown().await.unwrap();
+
+        let relay_url = Url::parse("ws://localhost:8080").unwrap();
+        let result = comms_accessor
+            .query_orders_page(Vec::new(), relay_url, None, 10)
+            .await;
+        assert!(matches!(
+            result,
+            Err(N3xbError::MpscSend(_) | N3xbError::OneshotRecv(_))
+        ));
+
+        let _ = fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[test]
+    fn test_query_opts_most_restrictive_since() {
+        let now = 1_000_000;
+        let query_opts = QueryOpts::default();
+        assert_eq!(query_opts.effective_since(now), None);
+
+        let query_opts = QueryOpts {
+            created_within: Some(Duration::from_secs(600)),
+            ..Default::default()
+        };
+        assert_eq!(query_opts.effective_since(now), Some(now - 600));
+
+        let query_opts = QueryOpts {
+            since: Some(now - 60),
+            created_within: Some(Duration::from_secs(600)),
+            ..Default::default()
+        };
+        assert_eq!(query_opts.effective_since(now), Some(now - 60));
+
+        let query_opts = QueryOpts {
+            since: Some(now - 6000),
+            created_within: Some(Duration::from_secs(600)),
+            ..Default::default()
+        };
+        assert_eq!(query_opts.effective_since(now), Some(now - 600));
+    }
+
+    #[test]
+    fn test_geohash_tagged_once_per_prefix() {
+        let order_tags = vec![OrderTag::Geohash("u4p".to_string())];
+        let event_tags: Vec<Vec<String>> = CommsActor::create_event_tags(order_tags, None)
+            .into_iter()
+            .map(|tag| tag.as_vec())
+            .collect();
+        assert_eq!(
+            event_tags,
+            vec![vec!["g", "u"], vec!["g", "u4"], vec!["g", "u4p"]]
+        );
+    }
+
+    #[test]
+    fn test_event_send_failures_into_error() {
+        let all_rejected = EventSendFailures {
+            rejected: vec!["wss://a - event not published: duplicate".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            all_rejected.into_error(),
+            N3xbError::EventRejected(_)
+        ));
+
+        // A connected relay failing the send is not a rejection
+        let some_failed = EventSendFailures {
+            rejected: vec!["wss://a - event not published: duplicate".to_string()],
+            failed: vec!["wss://b - timeout".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            some_failed.into_error(),
+            N3xbError::EventSendFailed(_)
+        ));
+
+        let some_unreachable = EventSendFailures {
+            rejected: vec!["wss://a - event not published: duplicate".to_string()],
+            unreachable: vec!["wss://c - relay not connected".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            some_unreachable.into_error(),
+            N3xbError::RelayUnreachable(_)
+        ));
+
+        // No writable relay at all
+        assert!(matches!(
+            EventSendFailures::default().into_error(),
+            N3xbError::RelayUnreachable(_)
+        ));
+    }
+
+    fn some_maker_order_note_value() -> serde_json::Value {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker_order_note = MakerOrderNote {
+            maker_obligation: order.maker_obligation.content,
+            taker_obligation: order.taker_obligation.content,
+            trade_details: order.trade_details.content,
+            trade_engine_specifics: order.trade_engine_specifics,
+            pow_difficulty: order.pow_difficulty,
+        };
+        serde_json::to_value(maker_order_note).unwrap()
+    }
+
+    fn some_default_specifics() -> Box<dyn SerdeGenericTrait> {
+        Box::new(SomeTradeEngineMakerOrderSpecifics {
+            test_specific_field: "default".to_string(),
+        })
+    }
+
+    fn deserialize_order_note(
+        value: &serde_json::Value,
+        default_specifics: Option<&dyn SerdeGenericTrait>,
+    ) -> Result<(MakerOrderNote, bool), N3xbError> {
+        CommsActor::deserialize_with_default_specifics(
+            &value.to_string(),
+            |_| Some("/trade_engine_specifics"),
+            default_specifics,
+        )
+    }
+
+    fn deserialize_peer_message(
+        value: &serde_json::Value,
+        default_specifics: Option<&dyn SerdeGenericTrait>,
+    ) -> Result<(PeerMessage, bool), N3xbError> {
+        CommsActor::deserialize_with_default_specifics(
+            &value.to_string(),
+            PeerMessage::offer_specifics_pointer,
+            default_specifics,
+        )
+    }
+
+    #[test]
+    fn test_default_specifics_substituted_in_maker_order_note() {
+        let some_default_specifics = some_default_specifics();
+        let default_specifics = Some(some_default_specifics.as_ref());
+
+        // Well-formed specifics are left as they are
+        let value = some_maker_order_note_value();
+        let (_, defaulted) = deserialize_order_note(&value, default_specifics).unwrap();
+        assert!(!defaulted);
+
+        // Specifics of an unknown type are only taken in with a default registered
+        let mut value = some_maker_order_note_value();
+        value["trade_engine_specifics"] = serde_json::json!({ "type": "some-unknown-specifics" });
+        assert!(deserialize_order_note(&value, None).is_err());
+        let (maker_order_note, defaulted) =
+            deserialize_order_note(&value, default_specifics).unwrap();
+        assert!(defaulted);
+        let specifics = maker_order_note
+            .trade_engine_specifics
+            .downcast_ref::<SomeTradeEngineMakerOrderSpecifics>()
+            .unwrap();
+        assert_eq!(specifics.test_specific_field, "default");
+
+        // Absent specifics are added
+        let mut value = some_maker_order_note_value();
+        let maker_order_note = value.as_object_mut().unwrap();
+        maker_order_note.remove("trade_engine_specifics");
+        let (_, defaulted) = deserialize_order_note(&value, default_specifics).unwrap();
+        assert!(defaulted);
+
+        // Anything else malformed still fails
+        let maker_order_note = value.as_object_mut().unwrap();
+        maker_order_note.remove("pow_difficulty");
+        assert!(deserialize_order_note(&value, default_specifics).is_err());
+    }
+
+    #[test]
+    fn test_default_specifics_substituted_in_offer_only() {
+        let some_default_specifics = some_default_specifics();
+        let default_specifics = Some(some_default_specifics.as_ref());
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let peer_message = PeerMessage::new(
+            None,
+            SomeTestParams::some_event_id(0),
+            SomeTestOrderParams::some_uuid(),
+            SerdeGenericType::TakerOffer,
+            Box::new(offer),
+            None,
+            None,
+        );
+        let mut value = serde_json::to_value(&peer_message).unwrap();
+        let offer = value["message"].as_object_mut().unwrap();
+        offer.remove("trade_engine_specifics");
+
+        let (peer_message, defaulted) =
+            deserialize_peer_message(&value, default_specifics).unwrap();
+        assert!(defaulted);
+        let offer = peer_message.message.downcast_ref::<Offer>().unwrap();
+        assert!(offer
+            .trade_engine_specifics
+            .downcast_ref::<SomeTradeEngineMakerOrderSpecifics>()
+            .is_some());
+
+        // Other messages don't get the default
+        value["message_type"] = serde_json::json!("TradeEngineSpecific");
+        assert!(deserialize_peer_message(&value, default_specifics).is_err());
     }
 }
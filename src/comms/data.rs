@@ -2,25 +2,35 @@ use std::{
     borrow::Borrow,
     collections::{HashMap, HashSet},
     net::SocketAddr,
-    path::{Path, PathBuf},
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 use tracing::debug;
 
 use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::common::{
     error::N3xbError,
     persist::Persister,
+    store::N3xbStore,
     types::{BitcoinNetwork, SerdeGenericTrait},
 };
 
+use super::{comms::RelayCapability, relay_url::normalize_relay_url};
+
+const COMMS_DATA_VERSION: u32 = 5;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct CommsDataStore {
+    version: u32,
     relays: HashMap<url::Url, Option<SocketAddr>>,
+    #[serde(default)]
+    relay_capabilities: HashMap<url::Url, RelayCapability>, // Relays absent are ReadWrite
     // filters:
     event_ids: HashSet<String>,
+    peer_allowlist: Option<HashSet<XOnlyPublicKey>>, // Everyone is allowed if None
+    order_note_ids: HashSet<String>, // Maker Order Notes published and not yet deleted
 }
 
 #[typetag::serde(name = "n3xb_comms_data")]
@@ -37,28 +47,32 @@ pub(crate) struct CommsData {
 
 impl CommsData {
     pub(crate) fn new(
-        dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
         pubkey: XOnlyPublicKey,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
     ) -> Result<Self, N3xbError> {
-        let data_path = Self::setup_data_path(&dir_path, pubkey, trade_engine_name, network)?;
+        let data_key = Self::data_key(pubkey, trade_engine_name, network);
 
         let mut store = CommsDataStore {
+            version: COMMS_DATA_VERSION,
             relays: HashMap::new(),
+            relay_capabilities: HashMap::new(),
             event_ids: HashSet::new(),
+            peer_allowlist: None,
+            order_note_ids: HashSet::new(),
         };
 
-        if data_path.exists() {
-            match Self::restore(&data_path) {
+        if backend.exists(&data_key)? {
+            match Self::restore(backend.as_ref(), &data_key) {
                 Ok(restored_data) => {
                     store = restored_data;
                 }
                 Err(err) => {
                     panic!(
-                        "Comms w/ Pubkey {} - Error restoring data from path {}: {}. Creating new",
+                        "Comms w/ Pubkey {} - Error restoring data from key {}: {}. Creating new",
                         pubkey.to_string(),
-                        data_path.display().to_string(),
+                        data_key,
                         err
                     );
                 }
@@ -67,39 +81,72 @@ impl CommsData {
 
         let store = Arc::new(RwLock::new(store));
         let generic_store: Arc<RwLock<dyn SerdeGenericTrait + 'static>> = store.clone();
-        let persister = Persister::new(generic_store, data_path);
+        let persister = Persister::new(generic_store, backend, data_key);
         persister.queue();
 
         let comms_data = Self { store, persister };
         Ok(comms_data)
     }
 
-    fn restore(data_path: impl AsRef<Path>) -> Result<CommsDataStore, N3xbError> {
-        let json = Persister::restore(&data_path)?;
-        debug!(
-            "Restored JSON from path: {} - {}",
-            data_path.as_ref().display().to_string(),
-            &json
-        );
+    fn restore(backend: &dyn N3xbStore, data_key: &str) -> Result<CommsDataStore, N3xbError> {
+        let json =
+            Persister::restore_migrated(backend, data_key, COMMS_DATA_VERSION, Self::migrate)?;
+        debug!("Restored JSON from key: {} - {}", data_key, &json);
         let store: CommsDataStore = serde_json::from_str(&json)?;
         Ok(store)
     }
 
-    fn setup_data_path(
-        data_dir_path: impl AsRef<Path>,
+    fn migrate(version: u32, store: &mut Map<String, Value>) -> Result<(), N3xbError> {
+        match version {
+            // Fill in fields added before persisted data was versioned
+            1 => {
+                store
+                    .entry("relay_capabilities")
+                    .or_insert(Value::Object(Map::new()));
+            }
+            2 => {
+                store.entry("peer_allowlist").or_insert(Value::Null);
+            }
+            3 => {
+                store
+                    .entry("order_note_ids")
+                    .or_insert(Value::Array(Vec::new()));
+            }
+            4 => {
+                // Relays added before URLs were normalized could be held under several spellings
+                for key in ["relays", "relay_capabilities"] {
+                    if let Some(Value::Object(entries)) = store.get_mut(key) {
+                        *entries = std::mem::take(entries)
+                            .into_iter()
+                            .map(|(url, value)| match url::Url::parse(&url) {
+                                Ok(url) => (normalize_relay_url(&url).to_string(), value),
+                                Err(_) => (url, value),
+                            })
+                            .collect();
+                    }
+                }
+            }
+            _ => {
+                return Err(N3xbError::Simple(format!(
+                    "Comms Data has no migration from version {}",
+                    version
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn data_key(
         pubkey: XOnlyPublicKey,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
-    ) -> Result<PathBuf, N3xbError> {
-        let dir_path = data_dir_path.as_ref().join(format!(
-            "{}/{}/{}",
+    ) -> String {
+        format!(
+            "{}/{}/{}/comms.json",
             pubkey.to_string(),
             trade_engine_name.as_ref(),
             network.borrow().to_string().to_lowercase()
-        ));
-        std::fs::create_dir_all(&dir_path)?;
-        let data_path = dir_path.join("comms.json");
-        Ok(data_path)
+        )
     }
 
     fn read_store(&self) -> RwLockReadGuard<'_, CommsDataStore> {
@@ -125,10 +172,23 @@ impl CommsData {
         relays.into_iter().collect()
     }
 
-    pub(crate) fn add_relays(&self, relays: Vec<(url::Url, Option<SocketAddr>)>) {
+    pub(crate) fn relay_capability(&self, url: &url::Url) -> RelayCapability {
+        self.read_store()
+            .relay_capabilities
+            .get(url)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn add_relays(&self, relays: Vec<(url::Url, Option<SocketAddr>, RelayCapability)>) {
         let mut store = self.write_store();
-        for (url, addr) in relays {
-            store.relays.insert(url, addr);
+        for (url, addr, capability) in relays {
+            store.relays.insert(url.clone(), addr);
+            if capability == RelayCapability::ReadWrite {
+                store.relay_capabilities.remove(&url);
+            } else {
+                store.relay_capabilities.insert(url, capability);
+            }
         }
         self.persister.queue();
     }
@@ -136,6 +196,7 @@ impl CommsData {
     pub(crate) fn remove_relay(&self, url: &url::Url) {
         let mut store = self.write_store();
         store.relays.remove(url);
+        store.relay_capabilities.remove(url);
         self.persister.queue();
     }
 
@@ -149,7 +210,117 @@ impl CommsData {
         self.persister.queue();
     }
 
+    pub(crate) fn peer_allowed(&self, pubkey: &XOnlyPublicKey) -> bool {
+        match &self.read_store().peer_allowlist {
+            Some(allowlist) => allowlist.contains(pubkey),
+            None => true,
+        }
+    }
+
+    pub(crate) fn set_peer_allowlist(&self, allowlist: Option<HashSet<XOnlyPublicKey>>) {
+        self.write_store().peer_allowlist = allowlist;
+        self.persister.queue();
+    }
+
+    pub(crate) fn order_note_ids(&self) -> HashSet<String> {
+        self.read_store().order_note_ids.clone()
+    }
+
+    pub(crate) fn insert_order_note_id(&self, event_id: impl Into<String>) {
+        self.write_store().order_note_ids.insert(event_id.into());
+        self.persister.queue();
+    }
+
+    pub(crate) fn remove_order_note_id(&self, event_id: impl AsRef<str>) {
+        self.write_store().order_note_ids.remove(event_id.as_ref());
+        self.persister.queue();
+    }
+
     pub(crate) fn terminate(self) {
         self.persister.terminate();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::Secp256k1;
+
+    use super::*;
+    use crate::{common::store::FsStore, testing::SomeTestParams};
+
+    #[test]
+    fn test_comms_data_restore_v1() {
+        let dir_path = "test_comms_data_restore_v1";
+        let _ = std::fs::remove_dir_all(dir_path);
+        let backend = FsStore::new(dir_path);
+
+        // Comms Data as persisted before versioning and Relay capabilities were introduced
+        let v1_json = r#"{"type":"n3xb_comms_data","relays":{"wss://relay.example.com/":null},"event_ids":["some-event-id"]}"#;
+        backend.save("comms.json", v1_json.as_bytes()).unwrap();
+
+        let store = CommsData::restore(&backend, "comms.json").unwrap();
+        assert_eq!(store.version, COMMS_DATA_VERSION);
+        assert!(store
+            .relays
+            .contains_key(&url::Url::from_str("wss://relay.example.com/").unwrap()));
+        assert!(store.relay_capabilities.is_empty());
+        assert!(store.event_ids.contains("some-event-id"));
+        assert!(store.peer_allowlist.is_none());
+        assert!(store.order_note_ids.is_empty());
+
+        std::fs::remove_dir_all(dir_path).unwrap();
+    }
+
+    #[test]
+    fn test_comms_data_restore_v4_relays_normalized() {
+        let dir_path = "test_comms_data_restore_v4";
+        let _ = std::fs::remove_dir_all(dir_path);
+        let backend = FsStore::new(dir_path);
+
+        // Same relay added under different spellings, before URLs were normalized
+        let v4_json = r#"{"type":"n3xb_comms_data","version":4,"relays":{"wss://relay.example.com/nostr/":null,"wss://relay.example.com/nostr":null},"relay_capabilities":{"wss://relay.example.com/nostr/":"ReadOnly"},"event_ids":[],"peer_allowlist":null,"order_note_ids":[]}"#;
+        backend.save("comms.json", v4_json.as_bytes()).unwrap();
+
+        let store = CommsData::restore(&backend, "comms.json").unwrap();
+        let relay_url = url::Url::from_str("wss://relay.example.com/nostr").unwrap();
+        assert_eq!(store.relays.len(), 1);
+        assert!(store.relays.contains_key(&relay_url));
+        assert_eq!(
+            store.relay_capabilities.get(&relay_url),
+            Some(&RelayCapability::ReadOnly)
+        );
+
+        std::fs::remove_dir_all(dir_path).unwrap();
+    }
+
+    #[test]
+    fn test_comms_data_peer_allowlist_persisted() {
+        let dir_path = "test_comms_data_peer_allowlist_persisted";
+        let _ = std::fs::remove_dir_all(dir_path);
+        let backend: Arc<dyn N3xbStore> = Arc::new(FsStore::new(dir_path));
+
+        let secp = Secp256k1::new();
+        let (pubkey, _) = SomeTestParams::maker_private_key().x_only_public_key(&secp);
+        let (other_pubkey, _) = SomeTestParams::taker_private_key().x_only_public_key(&secp);
+
+        let data = CommsData::new(
+            backend.clone(),
+            pubkey,
+            "some-engine",
+            BitcoinNetwork::Regtest,
+        )
+        .unwrap();
+        assert!(data.peer_allowed(&other_pubkey));
+        data.set_peer_allowlist(Some(HashSet::from([pubkey])));
+        data.terminate();
+
+        let data = CommsData::new(backend, pubkey, "some-engine", BitcoinNetwork::Regtest).unwrap();
+        assert!(data.peer_allowed(&pubkey));
+        assert!(!data.peer_allowed(&other_pubkey));
+        data.terminate();
+
+        std::fs::remove_dir_all(dir_path).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use secp256k1::XOnlyPublicKey;
+use uuid::Uuid;
+
+use crate::{common::types::EventIdString, order::OrderEnvelope};
+
+// Parsed Order Event Cache
+
+// Order Envelopes parsed from Maker Order Notes, shared across all queries so overlapping
+// queries don't reparse the same events. Least recently used entries are evicted past capacity
+type OrderVersionKey = (XOnlyPublicKey, Uuid);
+
+struct CachedEvent {
+    order_envelope: OrderEnvelope,
+    last_used: u64,
+}
+
+pub(crate) struct EventCache {
+    capacity: usize,
+    events: HashMap<EventIdString, CachedEvent>,
+    latest_versions: HashMap<OrderVersionKey, EventIdString>,
+    use_counter: u64,
+}
+
+impl EventCache {
+    // Nothing is cached with a capacity of 0
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: HashMap::new(),
+            latest_versions: HashMap::new(),
+            use_counter: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, event_id: &EventIdString) -> Option<OrderEnvelope> {
+        self.use_counter += 1;
+        let cached_event = self.events.get_mut(event_id)?;
+        cached_event.last_used = self.use_counter;
+        Some(cached_event.order_envelope.clone())
+    }
+
+    // A Maker can post a newer version of an Order under the same TradeUUID. Only the newest
+    // version by created_at is kept, and older versions are not cached
+    pub(crate) fn insert(&mut self, order_envelope: OrderEnvelope) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let version_key = (order_envelope.pubkey, order_envelope.order.trade_uuid);
+        if let Some(latest_event_id) = self.latest_versions.get(&version_key).cloned() {
+            if let Some(latest) = self.events.get(&latest_event_id) {
+                if latest.order_envelope.created_at > order_envelope.created_at {
+                    return;
+                }
+            }
+            self.events.remove(&latest_event_id);
+        }
+
+        self.use_counter += 1;
+        self.latest_versions
+            .insert(version_key, order_envelope.event_id.clone());
+        self.events.insert(
+            order_envelope.event_id.clone(),
+            CachedEvent {
+                order_envelope,
+                last_used: self.use_counter,
+            },
+        );
+        self.evict();
+    }
+
+    // Cached entries were checked against the Trade Engine specifics rules at the time of parsing
+    pub(crate) fn clear(&mut self) {
+        self.events.clear();
+        self.latest_versions.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.events.len() > self.capacity {
+            let Some(lru_event_id) = self
+                .events
+                .iter()
+                .min_by_key(|(_, cached_event)| cached_event.last_used)
+                .map(|(event_id, _)| event_id.clone())
+            else {
+                return;
+            };
+
+            if let Some(cached_event) = self.events.remove(&lru_event_id) {
+                let order_envelope = cached_event.order_envelope;
+                self.latest_versions
+                    .remove(&(order_envelope.pubkey, order_envelope.order.trade_uuid));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{SomeTestOrderParams, SomeTestParams};
+
+    // Same Order posted under a particular event
+    fn order_envelope_for(
+        event_id: &EventIdString,
+        trade_uuid: Uuid,
+        created_at: u64,
+    ) -> OrderEnvelope {
+        let order = SomeTestOrderParams::default_buy_builder()
+            .trade_uuid(trade_uuid)
+            .build()
+            .unwrap();
+        SomeTestOrderParams::some_order_envelope(order, event_id.to_owned(), created_at)
+    }
+
+    #[test]
+    fn test_event_cache_evicts_least_recently_used() {
+        let mut cache = EventCache::new(2);
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        let event3 = SomeTestParams::some_event_id(3);
+        cache.insert(order_envelope_for(&event1, Uuid::new_v4(), 1));
+        cache.insert(order_envelope_for(&event2, Uuid::new_v4(), 1));
+
+        // Touching event1 leaves event2 as the least recently used
+        assert!(cache.get(&event1).is_some());
+        cache.insert(order_envelope_for(&event3, Uuid::new_v4(), 1));
+
+        assert!(cache.get(&event1).is_some());
+        assert!(cache.get(&event2).is_none());
+        assert!(cache.get(&event3).is_some());
+        assert_eq!(cache.latest_versions.len(), 2);
+    }
+
+    #[test]
+    fn test_event_cache_newer_version_wins() {
+        let mut cache = EventCache::new(10);
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        let event3 = SomeTestParams::some_event_id(3);
+        let trade_uuid = Uuid::new_v4();
+        cache.insert(order_envelope_for(&event2, trade_uuid, 2));
+        cache.insert(order_envelope_for(&event1, trade_uuid, 1));
+        assert!(cache.get(&event1).is_none());
+        assert!(cache.get(&event2).is_some());
+
+        cache.insert(order_envelope_for(&event3, trade_uuid, 3));
+        assert!(cache.get(&event2).is_none());
+        assert!(cache.get(&event3).is_some());
+    }
+
+    #[test]
+    fn test_event_cache_disabled() {
+        let mut cache = EventCache::new(0);
+        let event1 = SomeTestParams::some_event_id(1);
+        cache.insert(order_envelope_for(&event1, Uuid::new_v4(), 1));
+        assert!(cache.get(&event1).is_none());
+    }
+}
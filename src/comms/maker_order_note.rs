@@ -1,7 +1,11 @@
+use crate::common::error::N3xbError;
 use crate::common::types::SerdeGenericTrait;
 use crate::order::*;
+use nostr_sdk::nostr::nips::nip26::{DelegationTag, EventProperties};
+use nostr_sdk::prelude::Event;
+use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::{fmt::Debug, str::FromStr};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MakerOrderNote {
@@ -11,3 +15,96 @@ pub struct MakerOrderNote {
     pub trade_engine_specifics: Box<dyn SerdeGenericTrait>,
     pub pow_difficulty: u64,
 }
+
+impl MakerOrderNote {
+    pub(crate) const DELEGATION_TAG_KEY: &'static str = "delegation";
+    pub(crate) const EXPIRATION_TAG_KEY: &'static str = "expiration";
+
+    // NIP-40 expiration tag of the Maker Order Note, if any
+    pub(crate) fn extract_expiration(event: &Event) -> Option<u64> {
+        event
+            .tags
+            .iter()
+            .map(|tag| tag.as_vec())
+            .find(|tag_vec| {
+                tag_vec.first().map(|key| key.as_str()) == Some(Self::EXPIRATION_TAG_KEY)
+            })
+            .and_then(|tag_vec| tag_vec.get(1).and_then(|value| value.parse::<u64>().ok()))
+    }
+
+    // NIP-26 delegator of the Maker Order Note, if any. Errs on a delegation tag that doesn't validate
+    pub(crate) fn extract_delegator_pubkey(
+        event: &Event,
+    ) -> Result<Option<XOnlyPublicKey>, N3xbError> {
+        let Some(tag_vec) = event.tags.iter().map(|tag| tag.as_vec()).find(|tag_vec| {
+            tag_vec.first().map(|key| key.as_str()) == Some(Self::DELEGATION_TAG_KEY)
+        }) else {
+            return Ok(None);
+        };
+
+        let delegation_tag = DelegationTag::from_str(&serde_json::to_string(&tag_vec)?)
+            .map_err(|error| N3xbError::Simple(error.to_string()))?;
+
+        match delegation_tag.validate(event.pubkey, &EventProperties::from_event(event)) {
+            Ok(_) => Ok(Some(delegation_tag.delegator_pubkey())),
+            Err(error) => Err(N3xbError::Simple(format!(
+                "Event {} has invalid delegation tag - {}",
+                event.id, error
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::nostr::nips::nip26::Conditions;
+    use nostr_sdk::prelude::{EventBuilder, Keys, Kind, Tag, TagKind, Timestamp};
+
+    use super::*;
+
+    fn some_delegated_event(delegator_keys: &Keys, delegatee_keys: &Keys, signer: &Keys) -> Event {
+        let now = Timestamp::now().as_u64();
+        let conditions = Conditions::from_str(&format!(
+            "kind=61021&created_at>{}&created_at<{}",
+            now - 60,
+            now + 3600
+        ))
+        .unwrap();
+        let delegation_tag =
+            DelegationTag::new(delegator_keys, delegatee_keys.public_key(), conditions).unwrap();
+        let mut tag_values: Vec<String> = serde_json::from_str(&delegation_tag.as_json()).unwrap();
+        tag_values.remove(0);
+
+        let tags = [Tag::Generic(
+            TagKind::Custom(MakerOrderNote::DELEGATION_TAG_KEY.to_string()),
+            tag_values,
+        )];
+        EventBuilder::new(Kind::Custom(61021), "", &tags)
+            .to_event(signer)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_delegator_pubkey() {
+        let delegator_keys = Keys::generate();
+        let delegatee_keys = Keys::generate();
+
+        let event = some_delegated_event(&delegator_keys, &delegatee_keys, &delegatee_keys);
+        assert_eq!(
+            MakerOrderNote::extract_delegator_pubkey(&event).unwrap(),
+            Some(delegator_keys.public_key())
+        );
+
+        // Delegation carried by an event not signed by the delegatee
+        let event = some_delegated_event(&delegator_keys, &delegatee_keys, &Keys::generate());
+        assert!(MakerOrderNote::extract_delegator_pubkey(&event).is_err());
+
+        let event = EventBuilder::new(Kind::Custom(61021), "", &[])
+            .to_event(&delegatee_keys)
+            .unwrap();
+        assert_eq!(
+            MakerOrderNote::extract_delegator_pubkey(&event).unwrap(),
+            None
+        );
+    }
+}
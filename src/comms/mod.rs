@@ -1,7 +1,18 @@
 mod comms;
 mod data;
+mod event_cache;
 mod maker_order_note;
+mod order_cache;
+mod profile_cache;
+mod relay_stats;
+mod relay_url;
 mod router;
 
 pub(crate) use comms::{Comms, CommsAccess};
-pub use comms::{RelayInfo, RelayInformationDocument, RelayStatus};
+pub(crate) use maker_order_note::MakerOrderNote;
+pub use comms::{
+    AddRelayOpts, CommsOptions, IdentityRotation, Metadata, OrderUpdate, QueryOpts,
+    RelayCapability, RelayConfig, RelayConfigEntry, RelayInfo, RelayInformationDocument,
+    RelayStatus, SkippedRelay,
+};
+pub use relay_stats::RelayParseStats;
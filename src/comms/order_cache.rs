@@ -0,0 +1,287 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use secp256k1::XOnlyPublicKey;
+use uuid::Uuid;
+
+use crate::{common::types::EventIdString, order::OrderEnvelope};
+
+// Order Envelope Query Cache
+
+// A Maker can post a newer version of an Order under the same TradeUUID.
+// Only the newest version by created_at is ever kept
+type OrderVersionKey = (XOnlyPublicKey, Uuid);
+
+struct CachedOrder {
+    order_envelope: OrderEnvelope,
+    created_at: u64,
+}
+
+struct CachedQuery {
+    fetched_at: Instant,
+    version_keys: Vec<OrderVersionKey>,
+}
+
+// Queries past the TTL are dropped, and the least recently fetched give way beyond the maximum.
+// Orders go along with the last query referencing them
+pub(crate) struct OrderCache {
+    orders: HashMap<EventIdString, CachedOrder>,
+    latest_versions: HashMap<OrderVersionKey, EventIdString>,
+    queries: HashMap<String, CachedQuery>,
+    max_queries: usize,
+    query_ttl: Duration,
+}
+
+impl OrderCache {
+    const MAX_QUERIES: usize = 100;
+    const QUERY_TTL: Duration = Duration::from_secs(10 * 60);
+
+    pub(crate) fn new() -> Self {
+        Self {
+            orders: HashMap::new(),
+            latest_versions: HashMap::new(),
+            queries: HashMap::new(),
+            max_queries: Self::MAX_QUERIES,
+            query_ttl: Self::QUERY_TTL,
+        }
+    }
+
+    pub(crate) fn get(&self, query_key: &str, max_age: Duration) -> Option<Vec<OrderEnvelope>> {
+        let query = self.queries.get(query_key)?;
+        if query.fetched_at.elapsed() >= max_age.min(self.query_ttl) {
+            return None;
+        }
+
+        let order_envelopes = query
+            .version_keys
+            .iter()
+            .filter_map(|version_key| self.latest_versions.get(version_key))
+            .filter_map(|event_id| self.orders.get(event_id))
+            .map(|cached_order| cached_order.order_envelope.clone())
+            .collect();
+        Some(order_envelopes)
+    }
+
+    // Merges freshly fetched Order Envelopes, along with their created_at, in as the result of the query.
+    // Returns the merged result, with any stale version replaced by the newest one known
+    pub(crate) fn insert(
+        &mut self,
+        query_key: impl Into<String>,
+        order_envelopes: Vec<(OrderEnvelope, u64)>,
+    ) -> Vec<OrderEnvelope> {
+        let mut version_keys: Vec<OrderVersionKey> = Vec::new();
+
+        for (order_envelope, created_at) in order_envelopes {
+            let version_key = (order_envelope.pubkey, order_envelope.order.trade_uuid);
+            if !version_keys.contains(&version_key) {
+                version_keys.push(version_key);
+            }
+
+            if let Some(latest_event_id) = self.latest_versions.get(&version_key) {
+                if let Some(latest) = self.orders.get(latest_event_id) {
+                    if latest.created_at > created_at {
+                        continue;
+                    }
+                }
+                let latest_event_id = latest_event_id.to_owned();
+                self.orders.remove(&latest_event_id);
+            }
+
+            self.latest_versions
+                .insert(version_key, order_envelope.event_id.clone());
+            self.orders.insert(
+                order_envelope.event_id.clone(),
+                CachedOrder {
+                    order_envelope,
+                    created_at,
+                },
+            );
+        }
+
+        let query_key = query_key.into();
+        self.queries.insert(
+            query_key.clone(),
+            CachedQuery {
+                fetched_at: Instant::now(),
+                version_keys,
+            },
+        );
+        self.evict();
+        self.prune();
+
+        let order_envelopes = self.queries[&query_key]
+            .version_keys
+            .iter()
+            .filter_map(|version_key| self.latest_versions.get(version_key))
+            .filter_map(|event_id| self.orders.get(event_id))
+            .map(|cached_order| cached_order.order_envelope.clone())
+            .collect();
+        order_envelopes
+    }
+
+    fn evict(&mut self) {
+        let query_ttl = self.query_ttl;
+        self.queries
+            .retain(|_, query| query.fetched_at.elapsed() < query_ttl);
+
+        while self.queries.len() > self.max_queries {
+            let Some(oldest_query_key) = self
+                .queries
+                .iter()
+                .min_by_key(|(_, query)| query.fetched_at)
+                .map(|(query_key, _)| query_key.clone())
+            else {
+                break;
+            };
+            self.queries.remove(&oldest_query_key);
+        }
+    }
+
+    // Drop Orders no longer referenced by any cached query
+    fn prune(&mut self) {
+        let referenced: HashSet<OrderVersionKey> = self
+            .queries
+            .values()
+            .flat_map(|query| query.version_keys.iter().cloned())
+            .collect();
+
+        let orders = &mut self.orders;
+        self.latest_versions.retain(|version_key, event_id| {
+            let keep = referenced.contains(version_key);
+            if !keep {
+                orders.remove(event_id);
+            }
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{SomeTestOrderParams, SomeTestParams};
+
+    // Same Order posted under a particular event
+    fn order_envelope_for(event_id: &EventIdString, trade_uuid: Uuid) -> OrderEnvelope {
+        let order = SomeTestOrderParams::default_buy_builder()
+            .trade_uuid(trade_uuid)
+            .build()
+            .unwrap();
+        SomeTestOrderParams::some_order_envelope(order, event_id.to_owned(), 0)
+    }
+
+    #[test]
+    fn test_order_cache_hit_within_max_age() {
+        let mut cache = OrderCache::new();
+        let event1 = SomeTestParams::some_event_id(1);
+        let trade_uuid = Uuid::new_v4();
+        cache.insert("query", vec![(order_envelope_for(&event1, trade_uuid), 1)]);
+
+        let order_envelopes = cache.get("query", Duration::from_secs(60)).unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+        assert_eq!(order_envelopes[0].event_id, event1);
+        assert!(cache.get("other-query", Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_order_cache_miss_when_expired() {
+        let mut cache = OrderCache::new();
+        let event1 = SomeTestParams::some_event_id(1);
+        let trade_uuid = Uuid::new_v4();
+        cache.insert("query", vec![(order_envelope_for(&event1, trade_uuid), 1)]);
+        assert!(cache.get("query", Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_order_cache_newer_version_invalidates() {
+        let mut cache = OrderCache::new();
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        let trade_uuid = Uuid::new_v4();
+        cache.insert("query1", vec![(order_envelope_for(&event1, trade_uuid), 1)]);
+        cache.insert("query2", vec![(order_envelope_for(&event2, trade_uuid), 2)]);
+
+        // Older query now resolves to the newer version of the Order
+        let order_envelopes = cache.get("query1", Duration::from_secs(60)).unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+        assert_eq!(order_envelopes[0].event_id, event2);
+    }
+
+    #[test]
+    fn test_order_cache_ignores_stale_version() {
+        let mut cache = OrderCache::new();
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        let trade_uuid = Uuid::new_v4();
+        cache.insert("query1", vec![(order_envelope_for(&event2, trade_uuid), 2)]);
+        let order_envelopes =
+            cache.insert("query2", vec![(order_envelope_for(&event1, trade_uuid), 1)]);
+
+        assert_eq!(order_envelopes.len(), 1);
+        assert_eq!(order_envelopes[0].event_id, event2);
+    }
+
+    #[test]
+    fn test_order_cache_prunes_unreferenced() {
+        let mut cache = OrderCache::new();
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        cache.insert(
+            "query",
+            vec![(order_envelope_for(&event1, Uuid::new_v4()), 1)],
+        );
+        cache.insert(
+            "query",
+            vec![(order_envelope_for(&event2, Uuid::new_v4()), 1)],
+        );
+
+        assert_eq!(cache.orders.len(), 1);
+        assert!(cache.orders.contains_key(&event2));
+    }
+
+    #[test]
+    fn test_order_cache_evicts_oldest_query_over_limit() {
+        let mut cache = OrderCache {
+            max_queries: 2,
+            ..OrderCache::new()
+        };
+        let event1 = SomeTestParams::some_event_id(1);
+        let event2 = SomeTestParams::some_event_id(2);
+        let event3 = SomeTestParams::some_event_id(3);
+        cache.insert(
+            "query1",
+            vec![(order_envelope_for(&event1, Uuid::new_v4()), 1)],
+        );
+        cache.insert(
+            "query2",
+            vec![(order_envelope_for(&event2, Uuid::new_v4()), 1)],
+        );
+        cache.insert(
+            "query3",
+            vec![(order_envelope_for(&event3, Uuid::new_v4()), 1)],
+        );
+
+        assert!(cache.get("query1", Duration::from_secs(60)).is_none());
+        assert!(cache.get("query2", Duration::from_secs(60)).is_some());
+        assert!(cache.get("query3", Duration::from_secs(60)).is_some());
+        assert!(!cache.orders.contains_key(&event1));
+    }
+
+    #[test]
+    fn test_order_cache_drops_query_past_ttl() {
+        let mut cache = OrderCache {
+            query_ttl: Duration::ZERO,
+            ..OrderCache::new()
+        };
+        let event1 = SomeTestParams::some_event_id(1);
+        cache.insert(
+            "query",
+            vec![(order_envelope_for(&event1, Uuid::new_v4()), 1)],
+        );
+
+        assert!(cache.get("query", Duration::from_secs(60)).is_none());
+        assert!(cache.orders.is_empty());
+    }
+}
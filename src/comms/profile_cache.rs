@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use nostr_sdk::prelude::Metadata;
+use secp256k1::XOnlyPublicKey;
+
+// Profile Metadata Cache
+
+// Kind-0 Metadata of pubkeys, so showing the Maker of each Order doesn't refetch from relays.
+// A pubkey without any Metadata is cached too. NIP-05 verification results are kept against the
+// identifier verified, and are dropped whenever the Metadata is refetched. The profile fetched
+// longest ago is evicted once the cache holds its maximum number of profiles
+struct CachedProfile {
+    fetched_at: Instant,
+    metadata: Option<Metadata>,
+    nip05_verified: Option<(String, bool)>,
+}
+
+pub(crate) struct ProfileCache {
+    profiles: HashMap<XOnlyPublicKey, CachedProfile>,
+    max_profiles: usize,
+}
+
+impl ProfileCache {
+    pub(crate) fn new(max_profiles: usize) -> Self {
+        Self {
+            profiles: HashMap::new(),
+            max_profiles,
+        }
+    }
+
+    // Outer None is a cache miss. Inner None is a pubkey known to not have any Metadata
+    pub(crate) fn get(
+        &self,
+        pubkey: &XOnlyPublicKey,
+        max_age: Duration,
+    ) -> Option<Option<Metadata>> {
+        let profile = self.profiles.get(pubkey)?;
+        if profile.fetched_at.elapsed() >= max_age {
+            return None;
+        }
+        Some(profile.metadata.clone())
+    }
+
+    pub(crate) fn insert(&mut self, pubkey: XOnlyPublicKey, metadata: Option<Metadata>) {
+        let profile = CachedProfile {
+            fetched_at: Instant::now(),
+            metadata,
+            nip05_verified: None,
+        };
+        self.profiles.insert(pubkey, profile);
+
+        while self.profiles.len() > self.max_profiles {
+            let Some(oldest_pubkey) = self
+                .profiles
+                .iter()
+                .min_by_key(|(_, profile)| profile.fetched_at)
+                .map(|(pubkey, _)| *pubkey)
+            else {
+                break;
+            };
+            self.profiles.remove(&oldest_pubkey);
+        }
+    }
+
+    pub(crate) fn nip05_verified(&self, pubkey: &XOnlyPublicKey, nip05: &str) -> Option<bool> {
+        match &self.profiles.get(pubkey)?.nip05_verified {
+            Some((verified_nip05, verified)) if verified_nip05 == nip05 => Some(*verified),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set_nip05_verified(
+        &mut self,
+        pubkey: &XOnlyPublicKey,
+        nip05: impl Into<String>,
+        verified: bool,
+    ) {
+        if let Some(profile) = self.profiles.get_mut(pubkey) {
+            profile.nip05_verified = Some((nip05.into(), verified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::Secp256k1;
+
+    use super::*;
+    use crate::testing::SomeTestParams;
+
+    fn some_pubkey() -> XOnlyPublicKey {
+        SomeTestParams::maker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0
+    }
+
+    fn other_pubkey() -> XOnlyPublicKey {
+        SomeTestParams::taker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0
+    }
+
+    #[test]
+    fn test_profile_cache_hit_within_max_age() {
+        let mut cache = ProfileCache::new(10);
+        let metadata = Metadata::new().name("alice");
+        cache.insert(some_pubkey(), Some(metadata.clone()));
+
+        let cached = cache.get(&some_pubkey(), Duration::from_secs(60)).unwrap();
+        assert_eq!(cached, Some(metadata));
+        assert!(cache.get(&some_pubkey(), Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_profile_cache_nip05_reset_on_refetch() {
+        let mut cache = ProfileCache::new(10);
+        cache.insert(
+            some_pubkey(),
+            Some(Metadata::new().nip05("alice@example.com")),
+        );
+        cache.set_nip05_verified(&some_pubkey(), "alice@example.com", true);
+        assert_eq!(
+            cache.nip05_verified(&some_pubkey(), "alice@example.com"),
+            Some(true)
+        );
+        assert!(cache
+            .nip05_verified(&some_pubkey(), "bob@example.com")
+            .is_none());
+
+        cache.insert(some_pubkey(), None);
+        assert!(cache
+            .nip05_verified(&some_pubkey(), "alice@example.com")
+            .is_none());
+        assert_eq!(
+            cache.get(&some_pubkey(), Duration::from_secs(60)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_profile_cache_evicts_oldest() {
+        let mut cache = ProfileCache::new(1);
+        cache.insert(some_pubkey(), Some(Metadata::new().name("alice")));
+        cache.insert(other_pubkey(), Some(Metadata::new().name("bob")));
+
+        assert!(cache.get(&some_pubkey(), Duration::from_secs(60)).is_none());
+        assert_eq!(
+            cache.get(&other_pubkey(), Duration::from_secs(60)).unwrap(),
+            Some(Metadata::new().name("bob"))
+        );
+    }
+}
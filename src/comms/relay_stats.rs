@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+// Outcome of parsing the events a relay served. A relay flooding malformed events can be told
+// apart from the rest, and skipped during queries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RelayParseStats {
+    pub parsed: u64,
+    pub failed: u64,
+    decayed_at: Option<Instant>,
+}
+
+impl RelayParseStats {
+    // Relays with fewer events than this are never judged as misbehaving
+    const MIN_SAMPLES: u64 = 10;
+    const DECAY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+    pub fn failure_rate(&self) -> f64 {
+        let total = self.parsed + self.failed;
+        if total == 0 {
+            0.0
+        } else {
+            self.failed as f64 / total as f64
+        }
+    }
+
+    pub(crate) fn record(&mut self, parsed: bool) {
+        self.decay(Instant::now());
+        if parsed {
+            self.parsed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    pub(crate) fn exceeds(&self, max_failure_rate: f64) -> bool {
+        self.parsed + self.failed >= Self::MIN_SAMPLES && self.failure_rate() > max_failure_rate
+    }
+
+    // Counts halve every interval, so a relay that stopped serving malformed events, even one
+    // skipped for it and no longer sampled, is eventually judged on its recent events alone
+    pub(crate) fn decay(&mut self, now: Instant) {
+        let decayed_at = *self.decayed_at.get_or_insert(now);
+        let intervals =
+            now.saturating_duration_since(decayed_at).as_secs() / Self::DECAY_INTERVAL.as_secs();
+        if intervals == 0 {
+            return;
+        }
+
+        let halvings = intervals.min(u64::BITS as u64 - 1) as u32;
+        self.parsed >>= halvings;
+        self.failed >>= halvings;
+        self.decayed_at = Some(decayed_at + Self::DECAY_INTERVAL * intervals as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_parse_stats_exceeds() {
+        let mut stats = RelayParseStats::default();
+        for _ in 0..3 {
+            stats.record(false);
+        }
+        // Not enough samples to judge yet
+        assert!(!stats.exceeds(0.5));
+
+        for _ in 0..7 {
+            stats.record(true);
+        }
+        assert_eq!(stats.failure_rate(), 0.3);
+        assert!(!stats.exceeds(0.5));
+        assert!(stats.exceeds(0.2));
+    }
+
+    #[test]
+    fn test_relay_parse_stats_decay() {
+        let mut stats = RelayParseStats::default();
+        for _ in 0..40 {
+            stats.record(false);
+        }
+        assert!(stats.exceeds(0.5));
+
+        let decayed_at = stats.decayed_at.unwrap();
+        stats.decay(decayed_at + RelayParseStats::DECAY_INTERVAL / 2);
+        assert_eq!(stats.failed, 40);
+
+        // Two intervals on, a quarter of the failures remain. Too few left to judge the relay on
+        stats.decay(decayed_at + RelayParseStats::DECAY_INTERVAL * 2);
+        assert_eq!(stats.failed, 10);
+        stats.decay(decayed_at + RelayParseStats::DECAY_INTERVAL * 3);
+        assert_eq!(stats.failed, 5);
+        assert!(!stats.exceeds(0.5));
+    }
+}
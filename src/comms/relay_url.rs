@@ -0,0 +1,110 @@
+use std::{collections::HashSet, net::SocketAddr};
+
+use url::Url;
+
+// Relay URL Normalization
+
+pub(crate) fn normalize_relay_url(url: &Url) -> Url {
+    // Re-parsing lowercases the scheme & host, and strips default ports for ws/wss
+    let mut normalized = match Url::parse(url.as_str()) {
+        Ok(url) => url,
+        Err(_) => url.to_owned(),
+    };
+
+    if normalized.port() == default_port(normalized.scheme()) {
+        let _ = normalized.set_port(None);
+    }
+
+    let path = normalized.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        normalized.set_path(path.trim_end_matches('/'));
+    }
+
+    if normalized.query() == Some("") {
+        normalized.set_query(None);
+    }
+    normalized.set_fragment(None);
+    normalized
+}
+
+pub(crate) fn normalize_relay_addrs(
+    relay_addrs: Vec<(Url, Option<SocketAddr>)>,
+) -> Vec<(Url, Option<SocketAddr>)> {
+    let mut seen_urls = HashSet::<Url>::new();
+    relay_addrs
+        .into_iter()
+        .map(|(url, addr)| (normalize_relay_url(&url), addr))
+        .filter(|(url, _)| seen_urls.insert(url.clone()))
+        .collect()
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "ws" | "http" => Some(80),
+        "wss" | "https" => Some(443),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_relay_url_equivalent_spellings() {
+        let expected = Url::from_str("wss://relay.example.com").unwrap();
+        let spellings = [
+            "wss://relay.example.com",
+            "wss://relay.example.com/",
+            "WSS://Relay.Example.COM/",
+            "wss://relay.example.com:443",
+            "wss://relay.example.com:443/",
+            "wss://relay.example.com/#fragment",
+            "wss://relay.example.com/?",
+        ];
+
+        for spelling in spellings {
+            let url = Url::from_str(spelling).unwrap();
+            print!("{} -> {}\n", spelling, normalize_relay_url(&url));
+            assert_eq!(normalize_relay_url(&url), expected);
+        }
+    }
+
+    #[test]
+    fn test_normalize_relay_url_trailing_slash_on_path() {
+        let url = Url::from_str("wss://relay.example.com/nostr/").unwrap();
+        let expected = Url::from_str("wss://relay.example.com/nostr").unwrap();
+        assert_eq!(normalize_relay_url(&url), expected);
+    }
+
+    #[test]
+    fn test_normalize_relay_url_keeps_non_default_port() {
+        let url = Url::from_str("ws://localhost:8080/").unwrap();
+        let normalized = normalize_relay_url(&url);
+        assert_eq!(normalized.port(), Some(8080));
+        assert_eq!(normalized, Url::from_str("ws://localhost:8080").unwrap());
+    }
+
+    #[test]
+    fn test_normalize_relay_addrs_deduplicates() {
+        let relay_addrs = vec![
+            (Url::from_str("wss://relay.example.com").unwrap(), None),
+            (Url::from_str("wss://relay.example.com/").unwrap(), None),
+            (Url::from_str("wss://RELAY.example.com:443").unwrap(), None),
+            (Url::from_str("wss://other.example.com").unwrap(), None),
+        ];
+
+        let normalized = normalize_relay_addrs(relay_addrs);
+        assert_eq!(normalized.len(), 2);
+        assert_eq!(
+            normalized[0].0,
+            Url::from_str("wss://relay.example.com").unwrap()
+        );
+        assert_eq!(
+            normalized[1].0,
+            Url::from_str("wss://other.example.com").unwrap()
+        );
+    }
+}
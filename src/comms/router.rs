@@ -1,5 +1,8 @@
-use std::collections::{HashMap, HashSet};
-use tracing::debug;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
 
 use secp256k1::XOnlyPublicKey;
 use tokio::sync::mpsc;
@@ -11,9 +14,23 @@ use crate::{
     peer_msg::{PeerEnvelope, PeerMessage},
 };
 
+// Without a fallback registrant, peer messages for a Trade UUID not yet registered are buffered,
+// so an Offer arriving before its Maker is up is replayed to the Maker once it registers. Each
+// Trade UUID only gets a share of the buffer, so one noisy peer can't push out everyone else's
+const UNROUTED_PEER_MESSAGE_CAPACITY: usize = 64;
+const UNROUTED_PEER_MESSAGES_PER_TRADE: usize = 8;
+const UNROUTED_PEER_MESSAGE_TTL: Duration = Duration::from_secs(300);
+
+struct UnroutedPeerMessage {
+    received_at: Instant,
+    trade_uuid: Uuid,
+    envelope: PeerEnvelope,
+}
+
 pub(super) struct Router {
     peer_message_tx_map: HashMap<Uuid, mpsc::Sender<PeerEnvelope>>,
     peer_message_fallback_tx: Option<mpsc::Sender<PeerEnvelope>>,
+    unrouted_peer_messages: VecDeque<UnroutedPeerMessage>,
 }
 
 impl Router {
@@ -21,6 +38,7 @@ impl Router {
         Router {
             peer_message_tx_map: HashMap::new(),
             peer_message_fallback_tx: None,
+            unrouted_peer_messages: VecDeque::new(),
         }
     }
 
@@ -30,15 +48,37 @@ impl Router {
         tx: mpsc::Sender<PeerEnvelope>,
     ) -> Result<(), N3xbError> {
         debug!("register_tx_for_trade_uuid() for {}", trade_uuid);
-        if self.peer_message_tx_map.insert(trade_uuid, tx).is_some() {
-            let error = N3xbError::Simple(format!(
-                "register_tx_for_trade_uuid() for {} already registered",
-                trade_uuid
-            ));
-            Err(error)
-        } else {
-            Ok(())
+        // The existing registrant keeps receiving, rather than silently losing messages to the new one
+        if self.peer_message_tx_map.contains_key(&trade_uuid) {
+            return Err(N3xbError::TradeAlreadyRegistered(trade_uuid));
+        }
+        self.peer_message_tx_map.insert(trade_uuid, tx.clone());
+
+        self.prune_unrouted_peer_messages();
+        let (replays, unrouted): (VecDeque<_>, VecDeque<_>) = self
+            .unrouted_peer_messages
+            .drain(..)
+            .partition(|unrouted| unrouted.trade_uuid == trade_uuid);
+        self.unrouted_peer_messages = unrouted;
+
+        for replay in replays {
+            debug!(
+                "register_tx_for_trade_uuid() for {} replaying buffered peer message w/ EventID {}",
+                trade_uuid, replay.envelope.event_id
+            );
+            // Don't block the Comms actor on a Trade that is not yet receiving
+            if let Some(error) = tx.try_send(replay.envelope).err() {
+                warn!(
+                    "register_tx_for_trade_uuid() for {} failed to replay buffered peer message - {}",
+                    trade_uuid, error
+                );
+            }
         }
+        Ok(())
+    }
+
+    pub(super) fn trade_uuids(&self) -> Vec<Uuid> {
+        self.peer_message_tx_map.keys().cloned().collect()
     }
 
     pub(super) fn unregister_peer_message_tx(&mut self, trade_uuid: Uuid) -> Result<(), N3xbError> {
@@ -71,6 +111,10 @@ impl Router {
         result
     }
 
+    pub(super) fn has_peer_message_fallback_tx(&self) -> bool {
+        self.peer_message_fallback_tx.is_some()
+    }
+
     pub(super) fn unregister_peer_message_fallback_tx(&mut self) -> Result<(), N3xbError> {
         debug!("unregister_peer_message_fallback_tx()");
 
@@ -90,29 +134,76 @@ impl Router {
         pubkey: XOnlyPublicKey,
         url: Url,
         event_id: EventIdString,
+        created_at: u64,
         peer_message: PeerMessage,
     ) -> Result<(), N3xbError> {
-        let envelope = PeerEnvelope {
+        let trade_uuid = peer_message.trade_uuid;
+        let envelope = PeerEnvelope::new(
             pubkey,
-            urls: HashSet::from([url]),
+            HashSet::from([url]),
             event_id,
-            message_type: peer_message.message_type.clone(),
-            message: peer_message.message.clone(),
-        };
+            created_at,
+            peer_message,
+        );
 
-        if let Some(tx) = self.peer_message_tx_map.get(&peer_message.trade_uuid) {
+        if let Some(tx) = self.peer_message_tx_map.get(&trade_uuid) {
             tx.send(envelope).await?;
             return Ok(());
         }
 
+        // The fallback registrant takes ownership of unrouted peer messages, nothing is buffered
         if let Some(tx) = &self.peer_message_fallback_tx {
             tx.send(envelope).await?;
             return Ok(());
         }
 
-        Err(N3xbError::Simple(
-            "No channel Tx registered for peer message routing".to_string(),
-        ))
+        self.buffer_unrouted_peer_message(trade_uuid, envelope);
+        Ok(())
+    }
+
+    fn buffer_unrouted_peer_message(&mut self, trade_uuid: Uuid, envelope: PeerEnvelope) {
+        debug!(
+            "Buffering unrouted peer message w/ EventID {} for TradeUUID {}",
+            envelope.event_id, trade_uuid
+        );
+        self.prune_unrouted_peer_messages();
+
+        let buffered_for_trade = self
+            .unrouted_peer_messages
+            .iter()
+            .filter(|unrouted| unrouted.trade_uuid == trade_uuid)
+            .count();
+        if buffered_for_trade >= UNROUTED_PEER_MESSAGES_PER_TRADE {
+            if let Some(index) = self
+                .unrouted_peer_messages
+                .iter()
+                .position(|unrouted| unrouted.trade_uuid == trade_uuid)
+            {
+                if let Some(dropped) = self.unrouted_peer_messages.remove(index) {
+                    warn!(
+                        "Unrouted peer messages for TradeUUID {} at limit, dropping buffered peer message w/ EventID {}",
+                        trade_uuid, dropped.envelope.event_id
+                    );
+                }
+            }
+        } else if self.unrouted_peer_messages.len() >= UNROUTED_PEER_MESSAGE_CAPACITY {
+            if let Some(dropped) = self.unrouted_peer_messages.pop_front() {
+                warn!(
+                    "Unrouted peer message buffer full, dropping buffered peer message w/ EventID {} for TradeUUID {}",
+                    dropped.envelope.event_id, dropped.trade_uuid
+                );
+            }
+        }
+        self.unrouted_peer_messages.push_back(UnroutedPeerMessage {
+            received_at: Instant::now(),
+            trade_uuid,
+            envelope,
+        });
+    }
+
+    fn prune_unrouted_peer_messages(&mut self) {
+        self.unrouted_peer_messages
+            .retain(|unrouted| unrouted.received_at.elapsed() < UNROUTED_PEER_MESSAGE_TTL);
     }
 }
 
@@ -124,7 +215,8 @@ mod tests {
     use crate::{
         common::types::SerdeGenericType,
         offer::Offer,
-        testing::{SomeTestOfferParams, SomeTestOrderParams},
+        peer_msg::{PEER_MESSAGE_TYPE, PEER_MESSAGE_VERSION},
+        testing::{SomeTestOfferParams, SomeTestOrderParams, SomeTestParams},
     };
 
     #[tokio::test]
@@ -145,19 +237,24 @@ mod tests {
         let url = Url::from_str("ws://example.com").unwrap();
 
         let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
             responding_to_id: Option::None,
-            maker_order_note_id: "".to_string(),
+            maker_order_note_id: SomeTestParams::some_event_id(0),
             trade_uuid,
             message_type: SerdeGenericType::TakerOffer,
             message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
         };
 
         router
             .handle_peer_message(
                 SomeTestOfferParams::some_x_only_public_key(),
                 url,
-                "".to_string(),
+                SomeTestParams::some_event_id(1),
+                0,
                 peer_message,
             )
             .await
@@ -218,19 +315,24 @@ mod tests {
         let url = Url::from_str("ws://example.com").unwrap();
 
         let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
             responding_to_id: Option::None,
-            maker_order_note_id: "".to_string(),
+            maker_order_note_id: SomeTestParams::some_event_id(0),
             trade_uuid,
             message_type: SerdeGenericType::TakerOffer,
             message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
         };
 
         router
             .handle_peer_message(
                 SomeTestOfferParams::some_x_only_public_key(),
                 url,
-                "".to_string(),
+                SomeTestParams::some_event_id(1),
+                0,
                 peer_message,
             )
             .await
@@ -271,6 +373,7 @@ mod tests {
 
         assert_eq!(0, event_count);
         assert_eq!(1, fallback_count);
+        assert!(router.unrouted_peer_messages.is_empty());
     }
 
     #[tokio::test]
@@ -287,19 +390,24 @@ mod tests {
         let url = Url::from_str("ws://example.com").unwrap();
 
         let peer_message = PeerMessage {
-            r#type: "n3xb-peer-message".to_string(),
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
             responding_to_id: Option::None,
-            maker_order_note_id: "".to_string(),
+            maker_order_note_id: SomeTestParams::some_event_id(0),
             trade_uuid,
             message_type: SerdeGenericType::TakerOffer,
             message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
         };
 
         let result = router
             .handle_peer_message(
                 SomeTestOfferParams::some_x_only_public_key(),
                 url,
-                "".to_string(),
+                SomeTestParams::some_event_id(1),
+                0,
                 peer_message,
             )
             .await;
@@ -322,8 +430,190 @@ mod tests {
             }
         }
 
-        assert!(result.is_err());
+        // Buffered for when the Trade UUID registers, rather than dropped
+        assert!(result.is_ok());
         assert_eq!(0, event_count);
         assert_eq!(0, fallback_count);
+        assert_eq!(1, router.unrouted_peer_messages.len());
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_peer_message_replayed_on_register() {
+        let trade_uuid = SomeTestOrderParams::some_uuid();
+        let mut router = Router::new();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+
+        let url = Url::from_str("ws://example.com").unwrap();
+
+        let peer_message = PeerMessage {
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
+            responding_to_id: Option::None,
+            maker_order_note_id: SomeTestParams::some_event_id(0),
+            trade_uuid,
+            message_type: SerdeGenericType::TakerOffer,
+            message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
+        };
+
+        // Nothing registered yet, the peer message gets buffered
+        router
+            .handle_peer_message(
+                SomeTestOfferParams::some_x_only_public_key(),
+                url,
+                SomeTestParams::some_event_id(1),
+                0,
+                peer_message,
+            )
+            .await
+            .unwrap();
+
+        // Registering for another Trade UUID does not replay
+        let (other_tx, mut other_rx) = mpsc::channel::<PeerEnvelope>(1);
+        router
+            .register_peer_message_tx(Uuid::new_v4(), other_tx)
+            .unwrap();
+        assert!(other_rx.try_recv().is_err());
+
+        let (event_tx, mut event_rx) = mpsc::channel::<PeerEnvelope>(1);
+        router
+            .register_peer_message_tx(trade_uuid, event_tx)
+            .unwrap();
+
+        let peer_envelope = event_rx.try_recv().unwrap();
+        assert_eq!(peer_envelope.event_id, SomeTestParams::some_event_id(1));
+        let _ = peer_envelope.message.downcast_ref::<Offer>().unwrap();
+        assert!(router.unrouted_peer_messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_peer_messages_limited_per_trade() {
+        let trade_uuid = SomeTestOrderParams::some_uuid();
+        let other_trade_uuid = Uuid::new_v4();
+        let mut router = Router::new();
+        let url = Url::from_str("ws://example.com").unwrap();
+
+        let total = UNROUTED_PEER_MESSAGES_PER_TRADE + 2;
+        for index in 0..total {
+            let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+            let peer_message = PeerMessage {
+                r#type: PEER_MESSAGE_TYPE.to_string(),
+                version: PEER_MESSAGE_VERSION,
+                sequence: None,
+                message_id: None,
+                responding_to_id: Option::None,
+                maker_order_note_id: SomeTestParams::some_event_id(0),
+                trade_uuid,
+                message_type: SerdeGenericType::TakerOffer,
+                message: Box::new(offer),
+                trade_engine_specifics_defaulted: false,
+            };
+            router
+                .handle_peer_message(
+                    SomeTestOfferParams::some_x_only_public_key(),
+                    url.clone(),
+                    SomeTestParams::some_event_id(index as u8 + 1),
+                    0,
+                    peer_message,
+                )
+                .await
+                .unwrap();
+        }
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let peer_message = PeerMessage {
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
+            responding_to_id: Option::None,
+            maker_order_note_id: SomeTestParams::some_event_id(0),
+            trade_uuid: other_trade_uuid,
+            message_type: SerdeGenericType::TakerOffer,
+            message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
+        };
+        router
+            .handle_peer_message(
+                SomeTestOfferParams::some_x_only_public_key(),
+                url,
+                SomeTestParams::some_event_id(100),
+                0,
+                peer_message,
+            )
+            .await
+            .unwrap();
+
+        // Oldest dropped past the per trade limit, other Trade UUIDs unaffected
+        let (event_tx, mut event_rx) = mpsc::channel::<PeerEnvelope>(total);
+        router
+            .register_peer_message_tx(trade_uuid, event_tx)
+            .unwrap();
+        let mut event_ids = Vec::new();
+        while let Ok(peer_envelope) = event_rx.try_recv() {
+            event_ids.push(peer_envelope.event_id);
+        }
+        let expected_event_ids: Vec<EventIdString> = (total - UNROUTED_PEER_MESSAGES_PER_TRADE
+            ..total)
+            .map(|index| SomeTestParams::some_event_id(index as u8 + 1))
+            .collect();
+        assert_eq!(event_ids, expected_event_ids);
+
+        assert_eq!(1, router.unrouted_peer_messages.len());
+        assert_eq!(
+            router.unrouted_peer_messages[0].trade_uuid,
+            other_trade_uuid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_peer_message_tx_duplicate() {
+        let trade_uuid = SomeTestOrderParams::some_uuid();
+        let mut router = Router::new();
+        let (first_tx, mut first_rx) = mpsc::channel::<PeerEnvelope>(1);
+        let (second_tx, _second_rx) = mpsc::channel::<PeerEnvelope>(1);
+        router
+            .register_peer_message_tx(trade_uuid, first_tx)
+            .unwrap();
+
+        let result = router.register_peer_message_tx(trade_uuid, second_tx.clone());
+        assert!(matches!(
+            result,
+            Err(N3xbError::TradeAlreadyRegistered(uuid)) if uuid == trade_uuid
+        ));
+
+        // The first registrant still receives the Trade's messages
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let peer_message = PeerMessage {
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence: None,
+            message_id: None,
+            responding_to_id: Option::None,
+            maker_order_note_id: SomeTestParams::some_event_id(0),
+            trade_uuid,
+            message_type: SerdeGenericType::TakerOffer,
+            message: Box::new(offer),
+            trade_engine_specifics_defaulted: false,
+        };
+        router
+            .handle_peer_message(
+                SomeTestOfferParams::some_x_only_public_key(),
+                Url::from_str("ws://example.com").unwrap(),
+                SomeTestParams::some_event_id(1),
+                0,
+                peer_message,
+            )
+            .await
+            .unwrap();
+        assert!(first_rx.try_recv().is_ok());
+
+        router.unregister_peer_message_tx(trade_uuid).unwrap();
+        router
+            .register_peer_message_tx(trade_uuid, second_tx)
+            .unwrap();
     }
 }
@@ -0,0 +1,81 @@
+use std::{any::Any, collections::HashSet, fmt::Debug};
+
+use secp256k1::XOnlyPublicKey;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::common::{error::N3xbError, types::*};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DisputeRequestEnvelope {
+    pub pubkey: XOnlyPublicKey,
+    pub urls: HashSet<Url>,
+    pub event_id: EventIdString,
+    pub dispute_request: DisputeRequest,
+    pub(crate) _private: (),
+}
+
+// Raised by either party. Also sent to the trusted pubkeys of the Order, if any, for arbitration
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DisputeRequest {
+    pub reason: String,
+    pub evidence_ref: Option<String>, // Opaque reference to evidence, interpreted by the Trade Engine
+}
+
+#[typetag::serde(name = "n3xB-dispute-request")]
+impl SerdeGenericTrait for DisputeRequest {
+    fn any_ref(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl DisputeRequest {
+    pub fn new(reason: impl Into<String>, evidence_ref: Option<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            evidence_ref,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), N3xbError> {
+        if self.reason.trim().is_empty() {
+            return Err(N3xbError::Simple(format!(
+                "Dispute Request should have a reason"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispute_request_validate() {
+        let dispute_request = DisputeRequest::new(
+            "Fiat payment not received",
+            Some("some-evidence-ref".to_string()),
+        );
+        assert!(dispute_request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_dispute_request_empty_reason() {
+        let dispute_request = DisputeRequest::new("  ", None);
+        assert!(dispute_request.validate().is_err());
+    }
+
+    #[test]
+    fn test_dispute_request_serde() {
+        let dispute_request = DisputeRequest::new("Fiat payment not received", None);
+        let generic: Box<dyn SerdeGenericTrait> = Box::new(dispute_request.clone());
+
+        let json = serde_json::to_string(&generic).unwrap();
+        print!("Dispute Request JSON: {}\n", json);
+
+        let deserialized: Box<dyn SerdeGenericTrait> = serde_json::from_str(&json).unwrap();
+        let deserialized = deserialized.downcast_ref::<DisputeRequest>().unwrap();
+        assert_eq!(deserialized, &dispute_request);
+    }
+}
@@ -0,0 +1,3 @@
+mod dispute;
+
+pub use dispute::*;
@@ -1,13 +1,26 @@
+// Library output goes through tracing, for consumers to control. Only tests print to stdout
+#![cfg_attr(
+    not(test),
+    deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)
+)]
+
 pub mod common;
+pub mod dispute;
 pub mod maker;
 pub mod manager;
 pub mod offer;
 pub mod order;
 pub mod peer_msg;
+pub mod settlement;
 pub mod taker;
 pub mod testing;
+pub mod trade_record;
 pub mod trade_rsp;
 
 mod comms;
 
-pub use comms::{RelayInfo, RelayInformationDocument, RelayStatus};
+pub use comms::{
+    AddRelayOpts, CommsOptions, IdentityRotation, Metadata, QueryOpts, RelayCapability,
+    RelayConfig, RelayConfigEntry, RelayInfo, RelayInformationDocument, RelayParseStats,
+    RelayStatus, SkippedRelay,
+};
@@ -1,38 +1,93 @@
 use std::{
-    collections::{HashMap, HashSet},
-    path::Path,
+    collections::{HashMap, HashSet, VecDeque},
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
 };
 
+use secp256k1::{SecretKey, XOnlyPublicKey};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use url::Url;
 use uuid::Uuid;
 
+use super::maker::MaxOffers;
 use crate::{
     common::{
-        error::N3xbError,
+        error::{N3xbError, OfferInvalidReason},
         persist::Persister,
+        store::N3xbStore,
         types::{EventIdString, SerdeGenericTrait},
     },
+    dispute::DisputeRequest,
     offer::OfferEnvelope,
     order::Order,
+    peer_msg::{PeerEnvelope, PeerMessageLogEntry, PEER_MESSAGE_RECEIVED_IDS_CAPACITY},
+    settlement::SettlementInfo,
+    trade_record::TradeRecordEntry,
     trade_rsp::TradeResponse,
 };
 
+const MAKER_DATA_VERSION: u32 = 11;
+
+// Explicitly rejected Offer, kept for a while in case the Maker reconsiders
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct RejectedOffer {
+    pub(crate) offer_envelope: OfferEnvelope,
+    pub(crate) rejected_at: u64, // Unix seconds
+    #[serde(default)]
+    pub(crate) reason: Option<OfferInvalidReason>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct MakerDataStore {
+    version: u32,
+
     // Order state data
     order: Order,
     relay_urls: HashSet<Url>,
     order_event_id: Option<EventIdString>,
+    #[serde(default)]
+    order_expiration: Option<u64>, // Unix seconds, of the Maker Order Note as last posted
     offer_envelopes: HashMap<EventIdString, OfferEnvelope>,
     accepted_offer_event_id: Option<EventIdString>,
     trade_rsp: Option<TradeResponse>,
     trade_rsp_event_id: Option<EventIdString>,
     trade_completed: bool,
+    #[serde(default)]
+    peer_msg_seq_sent: u64, // Sequence of the last Trade Engine specific message sent
+    #[serde(default)]
+    peer_msg_seq_received: u64, // Highest sequence of Trade Engine specific message received
+    #[serde(default)]
+    taker_relay_list: HashSet<Url>, // Latest NIP-65 read relays of the accepted Taker
+    #[serde(default)]
+    blocked_pubkeys: HashSet<XOnlyPublicKey>, // Offers from these pubkeys are always rejected
+    #[serde(default)]
+    peer_message_log: VecDeque<PeerMessageLogEntry>,
+    #[serde(default)]
+    settlement_infos: Vec<TradeRecordEntry<SettlementInfo>>, // Sent and received, never dropped
+    #[serde(default)]
+    disputes: Vec<TradeRecordEntry<DisputeRequest>>, // Raised and received, never dropped
+    #[serde(default)]
+    rejected_offers: HashMap<EventIdString, RejectedOffer>,
+    #[serde(default)]
+    received_peer_message_ids: VecDeque<Uuid>, // For dropping resends that had actually landed
+    #[serde(default)]
+    encryption_secret_key: Option<SecretKey>, // Of the per-trade pubkey to accept an Offer with
+    #[serde(default)]
+    offers_seen_at: Option<u64>, // Unix seconds, of the newest Offer seen, to resync missed from
 
     // Order specific settings
     reject_invalid_offers_silently: bool,
+    #[serde(default)]
+    offer_acceptance_deadline: Option<Duration>,
+    #[serde(default)]
+    max_offers: Option<MaxOffers>,
+    #[serde(default)]
+    peer_message_log_capacity: usize,
+    #[serde(default)]
+    delete_on_expiry: bool,
+    #[serde(default)]
+    notify_order_seen_on: bool,
 }
 
 #[typetag::serde(name = "n3xb_maker_data")]
@@ -50,28 +105,53 @@ pub(crate) struct MakerData {
 
 impl MakerData {
     pub(crate) fn new(
-        dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        dir_key: impl AsRef<str>,
         order: Order,
         reject_invalid_offers_silently: bool,
+        offer_acceptance_deadline: Option<Duration>,
+        max_offers: Option<MaxOffers>,
+        peer_message_log_capacity: usize,
+        delete_on_expiry: bool,
+        notify_order_seen_on: bool,
+        encryption_secret_key: Option<SecretKey>,
     ) -> Self {
         let trade_uuid = order.trade_uuid;
-        let data_path = dir_path.as_ref().join(format!("{}-maker.json", trade_uuid));
+        let data_key = format!("{}/{}-maker.json", dir_key.as_ref(), trade_uuid);
 
         let store = MakerDataStore {
+            version: MAKER_DATA_VERSION,
             order,
             relay_urls: HashSet::new(),
             order_event_id: None,
+            order_expiration: None,
             offer_envelopes: HashMap::new(),
             accepted_offer_event_id: None,
             trade_rsp: None,
             trade_rsp_event_id: None,
             trade_completed: false,
+            peer_msg_seq_sent: 0,
+            peer_msg_seq_received: 0,
+            taker_relay_list: HashSet::new(),
+            blocked_pubkeys: HashSet::new(),
+            peer_message_log: VecDeque::new(),
+            settlement_infos: Vec::new(),
+            disputes: Vec::new(),
+            rejected_offers: HashMap::new(),
+            received_peer_message_ids: VecDeque::new(),
+            encryption_secret_key,
+            offers_seen_at: None,
             reject_invalid_offers_silently,
+            offer_acceptance_deadline,
+            max_offers,
+            peer_message_log_capacity,
+            delete_on_expiry,
+            notify_order_seen_on,
         };
 
         let store = Arc::new(RwLock::new(store));
         let generic_store: Arc<RwLock<dyn SerdeGenericTrait + 'static>> = store.clone();
-        let persister = Persister::new(generic_store, data_path);
+        let persister = Persister::new(generic_store, backend, data_key);
         persister.queue();
 
         Self {
@@ -81,15 +161,23 @@ impl MakerData {
         }
     }
 
-    pub(crate) fn restore(data_path: impl AsRef<Path>) -> Result<(Uuid, Self), N3xbError> {
-        let json = Persister::restore(&data_path)?;
+    pub(crate) fn restore(
+        backend: Arc<dyn N3xbStore>,
+        data_key: impl AsRef<str>,
+    ) -> Result<(Uuid, Self), N3xbError> {
+        let json = Persister::restore_migrated(
+            backend.as_ref(),
+            data_key.as_ref(),
+            MAKER_DATA_VERSION,
+            Self::migrate,
+        )?;
         let store: MakerDataStore = serde_json::from_str(&json)?;
 
         let trade_uuid = store.order.trade_uuid;
 
         let store = Arc::new(RwLock::new(store));
         let generic_store: Arc<RwLock<dyn SerdeGenericTrait + 'static>> = store.clone();
-        let persister = Persister::new(generic_store, &data_path);
+        let persister = Persister::new(generic_store, backend, data_key.as_ref());
         persister.queue();
 
         let data = Self {
@@ -101,6 +189,70 @@ impl MakerData {
         Ok((trade_uuid, data))
     }
 
+    fn migrate(version: u32, store: &mut Map<String, Value>) -> Result<(), N3xbError> {
+        match version {
+            // Fill in fields added before persisted data was versioned
+            1 => {
+                store.entry("peer_msg_seq_sent").or_insert(0.into());
+                store.entry("peer_msg_seq_received").or_insert(0.into());
+                store
+                    .entry("taker_relay_list")
+                    .or_insert(Value::Array(Vec::new()));
+                store
+                    .entry("blocked_pubkeys")
+                    .or_insert(Value::Array(Vec::new()));
+                store
+                    .entry("offer_acceptance_deadline")
+                    .or_insert(Value::Null);
+            }
+            2 => {
+                store.entry("max_offers").or_insert(Value::Null);
+            }
+            3 => {
+                store
+                    .entry("peer_message_log")
+                    .or_insert(Value::Array(Vec::new()));
+                store.entry("peer_message_log_capacity").or_insert(0.into());
+            }
+            4 => {
+                store
+                    .entry("rejected_offers")
+                    .or_insert(Value::Object(Map::new()));
+            }
+            5 => {
+                store
+                    .entry("received_peer_message_ids")
+                    .or_insert(Value::Array(Vec::new()));
+            }
+            6 => {
+                store.entry("order_expiration").or_insert(Value::Null);
+                store.entry("delete_on_expiry").or_insert(false.into());
+            }
+            7 => {
+                store.entry("encryption_secret_key").or_insert(Value::Null);
+            }
+            8 => {
+                store.entry("offers_seen_at").or_insert(Value::Null);
+            }
+            9 => {
+                store.entry("notify_order_seen_on").or_insert(false.into());
+            }
+            10 => {
+                store
+                    .entry("settlement_infos")
+                    .or_insert(Value::Array(Vec::new()));
+                store.entry("disputes").or_insert(Value::Array(Vec::new()));
+            }
+            _ => {
+                return Err(N3xbError::Simple(format!(
+                    "Maker Data has no migration from version {}",
+                    version
+                )));
+            }
+        }
+        Ok(())
+    }
+
     fn read_store(&self) -> RwLockReadGuard<'_, MakerDataStore> {
         match self.store.read() {
             Ok(store) => store,
@@ -134,10 +286,25 @@ impl MakerData {
         self.read_store().order_event_id.to_owned()
     }
 
+    pub(crate) fn order_expiration(&self) -> Option<u64> {
+        self.read_store().order_expiration
+    }
+
     pub(crate) fn offer_envelopes(&self) -> HashMap<EventIdString, OfferEnvelope> {
         self.read_store().offer_envelopes.to_owned()
     }
 
+    pub(crate) fn offer_envelopes_sorted(&self) -> Vec<OfferEnvelope> {
+        let mut offer_envelopes: Vec<OfferEnvelope> =
+            self.read_store().offer_envelopes.values().cloned().collect();
+        offer_envelopes.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.event_id.cmp(&b.event_id))
+        });
+        offer_envelopes
+    }
+
     pub(crate) fn accepted_offer_event_id(&self) -> Option<EventIdString> {
         self.read_store().accepted_offer_event_id.to_owned()
     }
@@ -156,19 +323,115 @@ impl MakerData {
         self.read_store().trade_completed
     }
 
+    pub(crate) fn peer_msg_seq_sent(&self) -> u64 {
+        self.read_store().peer_msg_seq_sent
+    }
+
+    pub(crate) fn peer_msg_seq_received(&self) -> u64 {
+        self.read_store().peer_msg_seq_received
+    }
+
+    pub(crate) fn taker_relay_list(&self) -> HashSet<Url> {
+        self.read_store().taker_relay_list.to_owned()
+    }
+
+    pub(crate) fn blocked_pubkeys(&self) -> HashSet<XOnlyPublicKey> {
+        self.read_store().blocked_pubkeys.to_owned()
+    }
+
     pub(crate) fn reject_invalid_offers_silently(&self) -> bool {
         self.read_store().reject_invalid_offers_silently.to_owned()
     }
 
+    pub(crate) fn offer_acceptance_deadline(&self) -> Option<Duration> {
+        self.read_store().offer_acceptance_deadline.to_owned()
+    }
+
+    pub(crate) fn max_offers(&self) -> Option<MaxOffers> {
+        self.read_store().max_offers.to_owned()
+    }
+
+    pub(crate) fn peer_message_log(&self) -> Vec<PeerMessageLogEntry> {
+        self.read_store().peer_message_log.iter().cloned().collect()
+    }
+
+    pub(crate) fn settlement_infos(&self) -> Vec<TradeRecordEntry<SettlementInfo>> {
+        self.read_store().settlement_infos.clone()
+    }
+
+    pub(crate) fn disputes(&self) -> Vec<TradeRecordEntry<DisputeRequest>> {
+        self.read_store().disputes.clone()
+    }
+
+    pub(crate) fn delete_on_expiry(&self) -> bool {
+        self.read_store().delete_on_expiry
+    }
+
+    pub(crate) fn notify_order_seen_on(&self) -> bool {
+        self.read_store().notify_order_seen_on
+    }
+
+    pub(crate) fn encryption_secret_key(&self) -> Option<SecretKey> {
+        self.read_store().encryption_secret_key
+    }
+
+    pub(crate) fn offers_seen_at(&self) -> Option<u64> {
+        self.read_store().offers_seen_at
+    }
+
+    pub(crate) fn rejected_offers(&self) -> HashMap<EventIdString, RejectedOffer> {
+        self.read_store().rejected_offers.to_owned()
+    }
+
     // Setter methods
 
+    // Returns true if a message of the same message ID was already received
+    pub(crate) fn record_peer_message_received(&mut self, envelope: &PeerEnvelope) -> bool {
+        let resend = envelope.is_resend(
+            &mut self.write_store().received_peer_message_ids,
+            PEER_MESSAGE_RECEIVED_IDS_CAPACITY,
+        );
+        if !resend {
+            self.persister.queue();
+        }
+        resend
+    }
+
+    pub(crate) fn push_settlement_info(&mut self, entry: TradeRecordEntry<SettlementInfo>) {
+        self.write_store().settlement_infos.push(entry);
+        self.persister.queue();
+    }
+
+    pub(crate) fn push_dispute(&mut self, entry: TradeRecordEntry<DisputeRequest>) {
+        self.write_store().disputes.push(entry);
+        self.persister.queue();
+    }
+
+    pub(crate) fn push_peer_message_log(&mut self, entry: PeerMessageLogEntry) {
+        let mut store = self.write_store();
+        let capacity = store.peer_message_log_capacity;
+        if capacity == 0 {
+            return;
+        }
+        PeerMessageLogEntry::push_capped(&mut store.peer_message_log, capacity, entry);
+        drop(store);
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_order(&mut self, order: Order) {
+        self.write_store().order = order;
+        self.persister.queue();
+    }
+
     pub(crate) fn update_maker_order(
         &mut self,
         order_event_id: EventIdString,
         relay_urls: HashSet<Url>,
+        order_expiration: Option<u64>,
     ) {
         self.write_store().order_event_id = Some(order_event_id);
         self.write_store().relay_urls = relay_urls;
+        self.write_store().order_expiration = order_expiration;
         self.persister.queue();
     }
 
@@ -183,6 +446,62 @@ impl MakerData {
         self.persister.queue();
     }
 
+    pub(crate) fn remove_offer_envelope(
+        &mut self,
+        offer_event_id: &EventIdString,
+    ) -> Option<OfferEnvelope> {
+        let offer_envelope = self.write_store().offer_envelopes.remove(offer_event_id);
+        self.persister.queue();
+        offer_envelope
+    }
+
+    // The earliest rejected give way once over the limit
+    pub(crate) fn insert_rejected_offer(
+        &mut self,
+        offer_event_id: EventIdString,
+        rejected_offer: RejectedOffer,
+        limit: usize,
+    ) {
+        let mut store = self.write_store();
+        store.rejected_offers.insert(offer_event_id, rejected_offer);
+        while store.rejected_offers.len() > limit {
+            let Some(earliest_event_id) = store
+                .rejected_offers
+                .iter()
+                .min_by_key(|(event_id, rejected_offer)| (rejected_offer.rejected_at, *event_id))
+                .map(|(event_id, _)| event_id.clone())
+            else {
+                break;
+            };
+            store.rejected_offers.remove(&earliest_event_id);
+        }
+        drop(store);
+        self.persister.queue();
+    }
+
+    pub(crate) fn remove_rejected_offer(
+        &mut self,
+        offer_event_id: &EventIdString,
+    ) -> Option<RejectedOffer> {
+        let rejected_offer = self.write_store().rejected_offers.remove(offer_event_id);
+        self.persister.queue();
+        rejected_offer
+    }
+
+    // Drops Rejected Offers rejected before the cutoff, in Unix seconds
+    pub(crate) fn prune_rejected_offers(&mut self, cutoff: u64) {
+        let mut store = self.write_store();
+        let count = store.rejected_offers.len();
+        store
+            .rejected_offers
+            .retain(|_, rejected_offer| rejected_offer.rejected_at >= cutoff);
+        let pruned = store.rejected_offers.len() != count;
+        drop(store);
+        if pruned {
+            self.persister.queue();
+        }
+    }
+
     pub(crate) fn set_accepted_offer_event_id(&mut self, accepted_offer_event_id: EventIdString) {
         self.write_store().accepted_offer_event_id = Some(accepted_offer_event_id);
         self.persister.queue();
@@ -203,6 +522,41 @@ impl MakerData {
         self.persister.queue();
     }
 
+    // Wiped once the trade ends, so the per-trade key doesn't linger on disk
+    pub(crate) fn clear_encryption_secret_key(&mut self) {
+        self.write_store().encryption_secret_key = None;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_peer_msg_seq_sent(&mut self, peer_msg_seq_sent: u64) {
+        self.write_store().peer_msg_seq_sent = peer_msg_seq_sent;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_peer_msg_seq_received(&mut self, peer_msg_seq_received: u64) {
+        self.write_store().peer_msg_seq_received = peer_msg_seq_received;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_taker_relay_list(&mut self, taker_relay_list: HashSet<Url>) {
+        self.write_store().taker_relay_list = taker_relay_list;
+        self.persister.queue();
+    }
+
+    // Only ever moves forward, as Offers can arrive out of order
+    pub(crate) fn set_offers_seen_at(&mut self, seen_at: u64) {
+        let mut store = self.write_store();
+        let offers_seen_at = store.offers_seen_at.unwrap_or_default().max(seen_at);
+        store.offers_seen_at = Some(offers_seen_at);
+        drop(store);
+        self.persister.queue();
+    }
+
+    pub(crate) fn insert_blocked_pubkey(&mut self, pubkey: XOnlyPublicKey) {
+        self.write_store().blocked_pubkeys.insert(pubkey);
+        self.persister.queue();
+    }
+
     pub(crate) fn set_reject_invalid_offers_silently(
         &mut self,
         reject_invalid_offers_silently: bool,
@@ -1,30 +1,109 @@
-use std::{collections::HashMap, path::Path};
+use secp256k1::{rand::rngs::OsRng, Secp256k1, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use strum_macros::{Display, IntoStaticStr};
 use tracing::{debug, error, info, warn};
+use url::Url;
 use uuid::Uuid;
 
 use tokio::{
     select,
     sync::{mpsc, oneshot},
+    time::{interval, sleep, sleep_until, Instant, MissedTickBehavior},
 };
 
-use super::data::MakerData;
+use super::data::{MakerData, RejectedOffer};
 
 use crate::{
     common::{
         error::{N3xbError, OfferInvalidReason},
+        store::N3xbStore,
         types::{EventIdString, SerdeGenericTrait, SerdeGenericType},
+        utils::send_rsp,
     },
     comms::CommsAccess,
+    dispute::{DisputeRequest, DisputeRequestEnvelope},
     offer::{Offer, OfferEnvelope},
-    order::Order,
-    peer_msg::PeerEnvelope,
+    order::{Order, TradeDetails},
+    peer_msg::{
+        OfferWithdrawal, PeerEnvelope, PeerMessageGap, PeerMessageLogEntry,
+        PeerMessageReorderBuffer, PendingPeerMessage, PEER_MESSAGE_RESEND_DELAY,
+        PEER_MESSAGE_SEND_ATTEMPTS,
+    },
+    settlement::{SettlementInfo, SettlementInfoEnvelope},
+    trade_record::{TradeRecord, TradeRecordEntry},
     trade_rsp::{TradeResponse, TradeResponseBuilder, TradeResponseStatus},
 };
 
 pub enum MakerNotif {
     Offer(OfferEnvelope),
+    SettlementInfo(SettlementInfoEnvelope),
+    TradeComplete,
+    Dispute(DisputeRequestEnvelope),
+    PeerMessageGap(PeerMessageGap),
     Peer(PeerEnvelope),
+    OrderExpired, // Maker Order Note deleted past expiry, see MakerOpts::delete_on_expiry
+    OrderSeenOn(Url), // Maker Order Note observed on a relay, see MakerOpts::notify_order_seen_on
+    OfferWithdrawn(EventIdString), // Pending Offer withdrawn by its Taker, and no longer held
+}
+
+// Phase of a Maker's trade as derived from its persisted data, so restored Makers can be resumed accordingly
+#[derive(Clone, Copy, Debug, PartialEq, Display, IntoStaticStr)]
+pub enum MakerPhase {
+    New,       // Order not yet posted
+    Pending,   // Order posted, no Offer accepted yet
+    Trading,   // Offer accepted
+    Completed, // Trade marked as complete
+}
+
+// Finer grained lifecycle of a Maker's trade, for presenting to users
+#[derive(Clone, Copy, Debug, PartialEq, Display, IntoStaticStr)]
+pub enum MakerTradeStatus {
+    Draft,         // Order not yet posted
+    Posted,        // Order posted, no Offer received yet
+    OfferReceived, // Offers received, none accepted yet
+    Accepted,      // Offer accepted, no peer messages exchanged yet
+    Trading,       // Offer accepted, peer messages exchanged
+    Complete,      // Trade marked as complete
+    Cancelled,     // Order cancelled or expired before any Offer was accepted
+}
+
+// Where an Offer received stands with the Maker. Rejected Offers are only tracked for as long
+// as they are retained for reconsideration
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OfferStatus {
+    Pending,
+    Accepted,
+    Rejected(OfferInvalidReason),
+}
+
+// Which Offer gives way when a new Offer takes a Maker over its maximum number of stored Offers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OfferEvictionPolicy {
+    KeepEarliest,      // The newest Offer is evicted, which is usually the one just received
+    KeepLatest,        // The oldest Offer is evicted
+    KeepHighestAmount, // The Offer with the lowest Maker obligation amount is evicted
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaxOffers {
+    pub limit: usize,
+    pub policy: OfferEvictionPolicy,
+    pub notify_evicted: bool, // Send a rejection to Takers whose stored Offers are evicted
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MakerOpts {
+    pub offer_acceptance_deadline: Option<Duration>, // Pending Offers are rejected as Expired after this
+    pub max_offers: Option<MaxOffers>,               // Unbounded if None
+    pub peer_message_log_capacity: usize, // Peer messages persisted for rebuilding a restored Trade
+    pub delete_on_expiry: bool, // Take the Order down at expiry if no Offer was accepted, for relays ignoring NIP-40
+    pub trade_encryption_key: bool, // Agree on per-trade keys for peer messages with Takers offering one
+    pub notify_order_seen_on: bool, // Notify OrderSeenOn for each relay the Order Note shows up on
 }
 
 #[derive(Clone)]
@@ -44,6 +123,59 @@ impl MakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    // The Order may still be posted after timing out, as the Maker keeps working on the request
+    pub async fn post_new_order_with_timeout(&self, timeout: Duration) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::SendMakerOrder { rsp_tx };
+        // Queueing counts towards the timeout too, for when requests are backed up
+        let send_and_rsp = async {
+            self.tx.send(request).await?;
+            rsp_rx.await?
+        };
+        select! {
+            result = send_and_rsp => result,
+            _ = sleep(timeout) => Err(N3xbError::Timeout("Post New Order".to_string())),
+        }
+    }
+
+    pub async fn query_phase(&self) -> MakerPhase {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<MakerPhase>();
+        let request = MakerRequest::QueryPhase { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn status(&self) -> MakerTradeStatus {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<MakerTradeStatus>();
+        let request = MakerRequest::QueryStatus { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Pubkey of the Taker whose Offer was accepted, if any
+    pub async fn counterparty_pubkey(&self) -> Option<XOnlyPublicKey> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Option<XOnlyPublicKey>>();
+        let request = MakerRequest::QueryCounterpartyPubkey { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Latest Trade Engine specific messages exchanged, if logging was enabled through MakerOpts
+    pub async fn peer_message_log(&self) -> Vec<PeerMessageLogEntry> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Vec<PeerMessageLogEntry>>();
+        let request = MakerRequest::QueryPeerMessageLog { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Everything exchanged for the trade so far, for archiving once the trade completes
+    pub async fn trade_record(&self) -> TradeRecord {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<TradeRecord>();
+        let request = MakerRequest::QueryTradeRecord { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn query_offers(&self) -> HashMap<EventIdString, OfferEnvelope> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<HashMap<EventIdString, OfferEnvelope>>();
         let request = MakerRequest::QueryOffers { rsp_tx };
@@ -51,6 +183,21 @@ impl MakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub async fn query_offers_sorted(&self) -> Vec<OfferEnvelope> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Vec<OfferEnvelope>>();
+        let request = MakerRequest::QueryOffersSorted { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Offers of the given status, in the order received
+    pub async fn query_offers_by_status(&self, status: OfferStatus) -> Vec<OfferEnvelope> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Vec<OfferEnvelope>>();
+        let request = MakerRequest::QueryOffersByStatus { status, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn query_offer(&self, event_id: EventIdString) -> Option<OfferEnvelope> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Option<OfferEnvelope>>();
         let request = MakerRequest::QueryOffer { event_id, rsp_tx };
@@ -72,6 +219,60 @@ impl MakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    // Moves an Offer explicitly rejected within the retention window back to pending, so it can
+    // be accepted after all. The Offer must still be valid against the current Order
+    pub async fn reconsider_offer(&self, event_id: EventIdString) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::ReconsiderOffer { event_id, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Refetches Offers DMed since the newest Offer seen, and processes any that were missed while
+    // disconnected. New Offers are notified as usual. Returns the number of valid Offers picked up
+    pub async fn resync_offers(&self) -> Result<usize, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<usize, N3xbError>>();
+        let request = MakerRequest::ResyncOffers { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Rejects all pending Offers from the pubkey. Returns the number of Offers rejected
+    pub async fn reject_offers_from(
+        &self,
+        pubkey: XOnlyPublicKey,
+        reason: OfferInvalidReason,
+    ) -> Result<usize, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<usize, N3xbError>>();
+        let request = MakerRequest::RejectOffersFrom {
+            pubkey,
+            reason,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Rejects all pending and future Offers from the pubkey as Blocked
+    pub async fn block_taker(&self, pubkey: XOnlyPublicKey) -> Result<usize, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<usize, N3xbError>>();
+        let request = MakerRequest::BlockTaker { pubkey, rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Amends the trading terms of an Order not yet accepted. A posted Order is republished,
+    // while Offers received so far are kept
+    pub async fn update_trade_details(&self, trade_details: TradeDetails) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::UpdateTradeDetails {
+            trade_details,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn cancel_order(&self) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
         let request = MakerRequest::CancelOrder { rsp_tx };
@@ -92,6 +293,29 @@ impl MakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub async fn send_settlement_info(
+        &self,
+        settlement_info: SettlementInfo,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::SendSettlementInfo {
+            settlement_info,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn raise_dispute(&self, dispute_request: DisputeRequest) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::RaiseDispute {
+            dispute_request,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn trade_complete(&self) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
         let request = MakerRequest::TradeComplete { rsp_tx };
@@ -99,6 +323,20 @@ impl MakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub async fn pause(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::Pause { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn resume(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = MakerRequest::Resume { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn register_notif_tx(
         &self,
         tx: mpsc::Sender<Result<MakerNotif, N3xbError>>,
@@ -135,20 +373,23 @@ impl Maker {
     pub(crate) fn new(
         comms_accessor: CommsAccess,
         order: Order,
-        maker_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        maker_dir_key: impl AsRef<str>,
+        opts: MakerOpts,
     ) -> Self {
         let (tx, rx) = mpsc::channel::<MakerRequest>(Self::MAKER_REQUEST_CHANNEL_SIZE);
-        let actor = MakerActor::new(rx, comms_accessor, order, maker_dir_path);
+        let actor = MakerActor::new(rx, comms_accessor, order, backend, maker_dir_key, opts);
         let task_handle = tokio::spawn(async move { actor.run().await });
         Self { tx, task_handle }
     }
 
     pub(crate) fn restore(
         comms_accessor: CommsAccess,
-        maker_data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        maker_data_key: impl AsRef<str>,
     ) -> Result<(Uuid, Self), N3xbError> {
         let (tx, rx) = mpsc::channel::<MakerRequest>(Self::MAKER_REQUEST_CHANNEL_SIZE);
-        let (trade_uuid, actor) = MakerActor::restore(rx, comms_accessor, maker_data_path)?;
+        let (trade_uuid, actor) = MakerActor::restore(rx, comms_accessor, backend, maker_data_key)?;
         let task_handle = tokio::spawn(async move { actor.run().await });
         let maker = Self { tx, task_handle };
         Ok((trade_uuid, maker))
@@ -164,9 +405,31 @@ pub(super) enum MakerRequest {
     SendMakerOrder {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    QueryPhase {
+        rsp_tx: oneshot::Sender<MakerPhase>,
+    },
+    QueryStatus {
+        rsp_tx: oneshot::Sender<MakerTradeStatus>,
+    },
+    QueryCounterpartyPubkey {
+        rsp_tx: oneshot::Sender<Option<XOnlyPublicKey>>,
+    },
+    QueryPeerMessageLog {
+        rsp_tx: oneshot::Sender<Vec<PeerMessageLogEntry>>,
+    },
+    QueryTradeRecord {
+        rsp_tx: oneshot::Sender<TradeRecord>,
+    },
     QueryOffers {
         rsp_tx: oneshot::Sender<HashMap<EventIdString, OfferEnvelope>>,
     },
+    QueryOffersSorted {
+        rsp_tx: oneshot::Sender<Vec<OfferEnvelope>>,
+    },
+    QueryOffersByStatus {
+        status: OfferStatus,
+        rsp_tx: oneshot::Sender<Vec<OfferEnvelope>>,
+    },
     QueryOffer {
         event_id: EventIdString,
         rsp_tx: oneshot::Sender<Option<OfferEnvelope>>,
@@ -179,6 +442,26 @@ pub(super) enum MakerRequest {
         trade_rsp: TradeResponse,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    ReconsiderOffer {
+        event_id: EventIdString,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    ResyncOffers {
+        rsp_tx: oneshot::Sender<Result<usize, N3xbError>>,
+    },
+    RejectOffersFrom {
+        pubkey: XOnlyPublicKey,
+        reason: OfferInvalidReason,
+        rsp_tx: oneshot::Sender<Result<usize, N3xbError>>,
+    },
+    BlockTaker {
+        pubkey: XOnlyPublicKey,
+        rsp_tx: oneshot::Sender<Result<usize, N3xbError>>,
+    },
+    UpdateTradeDetails {
+        trade_details: TradeDetails,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     CancelOrder {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
@@ -186,9 +469,23 @@ pub(super) enum MakerRequest {
         message: Box<dyn SerdeGenericTrait>,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    SendSettlementInfo {
+        settlement_info: SettlementInfo,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RaiseDispute {
+        dispute_request: DisputeRequest,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     TradeComplete {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    Pause {
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    Resume {
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     RegisterNotifTx {
         tx: mpsc::Sender<Result<MakerNotif, N3xbError>>,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
@@ -206,38 +503,87 @@ struct MakerActor {
     comms_accessor: CommsAccess,
     data: MakerData,
     notif_tx: Option<mpsc::Sender<Result<MakerNotif, N3xbError>>>,
+    paused: bool,
+    paused_envelopes: VecDeque<PeerEnvelope>,
+    offer_deadlines: HashMap<EventIdString, Instant>,
+    order_expiry: Option<Instant>,
+    order_seen_tx: Option<mpsc::Sender<Url>>,
+    decrypt_failure_tx: Option<mpsc::Sender<N3xbError>>,
+    pending_peer_message: Option<PendingPeerMessage>,
+    reorder_buffer: PeerMessageReorderBuffer,
 }
 
 impl MakerActor {
+    const PAUSED_PEER_MESSAGE_BUFFER_SIZE: usize = 50;
+    const TAKER_RELAY_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+    const MAX_TAKER_RELAY_LIST_RELAYS: usize = 5;
+    const REJECTED_OFFER_RETENTION: Duration = Duration::from_secs(10 * 60);
+    const MAX_REJECTED_OFFERS: usize = 100;
+    const OFFER_RESYNC_MARGIN: Duration = Duration::from_secs(60); // For clock skew across relays
+    const ORDER_EXPIRY_RETRY_DELAY: Duration = Duration::from_secs(30);
+
     pub(crate) fn new(
         rx: mpsc::Receiver<MakerRequest>,
         comms_accessor: CommsAccess,
         order: Order,
-        maker_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        maker_dir_key: impl AsRef<str>,
+        opts: MakerOpts,
     ) -> Self {
-        let data = MakerData::new(maker_dir_path, order, true);
+        let data = MakerData::new(
+            backend,
+            maker_dir_key,
+            order,
+            true,
+            opts.offer_acceptance_deadline,
+            opts.max_offers,
+            opts.peer_message_log_capacity,
+            opts.delete_on_expiry,
+            opts.notify_order_seen_on,
+            opts.trade_encryption_key
+                .then(|| Secp256k1::new().generate_keypair(&mut OsRng).0),
+        );
 
         MakerActor {
             rx,
             comms_accessor,
             data,
             notif_tx: None,
+            paused: false,
+            paused_envelopes: VecDeque::new(),
+            offer_deadlines: HashMap::new(),
+            order_expiry: None,
+            order_seen_tx: None,
+            decrypt_failure_tx: None,
+            pending_peer_message: None,
+            reorder_buffer: PeerMessageReorderBuffer::default(),
         }
     }
 
     pub(crate) fn restore(
         rx: mpsc::Receiver<MakerRequest>,
         comms_accessor: CommsAccess,
-        maker_data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        maker_data_key: impl AsRef<str>,
     ) -> Result<(Uuid, Self), N3xbError> {
-        let (trade_uuid, data) = MakerData::restore(maker_data_path)?;
+        let (trade_uuid, data) = MakerData::restore(backend, maker_data_key)?;
 
-        let actor = MakerActor {
+        let mut actor = MakerActor {
             rx,
             comms_accessor,
             data,
             notif_tx: None,
+            paused: false,
+            paused_envelopes: VecDeque::new(),
+            offer_deadlines: HashMap::new(),
+            order_expiry: None,
+            order_seen_tx: None,
+            decrypt_failure_tx: None,
+            pending_peer_message: None,
+            reorder_buffer: PeerMessageReorderBuffer::default(),
         };
+        actor.rearm_offer_deadlines();
+        actor.arm_order_expiry();
 
         Ok((trade_uuid, actor))
     }
@@ -257,8 +603,30 @@ impl MakerActor {
                 error
             );
         }
+        self.register_trade_encryption().await;
+
+        let (order_seen_tx, mut order_seen_rx) = mpsc::channel::<Url>(20);
+        self.order_seen_tx = Some(order_seen_tx);
+        self.watch_order_propagation().await;
+
+        let (decrypt_failure_tx, mut decrypt_failure_rx) = mpsc::channel::<N3xbError>(20);
+        self.decrypt_failure_tx = Some(decrypt_failure_tx);
+        self.watch_counterparty().await;
+
+        // Ticks missed while no trade is in progress are not to be caught up in a burst
+        let mut taker_relay_list_refresh = interval(Self::TAKER_RELAY_LIST_REFRESH_INTERVAL);
+        taker_relay_list_refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         loop {
+            let next_offer_deadline = self.offer_deadlines.values().min().cloned();
+            let next_resend = self
+                .pending_peer_message
+                .as_ref()
+                .map(|pending| pending.resend_at);
+            let reorder_deadline = self.reorder_buffer.deadline();
+            let trade_in_progress =
+                self.data.accepted_offer_event_id().is_some() && !self.data.trade_completed();
+
             select! {
                 Some(request) = self.rx.recv() => {
                     if self.handle_request(request).await {
@@ -266,12 +634,38 @@ impl MakerActor {
                     }
                 },
                 Some(envelope) = rx.recv() => {
-                    self.handle_peer_message(envelope).await;
+                    if self.paused {
+                        self.buffer_peer_message(envelope);
+                    } else {
+                        self.handle_peer_message(envelope).await;
+                    }
+                },
+                _ = sleep_until(next_offer_deadline.unwrap_or_else(Instant::now)), if next_offer_deadline.is_some() => {
+                    self.expire_offers().await;
+                },
+                _ = sleep_until(self.order_expiry.unwrap_or_else(Instant::now)), if self.order_expiry.is_some() => {
+                    self.expire_order().await;
+                },
+                _ = sleep_until(next_resend.unwrap_or_else(Instant::now)), if next_resend.is_some() => {
+                    self.resend_peer_message().await;
+                },
+                _ = sleep_until(reorder_deadline.unwrap_or_else(Instant::now)), if reorder_deadline.is_some() => {
+                    self.flush_reordered_peer_messages().await;
+                },
+                _ = taker_relay_list_refresh.tick(), if trade_in_progress => {
+                    self.refresh_taker_relay_list().await;
+                },
+                Some(url) = order_seen_rx.recv() => {
+                    self.notify_order_seen_on(url).await;
+                },
+                Some(error) = decrypt_failure_rx.recv() => {
+                    self.notify_decrypt_failure(error).await;
                 },
                 else => break,
             }
         }
         info!("Maker w/ TradeUUID {} terminating", self.data.trade_uuid);
+        self.abandon_peer_message();
         self.data.terminate();
     }
 
@@ -287,7 +681,20 @@ impl MakerActor {
 
         match request {
             MakerRequest::SendMakerOrder { rsp_tx } => self.send_maker_order(rsp_tx).await,
+            MakerRequest::QueryPhase { rsp_tx } => self.query_phase(rsp_tx),
+            MakerRequest::QueryStatus { rsp_tx } => self.query_status(rsp_tx),
+            MakerRequest::QueryCounterpartyPubkey { rsp_tx } => {
+                self.query_counterparty_pubkey(rsp_tx)
+            }
+            MakerRequest::QueryPeerMessageLog { rsp_tx } => {
+                send_rsp(rsp_tx, self.data.peer_message_log());
+            }
+            MakerRequest::QueryTradeRecord { rsp_tx } => self.query_trade_record(rsp_tx).await,
             MakerRequest::QueryOffers { rsp_tx } => self.query_offers(rsp_tx),
+            MakerRequest::QueryOffersSorted { rsp_tx } => self.query_offers_sorted(rsp_tx),
+            MakerRequest::QueryOffersByStatus { status, rsp_tx } => {
+                self.query_offers_by_status(status, rsp_tx)
+            }
             MakerRequest::QueryOffer { event_id, rsp_tx } => {
                 self.query_offer(event_id, rsp_tx);
             }
@@ -297,15 +704,60 @@ impl MakerActor {
             MakerRequest::RejectOffer { trade_rsp, rsp_tx } => {
                 self.reject_offer(trade_rsp, rsp_tx).await;
             }
+            MakerRequest::ReconsiderOffer { event_id, rsp_tx } => {
+                self.reconsider_offer(event_id, rsp_tx).await;
+            }
+            MakerRequest::ResyncOffers { rsp_tx } => {
+                self.resync_offers(rsp_tx).await;
+            }
+            MakerRequest::RejectOffersFrom {
+                pubkey,
+                reason,
+                rsp_tx,
+            } => {
+                let rejected_count = self.reject_offers_from(pubkey, reason).await;
+                send_rsp(rsp_tx, Ok(rejected_count));
+            }
+            MakerRequest::BlockTaker { pubkey, rsp_tx } => {
+                self.data.insert_blocked_pubkey(pubkey);
+                let rejected_count = self
+                    .reject_offers_from(pubkey, OfferInvalidReason::Blocked)
+                    .await;
+                send_rsp(rsp_tx, Ok(rejected_count));
+            }
+            MakerRequest::UpdateTradeDetails {
+                trade_details,
+                rsp_tx,
+            } => {
+                self.update_trade_details(trade_details, rsp_tx).await;
+            }
             MakerRequest::CancelOrder { rsp_tx } => {
                 self.cancel_order(rsp_tx).await;
             }
             MakerRequest::PeerMessage { message, rsp_tx } => {
                 self.send_peer_message(message, rsp_tx).await;
             }
+            MakerRequest::SendSettlementInfo {
+                settlement_info,
+                rsp_tx,
+            } => {
+                self.send_settlement_info(settlement_info, rsp_tx).await;
+            }
+            MakerRequest::RaiseDispute {
+                dispute_request,
+                rsp_tx,
+            } => {
+                self.raise_dispute(dispute_request, rsp_tx).await;
+            }
             MakerRequest::TradeComplete { rsp_tx } => {
                 self.trade_complete(rsp_tx).await;
             }
+            MakerRequest::Pause { rsp_tx } => {
+                self.pause(rsp_tx);
+            }
+            MakerRequest::Resume { rsp_tx } => {
+                self.resume(rsp_tx).await;
+            }
             MakerRequest::RegisterNotifTx { tx, rsp_tx } => {
                 self.register_notif_tx(tx, rsp_tx);
             }
@@ -322,7 +774,7 @@ impl MakerActor {
 
     async fn send_maker_order(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
@@ -330,18 +782,147 @@ impl MakerActor {
         let result = self.comms_accessor.send_maker_order_note(order).await;
         match result {
             Ok(order_envelope) => {
-                self.data
-                    .update_maker_order(order_envelope.event_id, order_envelope.urls);
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                // No Offer can predate the Order Note
+                self.data.set_offers_seen_at(order_envelope.created_at);
+                self.data.update_maker_order(
+                    order_envelope.event_id,
+                    order_envelope.urls,
+                    order_envelope.expiration,
+                );
+                self.arm_order_expiry();
+                self.watch_order_propagation().await;
+                send_rsp(rsp_tx, Ok(()));
             }
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
             }
         }
     }
 
+    fn query_phase(&mut self, rsp_tx: oneshot::Sender<MakerPhase>) {
+        let phase = if self.data.trade_completed() {
+            MakerPhase::Completed
+        } else if self.data.accepted_offer_event_id().is_some() {
+            MakerPhase::Trading
+        } else if self.data.order_event_id().is_some() {
+            MakerPhase::Pending
+        } else {
+            MakerPhase::New
+        };
+        send_rsp(rsp_tx, phase);
+    }
+
+    fn query_status(&mut self, rsp_tx: oneshot::Sender<MakerTradeStatus>) {
+        let accepted = self.data.accepted_offer_event_id().is_some();
+        let status = if self.data.trade_completed() {
+            if accepted {
+                MakerTradeStatus::Complete
+            } else {
+                MakerTradeStatus::Cancelled
+            }
+        } else if accepted {
+            if self.data.peer_msg_seq_sent() == 0 && self.data.peer_msg_seq_received() == 0 {
+                MakerTradeStatus::Accepted
+            } else {
+                MakerTradeStatus::Trading
+            }
+        } else if !self.data.offer_envelopes().is_empty() {
+            MakerTradeStatus::OfferReceived
+        } else if self.data.order_event_id().is_some() {
+            MakerTradeStatus::Posted
+        } else {
+            MakerTradeStatus::Draft
+        };
+        send_rsp(rsp_tx, status);
+    }
+
+    fn query_counterparty_pubkey(&mut self, rsp_tx: oneshot::Sender<Option<XOnlyPublicKey>>) {
+        let pubkey = self
+            .accepted_counterparty()
+            .ok()
+            .map(|(pubkey, _maker_order_note_id)| pubkey);
+        send_rsp(rsp_tx, pubkey);
+    }
+
+    async fn query_trade_record(&mut self, rsp_tx: oneshot::Sender<TradeRecord>) {
+        let accepted_offer_event_id = self.data.accepted_offer_event_id();
+        let accepted_offer_envelope = accepted_offer_event_id
+            .as_ref()
+            .and_then(|event_id| self.data.offer_envelopes().remove(event_id));
+
+        let trade_record = TradeRecord {
+            trade_uuid: self.data.trade_uuid,
+            maker_pubkey: self.comms_accessor.get_pubkey().await,
+            taker_pubkey: accepted_offer_envelope
+                .as_ref()
+                .map(|offer_envelope| offer_envelope.pubkey),
+            order: self.data.order(),
+            order_event_id: self.data.order_event_id(),
+            offer: accepted_offer_envelope.map(|offer_envelope| offer_envelope.offer),
+            offer_event_id: accepted_offer_event_id,
+            trade_rsp: self.data.trade_rsp(),
+            trade_rsp_event_id: self.data.trade_rsp_event_id(),
+            settlement_infos: self.data.settlement_infos(),
+            disputes: self.data.disputes(),
+            peer_messages: self.data.peer_message_log(),
+            trade_completed: self.data.trade_completed(),
+        };
+        send_rsp(rsp_tx, trade_record);
+    }
+
     fn query_offers(&mut self, rsp_tx: oneshot::Sender<HashMap<EventIdString, OfferEnvelope>>) {
-        rsp_tx.send(self.data.offer_envelopes()).unwrap(); // oneshot should not fail
+        send_rsp(rsp_tx, self.data.offer_envelopes());
+    }
+
+    fn query_offers_sorted(&mut self, rsp_tx: oneshot::Sender<Vec<OfferEnvelope>>) {
+        send_rsp(rsp_tx, self.data.offer_envelopes_sorted());
+    }
+
+    // Offers left stored once another is accepted, or the Order is cancelled, were sent a rejection.
+    // Expired Orders set theirs aside as rejected Offers instead
+    fn query_offers_by_status(
+        &mut self,
+        status: OfferStatus,
+        rsp_tx: oneshot::Sender<Vec<OfferEnvelope>>,
+    ) {
+        let accepted_offer_event_id = self.data.accepted_offer_event_id();
+        let trade_completed = self.data.trade_completed();
+
+        let mut offer_envelopes: Vec<OfferEnvelope> = self
+            .data
+            .offer_envelopes_sorted()
+            .into_iter()
+            .filter(|offer_envelope| {
+                let offer_status = match &accepted_offer_event_id {
+                    Some(event_id) if event_id == &offer_envelope.event_id => OfferStatus::Accepted,
+                    Some(_) => OfferStatus::Rejected(OfferInvalidReason::PendingAnother),
+                    None if trade_completed => OfferStatus::Rejected(OfferInvalidReason::Cancelled),
+                    None => OfferStatus::Pending,
+                };
+                offer_status == status
+            })
+            .collect();
+
+        if let OfferStatus::Rejected(reason) = &status {
+            let mut rejected_offers: Vec<RejectedOffer> = self
+                .data
+                .rejected_offers()
+                .into_values()
+                .filter(|rejected_offer| rejected_offer.reason.as_ref() == Some(reason))
+                .collect();
+            rejected_offers.sort_by(|a, b| {
+                a.offer_envelope
+                    .created_at
+                    .cmp(&b.offer_envelope.created_at)
+                    .then_with(|| a.offer_envelope.event_id.cmp(&b.offer_envelope.event_id))
+            });
+            offer_envelopes.extend(
+                rejected_offers
+                    .into_iter()
+                    .map(|rejected_offer| rejected_offer.offer_envelope),
+            );
+        }
+        send_rsp(rsp_tx, offer_envelopes);
     }
 
     fn query_offer(
@@ -350,7 +931,7 @@ impl MakerActor {
         rsp_tx: oneshot::Sender<Option<OfferEnvelope>>,
     ) {
         let offer = self.data.offer_envelopes().get(&event_id).cloned();
-        rsp_tx.send(offer).unwrap(); // oneshot should not fail
+        send_rsp(rsp_tx, offer);
     }
 
     async fn accept_offer(
@@ -359,7 +940,7 @@ impl MakerActor {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
@@ -372,25 +953,50 @@ impl MakerActor {
                     trade_rsp.offer_event_id
                 )
             );
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
+        // Echo the Order's Trade Details as the binding terms if the Maker did not specify any
+        let mut trade_rsp = trade_rsp;
+        if trade_rsp.trade_response == TradeResponseStatus::Accepted
+            && trade_rsp.agreed_trade_details.is_none()
+        {
+            trade_rsp.agreed_trade_details = Some(self.data.order().trade_details);
+        }
+
         let accepted_offer_event_id = trade_rsp.offer_event_id.clone();
         self.data
             .set_accepted_offer_event_id(accepted_offer_event_id.clone());
+        self.offer_deadlines.clear();
+        self.order_expiry = None;
+
+        let (pubkey, relay_hints, taker_encryption_pubkey) =
+            match self.data.offer_envelopes().get(&accepted_offer_event_id) {
+                Some(offer_envelope) => (
+                    offer_envelope.pubkey.clone(),
+                    offer_envelope.offer.taker_relay_urls.clone(),
+                    offer_envelope.offer.encryption_pubkey,
+                ),
+                None => {
+                    let error = N3xbError::Simple(format!(
+                        "Maker w/ TradeUUID {} expected, but does not contain accepted Offer {}",
+                        self.data.trade_uuid, accepted_offer_event_id
+                    ));
+                    send_rsp(rsp_tx, Err(error));
+                    return;
+                }
+            };
 
-        let pubkey = match self.data.offer_envelopes().get(&accepted_offer_event_id) {
-            Some(offer_envelope) => offer_envelope.pubkey.clone(),
-            None => {
-                let error = N3xbError::Simple(format!(
-                    "Maker w/ TradeUUID {} expected, but does not contain accepted Offer {}",
-                    self.data.trade_uuid, accepted_offer_event_id
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
-                return;
+        // Agree on per-trade keys for peer messages if the Taker offered one
+        if trade_rsp.trade_response == TradeResponseStatus::Accepted
+            && taker_encryption_pubkey.is_some()
+        {
+            if let Some(secret_key) = self.data.encryption_secret_key() {
+                let encryption_pubkey = secret_key.x_only_public_key(&Secp256k1::new()).0;
+                trade_rsp.encryption_pubkey = Some(encryption_pubkey);
             }
-        };
+        }
 
         let maker_order_note_id = match self.data.order_event_id() {
             Some(event_id) => event_id,
@@ -401,7 +1007,7 @@ impl MakerActor {
                         self.data.trade_uuid
                     )
                 );
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
                 return;
             }
         };
@@ -444,15 +1050,18 @@ impl MakerActor {
                 maker_order_note_id.clone(),
                 self.data.trade_uuid,
                 trade_rsp_clone,
+                relay_hints,
             )
             .await;
 
         match result {
             Ok(event_id) => {
                 self.data.set_trade_rsp(trade_rsp, event_id);
+                self.register_trade_encryption().await;
+                self.watch_counterparty().await;
             }
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
                 return;
             }
         }
@@ -462,14 +1071,15 @@ impl MakerActor {
             .comms_accessor
             .delete_maker_order_note(maker_order_note_id.clone(), "Trade Started on Offer Accept")
             .await;
+        self.unwatch_order_propagation().await;
 
         // Send response back to user
         match result {
             Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Ok(()));
             }
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
             }
         }
     }
@@ -480,18 +1090,17 @@ impl MakerActor {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
         let offer_event_id = trade_rsp.offer_event_id.clone();
+        let reason = trade_rsp.reject_reason.first().cloned();
 
-        let pubkey = match self.data.offer_envelopes().get(&offer_event_id) {
-            Some(offer_envelope) => offer_envelope.pubkey.clone(),
-            None => {
-                let error = N3xbError::Simple(format!(
-                    "Maker w/ TradeUUID {} expected, but does not contain Offer {}",
-                    self.data.trade_uuid, offer_event_id
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
-                return;
-            }
+        let Some(offer_envelope) = self.data.offer_envelopes().get(&offer_event_id).cloned() else {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} expected, but does not contain Offer {}",
+                self.data.trade_uuid, offer_event_id
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
         };
+        let pubkey = offer_envelope.pubkey;
 
         let maker_order_note_id = match self.data.order_event_id() {
             Some(event_id) => event_id,
@@ -502,7 +1111,7 @@ impl MakerActor {
                         self.data.trade_uuid
                     )
                 );
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
                 return;
             }
         };
@@ -511,109 +1120,252 @@ impl MakerActor {
             .comms_accessor
             .send_trade_response(
                 pubkey,
-                Some(offer_event_id),
+                Some(offer_event_id.clone()),
                 maker_order_note_id,
                 self.data.trade_uuid,
                 trade_rsp,
+                HashSet::new(),
             )
             .await;
 
         match result {
             Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                self.retain_rejected_offer(offer_envelope, reason);
+                send_rsp(rsp_tx, Ok(()));
             }
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
             }
         }
     }
 
-    async fn cancel_order(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
-        if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+    // Rejected Offers are set aside instead of being dropped, for the Maker to reconsider. Up to a
+    // limit, with the earliest rejected giving way
+    fn retain_rejected_offer(
+        &mut self,
+        offer_envelope: OfferEnvelope,
+        reason: Option<OfferInvalidReason>,
+    ) {
+        let now = Self::now_secs();
+        self.data
+            .prune_rejected_offers(now.saturating_sub(Self::REJECTED_OFFER_RETENTION.as_secs()));
+
+        let offer_event_id = offer_envelope.event_id.clone();
+        self.offer_deadlines.remove(&offer_event_id);
+        self.data.remove_offer_envelope(&offer_event_id);
+
+        if !is_reconsiderable(reason.as_ref()) {
             return;
         }
 
-        let maker_order_note_id = match self.data.order_event_id() {
-            Some(event_id) => event_id,
-            None => {
-                let error = N3xbError::Simple(
-                    format!(
-                        "Maker w/ TradeUUID {} expected to already have sent Maker Order Note and receive Event ID",
-                        self.data.trade_uuid
-                    )
-                );
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
-                return;
-            }
+        let rejected_offer = RejectedOffer {
+            offer_envelope,
+            rejected_at: now,
+            reason,
         };
+        self.data
+            .insert_rejected_offer(offer_event_id, rejected_offer, Self::MAX_REJECTED_OFFERS);
+    }
 
-        // Send Trade Response Cancelled to all Offers received so far
-        for offer_envelope in self.data.offer_envelopes().values() {
-            if let Some(reject_err) = self
-                .reject_taker_offer(offer_envelope.clone(), OfferInvalidReason::Cancelled)
-                .await
-                .err()
-            {
-                error!(
-                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
-                    self.data.trade_uuid, offer_envelope.event_id, reject_err
-                );
-            }
+    async fn reconsider_offer(
+        &mut self,
+        offer_event_id: EventIdString,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
         }
 
-        // Delete Order Note
-        let result = self
-            .comms_accessor
-            .delete_maker_order_note(maker_order_note_id.clone(), "Trade Cancelled")
-            .await;
-
-        self.data.set_trade_completed(true);
+        let now = Self::now_secs();
+        self.data
+            .prune_rejected_offers(now.saturating_sub(Self::REJECTED_OFFER_RETENTION.as_secs()));
 
-        // Send response back to user
-        match result {
-            Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
-            }
+        let Some(rejected_offer) = self.data.rejected_offers().remove(&offer_event_id) else {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} has no Offer {} rejected within the last {} seconds",
+                self.data.trade_uuid,
+                offer_event_id,
+                Self::REJECTED_OFFER_RETENTION.as_secs()
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        };
+        let offer_envelope = rejected_offer.offer_envelope;
+
+        // The Order might have been amended or another Offer accepted since the rejection
+        let reason = if self.data.accepted_offer_event_id().is_some() {
+            Some(OfferInvalidReason::PendingAnother)
+        } else if self.data.blocked_pubkeys().contains(&offer_envelope.pubkey) {
+            Some(OfferInvalidReason::Blocked)
+        } else if !self
+            .data
+            .order()
+            .trade_details
+            .is_taker_trusted(&offer_envelope.pubkey)
+        {
+            Some(OfferInvalidReason::UntrustedTaker)
+        } else {
+            offer_envelope
+                .offer
+                .validate_against(&self.data.order())
+                .err()
+        };
+
+        if let Some(reason) = reason {
+            send_rsp(rsp_tx, Err(N3xbError::InvalidOffer(reason)));
+            return;
+        }
+
+        self.data.remove_rejected_offer(&offer_event_id);
+        self.data
+            .insert_offer_envelope(offer_event_id.clone(), offer_envelope);
+        if let Some(deadline) = self.data.offer_acceptance_deadline() {
+            self.offer_deadlines
+                .insert(offer_event_id, Instant::now() + deadline);
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn resync_offers(&mut self, rsp_tx: oneshot::Sender<Result<usize, N3xbError>>) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        if self.data.accepted_offer_event_id().is_some() {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} already accepted an Offer, no more Offers to resync",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let since = self
+            .data
+            .offers_seen_at()
+            .map(|seen_at| seen_at.saturating_sub(Self::OFFER_RESYNC_MARGIN.as_secs()));
+        let peer_envelopes = match self
+            .comms_accessor
+            .query_peer_messages(self.data.trade_uuid, since)
+            .await
+        {
+            Ok(peer_envelopes) => peer_envelopes,
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        // Offers already seen would otherwise be rejected as duplicates. Only those taken in count
+        let mut resynced_offers: usize = 0;
+        for peer_envelope in peer_envelopes {
+            if !matches!(peer_envelope.message_type, SerdeGenericType::TakerOffer)
+                || self
+                    .data
+                    .offer_envelopes()
+                    .contains_key(&peer_envelope.event_id)
+                || self
+                    .data
+                    .rejected_offers()
+                    .contains_key(&peer_envelope.event_id)
+            {
+                continue;
+            }
+            let offer_event_id = peer_envelope.event_id.clone();
+            self.handle_peer_message(peer_envelope).await;
+            if self.data.offer_envelopes().contains_key(&offer_event_id) {
+                debug!(
+                    "Maker w/ TradeUUID {} resynced missed Offer with Event ID {}",
+                    self.data.trade_uuid, offer_event_id
+                );
+                resynced_offers += 1;
             }
         }
+        send_rsp(rsp_tx, Ok(resynced_offers));
     }
 
-    async fn send_peer_message(
+    fn now_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+
+    async fn update_trade_details(
         &mut self,
-        message: Box<dyn SerdeGenericTrait>,
+        trade_details: TradeDetails,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
-        let accepted_offer_event_id = match self.data.accepted_offer_event_id() {
-            Some(event_id) => event_id,
-            None => {
-                let error = N3xbError::Simple(format!(
-                    "Maker w/ TradeUUID {} expected to already have accepted an Offer",
-                    self.data.trade_uuid
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
-                return;
-            }
+        if self.data.accepted_offer_event_id().is_some() {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} already accepted an Offer, Trade Details can no longer be amended",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Only the Trade Details change, the Obligations are kept as is
+        let mut order = self.data.order();
+        order.trade_details = trade_details;
+        if let Some(error) = order.validate().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let Some(old_order_note_id) = self.data.order_event_id() else {
+            self.data.set_order(order);
+            send_rsp(rsp_tx, Ok(()));
+            return;
         };
 
-        let pubkey = match self.data.offer_envelopes().get(&accepted_offer_event_id) {
-            Some(offer_envelope) => offer_envelope.pubkey.clone(),
-            None => {
-                let error = N3xbError::Simple(format!(
-                    "Maker w/ TradeUUID {} expected, but does not contain accepted Offer {}",
-                    self.data.trade_uuid, accepted_offer_event_id
-                ));
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+        let order_envelope = match self
+            .comms_accessor
+            .send_maker_order_note(order.clone())
+            .await
+        {
+            Ok(order_envelope) => order_envelope,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
                 return;
             }
         };
+        self.data.set_order(order);
+        self.data.update_maker_order(
+            order_envelope.event_id,
+            order_envelope.urls,
+            order_envelope.expiration,
+        );
+        self.arm_order_expiry();
+        self.watch_order_propagation().await;
+
+        // Takers would otherwise still find the superseded Order Note
+        if let Some(error) = self
+            .comms_accessor
+            .delete_maker_order_note(old_order_note_id.clone(), "Trade Details Amended")
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to delete superseded Order Note with Event ID {} - {}",
+                self.data.trade_uuid, old_order_note_id, error
+            );
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    async fn cancel_order(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
 
         let maker_order_note_id = match self.data.order_event_id() {
             Some(event_id) => event_id,
@@ -624,7 +1376,116 @@ impl MakerActor {
                         self.data.trade_uuid
                     )
                 );
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        // Send Trade Response Cancelled to all Offers received so far
+        for offer_envelope in self.data.offer_envelopes().values() {
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope.clone(), OfferInvalidReason::Cancelled)
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, offer_envelope.event_id, reject_err
+                );
+            }
+        }
+
+        // Delete Order Note
+        let result = self
+            .comms_accessor
+            .delete_maker_order_note(maker_order_note_id.clone(), "Trade Cancelled")
+            .await;
+        self.unwatch_order_propagation().await;
+
+        self.data.set_trade_completed(true);
+        self.offer_deadlines.clear();
+        self.order_expiry = None;
+        self.unregister_trade_encryption().await;
+
+        // Send response back to user
+        match result {
+            Ok(_) => {
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+            }
+        }
+    }
+
+    async fn send_peer_message(
+        &mut self,
+        message: Box<dyn SerdeGenericTrait>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        if let Err(error) = self.accepted_counterparty() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Sequences are to be sent in order. The next message has to wait for the pending one
+        if let Some(pending) = &self.pending_peer_message {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} still resending peer message {}",
+                self.data.trade_uuid, pending.message_id
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Only bump the sequence once actually sent, so a failed send doesn't show up as a gap
+        let sequence = self.data.peer_msg_seq_sent() + 1;
+        let log_entry = PeerMessageLogEntry {
+            outgoing: true,
+            sequence: Some(sequence),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            message: message.clone(),
+        };
+
+        // Resends carry the same message ID, so the Taker only processes whichever lands first
+        let pending = PendingPeerMessage {
+            message,
+            sequence,
+            message_id: Uuid::new_v4(),
+            attempt: 1,
+            resend_at: Instant::now(),
+            log_entry,
+            rsp_tx,
+        };
+        self.attempt_peer_message(pending).await;
+    }
+
+    async fn resend_peer_message(&mut self) {
+        let Some(pending) = self.pending_peer_message.take() else {
+            return;
+        };
+
+        // Trade might have been completed or cancelled in between attempts
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(pending.rsp_tx, Err(error));
+            return;
+        }
+        self.attempt_peer_message(pending).await;
+    }
+
+    async fn attempt_peer_message(&mut self, mut pending: PendingPeerMessage) {
+        let (pubkey, maker_order_note_id) = match self.accepted_counterparty() {
+            Ok(counterparty) => counterparty,
+            Err(error) => {
+                send_rsp(pending.rsp_tx, Err(error));
                 return;
             }
         };
@@ -636,104 +1497,631 @@ impl MakerActor {
                 None,
                 maker_order_note_id,
                 self.data.trade_uuid,
-                message,
+                pending.message.clone(),
+                pending.sequence,
+                pending.message_id,
+                self.accepted_offer_relay_hints(),
             )
             .await;
 
         match result {
             Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                self.data.set_peer_msg_seq_sent(pending.sequence);
+                self.data.push_peer_message_log(pending.log_entry);
+                send_rsp(pending.rsp_tx, Ok(()));
+            }
+            Err(error) if pending.attempt < PEER_MESSAGE_SEND_ATTEMPTS => {
+                warn!(
+                    "Maker w/ TradeUUID {} failed to send peer message {} on attempt {} - {}",
+                    self.data.trade_uuid, pending.message_id, pending.attempt, error
+                );
+                pending.attempt += 1;
+
+                // Taker might have moved relays since the Offer. No need to wait if a newer Relay List is found
+                pending.resend_at = if self.refresh_taker_relay_list().await {
+                    Instant::now()
+                } else {
+                    Instant::now() + PEER_MESSAGE_RESEND_DELAY
+                };
+                self.pending_peer_message = Some(pending);
             }
             Err(error) => {
-                rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+                send_rsp(pending.rsp_tx, Err(error));
             }
         }
     }
 
-    fn check_trade_completed(&self) -> Result<(), N3xbError> {
-        if self.data.trade_completed() {
+    fn abandon_peer_message(&mut self) {
+        if let Some(pending) = self.pending_peer_message.take() {
             let error = N3xbError::Simple(format!(
-                "Maker w/ TradeUUID {} already marked as Trade Complete",
-                self.data.trade_uuid
+                "Maker w/ TradeUUID {} terminated before peer message {} could be sent",
+                self.data.trade_uuid, pending.message_id
             ));
-            Err(error) // oneshot should not fail
-        } else {
-            Ok(())
+            send_rsp(pending.rsp_tx, Err(error));
         }
     }
 
-    async fn trade_complete(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+    async fn send_settlement_info(
+        &mut self,
+        settlement_info: SettlementInfo,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
-        // TODO: What else to do for Trade Complete?
-        self.data.set_trade_completed(true);
-        rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+        if let Some(error) = settlement_info.validate_for_order(&self.data.order()).err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let (pubkey, maker_order_note_id) = match self.accepted_counterparty() {
+            Ok(counterparty) => counterparty,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        let result = self
+            .comms_accessor
+            .send_settlement_info(
+                pubkey,
+                None,
+                maker_order_note_id,
+                self.data.trade_uuid,
+                settlement_info.clone(),
+                self.accepted_offer_relay_hints(),
+            )
+            .await;
+
+        match result {
+            Ok(event_id) => {
+                self.data.push_settlement_info(TradeRecordEntry {
+                    outgoing: true,
+                    pubkey,
+                    event_id,
+                    created_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    content: settlement_info,
+                });
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+            }
+        }
     }
 
-    fn register_notif_tx(
+    // Sent to the Taker, and to the Order's trusted pubkeys for arbitration if any
+    async fn raise_dispute(
         &mut self,
-        tx: mpsc::Sender<Result<MakerNotif, N3xbError>>,
+        dispute_request: DisputeRequest,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
-        let mut result = Ok(());
-        if self.notif_tx.is_some() {
-            let error = N3xbError::Simple(format!(
-                "Maker w/ TradeUUID {} already have notif_tx registered",
-                self.data.trade_uuid
-            ));
-            result = Err(error);
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
         }
-        self.notif_tx = Some(tx);
-        rsp_tx.send(result).unwrap();
-    }
 
-    fn unregister_notif_tx(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
-        let mut result = Ok(());
-        if self.notif_tx.is_none() {
-            let error = N3xbError::Simple(format!(
-                "Maker w/ TradeUUID {} expected to already have notif_tx registered",
-                self.data.trade_uuid
-            ));
-            result = Err(error);
+        if let Some(error) = dispute_request.validate().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let (pubkey, maker_order_note_id) = match self.accepted_counterparty() {
+            Ok(counterparty) => counterparty,
+            Err(error) => {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        };
+
+        let mut recipients = vec![(pubkey, self.accepted_offer_relay_hints())];
+        let arbiter_pubkeys = self.data.order().trade_details.content.trusted_pubkeys;
+        for arbiter_pubkey in arbiter_pubkeys.unwrap_or_default() {
+            recipients.push((arbiter_pubkey, HashSet::new()));
+        }
+
+        let mut undelivered_pubkeys = Vec::new();
+        for (pubkey, relay_hints) in recipients {
+            let result = self
+                .comms_accessor
+                .send_dispute_request(
+                    pubkey,
+                    None,
+                    maker_order_note_id.clone(),
+                    self.data.trade_uuid,
+                    dispute_request.clone(),
+                    relay_hints,
+                )
+                .await;
+
+            match result {
+                Ok(event_id) => {
+                    self.data.push_dispute(TradeRecordEntry {
+                        outgoing: true,
+                        pubkey,
+                        event_id,
+                        created_at: SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                        content: dispute_request.clone(),
+                    });
+                }
+                Err(error) => {
+                    warn!(
+                        "Maker w/ TradeUUID {} failed to send Dispute Request to pubkey {} - {}",
+                        self.data.trade_uuid, pubkey, error
+                    );
+                    undelivered_pubkeys.push(pubkey);
+                }
+            }
+        }
+
+        // Every recipient is still attempted. Those sent to are in the Trade Record
+        if undelivered_pubkeys.is_empty() {
+            send_rsp(rsp_tx, Ok(()));
+        } else {
+            send_rsp(
+                rsp_tx,
+                Err(N3xbError::DisputeUndelivered(undelivered_pubkeys)),
+            );
         }
-        self.notif_tx = None;
-        rsp_tx.send(result).unwrap();
     }
 
-    fn shutdown(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
-        rsp_tx.send(Ok(())).unwrap();
+    fn accepted_offer_relay_hints(&self) -> HashSet<Url> {
+        let offer_relay_urls = self
+            .data
+            .accepted_offer_event_id()
+            .and_then(|event_id| self.data.offer_envelopes().get(&event_id).cloned())
+            .map(|offer_envelope| offer_envelope.offer.taker_relay_urls)
+            .unwrap_or_default();
+        merge_taker_relay_list(
+            offer_relay_urls,
+            self.data.taker_relay_list(),
+            Self::MAX_TAKER_RELAY_LIST_RELAYS,
+        )
     }
 
-    // Bottom-up Peer Message Handling
+    // Returns true only if the Taker's Relay List changed
+    async fn refresh_taker_relay_list(&mut self) -> bool {
+        let Ok((pubkey, _)) = self.accepted_counterparty() else {
+            return false;
+        };
 
-    async fn handle_peer_message(&mut self, peer_envelope: PeerEnvelope) {
-        debug!(
-            "Maker w/ TradeUUID {} handle_peer_message() from pubkey {}, of event id {}, type {:?}",
-            self.data.trade_uuid,
-            peer_envelope.pubkey.to_string(),
-            peer_envelope.event_id.to_string(),
+        match self.comms_accessor.query_relay_list(pubkey).await {
+            Ok(relay_list) => {
+                let Some(relay_list) = updated_taker_relay_list(
+                    &self.data.taker_relay_list(),
+                    relay_list,
+                    Self::MAX_TAKER_RELAY_LIST_RELAYS,
+                ) else {
+                    return false;
+                };
+                debug!(
+                    "Maker w/ TradeUUID {} updated Taker Relay List to {:?}",
+                    self.data.trade_uuid, relay_list
+                );
+                self.data.set_taker_relay_list(relay_list);
+                true
+            }
+            Err(error) => {
+                warn!(
+                    "Maker w/ TradeUUID {} failed to query Taker Relay List - {}",
+                    self.data.trade_uuid, error
+                );
+                false
+            }
+        }
+    }
+
+    fn verify_counterparty(&self, envelope: &PeerEnvelope) -> Result<(), N3xbError> {
+        let (expected_pubkey, _) = self.accepted_counterparty()?;
+        envelope.verify_sender(&expected_pubkey)
+    }
+
+    fn accepted_counterparty(&self) -> Result<(XOnlyPublicKey, EventIdString), N3xbError> {
+        let accepted_offer_event_id = match self.data.accepted_offer_event_id() {
+            Some(event_id) => event_id,
+            None => {
+                return Err(N3xbError::Simple(format!(
+                    "Maker w/ TradeUUID {} expected to already have accepted an Offer",
+                    self.data.trade_uuid
+                )));
+            }
+        };
+
+        let pubkey = match self.data.offer_envelopes().get(&accepted_offer_event_id) {
+            Some(offer_envelope) => offer_envelope.pubkey.clone(),
+            None => {
+                return Err(N3xbError::Simple(format!(
+                    "Maker w/ TradeUUID {} expected, but does not contain accepted Offer {}",
+                    self.data.trade_uuid, accepted_offer_event_id
+                )));
+            }
+        };
+
+        let maker_order_note_id = match self.data.order_event_id() {
+            Some(event_id) => event_id,
+            None => {
+                return Err(N3xbError::Simple(
+                    format!(
+                        "Maker w/ TradeUUID {} expected to already have sent Maker Order Note and receive Event ID",
+                        self.data.trade_uuid
+                    )
+                ));
+            }
+        };
+
+        Ok((pubkey, maker_order_note_id))
+    }
+
+    fn check_trade_completed(&self) -> Result<(), N3xbError> {
+        if self.data.trade_completed() {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} already marked as Trade Complete",
+                self.data.trade_uuid
+            ));
+            Err(error) // oneshot should not fail
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn trade_complete(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Let the Taker know, if an Offer was ever accepted
+        if self.data.accepted_offer_event_id().is_some() {
+            let (pubkey, maker_order_note_id) = match self.accepted_counterparty() {
+                Ok(counterparty) => counterparty,
+                Err(error) => {
+                    send_rsp(rsp_tx, Err(error));
+                    return;
+                }
+            };
+
+            if let Some(error) = self
+                .comms_accessor
+                .send_trade_complete(
+                    pubkey,
+                    None,
+                    maker_order_note_id,
+                    self.data.trade_uuid,
+                    self.accepted_offer_relay_hints(),
+                )
+                .await
+                .err()
+            {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        }
+
+        self.data.set_trade_completed(true);
+        if let Some(error) = self
+            .comms_accessor
+            .unwatch_counterparty(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to unwatch counterparty - {}",
+                self.data.trade_uuid, error
+            );
+        }
+        self.unregister_trade_encryption().await;
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    fn pause(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        let mut result = Ok(());
+        if self.paused {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} already paused",
+                self.data.trade_uuid
+            ));
+            result = Err(error);
+        }
+        self.paused = true;
+        send_rsp(rsp_tx, result);
+    }
+
+    async fn resume(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        if !self.paused {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} expected to be paused",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        self.paused = false;
+        while let Some(envelope) = self.paused_envelopes.pop_front() {
+            self.handle_peer_message(envelope).await;
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    fn buffer_peer_message(&mut self, envelope: PeerEnvelope) {
+        if self.paused_envelopes.len() >= Self::PAUSED_PEER_MESSAGE_BUFFER_SIZE {
+            if let Some(dropped) = self.paused_envelopes.pop_front() {
+                warn!(
+                    "Maker w/ TradeUUID {} paused buffer full, dropping oldest Peer Message w/ Event ID {}",
+                    self.data.trade_uuid, dropped.event_id
+                );
+            }
+        }
+        self.paused_envelopes.push_back(envelope);
+    }
+
+    fn register_notif_tx(
+        &mut self,
+        tx: mpsc::Sender<Result<MakerNotif, N3xbError>>,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        let mut result = Ok(());
+        if self.notif_tx.is_some() {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} already have notif_tx registered",
+                self.data.trade_uuid
+            ));
+            result = Err(error);
+        }
+        self.notif_tx = Some(tx);
+        send_rsp(rsp_tx, result);
+    }
+
+    fn unregister_notif_tx(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        let mut result = Ok(());
+        if self.notif_tx.is_none() {
+            let error = N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} expected to already have notif_tx registered",
+                self.data.trade_uuid
+            ));
+            result = Err(error);
+        }
+        self.notif_tx = None;
+        send_rsp(rsp_tx, result);
+    }
+
+    fn shutdown(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // Order Note is watched across relays only while up for Takers to find
+    async fn watch_order_propagation(&self) {
+        let Some(order_seen_tx) = self.order_seen_tx.clone() else {
+            return;
+        };
+        if !self.data.notify_order_seen_on()
+            || self.data.trade_completed()
+            || self.data.accepted_offer_event_id().is_some()
+        {
+            return;
+        }
+        let Some(order_event_id) = self.data.order_event_id() else {
+            return;
+        };
+
+        if let Some(error) = self
+            .comms_accessor
+            .watch_order_propagation(self.data.trade_uuid, order_event_id, order_seen_tx)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to watch Order Note propagation - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    async fn unwatch_order_propagation(&self) {
+        if !self.data.notify_order_seen_on() {
+            return;
+        }
+
+        if let Some(error) = self
+            .comms_accessor
+            .unwatch_order_propagation(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to unwatch Order Note propagation - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    async fn notify_order_seen_on(&self, url: Url) {
+        debug!(
+            "Maker w/ TradeUUID {} Order Note seen on relay {}",
+            self.data.trade_uuid, url
+        );
+
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(MakerNotif::OrderSeenOn(url))).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with notify_order_seen_on - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn watch_counterparty(&self) {
+        let Some(decrypt_failure_tx) = self.decrypt_failure_tx.clone() else {
+            return;
+        };
+        if self.data.trade_completed() {
+            return;
+        }
+        let Ok((pubkey, _maker_order_note_id)) = self.accepted_counterparty() else {
+            return;
+        };
+
+        if let Some(error) = self
+            .comms_accessor
+            .watch_counterparty(self.data.trade_uuid, pubkey, decrypt_failure_tx)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to watch counterparty for decrypt failures - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    // Likely the keys got out of sync with the counterparty, which the Trade Engine should know of
+    async fn notify_decrypt_failure(&self, error: N3xbError) {
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Err(error)).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with notify_decrypt_failure - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    // Only once an Offer with a per-trade pubkey got accepted with one of our own
+    async fn register_trade_encryption(&self) {
+        let Some(secret_key) = self.data.encryption_secret_key() else {
+            return;
+        };
+        let agreed = matches!(
+            self.data.trade_rsp(),
+            Some(trade_rsp) if trade_rsp.encryption_pubkey.is_some()
+        );
+        let peer_pubkey = self.data.accepted_offer_event_id().and_then(|event_id| {
+            self.data
+                .offer_envelopes()
+                .get(&event_id)
+                .and_then(|offer_envelope| offer_envelope.offer.encryption_pubkey)
+        });
+        let (true, Some(peer_pubkey)) = (agreed, peer_pubkey) else {
+            return;
+        };
+
+        if let Some(error) = self
+            .comms_accessor
+            .register_trade_encryption(self.data.trade_uuid, secret_key, peer_pubkey)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to register per-trade encryption keys - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    // Per-trade keys are of no use once the trade ends, and are not to be kept around
+    async fn unregister_trade_encryption(&mut self) {
+        if self.data.encryption_secret_key().is_none() {
+            return;
+        }
+        self.data.clear_encryption_secret_key();
+
+        if let Some(error) = self
+            .comms_accessor
+            .unregister_trade_encryption(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to unregister per-trade encryption keys - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    // Bottom-up Peer Message Handling
+
+    async fn handle_peer_message(&mut self, peer_envelope: PeerEnvelope) {
+        debug!(
+            "Maker w/ TradeUUID {} handle_peer_message() from pubkey {}, of event id {}, type {:?}",
+            self.data.trade_uuid,
+            peer_envelope.pubkey.to_string(),
+            peer_envelope.event_id.to_string(),
             peer_envelope.message_type
         );
 
+        // Offers and their withdrawals come from prospective counterparties. Everything else must be
+        // from the accepted one
+        if !matches!(
+            peer_envelope.message_type,
+            SerdeGenericType::TakerOffer | SerdeGenericType::OfferWithdrawal
+        ) {
+            if let Err(error) = self.verify_counterparty(&peer_envelope) {
+                error!(
+                    "Maker w/ TradeUUID {} rejected peer message - {}",
+                    self.data.trade_uuid, error
+                );
+                if let Some(tx) = &self.notif_tx {
+                    if let Some(error) = tx.send(Err(error)).await.err() {
+                        error!(
+                            "Maker w/ TradeUUID {} failed in notifying user with handle_peer_message - {}",
+                            self.data.trade_uuid, error
+                        );
+                    }
+                }
+                return;
+            }
+        }
+
         match peer_envelope.message_type {
             SerdeGenericType::TakerOffer => {
-                let offer = peer_envelope.message
-                    .downcast_ref::<Offer>()
-                    .expect(
-                        &format!(
-                            "Maker w/ TradeUUID {} received peer message of SerdeGenericType::TakerOffer, but failed to downcast message into Offer",
-                            self.data.trade_uuid
-                        )
-                    )
-                    .to_owned();
+                let Some(offer) = peer_envelope.message.downcast_ref::<Offer>() else {
+                    let error = N3xbError::Simple(format!(
+                        "Maker w/ TradeUUID {} received peer message of SerdeGenericType::TakerOffer, but failed to downcast message into Offer",
+                        self.data.trade_uuid
+                    ));
+                    error!("{}", error);
+                    if let Some(tx) = &self.notif_tx {
+                        if let Some(error) = tx.send(Err(error)).await.err() {
+                            error!(
+                                "Maker w/ TradeUUID {} failed in notifying user with handle_peer_message - {}",
+                                self.data.trade_uuid, error
+                            );
+                        }
+                    }
+                    return;
+                };
+                let offer = offer.to_owned();
+                // A peer's clock running ahead would otherwise push the resync window past Offers missed
+                self.data
+                    .set_offers_seen_at(peer_envelope.created_at.min(Self::now_secs()));
                 let offer_envelope = OfferEnvelope {
                     pubkey: peer_envelope.pubkey,
                     urls: peer_envelope.urls,
                     event_id: peer_envelope.event_id,
+                    created_at: peer_envelope.created_at,
                     offer,
+                    trade_engine_specifics_defaulted: peer_envelope
+                        .trade_engine_specifics_defaulted,
                     _private: (),
                 };
                 self.handle_taker_offer(offer_envelope).await;
@@ -750,6 +2138,22 @@ impl MakerActor {
                 self.handle_engine_specific_peer_message(peer_envelope)
                     .await;
             }
+
+            SerdeGenericType::SettlementInfo => {
+                self.handle_settlement_info(peer_envelope).await;
+            }
+
+            SerdeGenericType::TradeComplete => {
+                self.handle_trade_complete().await;
+            }
+
+            SerdeGenericType::DisputeRequest => {
+                self.handle_dispute_request(peer_envelope).await;
+            }
+
+            SerdeGenericType::OfferWithdrawal => {
+                self.handle_offer_withdrawal(peer_envelope).await;
+            }
         }
     }
 
@@ -763,54 +2167,497 @@ impl MakerActor {
             .data
             .offer_envelopes()
             .contains_key(&offer_envelope.event_id)
+            || self
+                .data
+                .rejected_offers()
+                .contains_key(&offer_envelope.event_id)
         {
             Some(OfferInvalidReason::DuplicateOffer)
+        } else if self.data.blocked_pubkeys().contains(&offer_envelope.pubkey) {
+            Some(OfferInvalidReason::Blocked)
+        } else if !self
+            .data
+            .order()
+            .trade_details
+            .is_taker_trusted(&offer_envelope.pubkey)
+        {
+            Some(OfferInvalidReason::UntrustedTaker)
         } else if let Some(reason) = offer_envelope
             .offer
             .validate_against(&self.data.order())
             .err()
         {
             Some(reason)
+        } else if let Some(error) = self
+            .comms_accessor
+            .validate_trade_engine_specifics(offer_envelope.offer.trade_engine_specifics.clone())
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} refusing Offer with Event ID {} - {}",
+                self.data.trade_uuid, offer_envelope.event_id, error
+            );
+            Some(OfferInvalidReason::TradeEngineSpecific)
+        } else {
+            self.data
+                .insert_offer_envelope(offer_envelope.event_id.clone(), offer_envelope.clone());
+            if let Some(deadline) = self.data.offer_acceptance_deadline() {
+                self.offer_deadlines
+                    .insert(offer_envelope.event_id.clone(), Instant::now() + deadline);
+            }
+            self.evict_offer_over_limit(&offer_envelope.event_id).await
+        };
+
+        let offer_envelope_clone = offer_envelope.clone();
+
+        let accepted_offer_string =
+            if let Some(offer_event_id) = self.data.accepted_offer_event_id() {
+                offer_event_id.to_string()
+            } else {
+                "N/A".to_string()
+            };
+
+        debug!("Maker w/ TradeUUID {} handling Taker Offer with Event ID {} Accepted ID? {} - reason: {:?}", 
+                 self.data.trade_uuid, offer_envelope.event_id, accepted_offer_string, reason);
+
+        if let Some(reason) = reason {
+            notif_result = Err(N3xbError::InvalidOffer(reason.clone()));
+
+            // A duplicate is the same Offer as one already stored, which stands as is
+            if reason != OfferInvalidReason::DuplicateOffer {
+                self.retain_rejected_offer(offer_envelope.clone(), Some(reason.clone()));
+            }
+
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope_clone, reason)
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, offer_envelope.event_id, reject_err
+                );
+            }
+            if self.data.reject_invalid_offers_silently() {
+                return;
+            }
+        }
+
+        // Notify user of new Offer recieved
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(notif_result).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with handle_taker_offer - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    // An arriving Offer that is itself evicted is rejected like any other invalid Offer.
+    // Returns the reason to do so, while previously stored Offers are evicted here
+    async fn evict_offer_over_limit(
+        &mut self,
+        new_offer_event_id: &EventIdString,
+    ) -> Option<OfferInvalidReason> {
+        let max_offers = self.data.max_offers()?;
+        let offer_envelopes: Vec<OfferEnvelope> =
+            self.data.offer_envelopes().into_values().collect();
+        let evict_event_id =
+            select_offer_to_evict(&offer_envelopes, new_offer_event_id, &max_offers)?;
+
+        let offer_envelope = self.data.remove_offer_envelope(&evict_event_id)?;
+        self.offer_deadlines.remove(&evict_event_id);
+
+        if &evict_event_id == new_offer_event_id {
+            return Some(OfferInvalidReason::OfferLimitReached);
+        }
+
+        info!(
+            "Maker w/ TradeUUID {} evicted Offer with Event ID {} for exceeding {} Offers",
+            self.data.trade_uuid, evict_event_id, max_offers.limit
+        );
+        self.retain_rejected_offer(
+            offer_envelope.clone(),
+            Some(OfferInvalidReason::OfferLimitReached),
+        );
+
+        if max_offers.notify_evicted {
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope, OfferInvalidReason::OfferLimitReached)
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected evicted Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, evict_event_id, reject_err
+                );
+            }
+        }
+        None
+    }
+
+    // The accepted Offer is no longer pending, and is left alone
+    async fn reject_offers_from(
+        &mut self,
+        pubkey: XOnlyPublicKey,
+        reason: OfferInvalidReason,
+    ) -> usize {
+        let accepted_offer_event_id = self.data.accepted_offer_event_id();
+        let offer_envelopes: Vec<OfferEnvelope> = self
+            .data
+            .offer_envelopes()
+            .into_values()
+            .filter(|offer_envelope| {
+                offer_envelope.pubkey == pubkey
+                    && Some(&offer_envelope.event_id) != accepted_offer_event_id.as_ref()
+            })
+            .collect();
+
+        for offer_envelope in offer_envelopes.iter() {
+            self.retain_rejected_offer(offer_envelope.clone(), Some(reason.clone()));
+
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope.clone(), reason.clone())
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, offer_envelope.event_id, reject_err
+                );
+            }
+        }
+        offer_envelopes.len()
+    }
+
+    async fn reject_taker_offer(
+        &mut self,
+        offer_envelope: OfferEnvelope,
+        reason: OfferInvalidReason,
+    ) -> Result<(), N3xbError> {
+        let pubkey = offer_envelope.pubkey;
+        let offer_event_id = offer_envelope.event_id.clone();
+        let maker_order_note_id = match self.data.order_event_id() {
+            Some(event_id) => event_id,
+            None => {
+                return Err(N3xbError::Simple(format!(
+                    "Maker w/ TradeUUID {} expected to already have sent Maker Order Note and receive Event ID",
+                    self.data.trade_uuid
+                )));
+            }
+        };
+
+        let trade_rsp = TradeResponseBuilder::new()
+            .offer_event_id(offer_event_id)
+            .trade_response(TradeResponseStatus::Rejected)
+            .reject_reason(reason.clone())
+            .build()
+            .unwrap();
+
+        self.comms_accessor
+            .send_trade_response(
+                pubkey,
+                Some(offer_envelope.event_id.clone()),
+                maker_order_note_id,
+                self.data.trade_uuid.clone(),
+                trade_rsp,
+                HashSet::new(), // Rejections don't warrant connecting to hinted relays
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Offer Acceptance Deadline Handling
+
+    fn rearm_offer_deadlines(&mut self) {
+        let deadline = match self.data.offer_acceptance_deadline() {
+            Some(deadline) => deadline,
+            None => return,
+        };
+
+        if self.data.trade_completed() || self.data.accepted_offer_event_id().is_some() {
+            return;
+        }
+
+        // Offers restored from a previous session expire relative to their Nostr event timestamp
+        let now_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        for offer_envelope in self.data.offer_envelopes().values() {
+            let elapsed = Duration::from_secs(now_secs.saturating_sub(offer_envelope.created_at));
+            let remaining = deadline.saturating_sub(elapsed);
+            self.offer_deadlines
+                .insert(offer_envelope.event_id.clone(), Instant::now() + remaining);
+        }
+    }
+
+    async fn expire_offers(&mut self) {
+        let now = Instant::now();
+        let expired_event_ids: Vec<EventIdString> = self
+            .offer_deadlines
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(event_id, _)| event_id.clone())
+            .collect();
+
+        for event_id in expired_event_ids {
+            self.offer_deadlines.remove(&event_id);
+
+            let offer_envelope = match self.data.remove_offer_envelope(&event_id) {
+                Some(offer_envelope) => offer_envelope,
+                None => continue,
+            };
+
+            debug!(
+                "Maker w/ TradeUUID {} expiring Offer with Event ID {} past acceptance deadline",
+                self.data.trade_uuid, event_id
+            );
+            self.retain_rejected_offer(offer_envelope.clone(), Some(OfferInvalidReason::Expired));
+
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope, OfferInvalidReason::Expired)
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected expired Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, event_id, reject_err
+                );
+            }
+
+            if let Some(tx) = &self.notif_tx {
+                let notif_result = Err(N3xbError::InvalidOffer(OfferInvalidReason::Expired));
+                if let Some(error) = tx.send(notif_result).await.err() {
+                    error!(
+                        "Maker w/ TradeUUID {} failed in notifying user with expire_offers - {}",
+                        self.data.trade_uuid, error
+                    );
+                }
+            }
+        }
+    }
+
+    // Order Expiry Handling
+
+    // Expiration as posted is persisted, so restored Makers take the Order down at the same point
+    fn arm_order_expiry(&mut self) {
+        self.order_expiry = None;
+        if !self.data.delete_on_expiry()
+            || self.data.trade_completed()
+            || self.data.accepted_offer_event_id().is_some()
+        {
+            return;
+        }
+
+        if let Some(expiration) = self.data.order_expiration() {
+            let remaining = Duration::from_secs(expiration.saturating_sub(Self::now_secs()));
+            self.order_expiry = Some(Instant::now() + remaining);
+        }
+    }
+
+    // Ends the trade as if cancelled, with pending Offers rejected as Expired
+    async fn expire_order(&mut self) {
+        self.order_expiry = None;
+        if self.data.trade_completed() || self.data.accepted_offer_event_id().is_some() {
+            return;
+        }
+        let Some(maker_order_note_id) = self.data.order_event_id() else {
+            return;
+        };
+
+        debug!(
+            "Maker w/ TradeUUID {} deleting Order Note with Event ID {} past expiry",
+            self.data.trade_uuid, maker_order_note_id
+        );
+
+        // Order is only expired once actually taken down. Otherwise tried again in a while
+        if let Some(error) = self
+            .comms_accessor
+            .delete_maker_order_note(maker_order_note_id.clone(), "Order Expired")
+            .await
+            .err()
+        {
+            warn!(
+                "Maker w/ TradeUUID {} failed to delete expired Order Note with Event ID {}, retrying in {:?} - {}",
+                self.data.trade_uuid, maker_order_note_id, Self::ORDER_EXPIRY_RETRY_DELAY, error
+            );
+            self.order_expiry = Some(Instant::now() + Self::ORDER_EXPIRY_RETRY_DELAY);
+            return;
+        }
+
+        // Set aside with the reason, so they are not reported as Cancelled once the trade completes
+        for offer_envelope in self.data.offer_envelopes().into_values() {
+            if let Some(reject_err) = self
+                .reject_taker_offer(offer_envelope.clone(), OfferInvalidReason::Expired)
+                .await
+                .err()
+            {
+                error!(
+                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
+                    self.data.trade_uuid, offer_envelope.event_id, reject_err
+                );
+            }
+            self.retain_rejected_offer(offer_envelope, Some(OfferInvalidReason::Expired));
+        }
+
+        self.unwatch_order_propagation().await;
+        self.data.set_trade_completed(true);
+        self.offer_deadlines.clear();
+
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(MakerNotif::OrderExpired)).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with expire_order - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn handle_settlement_info(&mut self, envelope: PeerEnvelope) {
+        let notif_result = match envelope.message.downcast_ref::<SettlementInfo>() {
+            Some(settlement_info) => settlement_info
+                .validate_for_order(&self.data.order())
+                .map(|_| {
+                    MakerNotif::SettlementInfo(SettlementInfoEnvelope {
+                        pubkey: envelope.pubkey,
+                        urls: envelope.urls.clone(),
+                        event_id: envelope.event_id.clone(),
+                        settlement_info: settlement_info.to_owned(),
+                        _private: (),
+                    })
+                }),
+            None => Err(N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} received peer message of SerdeGenericType::SettlementInfo, but failed to downcast message into SettlementInfo",
+                self.data.trade_uuid
+            ))),
+        };
+
+        if let Ok(MakerNotif::SettlementInfo(received)) = &notif_result {
+            self.data.push_settlement_info(TradeRecordEntry {
+                outgoing: false,
+                pubkey: received.pubkey,
+                event_id: received.event_id.clone(),
+                created_at: envelope.created_at,
+                content: received.settlement_info.clone(),
+            });
+        }
+
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(notif_result).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with handle_settlement_info - {}",
+                    self.data.trade_uuid, error
+                );
+            }
         } else {
-            self.data
-                .insert_offer_envelope(offer_envelope.event_id.clone(), offer_envelope.clone());
-            None
-        };
-
-        let offer_envelope_clone = offer_envelope.clone();
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
 
-        let accepted_offer_string =
-            if let Some(offer_event_id) = self.data.accepted_offer_event_id() {
-                offer_event_id.to_string()
-            } else {
-                "N/A".to_string()
-            };
+    async fn handle_dispute_request(&mut self, envelope: PeerEnvelope) {
+        let notif_result = match envelope.message.downcast_ref::<DisputeRequest>() {
+            Some(dispute_request) => dispute_request.validate().map(|_| {
+                MakerNotif::Dispute(DisputeRequestEnvelope {
+                    pubkey: envelope.pubkey,
+                    urls: envelope.urls.clone(),
+                    event_id: envelope.event_id.clone(),
+                    dispute_request: dispute_request.to_owned(),
+                    _private: (),
+                })
+            }),
+            None => Err(N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} received peer message of SerdeGenericType::DisputeRequest, but failed to downcast message into DisputeRequest",
+                self.data.trade_uuid
+            ))),
+        };
 
-        debug!("Maker w/ TradeUUID {} handling Taker Offer with Event ID {} Accepted ID? {} - reason: {:?}", 
-                 self.data.trade_uuid, offer_envelope.event_id, accepted_offer_string, reason);
+        if let Ok(MakerNotif::Dispute(received)) = &notif_result {
+            self.data.push_dispute(TradeRecordEntry {
+                outgoing: false,
+                pubkey: received.pubkey,
+                event_id: received.event_id.clone(),
+                created_at: envelope.created_at,
+                content: received.dispute_request.clone(),
+            });
+        }
 
-        if let Some(reason) = reason {
-            notif_result = Err(N3xbError::InvalidOffer(reason.clone()));
-            if let Some(reject_err) = self
-                .reject_taker_offer(offer_envelope_clone, reason)
-                .await
-                .err()
-            {
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(notif_result).await.err() {
                 error!(
-                    "Maker w/ TradeUUID {} rejected Offer with Event ID {} but with error - {}",
-                    self.data.trade_uuid, offer_envelope.event_id, reject_err
+                    "Maker w/ TradeUUID {} failed in notifying user with handle_dispute_request - {}",
+                    self.data.trade_uuid, error
                 );
             }
-            if self.data.reject_invalid_offers_silently() {
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn handle_offer_withdrawal(&mut self, envelope: PeerEnvelope) {
+        let Some(withdrawal) = envelope.message.downcast_ref::<OfferWithdrawal>() else {
+            error!(
+                "Maker w/ TradeUUID {} received peer message of SerdeGenericType::OfferWithdrawal, but failed to downcast message into OfferWithdrawal",
+                self.data.trade_uuid
+            );
+            return;
+        };
+        let offer_event_id = withdrawal.offer_event_id.clone();
+
+        // Offers already rejected or never received have nothing left to withdraw
+        let offer_pubkey = match self.data.offer_envelopes().get(&offer_event_id) {
+            Some(offer_envelope) => offer_envelope.pubkey,
+            None => {
+                debug!(
+                    "Maker w/ TradeUUID {} received withdrawal of unknown Offer {}",
+                    self.data.trade_uuid, offer_event_id
+                );
                 return;
             }
+        };
+        if offer_pubkey != envelope.pubkey {
+            warn!(
+                "Maker w/ TradeUUID {} ignoring withdrawal of Offer {} from pubkey {} not the Taker of the Offer",
+                self.data.trade_uuid, offer_event_id, envelope.pubkey
+            );
+            return;
         }
 
-        // Notify user of new Offer recieved
+        let notif_result = if self.data.accepted_offer_event_id() == Some(offer_event_id.clone()) {
+            Err(N3xbError::Simple(format!(
+                "Maker w/ TradeUUID {} received withdrawal of already accepted Offer {}",
+                self.data.trade_uuid, offer_event_id
+            )))
+        } else {
+            self.offer_deadlines.remove(&offer_event_id);
+            self.data.remove_offer_envelope(&offer_event_id);
+            Ok(MakerNotif::OfferWithdrawn(offer_event_id))
+        };
+
         if let Some(tx) = &self.notif_tx {
             if let Some(error) = tx.send(notif_result).await.err() {
                 error!(
-                    "Maker w/ TradeUUID {} failed in notifying user with handle_taker_offer - {}",
+                    "Maker w/ TradeUUID {} failed in notifying user with handle_offer_withdrawal - {}",
                     self.data.trade_uuid, error
                 );
             }
@@ -822,80 +2669,106 @@ impl MakerActor {
         }
     }
 
-    async fn reject_taker_offer(
-        &mut self,
-        offer_envelope: OfferEnvelope,
-        reason: OfferInvalidReason,
-    ) -> Result<(), N3xbError> {
-        let mut reject_result: Result<(), N3xbError> = Ok(());
+    async fn handle_trade_complete(&mut self) {
+        // Counterparty completed after we did. Nothing left to converge on
+        if self.data.trade_completed() {
+            debug!(
+                "Maker w/ TradeUUID {} received TradeComplete message after already marked as Trade Complete",
+                self.data.trade_uuid
+            );
+            return;
+        }
 
-        let pubkey = offer_envelope.pubkey;
-        let offer_event_id = offer_envelope.event_id.clone();
-        let maker_order_note_id = match self.data.order_event_id() {
-            Some(event_id) => event_id,
-            None => {
-                reject_result = Err(
-                    N3xbError::Simple(
-                        format!(
-                            "Maker w/ TradeUUID {} expected to already have sent Maker Order Note and receive Event ID",
-                            self.data.trade_uuid
-                        )
-                    )
+        // User is expected to call trade_complete() in response to the notification
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(MakerNotif::TradeComplete)).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with handle_trade_complete - {}",
+                    self.data.trade_uuid, error
                 );
-                "".to_string()
             }
-        };
+        } else {
+            warn!(
+                "Maker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
 
-        let trade_rsp = TradeResponseBuilder::new()
-            .offer_event_id(offer_event_id)
-            .trade_response(TradeResponseStatus::Rejected)
-            .reject_reason(reason.clone())
-            .build()
-            .unwrap();
+    async fn check_peer_message_sequence(&mut self, sequence: u64) {
+        let last_received_sequence = self.data.peer_msg_seq_received();
+        self.data.set_peer_msg_seq_received(sequence);
 
-        self.comms_accessor
-            .send_trade_response(
-                pubkey,
-                Some(offer_envelope.event_id.clone()),
-                maker_order_note_id,
-                self.data.trade_uuid.clone(),
-                trade_rsp,
-            )
-            .await?;
+        let Some(gap) = PeerMessageGap::detect(last_received_sequence, sequence) else {
+            return;
+        };
 
-        reject_result
+        warn!(
+            "Maker w/ TradeUUID {} detected gap in TradeEngineSpecific messages - {:?}",
+            self.data.trade_uuid, gap
+        );
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(MakerNotif::PeerMessageGap(gap))).await.err() {
+                error!(
+                    "Maker w/ TradeUUID {} failed in notifying user with check_peer_message_sequence - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        }
     }
 
     async fn handle_engine_specific_peer_message(&mut self, envelope: PeerEnvelope) {
-        // Verify peer message is signed by the expected pubkey before passing to Trade Engine
-        let expected_pubkey =
-            if let Some(accepted_offer_event_id) = self.data.accepted_offer_event_id() {
-                match self.data.offer_envelopes().get(&accepted_offer_event_id) {
-                    Some(offer_envelope) => offer_envelope.pubkey.clone(),
-                    None => {
-                        error!(
-                            "Maker w/ TradeUUID {} expected to contain accepted Offer {}",
-                            self.data.trade_uuid, accepted_offer_event_id
-                        );
-                        return;
-                    }
-                }
-            } else {
-                error!(
-                    "Maker w/ TradeUUID {} expected to already have accepted an Offer",
-                    self.data.trade_uuid
-                );
-                return;
-            };
+        if self.data.record_peer_message_received(&envelope) {
+            debug!(
+                "Maker w/ TradeUUID {} dropped resent peer message w/ Event ID {}",
+                self.data.trade_uuid, envelope.event_id
+            );
+            return;
+        }
 
-        if envelope.pubkey != expected_pubkey {
-            error!(
-                "Maker w/ TradeUUID {} received TradeEngineSpecific message from unexpected pubkey {}",
-                self.data.trade_uuid,
-                envelope.pubkey
+        let Some(sequence) = envelope.sequence else {
+            self.deliver_engine_specific_peer_message(envelope).await;
+            return;
+        };
+
+        // Messages can arrive out of order across relays. Only delivered once back in sequence
+        let last_received_sequence = self.data.peer_msg_seq_received();
+        if self
+            .reorder_buffer
+            .is_duplicate(last_received_sequence, sequence)
+        {
+            warn!(
+                "Maker w/ TradeUUID {} dropped TradeEngineSpecific message with duplicate sequence {}, last received {}",
+                self.data.trade_uuid, sequence, last_received_sequence
             );
             return;
         }
+        let envelopes = self
+            .reorder_buffer
+            .push(last_received_sequence, sequence, envelope);
+        for envelope in envelopes {
+            self.deliver_engine_specific_peer_message(envelope).await;
+        }
+    }
+
+    // Missing messages are taken as lost. Whatever arrived after is delivered past the gap
+    async fn flush_reordered_peer_messages(&mut self) {
+        for envelope in self.reorder_buffer.flush() {
+            self.deliver_engine_specific_peer_message(envelope).await;
+        }
+    }
+
+    async fn deliver_engine_specific_peer_message(&mut self, envelope: PeerEnvelope) {
+        if let Some(sequence) = envelope.sequence {
+            self.check_peer_message_sequence(sequence).await;
+        }
+
+        self.data.push_peer_message_log(PeerMessageLogEntry {
+            outgoing: false,
+            sequence: envelope.sequence,
+            created_at: envelope.created_at,
+            message: envelope.message.clone(),
+        });
 
         // Let the Trade Engine / user to do the downcasting. Pass the SerdeGeneric message up as is
         if let Some(tx) = &self.notif_tx {
@@ -914,8 +2787,488 @@ impl MakerActor {
     }
 }
 
+// Relays the Taker hinted in the Offer come first. Only a limited number from its Relay List are
+// added on top, as a Relay List can be arbitrarily long
+fn merge_taker_relay_list(
+    offer_relay_urls: HashSet<Url>,
+    taker_relay_list: HashSet<Url>,
+    limit: usize,
+) -> HashSet<Url> {
+    let mut relay_list: Vec<Url> = taker_relay_list
+        .into_iter()
+        .filter(|url| !offer_relay_urls.contains(url))
+        .collect();
+    relay_list.sort();
+    let mut relay_hints = offer_relay_urls;
+    relay_hints.extend(relay_list.into_iter().take(limit));
+    relay_hints
+}
+
+// None if the Relay List queried is empty, or is no different from the current once limited
+fn updated_taker_relay_list(
+    current_relay_list: &HashSet<Url>,
+    queried_relay_list: HashSet<Url>,
+    limit: usize,
+) -> Option<HashSet<Url>> {
+    let mut relay_list: Vec<Url> = queried_relay_list.into_iter().collect();
+    relay_list.sort();
+    let relay_list: HashSet<Url> = relay_list.into_iter().take(limit).collect();
+    if relay_list.is_empty() || &relay_list == current_relay_list {
+        None
+    } else {
+        Some(relay_list)
+    }
+}
+
+// Offers from blocked or untrusted Takers, or without the required PoW, are as good as spam.
+// Keeping them for reconsideration would let anyone fill up the Maker's storage
+fn is_reconsiderable(reason: Option<&OfferInvalidReason>) -> bool {
+    !matches!(
+        reason,
+        Some(OfferInvalidReason::Blocked)
+            | Some(OfferInvalidReason::UntrustedTaker)
+            | Some(OfferInvalidReason::PowTooHigh)
+            | Some(OfferInvalidReason::DuplicateOffer)
+    )
+}
+
+// Ties are broken in favour of evicting the newly arrived Offer
+fn select_offer_to_evict(
+    offer_envelopes: &[OfferEnvelope],
+    new_offer_event_id: &EventIdString,
+    max_offers: &MaxOffers,
+) -> Option<EventIdString> {
+    if offer_envelopes.len() <= max_offers.limit {
+        return None;
+    }
+
+    let is_new = |offer_envelope: &OfferEnvelope| &offer_envelope.event_id == new_offer_event_id;
+    let evict = match max_offers.policy {
+        OfferEvictionPolicy::KeepEarliest => offer_envelopes
+            .iter()
+            .max_by_key(|offer_envelope| (offer_envelope.created_at, is_new(offer_envelope))),
+        OfferEvictionPolicy::KeepLatest => offer_envelopes
+            .iter()
+            .min_by_key(|offer_envelope| (offer_envelope.created_at, !is_new(offer_envelope))),
+        OfferEvictionPolicy::KeepHighestAmount => offer_envelopes.iter().min_by(|a, b| {
+            a.offer
+                .maker_obligation
+                .amount
+                .total_cmp(&b.offer.maker_obligation.amount)
+                .then(is_new(b).cmp(&is_new(a)))
+        }),
+    };
+    evict.map(|offer_envelope| offer_envelope.event_id.clone())
+}
+
 #[cfg(test)]
 mod tests {
+    use secp256k1::Secp256k1;
+
+    use super::*;
+    use crate::{
+        common::store::FsStore,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    fn some_offer_envelope(event_id: EventIdString, created_at: u64, amount: f64) -> OfferEnvelope {
+        let mut offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        offer.maker_obligation.amount = amount;
+        OfferEnvelope {
+            pubkey: SomeTestParams::taker_private_key()
+                .x_only_public_key(&Secp256k1::new())
+                .0,
+            urls: HashSet::new(),
+            event_id,
+            created_at,
+            offer,
+            trade_engine_specifics_defaulted: false,
+            _private: (),
+        }
+    }
+
+    #[test]
+    fn test_select_offer_to_evict_per_policy() {
+        let offer_envelopes = vec![
+            some_offer_envelope(SomeTestParams::some_event_id(1), 100, 2000.0),
+            some_offer_envelope(SomeTestParams::some_event_id(2), 200, 1000.0),
+            some_offer_envelope(SomeTestParams::some_event_id(3), 300, 3000.0),
+        ];
+        let new_offer_event_id = SomeTestParams::some_event_id(3);
+        let mut max_offers = MaxOffers {
+            limit: 3,
+            policy: OfferEvictionPolicy::KeepEarliest,
+            notify_evicted: false,
+        };
+        assert_eq!(
+            select_offer_to_evict(&offer_envelopes, &new_offer_event_id, &max_offers),
+            None
+        );
+
+        max_offers.limit = 2;
+        assert_eq!(
+            select_offer_to_evict(&offer_envelopes, &new_offer_event_id, &max_offers),
+            Some(SomeTestParams::some_event_id(3))
+        );
+
+        max_offers.policy = OfferEvictionPolicy::KeepLatest;
+        assert_eq!(
+            select_offer_to_evict(&offer_envelopes, &new_offer_event_id, &max_offers),
+            Some(SomeTestParams::some_event_id(1))
+        );
+
+        max_offers.policy = OfferEvictionPolicy::KeepHighestAmount;
+        assert_eq!(
+            select_offer_to_evict(&offer_envelopes, &new_offer_event_id, &max_offers),
+            Some(SomeTestParams::some_event_id(2))
+        );
+    }
+
+    fn some_relay_urls(ports: &[u16]) -> HashSet<Url> {
+        ports
+            .iter()
+            .map(|port| Url::parse(&format!("ws://localhost:{}", port)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_taker_relay_list_limited() {
+        let offer_relay_urls = some_relay_urls(&[1000, 1001]);
+        let taker_relay_list = some_relay_urls(&[1001, 1002, 1003, 1004]);
+
+        let relay_hints = merge_taker_relay_list(offer_relay_urls.clone(), taker_relay_list, 2);
+        assert_eq!(relay_hints, some_relay_urls(&[1000, 1001, 1002, 1003]));
+
+        let relay_hints = merge_taker_relay_list(offer_relay_urls.clone(), HashSet::new(), 2);
+        assert_eq!(relay_hints, offer_relay_urls);
+    }
+
+    #[test]
+    fn test_updated_taker_relay_list_on_refresh() {
+        let current_relay_list = some_relay_urls(&[1000, 1001]);
+
+        // Empty or unchanged Relay Lists leave the current one as is
+        assert_eq!(
+            updated_taker_relay_list(&current_relay_list, HashSet::new(), 2),
+            None
+        );
+        assert_eq!(
+            updated_taker_relay_list(&current_relay_list, some_relay_urls(&[1000, 1001, 1002]), 2),
+            None
+        );
+
+        assert_eq!(
+            updated_taker_relay_list(&current_relay_list, some_relay_urls(&[1002, 1003, 1004]), 2),
+            Some(some_relay_urls(&[1002, 1003]))
+        );
+    }
+
+    #[test]
+    fn test_spam_rejections_not_reconsiderable() {
+        assert!(is_reconsiderable(None));
+        assert!(is_reconsiderable(Some(&OfferInvalidReason::PendingAnother)));
+        assert!(is_reconsiderable(Some(&OfferInvalidReason::Expired)));
+        assert!(!is_reconsiderable(Some(&OfferInvalidReason::Blocked)));
+        assert!(!is_reconsiderable(Some(
+            &OfferInvalidReason::UntrustedTaker
+        )));
+        assert!(!is_reconsiderable(Some(&OfferInvalidReason::PowTooHigh)));
+    }
+
+    // Actor as spawned by MakerAccess, but driven directly. Comms has no relays to touch
+    async fn some_maker_actor(
+        data_dir_path: &str,
+    ) -> (MakerActor, mpsc::Receiver<Result<MakerNotif, N3xbError>>) {
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::maker_private_key(), data_dir_path).await;
+        let (_tx, rx) = mpsc::channel::<MakerRequest>(TESTING_DEFAULT_CHANNEL_SIZE);
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let mut actor = MakerActor::new(
+            rx,
+            comms.new_accessor(),
+            order,
+            Arc::new(FsStore::new(data_dir_path)),
+            "makers",
+            MakerOpts::default(),
+        );
+        let (notif_tx, notif_rx) = mpsc::channel(TESTING_DEFAULT_CHANNEL_SIZE);
+        actor.notif_tx = Some(notif_tx);
+        (actor, notif_rx)
+    }
+
+    fn some_engine_specific_envelope(sequence: u64) -> PeerEnvelope {
+        PeerEnvelope {
+            pubkey: SomeTestParams::taker_private_key()
+                .x_only_public_key(&Secp256k1::new())
+                .0,
+            urls: HashSet::new(),
+            event_id: SomeTestParams::some_event_id(sequence as u8),
+            created_at: 0,
+            message_type: SerdeGenericType::TradeEngineSpecific,
+            message: Box::new(OfferWithdrawal {
+                offer_event_id: SomeTestParams::some_event_id(0),
+            }),
+            sequence: Some(sequence),
+            message_id: Some(Uuid::new_v4()),
+            trade_engine_specifics_defaulted: false,
+        }
+    }
+
+    fn expect_peer_sequence(notif_rx: &mut mpsc::Receiver<Result<MakerNotif, N3xbError>>) -> u64 {
+        match notif_rx.try_recv().unwrap().unwrap() {
+            MakerNotif::Peer(envelope) => envelope.sequence.unwrap(),
+            _ => panic!("Maker only expects Peer notification at this point"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_peer_messages_delivered_in_sequence() {
+        let data_dir_path = "test_maker_peer_message_reorder";
+        let (mut actor, mut notif_rx) = some_maker_actor(data_dir_path).await;
+
+        // 2 and 3 arrive ahead of 1. The second 2 is a duplicate
+        for sequence in [2, 3, 2, 1] {
+            actor
+                .handle_engine_specific_peer_message(some_engine_specific_envelope(sequence))
+                .await;
+        }
+        for expected_sequence in 1..=3 {
+            assert_eq!(expect_peer_sequence(&mut notif_rx), expected_sequence);
+        }
+        assert!(notif_rx.try_recv().is_err());
+
+        // 4 never arrives. 5 is only delivered, past the gap, once the window is given up on
+        actor
+            .handle_engine_specific_peer_message(some_engine_specific_envelope(5))
+            .await;
+        assert!(notif_rx.try_recv().is_err());
+        assert!(actor.reorder_buffer.deadline().is_some());
+
+        actor.flush_reordered_peer_messages().await;
+        match notif_rx.try_recv().unwrap().unwrap() {
+            MakerNotif::PeerMessageGap(gap) => assert_eq!(
+                gap,
+                PeerMessageGap {
+                    expected_sequence: 4,
+                    received_sequence: 5
+                }
+            ),
+            _ => panic!("Maker only expects PeerMessageGap notification at this point"),
+        }
+        assert_eq!(expect_peer_sequence(&mut notif_rx), 5);
+        assert_eq!(actor.data.peer_msg_seq_received(), 5);
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_paused_peer_messages_bounded_and_drained_on_resume() {
+        let data_dir_path = "test_maker_paused_peer_messages";
+        let (mut actor, _) = some_maker_actor(data_dir_path).await;
+        let (notif_tx, mut notif_rx) =
+            mpsc::channel(MakerActor::PAUSED_PEER_MESSAGE_BUFFER_SIZE + 1);
+        actor.notif_tx = Some(notif_tx);
+
+        let offer_event_id = SomeTestParams::some_event_id(255);
+        actor
+            .data
+            .update_maker_order(SomeTestParams::some_event_id(254), HashSet::new(), None);
+        actor.data.insert_offer_envelope(
+            offer_event_id.clone(),
+            some_offer_envelope(offer_event_id.clone(), 0, 1000.0),
+        );
+        actor.data.set_accepted_offer_event_id(offer_event_id);
+
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        actor.pause(rsp_tx);
+        rsp_rx.await.unwrap().unwrap();
+
+        // The oldest give way once the buffer is full
+        let total = MakerActor::PAUSED_PEER_MESSAGE_BUFFER_SIZE as u64 + 2;
+        for sequence in 1..=total {
+            actor.buffer_peer_message(some_engine_specific_envelope(sequence));
+        }
+        assert_eq!(
+            actor.paused_envelopes.len(),
+            MakerActor::PAUSED_PEER_MESSAGE_BUFFER_SIZE
+        );
+        assert!(notif_rx.try_recv().is_err());
+
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        actor.resume(rsp_tx).await;
+        rsp_rx.await.unwrap().unwrap();
+        assert!(actor.paused_envelopes.is_empty());
+
+        // Buffered ones are handled as usual, past the gap left by the dropped ones
+        actor.flush_reordered_peer_messages().await;
+        match notif_rx.try_recv().unwrap().unwrap() {
+            MakerNotif::PeerMessageGap(gap) => assert_eq!(
+                gap,
+                PeerMessageGap {
+                    expected_sequence: 1,
+                    received_sequence: 3
+                }
+            ),
+            _ => panic!("Maker only expects PeerMessageGap notification at this point"),
+        }
+        for expected_sequence in 3..=total {
+            assert_eq!(expect_peer_sequence(&mut notif_rx), expected_sequence);
+        }
+        assert!(notif_rx.try_recv().is_err());
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_expire_order_rearmed_on_failed_delete() {
+        let data_dir_path = "test_maker_expire_order_failed_delete";
+        let (mut actor, mut notif_rx) = some_maker_actor(data_dir_path).await;
+        actor
+            .data
+            .update_maker_order(SomeTestParams::some_event_id(1), HashSet::new(), Some(0));
+
+        // No relay to delete the Order Note from
+        actor.expire_order().await;
+        assert!(actor.order_expiry.unwrap() > Instant::now());
+        assert!(!actor.data.trade_completed());
+        assert!(notif_rx.try_recv().is_err());
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
+    fn some_trade_complete_envelope(pubkey: XOnlyPublicKey) -> PeerEnvelope {
+        PeerEnvelope {
+            pubkey,
+            urls: HashSet::new(),
+            event_id: SomeTestParams::some_event_id(10),
+            created_at: 0,
+            message_type: SerdeGenericType::TradeComplete,
+            message: Box::new(crate::peer_msg::TradeComplete {}),
+            sequence: None,
+            message_id: Some(Uuid::new_v4()),
+            trade_engine_specifics_defaulted: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trade_complete_only_from_accepted_taker() {
+        let data_dir_path = "test_maker_trade_complete_accepted_taker";
+        let (mut actor, mut notif_rx) = some_maker_actor(data_dir_path).await;
+        let taker_pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+
+        // No Offer accepted yet
+        actor
+            .handle_peer_message(some_trade_complete_envelope(taker_pubkey))
+            .await;
+        assert!(notif_rx.try_recv().unwrap().is_err());
+
+        let offer_event_id = SomeTestParams::some_event_id(2);
+        actor
+            .data
+            .update_maker_order(SomeTestParams::some_event_id(1), HashSet::new(), None);
+        actor.data.insert_offer_envelope(
+            offer_event_id.clone(),
+            some_offer_envelope(offer_event_id.clone(), 0, 1000.0),
+        );
+        actor.data.set_accepted_offer_event_id(offer_event_id);
+
+        // Not from the Taker of the accepted Offer
+        actor
+            .handle_peer_message(some_trade_complete_envelope(
+                SomeTestParams::maker_private_key()
+                    .x_only_public_key(&Secp256k1::new())
+                    .0,
+            ))
+            .await;
+        assert!(notif_rx.try_recv().unwrap().is_err());
+
+        actor
+            .handle_peer_message(some_trade_complete_envelope(taker_pubkey))
+            .await;
+        match notif_rx.try_recv().unwrap().unwrap() {
+            MakerNotif::TradeComplete => {}
+            _ => panic!("Maker only expects TradeComplete notification at this point"),
+        }
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_raise_dispute_reports_undelivered_recipients() {
+        let data_dir_path = "test_maker_raise_dispute_undelivered";
+        let (mut actor, _notif_rx) = some_maker_actor(data_dir_path).await;
+        let taker_pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        let arbiter_pubkey = SomeTestOfferParams::some_x_only_public_key();
+
+        let mut order = actor.data.order();
+        order.trade_details.content.trusted_pubkeys = Some(HashSet::from([arbiter_pubkey]));
+        actor.data.set_order(order);
+
+        let offer_event_id = SomeTestParams::some_event_id(2);
+        actor
+            .data
+            .update_maker_order(SomeTestParams::some_event_id(1), HashSet::new(), None);
+        actor.data.insert_offer_envelope(
+            offer_event_id.clone(),
+            some_offer_envelope(offer_event_id.clone(), 0, 1000.0),
+        );
+        actor.data.set_accepted_offer_event_id(offer_event_id);
+
+        // No relay to send to. Arbiter still attempted after the Taker fails
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        actor
+            .raise_dispute(
+                DisputeRequest::new("Fiat payment not received", None),
+                rsp_tx,
+            )
+            .await;
+        match rsp_rx.await.unwrap() {
+            Err(N3xbError::DisputeUndelivered(pubkeys)) => {
+                assert_eq!(pubkeys, vec![taker_pubkey, arbiter_pubkey]);
+            }
+            _ => panic!("Maker only expects DisputeUndelivered error at this point"),
+        }
+        assert!(actor.data.disputes().is_empty());
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_post_new_order_with_timeout_while_requests_backed_up() {
+        // Maker too busy to take in any more requests
+        let (tx, _rx) = mpsc::channel::<MakerRequest>(1);
+        let (rsp_tx, _rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        tx.send(MakerRequest::SendMakerOrder { rsp_tx })
+            .await
+            .unwrap();
+
+        let maker_accessor = MakerAccess::new(tx);
+        let result = tokio::time::timeout(
+            Duration::from_secs(1),
+            maker_accessor.post_new_order_with_timeout(Duration::from_millis(100)),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, Err(N3xbError::Timeout(_))));
+    }
+
     // TODO: A lot to mock. Postponing this
 
     // #[tokio::test]
@@ -2,4 +2,7 @@ mod data;
 mod maker;
 
 pub(crate) use maker::Maker;
-pub use maker::{MakerAccess, MakerNotif};
+pub use maker::{
+    MakerAccess, MakerNotif, MakerOpts, MakerPhase, MakerTradeStatus, MaxOffers,
+    OfferEvictionPolicy, OfferStatus,
+};
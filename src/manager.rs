@@ -1,27 +1,37 @@
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, warn};
 
 use secp256k1::{SecretKey, XOnlyPublicKey};
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tokio::task::JoinError;
 use url::Url;
 use uuid::Uuid;
 
 use crate::common::error::N3xbError;
-use crate::common::types::BitcoinNetwork;
-use crate::comms::{Comms, CommsAccess, RelayInfo};
-use crate::maker::{Maker, MakerAccess};
+use crate::common::store::{FsStore, N3xbStore};
+use crate::common::types::{
+    BitcoinNetwork, EventIdString, SerdeGenericTrait, TradeEngineSpecificsValidator,
+};
+use crate::comms::{
+    AddRelayOpts, Comms, CommsAccess, CommsOptions, IdentityRotation, Metadata, OrderUpdate,
+    QueryOpts, RelayCapability, RelayConfig, RelayInfo, SkippedRelay,
+};
+use crate::maker::{Maker, MakerAccess, MakerOpts};
 use crate::offer::Offer;
 use crate::order::{FilterTag, Order, OrderEnvelope};
-use crate::taker::{Taker, TakerAccess};
+use crate::taker::{Taker, TakerAccess, TakerBatch, TakerOpts, TakerTradeStatus};
 
 // At the moment we only support a single Trade Engine at a time.
 // Might need to change to a dyn Trait if mulitple is to be supported at a time
 pub struct Manager {
-    manager_dir_path: PathBuf,
+    store: Arc<dyn N3xbStore>,
+    manager_dir_key: RwLock<String>,
+    retired_manager_dir_keys: RwLock<Vec<String>>, // Of identities rotated away from
     network: BitcoinNetwork,
     comms: Comms,
     comms_accessor: CommsAccess,
@@ -31,9 +41,25 @@ pub struct Manager {
     taker_accessors: RwLock<HashMap<Uuid, TakerAccess>>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsage {
+    pub maker_bytes: HashMap<Uuid, u64>,
+    pub taker_bytes: HashMap<Uuid, u64>,
+    pub total_bytes: u64, // Includes Comms data and anything else under the Manager's directory
+}
+
+// Data layout as store keys. Stores created from a root directory path keep these under n3xb_data/
+//
+// <pubkey>/<trade-engine-name>/<network>/
+//     comms.json
+//     makers/<trade-uuid>-maker.json
+//     takers/<trade-uuid>-taker.json
+
 const DATA_DIR_PATH_STR: &str = "n3xb_data";
 const MAKERS_DIR_STR: &str = "makers";
 const TAKERS_DIR_STR: &str = "takers";
+const MAKER_FILE_SUFFIX_STR: &str = "-maker.json";
+const TAKER_FILE_SUFFIX_STR: &str = "-taker.json";
 
 impl Manager {
     // Constructors
@@ -43,10 +69,16 @@ impl Manager {
         network: impl Borrow<BitcoinNetwork>,
         root_dir_path: impl AsRef<Path>,
     ) -> Manager {
-        let data_dir_path = root_dir_path.as_ref().join(DATA_DIR_PATH_STR);
+        let store = Self::fs_store(root_dir_path);
         // This will always create a new Comms with a randomly generated key pair
-        let comms = Comms::new(&trade_engine_name, network.borrow(), &data_dir_path).await;
-        Self::new_with_comms(comms, &trade_engine_name, network.borrow(), &data_dir_path).await
+        let comms = Comms::new(
+            &trade_engine_name,
+            network.borrow(),
+            store.clone(),
+            CommsOptions::default(),
+        )
+        .await;
+        Self::new_with_comms(comms, &trade_engine_name, network.borrow(), store).await
     }
 
     pub async fn new_with_key(
@@ -55,31 +87,73 @@ impl Manager {
         network: impl Borrow<BitcoinNetwork>,
         root_dir_path: impl AsRef<Path>,
     ) -> Manager {
-        let data_dir_path = root_dir_path.as_ref().join(DATA_DIR_PATH_STR);
+        Self::new_with_key_and_opts(
+            key,
+            CommsOptions::default(),
+            trade_engine_name,
+            network,
+            root_dir_path,
+        )
+        .await
+    }
+
+    pub async fn new_with_key_and_opts(
+        key: SecretKey,
+        comms_opts: CommsOptions,
+        trade_engine_name: impl AsRef<str>,
+        network: impl Borrow<BitcoinNetwork>,
+        root_dir_path: impl AsRef<Path>,
+    ) -> Manager {
+        let store = Self::fs_store(root_dir_path);
+        Self::new_with_store(key, comms_opts, trade_engine_name, network, store).await
+    }
+
+    // For persisting somewhere other than the filesystem, such as an embedded database
+    pub async fn new_with_store(
+        key: SecretKey,
+        comms_opts: CommsOptions,
+        trade_engine_name: impl AsRef<str>,
+        network: impl Borrow<BitcoinNetwork>,
+        store: Arc<dyn N3xbStore>,
+    ) -> Manager {
         // Will try to look for Comms data that matches the pubkey and restore relays if found. New Comms is created otherwise
-        let comms =
-            Comms::new_with_key(key, &trade_engine_name, network.borrow(), &data_dir_path).await;
-        Self::new_with_comms(comms, &trade_engine_name, network.borrow(), &data_dir_path).await
+        let comms = Comms::new_with_key(
+            key,
+            &trade_engine_name,
+            network.borrow(),
+            store.clone(),
+            comms_opts,
+        )
+        .await;
+        Self::new_with_comms(comms, &trade_engine_name, network.borrow(), store).await
+    }
+
+    fn fs_store(root_dir_path: impl AsRef<Path>) -> Arc<dyn N3xbStore> {
+        Arc::new(FsStore::new(root_dir_path.as_ref().join(DATA_DIR_PATH_STR)))
     }
 
     async fn new_with_comms(
         comms: Comms,
         trade_engine_name: impl AsRef<str>,
         network: impl Borrow<BitcoinNetwork>,
-        data_dir_path: impl AsRef<Path>,
+        store: Arc<dyn N3xbStore>,
     ) -> Manager {
         let comms_accessor = comms.new_accessor();
         let pubkey = comms_accessor.get_pubkey().await;
-        let manager_dir_path = data_dir_path.as_ref().join(format!(
+        let manager_dir_key = format!(
             "{}/{}/{}",
             pubkey.to_string(),
             trade_engine_name.as_ref(),
             network.borrow().to_string().to_lowercase(),
-        ));
+        );
 
-        let (makers, takers) =
-            Self::maker_taker_setup_restore(&comms_accessor, pubkey.to_string(), &manager_dir_path)
-                .await;
+        let (makers, takers) = Self::maker_taker_restore(
+            &comms_accessor,
+            pubkey.to_string(),
+            &store,
+            &manager_dir_key,
+        )
+        .await;
         let mut maker_accessors = HashMap::new();
         for maker in &makers {
             maker_accessors.insert(maker.0.clone(), maker.1.new_accessor());
@@ -90,7 +164,9 @@ impl Manager {
         }
 
         Manager {
-            manager_dir_path,
+            store,
+            manager_dir_key: RwLock::new(manager_dir_key),
+            retired_manager_dir_keys: RwLock::new(Vec::new()),
             network: network.borrow().clone(),
             comms,
             comms_accessor,
@@ -101,24 +177,20 @@ impl Manager {
         }
     }
 
-    async fn maker_taker_setup_restore(
+    async fn maker_taker_restore(
         comms_accessor: &CommsAccess,
         pubkey_string: impl AsRef<str>,
-        manager_dir_path: impl AsRef<Path>,
+        store: &Arc<dyn N3xbStore>,
+        manager_dir_key: &str,
     ) -> (HashMap<Uuid, Maker>, HashMap<Uuid, Taker>) {
         let result: Result<(HashMap<Uuid, Maker>, HashMap<Uuid, Taker>), N3xbError> = async {
-            // Create directories to data and manager with identifier if not already exist
-            let maker_dir_path = manager_dir_path.as_ref().join(MAKERS_DIR_STR);
-            std::fs::create_dir_all(&maker_dir_path)?;
-
-            // Restore Makers from files in maker directory
-            let makers = Self::restore_makers(comms_accessor, &maker_dir_path).await;
+            // Restore Makers from data under the maker key prefix
+            let maker_dir_key = format!("{}/{}", manager_dir_key, MAKERS_DIR_STR);
+            let makers = Self::restore_makers(comms_accessor, store, &maker_dir_key).await?;
 
             // Do the same for Takers
-            let taker_dir_path = manager_dir_path.as_ref().join(TAKERS_DIR_STR);
-            std::fs::create_dir_all(&taker_dir_path)?;
-
-            let takers = Self::restore_takers(comms_accessor, &taker_dir_path).await?;
+            let taker_dir_key = format!("{}/{}", manager_dir_key, TAKERS_DIR_STR);
+            let takers = Self::restore_takers(comms_accessor, store, &taker_dir_key).await?;
             Ok((makers, takers))
         }
         .await;
@@ -134,7 +206,7 @@ impl Manager {
                 (makers, takers)
             }
             Err(err) => {
-                warn!("Error setting up & restoring from data directory - {}", err);
+                warn!("Error restoring from data store - {}", err);
                 (HashMap::new(), HashMap::new())
             }
         }
@@ -142,49 +214,47 @@ impl Manager {
 
     async fn restore_makers(
         comms_accessor: &CommsAccess,
-        maker_dir_path: impl AsRef<Path>,
-    ) -> HashMap<Uuid, Maker> {
-        // Go through all files in maker directory and restore each file as a new Maker
+        store: &Arc<dyn N3xbStore>,
+        maker_dir_key: &str,
+    ) -> Result<HashMap<Uuid, Maker>, N3xbError> {
+        // Go through all keys under the maker key prefix and restore each as a new Maker
         let mut makers = HashMap::new();
-        let mut maker_files = std::fs::read_dir(maker_dir_path).unwrap();
-        while let Some(maker_file) = maker_files.next() {
-            let maker_file_path = maker_file.unwrap().path();
-            let (trade_uuid, maker) = match Maker::restore(comms_accessor.clone(), &maker_file_path)
-            {
-                Ok((trade_uuid, maker)) => (trade_uuid, maker),
-                Err(err) => {
-                    panic!(
-                        "Error restoring Maker from file {:?} - {}",
-                        maker_file_path, err
-                    );
-                    // continue;
-                }
-            };
+        for maker_data_key in store.list(maker_dir_key)? {
+            let (trade_uuid, maker) =
+                match Maker::restore(comms_accessor.clone(), store.clone(), &maker_data_key) {
+                    Ok((trade_uuid, maker)) => (trade_uuid, maker),
+                    Err(err) => {
+                        panic!(
+                            "Error restoring Maker from key {} - {}",
+                            maker_data_key, err
+                        );
+                        // continue;
+                    }
+                };
             makers.insert(trade_uuid, maker);
         }
-        makers
+        Ok(makers)
     }
 
     async fn restore_takers(
         comms_accessor: &CommsAccess,
-        taker_dir_path: impl AsRef<Path>,
+        store: &Arc<dyn N3xbStore>,
+        taker_dir_key: &str,
     ) -> Result<HashMap<Uuid, Taker>, N3xbError> {
-        // Go through all files in taker directory and restore each file as a new Taker
+        // Go through all keys under the taker key prefix and restore each as a new Taker
         let mut takers = HashMap::new();
-        let mut taker_files = std::fs::read_dir(taker_dir_path)?;
-        while let Some(taker_file) = taker_files.next() {
-            let taker_file_path = taker_file.unwrap().path();
-            let (trade_uuid, taker) = match Taker::restore(comms_accessor.clone(), &taker_file_path)
-            {
-                Ok((trade_uuid, taker)) => (trade_uuid, taker),
-                Err(err) => {
-                    panic!(
-                        "Error restoring Taker from file {:?} - {}",
-                        taker_file_path, err
-                    );
-                    // continue;
-                }
-            };
+        for taker_data_key in store.list(taker_dir_key)? {
+            let (trade_uuid, taker) =
+                match Taker::restore(comms_accessor.clone(), store.clone(), &taker_data_key) {
+                    Ok((trade_uuid, taker)) => (trade_uuid, taker),
+                    Err(err) => {
+                        panic!(
+                            "Error restoring Taker from key {} - {}",
+                            taker_data_key, err
+                        );
+                        // continue;
+                    }
+                };
             takers.insert(trade_uuid, taker);
         }
         Ok(takers)
@@ -195,18 +265,147 @@ impl Manager {
         self.comms_accessor.get_pubkey().await
     }
 
+    pub async fn npub(&self) -> String {
+        self.comms_accessor.get_npub().await
+    }
+
+    // Swaps the identity keypair, such as after a suspected leak. Relays carry over. Makers and
+    // Takers from before stay persisted under the old pubkey, and only restore with the old key.
+    // Those created afterwards are persisted under the new pubkey
+    pub async fn rotate_identity(
+        &self,
+        new_secret_key: SecretKey,
+    ) -> Result<IdentityRotation, N3xbError> {
+        debug!(
+            "Manager w/ pubkey {} rotating identity",
+            self.pubkey().await
+        );
+        let identity_rotation = self.comms_accessor.rotate_identity(new_secret_key).await?;
+
+        let mut manager_dir_key = self.manager_dir_key.write().await;
+        let old_pubkey_string = identity_rotation.old_pubkey.to_string();
+        let new_manager_dir_key = match manager_dir_key.strip_prefix(&old_pubkey_string) {
+            Some(dir_key_suffix) => format!("{}{}", identity_rotation.new_pubkey, dir_key_suffix),
+            None => {
+                return Err(N3xbError::Simple(format!(
+                    "Manager data directory {} is not under the old pubkey {}",
+                    manager_dir_key, old_pubkey_string
+                )));
+            }
+        };
+        let old_manager_dir_key = std::mem::replace(&mut *manager_dir_key, new_manager_dir_key);
+        self.retired_manager_dir_keys
+            .write()
+            .await
+            .push(old_manager_dir_key);
+        Ok(identity_rotation)
+    }
+
+    // Coarse access control for private n3xB networks, such as a trading group. Only peer messages,
+    // Offers included, from pubkeys in the allowlist are processed. None, the default, allows all.
+    // The allowlist is persisted, so it survives restarts until replaced
+    pub async fn set_peer_allowlist(
+        &self,
+        allowlist: Option<HashSet<XOnlyPublicKey>>,
+    ) -> Result<(), N3xbError> {
+        debug!(
+            "Manager w/ pubkey {} setting peer allowlist {:?}",
+            self.pubkey().await,
+            allowlist
+        );
+        self.comms_accessor.set_peer_allowlist(allowlist).await
+    }
+
+    // NIP-26 delegation tag in JSON form, as signed by the root identity for this Manager's pubkey.
+    // Maker Order Notes will carry the tag while set. None to stop delegating
+    pub async fn set_delegation_tag(&self, delegation_tag: Option<String>) -> Result<(), N3xbError> {
+        debug!(
+            "Manager w/ pubkey {} setting delegation tag {:?}",
+            self.pubkey().await,
+            delegation_tag
+        );
+        self.comms_accessor.set_delegation_tag(delegation_tag).await
+    }
+
+    // Once any type is registered, Orders and peer messages carrying Trade Engine specifics of
+    // types not registered are refused, instead of being handed up to fail on downcast
+    pub async fn register_trade_engine_specifics_type(
+        &self,
+        type_name: impl Into<String>,
+    ) -> Result<(), N3xbError> {
+        self.comms_accessor
+            .register_trade_engine_specifics_type(type_name)
+            .await
+    }
+
+    // Invoked on the Trade Engine specifics of every Order and Offer received. Orders refused are
+    // left out of query results, Offers refused are rejected as TradeEngineSpecific. None to remove
+    pub async fn register_trade_engine_specifics_validator(
+        &self,
+        validator: Option<TradeEngineSpecificsValidator>,
+    ) -> Result<(), N3xbError> {
+        self.comms_accessor
+            .register_trade_engine_specifics_validator(validator)
+            .await
+    }
+
+    // Stand in for absent or malformed Trade Engine specifics on Orders and Offers received, which
+    // are then flagged as defaulted. Orders and Offers carry different specifics, so each has its
+    // own default. Validator still applies to the defaults. None to stop defaulting either
+    pub async fn register_default_trade_engine_specifics(
+        &self,
+        default_order_specifics: Option<Box<dyn SerdeGenericTrait>>,
+        default_offer_specifics: Option<Box<dyn SerdeGenericTrait>>,
+    ) -> Result<(), N3xbError> {
+        self.comms_accessor
+            .register_default_trade_engine_specifics(
+                default_order_specifics,
+                default_offer_specifics,
+            )
+            .await
+    }
+
     pub async fn add_relays(
         &self,
         relay_addrs: Vec<(Url, Option<SocketAddr>)>,
         connect: bool,
     ) -> Result<(), N3xbError> {
+        let relay_addrs = relay_addrs
+            .into_iter()
+            .map(|(url, addr)| (url, addr, RelayCapability::ReadWrite))
+            .collect();
+        self.add_relays_with_capability(relay_addrs, connect).await
+    }
+
+    pub async fn add_relays_with_capability(
+        &self,
+        relay_addrs: Vec<(Url, Option<SocketAddr>, RelayCapability)>,
+        connect: bool,
+    ) -> Result<(), N3xbError> {
+        let opts = AddRelayOpts {
+            connect,
+            ..Default::default()
+        };
+        self.add_relays_with_opts(relay_addrs, opts).await?;
+        Ok(())
+    }
+
+    // Relays lacking any of the required NIPs are left out instead of failing the whole add, and
+    // are returned with the NIPs they are missing
+    pub async fn add_relays_with_opts(
+        &self,
+        relay_addrs: Vec<(Url, Option<SocketAddr>, RelayCapability)>,
+        opts: AddRelayOpts,
+    ) -> Result<Vec<SkippedRelay>, N3xbError> {
         debug!(
-            "Manager w/ pubkey {} adding relays {:?}",
+            "Manager w/ pubkey {} adding relays {:?} with {:?}",
             self.pubkey().await,
-            relay_addrs
+            relay_addrs,
+            opts
         );
-        self.comms_accessor.add_relays(relay_addrs, connect).await?;
-        Ok(())
+        self.comms_accessor
+            .add_relays(relay_addrs, opts.connect, opts.require_nips)
+            .await
     }
 
     pub async fn remove_relay(&self, relay_url: Url) -> Result<(), N3xbError> {
@@ -224,6 +423,35 @@ impl Manager {
         self.comms_accessor.get_relays().await
     }
 
+    // URL, proxy and capability of every Relay, for backing up or moving to another device
+    pub async fn export_relay_config(&self) -> RelayConfig {
+        debug!(
+            "Manager w/ pubkey {} exporting relay config",
+            self.pubkey().await
+        );
+        self.comms_accessor.export_relay_config().await
+    }
+
+    // Adds and connects the Relays in the config, on top of any already added
+    pub async fn import_relay_config(&self, config: RelayConfig) -> Result<(), N3xbError> {
+        debug!(
+            "Manager w/ pubkey {} importing relay config {:?}",
+            self.pubkey().await,
+            config
+        );
+        self.comms_accessor.import_relay_config(config).await
+    }
+
+    // JSON of the filters subscribed with across all Relays, for diagnosing events not arriving
+    pub async fn get_active_filters(&self) -> Vec<String> {
+        self.comms_accessor.get_active_filters().await
+    }
+
+    // Quick check before posting or querying. True if at least one Relay is connected
+    pub async fn is_connected(&self) -> bool {
+        self.comms_accessor.is_connected().await
+    }
+
     pub async fn connect_relay(&self, relay_url: Url) -> Result<(), N3xbError> {
         debug!(
             "Manager w/ pubkey {} connecting relay {:?}",
@@ -243,13 +471,47 @@ impl Manager {
         Ok(())
     }
 
+    pub async fn estimate_pow_time(difficulty: u8) -> Duration {
+        Comms::estimate_pow_time(difficulty).await
+    }
+
     // Order Management
-    pub async fn new_maker(&self, order: Order) -> MakerAccess {
+    pub async fn new_maker(&self, order: Order) -> Result<MakerAccess, N3xbError> {
+        self.new_maker_with_opts(order, MakerOpts::default()).await
+    }
+
+    pub async fn new_maker_with_offer_deadline(
+        &self,
+        order: Order,
+        offer_acceptance_deadline: Option<Duration>,
+    ) -> Result<MakerAccess, N3xbError> {
+        let opts = MakerOpts {
+            offer_acceptance_deadline,
+            ..Default::default()
+        };
+        self.new_maker_with_opts(order, opts).await
+    }
+
+    pub async fn new_maker_with_opts(
+        &self,
+        order: Order,
+        opts: MakerOpts,
+    ) -> Result<MakerAccess, N3xbError> {
+        order.validate_bitcoin_network(&self.network)?;
+
+        // Held from the check through the insert, so concurrent calls can't both add the TradeUUID
         let trade_uuid = order.trade_uuid;
+        let mut makers = self.makers.write().await;
+        let takers = self.takers.write().await;
+        self.check_trade_uuid_unique(&makers, &takers, trade_uuid)
+            .await?;
+
         let maker = Maker::new(
             self.comms.new_accessor(),
             order,
-            self.manager_dir_path.join(MAKERS_DIR_STR),
+            self.store.clone(),
+            self.dir_key(MAKERS_DIR_STR).await,
+            opts,
         );
         let maker_my_accessor = maker.new_accessor();
         let maker_returned_accessor = maker.new_accessor();
@@ -260,25 +522,37 @@ impl Manager {
             trade_uuid
         );
 
-        let mut makers = self.makers.write().await;
         makers.insert(trade_uuid, maker);
+        drop(takers);
+        drop(makers);
 
         let mut maker_accessors = self.maker_accessors.write().await;
         maker_accessors.insert(trade_uuid, maker_my_accessor);
 
-        maker_returned_accessor
+        Ok(maker_returned_accessor)
     }
 
     pub async fn query_orders(
         &self,
         filter_tags: Vec<FilterTag>,
     ) -> Result<Vec<OrderEnvelope>, N3xbError> {
-        let mut order_envelopes = self.comms_accessor.query_orders(filter_tags).await?;
+        self.query_orders_with_opts(filter_tags, QueryOpts::default()).await
+    }
+
+    pub async fn query_orders_with_opts(
+        &self,
+        filter_tags: Vec<FilterTag>,
+        query_opts: QueryOpts,
+    ) -> Result<Vec<OrderEnvelope>, N3xbError> {
+        let mut order_envelopes = self
+            .comms_accessor
+            .query_orders(filter_tags, query_opts)
+            .await?;
         let queried_length = order_envelopes.len();
 
         let valid_order_envelopes: Vec<OrderEnvelope> = order_envelopes
             .drain(..)
-            .filter(|order_envelope| self.order_filter_check(&order_envelope.order))
+            .filter(|order_envelope| Self::order_filter_check(&order_envelope.order, &self.network))
             .collect();
         let valid_length = valid_order_envelopes.len();
 
@@ -296,8 +570,139 @@ impl Manager {
         Ok(valid_order_envelopes)
     }
 
-    fn order_filter_check(&self, order: &Order) -> bool {
-        order.validate().is_ok() && order.check_bitcoin_network(self.network.clone())
+    fn order_filter_check(order: &Order, network: &BitcoinNetwork) -> bool {
+        order.validate().is_ok() && order.check_bitcoin_network(network.clone())
+    }
+
+    // Kind-0 profile Metadata of the Maker of an Order, for showing alongside the Order. With
+    // verify_nip05, the NIP-05 identifier is only kept if it verifies against the Maker pubkey
+    pub async fn get_maker_profile(
+        &self,
+        pubkey: XOnlyPublicKey,
+        verify_nip05: bool,
+    ) -> Result<Option<Metadata>, N3xbError> {
+        self.comms_accessor
+            .get_maker_profile(pubkey, verify_nip05)
+            .await
+    }
+
+    // Walks backwards through the Order history relay by relay, for archiving more Orders than
+    // would be sensible to fetch at once. Pages of up to page_size Orders are pushed into tx as
+    // they come in, leaving out Orders already pushed from an earlier page or another relay.
+    // tx is dropped once all relays are walked through, or as soon as the receiver is dropped
+    pub async fn query_orders_paged(
+        &self,
+        filter_tags: Vec<FilterTag>,
+        page_size: usize,
+        tx: mpsc::Sender<Result<Vec<OrderEnvelope>, N3xbError>>,
+    ) -> Result<(), N3xbError> {
+        if page_size == 0 {
+            return Err(N3xbError::Simple(
+                "Page size for paged Order query should be greater than 0".to_string(),
+            ));
+        }
+
+        let relay_urls: Vec<Url> = self
+            .comms_accessor
+            .get_relays()
+            .await
+            .into_iter()
+            .filter(|relay_info| relay_info.capability.can_read())
+            .map(|relay_info| relay_info.url)
+            .collect();
+        if relay_urls.is_empty() {
+            return Err(N3xbError::Simple(format!(
+                "Manager w/ pubkey {} has no relays to query Orders from",
+                self.pubkey().await
+            )));
+        }
+
+        tokio::spawn(Self::walk_order_pages(
+            self.comms_accessor.clone(),
+            self.network.clone(),
+            relay_urls,
+            filter_tags,
+            page_size,
+            tx,
+        ));
+        Ok(())
+    }
+
+    async fn walk_order_pages(
+        comms_accessor: CommsAccess,
+        network: BitcoinNetwork,
+        relay_urls: Vec<Url>,
+        filter_tags: Vec<FilterTag>,
+        page_size: usize,
+        tx: mpsc::Sender<Result<Vec<OrderEnvelope>, N3xbError>>,
+    ) {
+        let mut event_ids: HashSet<EventIdString> = HashSet::new();
+
+        for relay_url in relay_urls {
+            let mut until: Option<u64> = None;
+            loop {
+                let result = comms_accessor
+                    .query_orders_page(filter_tags.clone(), relay_url.clone(), until, page_size)
+                    .await;
+
+                // A failing relay is reported and the walk moves on to the next. Comms having shut
+                // down is reported and ends the walk
+                let order_page = match result {
+                    Ok(order_page) => order_page,
+                    Err(error) => {
+                        let comms_gone =
+                            matches!(error, N3xbError::MpscSend(_) | N3xbError::OneshotRecv(_));
+                        if tx.send(Err(error)).await.is_err() || comms_gone {
+                            return;
+                        }
+                        break;
+                    }
+                };
+
+                let order_envelopes: Vec<OrderEnvelope> = order_page
+                    .order_envelopes
+                    .into_iter()
+                    .filter(|order_envelope| {
+                        Self::order_filter_check(&order_envelope.order, &network)
+                    })
+                    .filter(|order_envelope| event_ids.insert(order_envelope.event_id.clone()))
+                    .collect();
+                if !order_envelopes.is_empty() && tx.send(Ok(order_envelopes)).await.is_err() {
+                    return;
+                }
+
+                let Some(oldest_created_at) = order_page.oldest_created_at else {
+                    break;
+                };
+
+                // Relays might cap the limit below page_size, so only an empty page ends the walk.
+                // A page stuck on a single second steps past it, which can skip Orders beyond the
+                // page size posted in that same second
+                until = if until == Some(oldest_created_at) {
+                    match oldest_created_at.checked_sub(1) {
+                        Some(until) => Some(until),
+                        None => break,
+                    }
+                } else {
+                    Some(oldest_created_at)
+                };
+            }
+        }
+    }
+
+    // Pushes republished versions and the deletion of a queried Order, instead of polling for them
+    pub async fn watch_order(
+        &self,
+        order_envelope: &OrderEnvelope,
+        tx: mpsc::Sender<OrderUpdate>,
+    ) -> Result<(), N3xbError> {
+        self.comms_accessor
+            .watch_order(order_envelope.pubkey, order_envelope.order.trade_uuid, tx)
+            .await
+    }
+
+    pub async fn unwatch_order(&self, trade_uuid: Uuid) -> Result<(), N3xbError> {
+        self.comms_accessor.unwatch_order(trade_uuid).await
     }
 
     pub async fn new_taker(
@@ -305,14 +710,40 @@ impl Manager {
         order_envelope: OrderEnvelope,
         offer: Offer,
     ) -> Result<TakerAccess, N3xbError> {
-        offer.validate_against(&order_envelope.order)?;
+        self.new_taker_with_opts(order_envelope, offer, TakerOpts::default())
+            .await
+    }
+
+    pub async fn new_taker_with_opts(
+        &self,
+        order_envelope: OrderEnvelope,
+        offer: Offer,
+        opts: TakerOpts,
+    ) -> Result<TakerAccess, N3xbError> {
+        // Pinned to the Order terms as seen, so the Maker rejects the Offer if they since changed
+        let mut offer = offer;
+        if offer.order_terms_hash.is_none() {
+            offer.order_terms_hash = Some(order_envelope.terms_hash()?);
+        }
+        offer.validate_against_envelope(&order_envelope)?;
+        order_envelope
+            .order
+            .validate_bitcoin_network(&self.network)?;
 
+        // Held from the check through the insert, so concurrent calls can't both add the TradeUUID
         let trade_uuid = order_envelope.order.trade_uuid;
+        let makers = self.makers.write().await;
+        let mut takers = self.takers.write().await;
+        self.check_trade_uuid_unique(&makers, &takers, trade_uuid)
+            .await?;
+
         let taker = Taker::new(
             self.comms.new_accessor(),
             order_envelope,
             offer,
-            self.manager_dir_path.join(TAKERS_DIR_STR),
+            self.store.clone(),
+            self.dir_key(TAKERS_DIR_STR).await,
+            opts,
         );
         let taker_my_accessor = taker.new_accessor();
         let taker_returned_accessor = taker.new_accessor();
@@ -323,8 +754,9 @@ impl Manager {
             trade_uuid
         );
 
-        let mut takers = self.takers.write().await;
         takers.insert(trade_uuid, taker);
+        drop(takers);
+        drop(makers);
 
         let mut taker_accessors = self.taker_accessors.write().await;
         taker_accessors.insert(trade_uuid, taker_my_accessor);
@@ -332,6 +764,156 @@ impl Manager {
         Ok(taker_returned_accessor)
     }
 
+    // Offers to several Orders at once. No Taker is created if any of the Offers fail to validate
+    pub async fn new_taker_batch(
+        &self,
+        offers: Vec<(OrderEnvelope, Offer)>,
+    ) -> Result<TakerBatch, N3xbError> {
+        let mut takers = HashMap::new();
+        let mut result = Ok(());
+        for (order_envelope, offer) in offers {
+            let trade_uuid = order_envelope.order.trade_uuid;
+            match self.new_taker(order_envelope, offer).await {
+                Ok(taker) => {
+                    takers.insert(trade_uuid, taker);
+                }
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        let trade_uuids: Vec<Uuid> = takers.keys().copied().collect();
+        let result = match result {
+            Ok(()) => TakerBatch::new(takers).await,
+            Err(error) => Err(error),
+        };
+
+        if result.is_err() {
+            for trade_uuid in trade_uuids {
+                if let Some(error) = self.cancel_taker(trade_uuid).await.err() {
+                    warn!(
+                        "Manager failed to cancel Taker w/ TradeUUID {} of failed Taker Batch - {}",
+                        trade_uuid, error
+                    );
+                }
+            }
+        }
+        result
+    }
+
+    // Cancels all Takers of the batch except the one to keep, typically the first to be accepted.
+    // Takers that got accepted regardless are left in place, and the first such error returned
+    pub async fn cancel_taker_batch(
+        &self,
+        taker_batch: TakerBatch,
+        keep_trade_uuid: Option<Uuid>,
+    ) -> Result<(), N3xbError> {
+        let mut result = Ok(());
+        for trade_uuid in taker_batch.trade_uuids() {
+            if Some(trade_uuid) == keep_trade_uuid {
+                continue;
+            }
+            if let Some(error) = self.cancel_taker(trade_uuid).await.err() {
+                warn!(
+                    "Manager failed to cancel Taker w/ TradeUUID {} of Taker Batch - {}",
+                    trade_uuid, error
+                );
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+        result
+    }
+
+    // The Maker is not told. A pending Offer is simply no longer followed up on by this Taker.
+    // An accepted Offer commits the Taker to the trade, so is refused to be cancelled
+    pub async fn cancel_taker(&self, trade_uuid: Uuid) -> Result<(), N3xbError> {
+        debug!(
+            "Manager w/ pubkey {} cancelling Taker w/ TradeUUID {}",
+            self.pubkey().await,
+            trade_uuid
+        );
+
+        let mut taker_accessors = self.taker_accessors.write().await;
+        let Some(taker_accessor) = taker_accessors.get(&trade_uuid).cloned() else {
+            return Err(N3xbError::Simple(format!(
+                "Manager has no Taker w/ TradeUUID {}",
+                trade_uuid
+            )));
+        };
+        let status = taker_accessor.status().await;
+        if matches!(
+            status,
+            TakerTradeStatus::Accepted | TakerTradeStatus::Trading
+        ) {
+            return Err(N3xbError::Simple(format!(
+                "Manager cannot cancel Taker w/ TradeUUID {} with an accepted Offer, status {}",
+                trade_uuid, status
+            )));
+        }
+        taker_accessors.remove(&trade_uuid);
+        drop(taker_accessors);
+        if let Some(error) = taker_accessor.shutdown().await.err() {
+            warn!("Manager error shutting down Taker: {}", error);
+        }
+        if let Some(taker) = self.takers.write().await.remove(&trade_uuid) {
+            taker.task_handle.await?;
+        }
+
+        if let Some(error) = self
+            .comms_accessor
+            .unregister_peer_message_tx(trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Manager failed to unregister Peer Messages for cancelled Taker w/ TradeUUID {} - {}",
+                trade_uuid, error
+            );
+        }
+        if let Some(error) = self
+            .comms_accessor
+            .unregister_trade_filter(trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Manager failed to unregister trade filter for cancelled Taker w/ TradeUUID {} - {}",
+                trade_uuid, error
+            );
+        }
+
+        // So the cancelled Taker is not restored on the next start. It could be from before a rotation
+        for manager_dir_key in self.manager_dir_keys().await {
+            let taker_data_key = format!(
+                "{}/{}/{}{}",
+                manager_dir_key, TAKERS_DIR_STR, trade_uuid, TAKER_FILE_SUFFIX_STR
+            );
+            self.store.delete(&taker_data_key)?;
+        }
+        Ok(())
+    }
+
+    async fn check_trade_uuid_unique(
+        &self,
+        makers: &HashMap<Uuid, Maker>,
+        takers: &HashMap<Uuid, Taker>,
+        trade_uuid: Uuid,
+    ) -> Result<(), N3xbError> {
+        if makers.contains_key(&trade_uuid) || takers.contains_key(&trade_uuid) {
+            warn!(
+                "Manager w/ pubkey {} already has an active trade w/ TradeUUID {}",
+                self.pubkey().await,
+                trade_uuid
+            );
+            return Err(N3xbError::DuplicateTradeUuid(trade_uuid));
+        }
+        Ok(())
+    }
+
     pub async fn get_makers(&self) -> HashMap<Uuid, MakerAccess> {
         self.maker_accessors.read().await.clone()
     }
@@ -340,6 +922,78 @@ impl Manager {
         self.taker_accessors.read().await.clone()
     }
 
+    // Storage Management
+
+    pub async fn disk_usage(&self) -> Result<DiskUsage, N3xbError> {
+        debug!("Manager w/ pubkey {} reporting disk usage", self.pubkey().await);
+
+        let mut maker_bytes = HashMap::new();
+        let mut taker_bytes = HashMap::new();
+        let mut total_bytes: u64 = 0;
+        for manager_dir_key in self.manager_dir_keys().await {
+            let maker_dir_key = format!("{}/{}", manager_dir_key, MAKERS_DIR_STR);
+            let taker_dir_key = format!("{}/{}", manager_dir_key, TAKERS_DIR_STR);
+            maker_bytes.extend(self.trade_blobs_bytes(&maker_dir_key, MAKER_FILE_SUFFIX_STR)?);
+            taker_bytes.extend(self.trade_blobs_bytes(&taker_dir_key, TAKER_FILE_SUFFIX_STR)?);
+            total_bytes += self.dir_bytes(&manager_dir_key)?
+                + self.dir_bytes(&maker_dir_key)?
+                + self.dir_bytes(&taker_dir_key)?;
+        }
+
+        Ok(DiskUsage {
+            maker_bytes,
+            taker_bytes,
+            total_bytes,
+        })
+    }
+
+    async fn dir_key(&self, dir_str: &str) -> String {
+        format!("{}/{}", self.manager_dir_key.read().await, dir_str)
+    }
+
+    // Current data directory, and those of identities rotated away from
+    async fn manager_dir_keys(&self) -> Vec<String> {
+        let mut manager_dir_keys = self.retired_manager_dir_keys.read().await.clone();
+        manager_dir_keys.push(self.manager_dir_key.read().await.clone());
+        manager_dir_keys
+    }
+
+    fn trade_blobs_bytes(
+        &self,
+        dir_key: &str,
+        key_suffix: &str,
+    ) -> Result<HashMap<Uuid, u64>, N3xbError> {
+        let mut trade_bytes = HashMap::new();
+        for key in self.store.list(dir_key)? {
+            let Some(uuid_str) = key
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.strip_suffix(key_suffix))
+            else {
+                continue;
+            };
+            let Ok(trade_uuid) = Uuid::parse_str(uuid_str) else {
+                continue;
+            };
+            trade_bytes.insert(trade_uuid, self.blob_bytes(&key)?);
+        }
+        Ok(trade_bytes)
+    }
+
+    // Blobs right under the key prefix only
+    fn dir_bytes(&self, dir_key: &str) -> Result<u64, N3xbError> {
+        let mut bytes: u64 = 0;
+        for key in self.store.list(dir_key)? {
+            bytes += self.blob_bytes(&key)?;
+        }
+        Ok(bytes)
+    }
+
+    fn blob_bytes(&self, key: &str) -> Result<u64, N3xbError> {
+        let blob = self.store.load(key)?;
+        Ok(blob.map_or(0, |blob| blob.len() as u64))
+    }
+
     pub async fn shutdown(self) -> Result<(), JoinError> {
         debug!("Manager w/ pubkey {} shutting down", self.pubkey().await);
 
@@ -1,4 +1,6 @@
-use std::result::Result;
+use std::{collections::HashSet, result::Result};
+
+use url::Url;
 
 use crate::common::{error::N3xbError, types::SerdeGenericTrait};
 
@@ -10,6 +12,8 @@ pub struct OfferBuilder {
     market_oracle_used: Option<String>,
     trade_engine_specifics: Option<Box<dyn SerdeGenericTrait>>,
     pow_difficulty: Option<u64>,
+    taker_relay_urls: Option<HashSet<Url>>,
+    order_terms_hash: Option<String>,
 }
 
 impl OfferBuilder {
@@ -20,6 +24,8 @@ impl OfferBuilder {
             market_oracle_used: None,
             trade_engine_specifics: None,
             pow_difficulty: None,
+            taker_relay_urls: None,
+            order_terms_hash: None,
         }
     }
 
@@ -51,6 +57,16 @@ impl OfferBuilder {
         self
     }
 
+    pub fn taker_relay_urls(&mut self, taker_relay_urls: HashSet<Url>) -> &mut Self {
+        self.taker_relay_urls = Some(taker_relay_urls);
+        self
+    }
+
+    pub fn order_terms_hash(&mut self, order_terms_hash: impl Into<String>) -> &mut Self {
+        self.order_terms_hash = Some(order_terms_hash.into());
+        self
+    }
+
     pub fn build(&mut self) -> Result<Offer, N3xbError> {
         let Some(maker_obligation) = self.maker_obligation.as_ref() else {
             return Err(N3xbError::Simple("No Maker Obligations defined".to_string()));  // TODO: Error handling?
@@ -70,6 +86,9 @@ impl OfferBuilder {
             market_oracle_used: self.market_oracle_used.take(),
             trade_engine_specifics,
             pow_difficulty: self.pow_difficulty.take(),
+            taker_relay_urls: self.taker_relay_urls.take().unwrap_or_default(),
+            order_terms_hash: self.order_terms_hash.take(),
+            encryption_pubkey: None,
             _private: (),
         };
 
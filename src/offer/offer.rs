@@ -5,8 +5,11 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::{
-    common::{error::OfferInvalidReason, types::*},
-    order::Order,
+    common::{
+        error::{N3xbError, OfferInvalidReason},
+        types::*,
+    },
+    order::{Order, OrderEnvelope, TradeParameter},
 };
 
 // Take Order Message Data Structure
@@ -15,7 +18,11 @@ pub struct OfferEnvelope {
     pub pubkey: XOnlyPublicKey,
     pub urls: HashSet<Url>,
     pub event_id: EventIdString,
+    #[serde(default)]
+    pub created_at: u64,
     pub offer: Offer,
+    #[serde(default)]
+    pub trade_engine_specifics_defaulted: bool, // Default substituted for absent or malformed ones
     pub(crate) _private: (),
 }
 
@@ -33,6 +40,12 @@ pub struct Offer {
     pub market_oracle_used: Option<String>, // TODO: Change to URL type
     pub trade_engine_specifics: Box<dyn SerdeGenericTrait>,
     pub pow_difficulty: Option<u64>,
+    #[serde(default)]
+    pub taker_relay_urls: HashSet<Url>, // Relays the Taker prefers to receive follow-up messages on
+    #[serde(default)]
+    pub order_terms_hash: Option<String>, // Order::terms_hash() of the Order as the Taker saw it
+    #[serde(default)]
+    pub encryption_pubkey: Option<XOnlyPublicKey>, // Per-trade peer message key. Filled by the Taker if enabled
     pub(crate) _private: (),
 }
 
@@ -45,8 +58,34 @@ impl SerdeGenericTrait for Offer {
 
 impl Offer {
     pub fn validate_against(&self, order: &Order) -> Result<(), OfferInvalidReason> {
+        self.validate_against_terms(order, order.terms_hash())
+    }
+
+    // For the Taker, whose Order might have had its Trade Engine specifics defaulted on receipt.
+    // See OrderEnvelope::terms_hash()
+    pub fn validate_against_envelope(
+        &self,
+        order_envelope: &OrderEnvelope,
+    ) -> Result<(), OfferInvalidReason> {
+        self.validate_against_terms(&order_envelope.order, order_envelope.terms_hash())
+    }
+
+    fn validate_against_terms(
+        &self,
+        order: &Order,
+        terms_hash: Result<String, N3xbError>,
+    ) -> Result<(), OfferInvalidReason> {
+        // Offers without a terms hash are from Takers not pinning the terms, and are validated as is
+        if let Some(order_terms_hash) = &self.order_terms_hash {
+            match terms_hash {
+                Ok(terms_hash) if &terms_hash == order_terms_hash => {}
+                _ => return Err(OfferInvalidReason::StaleTerms),
+            }
+        }
+
         self.validate_maker_obligation_against(order)?;
         self.validate_taker_obligation_against(order)?;
+        self.validate_partial_take_against(order)?;
 
         // Check Taker suggested PoW difficulty is higher than in initial Maker Order
         if let Some(pow_difficulty) = self.pow_difficulty {
@@ -58,6 +97,36 @@ impl Offer {
         Ok(())
     }
 
+    // Taker amount per unit of Maker amount, same convention as the Order's limit rate.
+    // None if the Offer's obligation kinds are not amongst the Order's, or the amounts can't form a rate
+    pub fn effective_rate(&self, order_envelope: &OrderEnvelope) -> Option<f64> {
+        let order = &order_envelope.order;
+        if !order
+            .maker_obligation
+            .kinds
+            .contains(&self.maker_obligation.kind)
+            || !order
+                .taker_obligation
+                .kinds
+                .contains(&self.taker_obligation.kind)
+        {
+            return None;
+        }
+
+        let maker_amount = self.maker_obligation.amount;
+        let taker_amount = self.taker_obligation.amount;
+        if !maker_amount.is_normal() || maker_amount < 0.0 || !taker_amount.is_finite() {
+            return None;
+        }
+
+        let rate = taker_amount / maker_amount;
+        if rate.is_finite() && rate >= 0.0 {
+            Some(rate)
+        } else {
+            None
+        }
+    }
+
     fn f64_amount_within_pct_of(float1: f64, float2: f64, pct: f64) -> bool {
         let max = float1 * (1.0 + pct / 100.0);
         let min = float1 * (1.0 - pct / 100.0);
@@ -76,7 +145,7 @@ impl Offer {
             }
             Ok(self.taker_obligation.amount as u64)
         } else {
-            panic!("Neither Maker nor Taker has Bitcoin obligation in Offer")
+            Err(OfferInvalidReason::NoBitcoinObligation)
         };
     }
 
@@ -121,6 +190,36 @@ impl Offer {
         Ok(())
     }
 
+    // Offers taking the full Order amount are not partial takes, and are not bound by them
+    fn validate_partial_take_against(&self, order: &Order) -> Result<(), OfferInvalidReason> {
+        if !order
+            .trade_details
+            .parameters
+            .contains(&TradeParameter::AcceptsPartialTake)
+            || self.maker_obligation.amount >= order.maker_obligation.content.amount
+        {
+            return Ok(());
+        }
+
+        let content = &order.trade_details.content;
+        if content.min_partial_amount.is_none() && content.max_partial_amount.is_none() {
+            return Ok(());
+        }
+
+        let sat_amount = self.transacted_sat_amount()?;
+        if let Some(min_partial_amount) = content.min_partial_amount {
+            if sat_amount < min_partial_amount {
+                return Err(OfferInvalidReason::PartialTakeAmountInvalid);
+            }
+        }
+        if let Some(max_partial_amount) = content.max_partial_amount {
+            if sat_amount > max_partial_amount {
+                return Err(OfferInvalidReason::PartialTakeAmountInvalid);
+            }
+        }
+        Ok(())
+    }
+
     fn validate_taker_obligation_against(&self, order: &Order) -> Result<(), OfferInvalidReason> {
         if !order
             .taker_obligation
@@ -172,14 +271,126 @@ mod tests {
     use iso_currency::Currency;
 
     use crate::{
-        common::types::{
-            BitcoinNetwork, BitcoinSettlementMethod, FiatPaymentMethod, ObligationKind,
+        common::{
+            error::OfferInvalidReason,
+            types::{BitcoinNetwork, BitcoinSettlementMethod, FiatPaymentMethod, ObligationKind},
         },
         offer::Obligation,
         order::{MakerObligation, MakerObligationContent, TradeDetails, TradeDetailsContent},
-        testing::{SomeTestOfferParams, SomeTestOrderParams},
+        testing::{SomeTestOfferParams, SomeTestOrderParams, SomeTestParams},
     };
 
+    fn assert_rate_near(rate: f64, expected: f64) {
+        print!("Effective rate: {}, expected: {}\n", rate, expected);
+        assert!((rate - expected).abs() <= expected * 0.00001);
+    }
+
+    #[tokio::test]
+    async fn test_effective_rate_cny_for_btc() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+
+        let rate = offer
+            .effective_rate(&SomeTestOrderParams::some_order_envelope(
+                order,
+                SomeTestParams::some_event_id(0),
+                0,
+            ))
+            .unwrap();
+        assert_rate_near(rate, 10000000.0 / 35000.0);
+    }
+
+    #[tokio::test]
+    async fn test_effective_rate_btc_for_eur() {
+        let order = SomeTestOrderParams::default_sell_builder().build().unwrap();
+        let offer = SomeTestOfferParams::default_sell_builder().build().unwrap();
+
+        let rate = offer
+            .effective_rate(&SomeTestOrderParams::some_order_envelope(
+                order,
+                SomeTestParams::some_event_id(0),
+                0,
+            ))
+            .unwrap();
+        assert_rate_near(rate, 4500.0 / 10000000.0);
+    }
+
+    #[tokio::test]
+    async fn test_effective_rate_partial_take() {
+        let maker_obligation = MakerObligation {
+            kinds: SomeTestOrderParams::obligation_fiat_cny_kinds(),
+            content: MakerObligationContent {
+                amount: 35000.0,
+                amount_min: Some(10000.0),
+            },
+        };
+        let order = SomeTestOrderParams::default_buy_builder()
+            .maker_obligation(maker_obligation)
+            .build()
+            .unwrap();
+
+        let mut maker_obligation = SomeTestOfferParams::maker_obligation_rmb_wechat();
+        maker_obligation.amount = 17500.0;
+        let mut taker_obligation =
+            SomeTestOfferParams::taker_obligation_bitcoin_lightning(BitcoinNetwork::Regtest);
+        taker_obligation.amount = 5000000.0;
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.maker_obligation(maker_obligation);
+        builder.taker_obligation(taker_obligation);
+        let offer = builder.build().unwrap();
+
+        // Taking half the Order should imply the same rate as taking all of it
+        let rate = offer
+            .effective_rate(&SomeTestOrderParams::some_order_envelope(
+                order,
+                SomeTestParams::some_event_id(0),
+                0,
+            ))
+            .unwrap();
+        assert_rate_near(rate, 10000000.0 / 35000.0);
+    }
+
+    #[tokio::test]
+    async fn test_effective_rate_kind_not_comparable() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.maker_obligation(SomeTestOfferParams::maker_obligation_bitcoin_lightning(
+            BitcoinNetwork::Regtest,
+        ));
+        builder.taker_obligation(SomeTestOfferParams::taker_obligation_usd_zelle());
+        let offer = builder.build().unwrap();
+
+        assert!(offer
+            .effective_rate(&SomeTestOrderParams::some_order_envelope(
+                order,
+                SomeTestParams::some_event_id(0),
+                0
+            ))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_effective_rate_zero_maker_amount() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+
+        let mut maker_obligation = SomeTestOfferParams::maker_obligation_rmb_wechat();
+        maker_obligation.amount = 0.0;
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.maker_obligation(maker_obligation);
+        let offer = builder.build().unwrap();
+
+        assert!(offer
+            .effective_rate(&SomeTestOrderParams::some_order_envelope(
+                order,
+                SomeTestParams::some_event_id(0),
+                0
+            ))
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_validate_offer() {
         let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
@@ -363,6 +574,10 @@ mod tests {
                 maker_bond_pct: None,
                 taker_bond_pct: Some(10),
                 trade_timeout: None,
+                trusted_pubkeys: None,
+                escrow: Some(SomeTestOrderParams::escrow_details()),
+                min_partial_amount: None,
+                max_partial_amount: None,
             },
         };
 
@@ -473,6 +688,10 @@ mod tests {
                 maker_bond_pct: Some(10),
                 taker_bond_pct: None,
                 trade_timeout: None,
+                trusted_pubkeys: None,
+                escrow: Some(SomeTestOrderParams::escrow_details()),
+                min_partial_amount: None,
+                max_partial_amount: None,
             },
         };
 
@@ -495,4 +714,84 @@ mod tests {
         let result = offer.validate_against(&order);
         assert!(result.is_err());
     }
+    #[tokio::test]
+    async fn test_validate_offer_stale_terms() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.order_terms_hash(order.terms_hash().unwrap());
+        let offer = builder.build().unwrap();
+        offer.validate_against(&order).unwrap();
+
+        let mut updated_order = order.clone();
+        updated_order.trade_details.content.trade_timeout = Some(3600);
+        let result = offer.validate_against(&updated_order);
+        assert!(matches!(result, Err(OfferInvalidReason::StaleTerms)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_offer_partial_take_amounts() {
+        let maker_obligation = MakerObligation {
+            kinds: SomeTestOrderParams::obligation_fiat_cny_kinds(),
+            content: MakerObligationContent {
+                amount: 35000.0,
+                amount_min: Some(10000.0),
+            },
+        };
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: TradeDetailsContent {
+                min_partial_amount: Some(6000000),
+                ..SomeTestOrderParams::trade_details_content()
+            },
+        };
+        let order = SomeTestOrderParams::default_buy_builder()
+            .maker_obligation(maker_obligation)
+            .trade_details(trade_details)
+            .build()
+            .unwrap();
+
+        // Taking half the Order is 5M sats, below the 6M sats minimum
+        let mut maker_obligation = SomeTestOfferParams::maker_obligation_rmb_wechat();
+        maker_obligation.amount = 17500.0;
+        maker_obligation.bond_amount = Some(500000.0);
+        let mut taker_obligation =
+            SomeTestOfferParams::taker_obligation_bitcoin_lightning(BitcoinNetwork::Regtest);
+        taker_obligation.amount = 5000000.0;
+        taker_obligation.bond_amount = Some(500000.0);
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.maker_obligation(maker_obligation);
+        builder.taker_obligation(taker_obligation);
+        let offer = builder.build().unwrap();
+
+        let result = offer.validate_against(&order);
+        assert!(matches!(
+            result,
+            Err(OfferInvalidReason::PartialTakeAmountInvalid)
+        ));
+
+        let mut lowered_order = order.clone();
+        lowered_order.trade_details.content.min_partial_amount = Some(4000000);
+        offer.validate_against(&lowered_order).unwrap();
+
+        // Taking the full Order is not a partial take
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        offer.validate_against(&order).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_offer_no_bitcoin_obligation() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+
+        let mut builder = SomeTestOfferParams::default_buy_builder();
+        builder.taker_obligation(SomeTestOfferParams::maker_obligation_rmb_wechat());
+        let offer = builder.build().unwrap();
+
+        let result = offer.validate_against(&order);
+        assert!(matches!(
+            result,
+            Err(OfferInvalidReason::NoBitcoinObligation)
+        ));
+    }
 }
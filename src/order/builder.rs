@@ -1,17 +1,25 @@
+use std::collections::{HashMap, HashSet};
+
+use secp256k1::XOnlyPublicKey;
 use uuid::Uuid;
 
 use super::{obligation::*, order::*, trade_details::*};
 
 use crate::common::error::*;
-use crate::common::types::SerdeGenericTrait;
+use crate::common::types::{ObligationKind, SerdeGenericTrait};
 
 pub struct OrderBuilder {
     trade_uuid: Option<Uuid>,
     maker_obligation: Option<MakerObligation>,
+    maker_obligation_kinds: HashSet<ObligationKind>,
     taker_obligation: Option<TakerObligation>,
+    taker_obligation_kinds: HashSet<ObligationKind>,
     trade_details: Option<TradeDetails>,
     trade_engine_specifics: Option<Box<dyn SerdeGenericTrait>>,
     pow_difficulty: Option<u64>,
+    expiration: Option<u64>,
+    custom_tags: HashMap<String, HashSet<String>>,
+    geohash: Option<String>,
 }
 
 impl OrderBuilder {
@@ -19,10 +27,15 @@ impl OrderBuilder {
         OrderBuilder {
             trade_uuid: Option::<Uuid>::None,
             maker_obligation: Option::<MakerObligation>::None,
+            maker_obligation_kinds: HashSet::new(),
             taker_obligation: Option::<TakerObligation>::None,
+            taker_obligation_kinds: HashSet::new(),
             trade_details: Option::<TradeDetails>::None,
             trade_engine_specifics: Option::None,
             pow_difficulty: Option::<u64>::None,
+            expiration: Option::<u64>::None,
+            custom_tags: HashMap::new(),
+            geohash: Option::<String>::None,
         }
     }
 
@@ -36,11 +49,23 @@ impl OrderBuilder {
         self
     }
 
+    // Accumulates on top of the kinds of the Maker Obligation set above
+    pub fn add_maker_obligation_kind(&mut self, kind: impl Into<ObligationKind>) -> &mut Self {
+        self.maker_obligation_kinds.insert(kind.into());
+        self
+    }
+
     pub fn taker_obligation(&mut self, taker_obligation: impl Into<TakerObligation>) -> &mut Self {
         self.taker_obligation = Some(taker_obligation.into());
         self
     }
 
+    // Accumulates on top of the kinds of the Taker Obligation set above
+    pub fn add_taker_obligation_kind(&mut self, kind: impl Into<ObligationKind>) -> &mut Self {
+        self.taker_obligation_kinds.insert(kind.into());
+        self
+    }
+
     pub fn trade_details(&mut self, trade_details: impl Into<TradeDetails>) -> &mut Self {
         self.trade_details = Some(trade_details.into());
         self
@@ -59,6 +84,32 @@ impl OrderBuilder {
         self
     }
 
+    // Unix seconds. Without this, the expiration is derived from the Trade Time Out if there is one
+    pub fn expiration(&mut self, expiration: impl Into<u64>) -> &mut Self {
+        self.expiration = Some(expiration.into());
+        self
+    }
+
+    // Values of the same key accumulate. Keys should not contain ':'
+    pub fn custom_tag(
+        &mut self,
+        key: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.custom_tags
+            .entry(key.into())
+            .or_default()
+            .extend(values.into_iter().map(|value| value.into()));
+        self
+    }
+
+    // Publicly discoverable area only, such as 5 or 6 characters. Exact meeting details should be
+    // arranged privately over Peer Messages
+    pub fn geohash(&mut self, geohash: impl Into<String>) -> &mut Self {
+        self.geohash = Some(geohash.into());
+        self
+    }
+
     pub fn build(&mut self) -> std::result::Result<Order, N3xbError> {
         let trade_uuid = if let Some(explicit_uuid) = self.trade_uuid.as_ref() {
             explicit_uuid.to_owned()
@@ -83,6 +134,7 @@ impl OrderBuilder {
             return Err(N3xbError::Simple("No Trade Details defined".to_string()));
             // TODO: Error handling?
         };
+        trade_details.validate_trade_timeout_consistent()?;
 
         let Some(trade_engine_specifics) = self.trade_engine_specifics.take() else {
             return Err(N3xbError::Simple(
@@ -92,13 +144,26 @@ impl OrderBuilder {
 
         let pow_difficulty = self.pow_difficulty.unwrap_or_else(|| 0);
 
+        let mut maker_obligation = maker_obligation.to_owned();
+        maker_obligation
+            .kinds
+            .extend(self.maker_obligation_kinds.iter().cloned());
+
+        let mut taker_obligation = taker_obligation.to_owned();
+        taker_obligation
+            .kinds
+            .extend(self.taker_obligation_kinds.iter().cloned());
+
         let order = Order {
             trade_uuid: trade_uuid,
-            maker_obligation: maker_obligation.to_owned(),
-            taker_obligation: taker_obligation.to_owned(),
+            maker_obligation,
+            taker_obligation,
             trade_details: trade_details.to_owned(),
             trade_engine_specifics: trade_engine_specifics,
             pow_difficulty,
+            expiration: self.expiration,
+            custom_tags: self.custom_tags.clone(),
+            geohash: self.geohash.clone(),
             _private: (),
         };
 
@@ -107,10 +172,96 @@ impl OrderBuilder {
     }
 }
 
+pub struct TradeDetailsBuilder {
+    parameters: HashSet<TradeParameter>,
+    content: TradeDetailsContent,
+}
+
+impl TradeDetailsBuilder {
+    pub fn new() -> Self {
+        TradeDetailsBuilder {
+            parameters: HashSet::new(),
+            content: TradeDetailsContent {
+                maker_bond_pct: None,
+                taker_bond_pct: None,
+                trade_timeout: None,
+                trusted_pubkeys: None,
+                escrow: None,
+                min_partial_amount: None,
+                max_partial_amount: None,
+            },
+        }
+    }
+
+    // Accumulates. A TradeTimesOut parameter replaces any prior one
+    pub fn parameter(&mut self, parameter: impl Into<TradeParameter>) -> &mut Self {
+        let parameter = parameter.into();
+        if let TradeParameter::TradeTimesOut(_) = parameter {
+            self.parameters
+                .retain(|parameter| !matches!(parameter, TradeParameter::TradeTimesOut(_)));
+        }
+        self.parameters.insert(parameter);
+        self
+    }
+
+    pub fn maker_bond_pct(&mut self, maker_bond_pct: impl Into<u32>) -> &mut Self {
+        self.content.maker_bond_pct = Some(maker_bond_pct.into());
+        self
+    }
+
+    pub fn taker_bond_pct(&mut self, taker_bond_pct: impl Into<u32>) -> &mut Self {
+        self.content.taker_bond_pct = Some(taker_bond_pct.into());
+        self
+    }
+
+    // Seconds
+    pub fn trade_timeout(&mut self, trade_timeout: impl Into<u32>) -> &mut Self {
+        self.content.trade_timeout = Some(trade_timeout.into());
+        self
+    }
+
+    pub fn trusted_pubkeys(&mut self, trusted_pubkeys: HashSet<XOnlyPublicKey>) -> &mut Self {
+        self.content.trusted_pubkeys = Some(trusted_pubkeys);
+        self
+    }
+
+    pub fn escrow(&mut self, escrow: impl Into<EscrowDetails>) -> &mut Self {
+        self.content.escrow = Some(escrow.into());
+        self
+    }
+
+    // Sats
+    pub fn min_partial_amount(&mut self, min_partial_amount: impl Into<u64>) -> &mut Self {
+        self.content.min_partial_amount = Some(min_partial_amount.into());
+        self
+    }
+
+    // Sats
+    pub fn max_partial_amount(&mut self, max_partial_amount: impl Into<u64>) -> &mut Self {
+        self.content.max_partial_amount = Some(max_partial_amount.into());
+        self
+    }
+
+    pub fn build(&self) -> std::result::Result<TradeDetails, N3xbError> {
+        let trade_details = TradeDetails {
+            parameters: self.parameters.clone(),
+            content: self.content.clone(),
+        };
+        trade_details.validate()?;
+        trade_details.validate_trade_timeout_consistent()?;
+        Ok(trade_details)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use iso_currency::Currency;
+
     use super::*;
-    use crate::{common::types::BitcoinNetwork, testing::*};
+    use crate::{
+        common::types::{BitcoinNetwork, BitcoinSettlementMethod, FiatPaymentMethod},
+        testing::*,
+    };
     use core::panic;
 
     #[tokio::test]
@@ -436,4 +587,250 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_order_builder_add_obligation_kinds() {
+        let mut builder: OrderBuilder = OrderBuilder::new();
+
+        builder.maker_obligation(MakerObligation {
+            kinds: HashSet::new(),
+            content: SomeTestOrderParams::maker_obligation_fiat_cny_content(),
+        });
+        builder
+            .add_maker_obligation_kind(ObligationKind::Fiat(
+                Currency::CNY,
+                Some(FiatPaymentMethod::WeChatPay),
+            ))
+            .add_maker_obligation_kind(ObligationKind::Fiat(
+                Currency::CNY,
+                Some(FiatPaymentMethod::AliPay),
+            ));
+
+        builder.taker_obligation(TakerObligation {
+            kinds: HashSet::new(),
+            content: SomeTestOrderParams::taker_obligation_bitcoin_rmb_content(),
+        });
+        builder.add_taker_obligation_kind(ObligationKind::Bitcoin(
+            BitcoinNetwork::Regtest,
+            Some(BitcoinSettlementMethod::Lightning),
+        ));
+
+        builder.trade_details(TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: SomeTestOrderParams::trade_details_content(),
+        });
+
+        let trade_engine_specifics = Box::new(SomeTradeEngineMakerOrderSpecifics {
+            test_specific_field: SomeTestParams::engine_specific_str(),
+        });
+        builder.trade_engine_specifics(trade_engine_specifics);
+
+        let order = builder.build().unwrap();
+        assert_eq!(
+            order.maker_obligation.kinds,
+            SomeTestOrderParams::obligation_fiat_cny_kinds()
+        );
+        assert_eq!(
+            order.taker_obligation.kinds,
+            SomeTestOrderParams::obligation_bitcoin_lightning_kinds(BitcoinNetwork::Regtest)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_builder_add_obligation_kind_currency_mismatch() {
+        let mut builder: OrderBuilder = OrderBuilder::new();
+
+        builder.maker_obligation(MakerObligation {
+            kinds: SomeTestOrderParams::obligation_fiat_cny_kinds(),
+            content: SomeTestOrderParams::maker_obligation_fiat_cny_content(),
+        });
+        builder.add_maker_obligation_kind(ObligationKind::Fiat(
+            Currency::USD,
+            Some(FiatPaymentMethod::Zelle),
+        ));
+
+        builder.taker_obligation(TakerObligation {
+            kinds: SomeTestOrderParams::obligation_bitcoin_lightning_kinds(BitcoinNetwork::Regtest),
+            content: SomeTestOrderParams::taker_obligation_bitcoin_rmb_content(),
+        });
+
+        builder.trade_details(TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: SomeTestOrderParams::trade_details_content(),
+        });
+
+        let trade_engine_specifics = Box::new(SomeTradeEngineMakerOrderSpecifics {
+            test_specific_field: SomeTestParams::engine_specific_str(),
+        });
+        builder.trade_engine_specifics(trade_engine_specifics);
+
+        assert!(builder.build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_order_builder_custom_tag_key_invalid() {
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.custom_tag("region:eu", ["fr"]);
+        assert!(builder.build().is_err());
+
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.custom_tag("", ["fr"]);
+        assert!(builder.build().is_err());
+
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder
+            .custom_tag("region", ["eu"])
+            .custom_tag("region", ["us"]);
+        let order = builder.build().unwrap();
+        assert_eq!(
+            order.custom_tags.get("region").unwrap(),
+            &HashSet::from(["eu".to_string(), "us".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_builder_geohash() {
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.geohash("u4pruy");
+        let order = builder.build().unwrap();
+        assert_eq!(order.geohash, Some("u4pruy".to_string()));
+
+        // 'a', 'i', 'l' and 'o' are not in the geohash alphabet
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.geohash("u4pruya");
+        assert!(builder.build().is_err());
+
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.geohash("");
+        assert!(builder.build().is_err());
+
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.geohash("u4pruydqqvj8p");
+        assert!(builder.build().is_err());
+    }
+
+    fn some_trade_details_builder() -> TradeDetailsBuilder {
+        let mut builder = TradeDetailsBuilder::new();
+        builder
+            .parameter(TradeParameter::BondsRequired)
+            .parameter(TradeParameter::TrustedEscrow)
+            .parameter(TradeParameter::AcceptsPartialTake)
+            .parameter(TradeParameter::TradeTimesOut(TradeTimeOutLimit::OneDay))
+            .maker_bond_pct(10u32)
+            .taker_bond_pct(10u32)
+            .trade_timeout(3600u32)
+            .escrow(SomeTestOrderParams::escrow_details())
+            .min_partial_amount(100000u64)
+            .max_partial_amount(1000000u64);
+        builder
+    }
+
+    #[test]
+    fn test_trade_details_builder_build() {
+        let trade_details = some_trade_details_builder().build().unwrap();
+        assert_eq!(trade_details.parameters.len(), 4);
+        assert_eq!(trade_details.content.maker_bond_pct, Some(10));
+        assert_eq!(trade_details.content.trade_timeout, Some(3600));
+        assert_eq!(
+            trade_details.trade_timeout_limit(),
+            Some(TradeTimeOutLimit::OneDay)
+        );
+
+        // Only the last TradeTimesOut parameter is kept
+        let mut builder = some_trade_details_builder();
+        builder.parameter(TradeParameter::TradeTimesOut(TradeTimeOutLimit::FourDays));
+        let trade_details = builder.build().unwrap();
+        assert_eq!(trade_details.parameters.len(), 4);
+        assert_eq!(
+            trade_details.trade_timeout_limit(),
+            Some(TradeTimeOutLimit::FourDays)
+        );
+    }
+
+    #[test]
+    fn test_trade_details_builder_bond_pcts_missing() {
+        let mut builder = some_trade_details_builder();
+        builder.content.maker_bond_pct = None;
+        assert!(builder.build().is_err());
+
+        let mut builder = some_trade_details_builder();
+        builder.content.taker_bond_pct = None;
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_bond_pcts_both_zeros() {
+        let mut builder = some_trade_details_builder();
+        builder.maker_bond_pct(0u32).taker_bond_pct(0u32);
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_trusted_pubkeys_empty() {
+        let mut builder = some_trade_details_builder();
+        builder.trusted_pubkeys(HashSet::new());
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_escrow_missing() {
+        let mut builder = some_trade_details_builder();
+        builder.content.escrow = None;
+        assert!(builder.build().is_err());
+
+        let mut builder = some_trade_details_builder();
+        builder.escrow(EscrowDetails {
+            pubkey: None,
+            endpoint: None,
+        });
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_partial_amounts_invalid() {
+        let mut builder = some_trade_details_builder();
+        builder.max_partial_amount(0u64);
+        assert!(builder.build().is_err());
+
+        let mut builder = some_trade_details_builder();
+        builder.min_partial_amount(2000000u64);
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_trade_timeout_without_parameter() {
+        let mut builder = some_trade_details_builder();
+        builder
+            .parameters
+            .retain(|parameter| !matches!(parameter, TradeParameter::TradeTimesOut(_)));
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_trade_details_builder_trade_timeout_exceeds_limit() {
+        let mut builder = some_trade_details_builder();
+        builder.trade_timeout(2 * 24 * 60 * 60u32);
+        assert!(builder.build().is_err());
+
+        // Trade Engine specific limits are not known to n3xB
+        builder.parameter(TradeParameter::TradeTimesOut(
+            TradeTimeOutLimit::TradeEngineSpecific,
+        ));
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn test_order_builder_trade_timeout_only_checked_when_building() {
+        let mut trade_details = some_trade_details_builder().build().unwrap();
+        trade_details.content.trade_timeout = Some(2 * 24 * 60 * 60);
+
+        let mut builder = SomeTestOrderParams::default_buy_builder();
+        builder.trade_details(trade_details.clone());
+        assert!(builder.build().is_err());
+
+        // Orders received are not held to the rule, as older versions posted them without it
+        let mut order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        order.trade_details = trade_details;
+        order.validate().unwrap();
+    }
 }
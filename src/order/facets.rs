@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use iso_currency::Currency;
+use serde::{Deserialize, Serialize};
+
+use super::{order::OrderEnvelope, summary::TradeDirection};
+use crate::common::types::{BitcoinSettlementMethod, FiatPaymentMethod};
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AmountRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl AmountRange {
+    fn include(&mut self, other: AmountRange) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+// Distinct values present across a set of Orders, each with the number of Orders it appears in
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OrderBookFacets {
+    pub total: usize,
+    pub directions: HashMap<TradeDirection, usize>,
+    pub currencies: HashMap<Currency, usize>,
+    pub fiat_methods: HashMap<FiatPaymentMethod, usize>,
+    pub bitcoin_methods: HashMap<BitcoinSettlementMethod, usize>,
+    pub custom_kinds: HashMap<String, usize>,
+    pub fiat_amount_ranges: HashMap<Currency, AmountRange>, // Of Orders where the Maker gives fiat
+    pub bitcoin_amount_range: Option<AmountRange>, // Of Orders where the Maker gives Bitcoin, in sats
+}
+
+pub fn aggregate_filters(orders: &[OrderEnvelope]) -> OrderBookFacets {
+    let mut facets = OrderBookFacets::default();
+
+    for order_envelope in orders {
+        let summary = order_envelope.order.summary();
+        facets.total += 1;
+        *facets
+            .directions
+            .entry(summary.direction.clone())
+            .or_default() += 1;
+
+        if let Some(currency) = summary.fiat_currency {
+            *facets.currencies.entry(currency).or_default() += 1;
+        }
+        for method in summary.fiat_methods {
+            *facets.fiat_methods.entry(method).or_default() += 1;
+        }
+        for method in summary.bitcoin_methods {
+            *facets.bitcoin_methods.entry(method).or_default() += 1;
+        }
+        for kind in summary.custom_kinds {
+            *facets.custom_kinds.entry(kind).or_default() += 1;
+        }
+
+        let range = AmountRange {
+            min: summary.amount_min.unwrap_or(summary.amount),
+            max: summary.amount,
+        };
+        match (summary.direction, summary.fiat_currency) {
+            (TradeDirection::BuyBitcoin, Some(currency)) => {
+                facets
+                    .fiat_amount_ranges
+                    .entry(currency)
+                    .and_modify(|existing| existing.include(range))
+                    .or_insert(range);
+            }
+            (TradeDirection::SellBitcoin, _) => match &mut facets.bitcoin_amount_range {
+                Some(existing) => existing.include(range),
+                None => facets.bitcoin_amount_range = Some(range),
+            },
+            _ => {}
+        }
+    }
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{common::types::EventIdString, testing::SomeTestOrderParams};
+
+    #[test]
+    fn test_aggregate_filters() {
+        let buy_order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let sell_order = SomeTestOrderParams::default_sell_builder().build().unwrap();
+        let orders = vec![
+            SomeTestOrderParams::some_order_envelope(
+                buy_order.clone(),
+                EventIdString::from_str(&format!("{:064x}", buy_order.trade_uuid.as_u128()))
+                    .unwrap(),
+                0,
+            ),
+            SomeTestOrderParams::some_order_envelope(
+                buy_order.clone(),
+                EventIdString::from_str(&format!("{:064x}", buy_order.trade_uuid.as_u128()))
+                    .unwrap(),
+                0,
+            ),
+            SomeTestOrderParams::some_order_envelope(
+                sell_order.clone(),
+                EventIdString::from_str(&format!("{:064x}", sell_order.trade_uuid.as_u128()))
+                    .unwrap(),
+                0,
+            ),
+        ];
+
+        let facets = aggregate_filters(&orders);
+        print!("Order Book Facets: {:?}", facets);
+
+        assert_eq!(facets.total, 3);
+        assert_eq!(facets.directions[&TradeDirection::BuyBitcoin], 2);
+        assert_eq!(facets.directions[&TradeDirection::SellBitcoin], 1);
+        assert_eq!(facets.currencies[&Currency::CNY], 2);
+        assert_eq!(facets.currencies[&Currency::EUR], 1);
+        assert_eq!(facets.fiat_methods[&FiatPaymentMethod::WeChatPay], 2);
+        assert_eq!(
+            facets.bitcoin_methods[&BitcoinSettlementMethod::Lightning],
+            3
+        );
+        assert_eq!(facets.bitcoin_methods[&BitcoinSettlementMethod::Onchain], 1);
+
+        let buy_amount = buy_order.maker_obligation.content.amount;
+        let cny_range = facets.fiat_amount_ranges[&Currency::CNY];
+        assert_eq!(cny_range.max, buy_amount);
+        assert_eq!(
+            cny_range.min,
+            buy_order
+                .maker_obligation
+                .content
+                .amount_min
+                .unwrap_or(buy_amount)
+        );
+        assert!(!facets.fiat_amount_ranges.contains_key(&Currency::EUR));
+        assert_eq!(
+            facets.bitcoin_amount_range.unwrap().max,
+            sell_order.maker_obligation.content.amount
+        );
+    }
+}
@@ -1,12 +1,16 @@
 mod builder;
+mod facets;
 mod obligation;
 mod order;
+mod summary;
 mod tags;
 mod trade_details;
 
-pub use builder::OrderBuilder;
+pub use builder::{OrderBuilder, TradeDetailsBuilder};
+pub use facets::{aggregate_filters, AmountRange, OrderBookFacets};
 pub use obligation::*;
 pub use order::{Order, OrderEnvelope};
+pub use summary::{OrderSummary, TradeDirection};
 pub use tags::FilterTag;
 pub(crate) use tags::*;
 pub use trade_details::*;
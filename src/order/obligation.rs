@@ -1,4 +1,6 @@
+use iso_currency::{Currency, IntoEnumIterator};
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator as _;
 
 use std::{collections::HashSet, fmt::Debug};
 
@@ -10,6 +12,16 @@ pub struct MakerObligation {
     pub content: MakerObligationContent,
 }
 
+impl MakerObligation {
+    // All kinds are validated to be of the same currency, so any kind can be used for formatting
+    pub fn format_amount(&self) -> Option<String> {
+        self.kinds
+            .iter()
+            .next()
+            .map(|kind| kind.format_amount(self.content.amount))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TakerObligation {
     pub kinds: HashSet<ObligationKind>,
@@ -28,3 +40,40 @@ pub struct TakerObligationContent {
     pub market_offset_pct: Option<f64>,
     pub market_oracles: Option<HashSet<String>>, // TODO: Change to hashset of URL type
 }
+
+// Enumerations of what can be encoded into Obligation Kinds, for populating order forms
+
+pub fn supported_currencies() -> Vec<Currency> {
+    Currency::iter().collect()
+}
+
+pub fn supported_fiat_methods() -> Vec<FiatPaymentMethod> {
+    FiatPaymentMethod::iter().collect()
+}
+
+pub fn supported_bitcoin_networks() -> Vec<BitcoinNetwork> {
+    BitcoinNetwork::iter().collect()
+}
+
+pub fn supported_bitcoin_methods() -> Vec<BitcoinSettlementMethod> {
+    BitcoinSettlementMethod::iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_obligation_enumerations() {
+        assert!(supported_currencies().contains(&Currency::USD));
+        assert!(supported_fiat_methods().contains(&FiatPaymentMethod::WeChatPay));
+        assert_eq!(supported_bitcoin_networks().len(), 4);
+        assert_eq!(
+            supported_bitcoin_methods(),
+            vec![
+                BitcoinSettlementMethod::Onchain,
+                BitcoinSettlementMethod::Lightning
+            ]
+        );
+    }
+}
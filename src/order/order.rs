@@ -1,15 +1,23 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use nostr_sdk::hashes::{sha256::Hash as Sha256Hash, Hash};
+use nostr_sdk::prelude::Event;
 use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
-use super::{obligation::*, trade_details::*};
+use super::{
+    obligation::*,
+    tags::{FilterTag, OrderTag, CUSTOM_TAG_SEPARATOR, GEOHASH_ALPHABET, GEOHASH_MAX_LEN},
+    trade_details::*,
+};
 use crate::common::{
     error::N3xbError,
     types::{BitcoinNetwork, EventIdString, ObligationKind, SerdeGenericTrait},
+    utils::event_id_leading_zero_bits,
 };
+use crate::comms::MakerOrderNote;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderEnvelope {
@@ -17,9 +25,177 @@ pub struct OrderEnvelope {
     pub urls: HashSet<Url>,
     pub event_id: EventIdString,
     pub order: Order,
+    #[serde(default)]
+    pub delegator_pubkey: Option<XOnlyPublicKey>, // Root identity if signed by a NIP-26 delegated key
+    #[serde(default)]
+    pub created_at: u64,
+    #[serde(default)]
+    pub expiration: Option<u64>, // NIP-40 expiration the Order was posted with, in Unix seconds
+    #[serde(default)]
+    pub(crate) signed_event: Option<Event>, // Maker Order Note as received, see verify_signature()
+    #[serde(default)]
+    pub trade_engine_specifics_defaulted: bool, // Default substituted for absent or malformed ones
     pub(crate) _private: (),
 }
 
+impl OrderEnvelope {
+    // Guard before taking Orders from untrusted sources. The signature is not checked here, see
+    // verify_signature() for Order Envelopes that carry the original Nostr event
+    pub fn verify(&self) -> Result<(), N3xbError> {
+        event_id_leading_zero_bits(self.event_id.as_str())?;
+        self.order.validate()
+    }
+
+    pub fn verify_pow(&self) -> Result<(), N3xbError> {
+        let leading_zero_bits = event_id_leading_zero_bits(self.event_id.as_str())?;
+        if leading_zero_bits < self.order.pow_difficulty {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} has PoW of {} leading zero bits, less than the Order's PoW difficulty of {}",
+                self.event_id, leading_zero_bits, self.order.pow_difficulty
+            )));
+        }
+        Ok(())
+    }
+
+    // Checks the Event ID and Schnorr signature of the Maker Order Note the Order was received in,
+    // and that the Order and envelope fields are what the Maker actually signed
+    pub fn verify_signature(&self) -> Result<(), N3xbError> {
+        let Some(event) = &self.signed_event else {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} does not carry the signed Maker Order Note",
+                self.event_id
+            )));
+        };
+        event.verify()?;
+
+        if EventIdString::from(event.id) != self.event_id
+            || event.pubkey != self.pubkey
+            || event.created_at.as_u64() != self.created_at
+        {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} does not match its signed Maker Order Note w/ Event ID {}",
+                self.event_id, event.id
+            )));
+        }
+
+        // Defaulted specifics stand in for what was signed, so only the rest is compared then
+        let order = &self.order;
+        let order_specifics = serde_json::to_value(&order.trade_engine_specifics)?;
+        let mut signed_content: serde_json::Value = serde_json::from_str(&event.content)?;
+        if self.trade_engine_specifics_defaulted {
+            if let Some(signed_content) = signed_content.as_object_mut() {
+                signed_content.insert(
+                    "trade_engine_specifics".to_string(),
+                    order_specifics.clone(),
+                );
+            }
+        }
+        let maker_order_note: MakerOrderNote = serde_json::from_value(signed_content)?;
+        if maker_order_note.maker_obligation != order.maker_obligation.content
+            || maker_order_note.taker_obligation != order.taker_obligation.content
+            || maker_order_note.trade_details != order.trade_details.content
+            || serde_json::to_value(&maker_order_note.trade_engine_specifics)? != order_specifics
+            || maker_order_note.pow_difficulty != order.pow_difficulty
+        {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} has Order content different from what was signed",
+                self.event_id
+            )));
+        }
+
+        if MakerOrderNote::extract_expiration(event) != self.expiration
+            || MakerOrderNote::extract_delegator_pubkey(event)? != self.delegator_pubkey
+        {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} has expiration or delegator different from what was signed",
+                self.event_id
+            )));
+        }
+
+        // Tags not defined by n3xB, such as for expiration and delegation, are not Order Tags
+        let signed_order_tags: Vec<OrderTag> = event
+            .tags
+            .iter()
+            .map(|tag| tag.as_vec())
+            .filter(|tag_vec| tag_vec.len() > 1 && tag_vec[0].len() == 1)
+            .filter_map(|mut tag_vec| {
+                let key = tag_vec.remove(0);
+                OrderTag::from_key_value(key, tag_vec).ok()
+            })
+            .collect();
+
+        let trade_engine_name = signed_order_tags.iter().find_map(|tag| match tag {
+            OrderTag::TradeEngineName(name) => Some(name.to_owned()),
+            _ => None,
+        });
+        let order_tags =
+            OrderTag::from_order(order.to_owned(), trade_engine_name.unwrap_or_default());
+        if signed_order_tags.len() != order_tags.len()
+            || !order_tags.iter().all(|tag| signed_order_tags.contains(tag))
+        {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} has Order Tags different from what was signed",
+                self.event_id
+            )));
+        }
+        Ok(())
+    }
+
+    // Order::terms_hash(), but over the Trade Engine specifics as the Maker sent them if they were
+    // defaulted on receipt. The Maker only knows of its own specifics, not of the default
+    pub fn terms_hash(&self) -> Result<String, N3xbError> {
+        if !self.trade_engine_specifics_defaulted {
+            return self.order.terms_hash();
+        }
+        let Some(event) = &self.signed_event else {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ Event ID {} has defaulted Trade Engine specifics, but does not carry the signed Maker Order Note",
+                self.event_id
+            )));
+        };
+        let signed_content: serde_json::Value = serde_json::from_str(&event.content)?;
+        let trade_engine_specifics = signed_content
+            .get("trade_engine_specifics")
+            .cloned()
+            .unwrap_or_default();
+        self.order.terms_hash_over(trade_engine_specifics)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiration
+            .map_or(false, |expiration| expiration <= now)
+    }
+
+    // Combines the same Order seen across separate queries or relays. The newest version by
+    // created_at is kept, along with the relay URLs it was seen on from both envelopes
+    pub fn merge(&self, other: &OrderEnvelope) -> Result<OrderEnvelope, N3xbError> {
+        if self.pubkey != other.pubkey || self.order.trade_uuid != other.order.trade_uuid {
+            return Err(N3xbError::Simple(format!(
+                "Order Envelope w/ TradeUUID {} cannot be merged with Order Envelope w/ TradeUUID {} from a different Order",
+                self.order.trade_uuid, other.order.trade_uuid
+            )));
+        }
+
+        let mut merged = if other.created_at > self.created_at {
+            other.to_owned()
+        } else {
+            self.to_owned()
+        };
+        merged.urls = self.urls.union(&other.urls).cloned().collect();
+        Ok(merged)
+    }
+
+    // Filter Tags for querying "more Orders like this one". Orders of the same direction, and
+    // sharing any of the Obligation currencies or methods match. This Order itself matches too
+    pub fn similarity_filter_tags(&self) -> Vec<FilterTag> {
+        vec![
+            FilterTag::TradeDirection(self.order.direction()),
+            FilterTag::MakerObligations(self.order.maker_obligation.kinds.clone()),
+            FilterTag::TakerObligations(self.order.taker_obligation.kinds.clone()),
+        ]
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Order {
     pub trade_uuid: Uuid,
@@ -28,34 +204,111 @@ pub struct Order {
     pub trade_details: TradeDetails,
     pub trade_engine_specifics: Box<dyn SerdeGenericTrait>,
     pub pow_difficulty: u64,
+    #[serde(default)]
+    pub expiration: Option<u64>, // Explicit expiration in Unix seconds, overrides the Trade Time Out
+    #[serde(default)]
+    pub custom_tags: HashMap<String, HashSet<String>>, // Trade Engine defined, see FilterTag::CustomTag
+    #[serde(default)]
+    pub geohash: Option<String>, // Meeting area for in-person trades, see FilterTag::near
     pub(crate) _private: (),
 }
 
 impl Order {
-    pub fn check_bitcoin_network(&self, expected_network: BitcoinNetwork) -> bool {
-        for kind in &self.maker_obligation.kinds {
-            match kind {
-                ObligationKind::Bitcoin(network, _method) => {
-                    if network.to_owned() != expected_network {
-                        return false;
-                    }
+    // Relays supporting NIP-40 will drop the Maker Order Note past this point in time
+    pub fn expiration_from(&self, now: u64) -> Option<u64> {
+        if self.expiration.is_some() {
+            return self.expiration;
+        }
+        self.trade_details
+            .trade_timeout_limit()
+            .and_then(|limit| limit.duration())
+            .map(|duration| now + duration.as_secs())
+    }
+
+    // Deterministic over the terms a Taker Offers against, see Offer::order_terms_hash. Only the
+    // fields listed here count, so fields added to the Order later don't change the hash.
+    // Expiration, PoW difficulty, Custom Tags and geohash are for finding the Order, not terms
+    pub fn terms_hash(&self) -> Result<String, N3xbError> {
+        self.terms_hash_over(serde_json::to_value(&self.trade_engine_specifics)?)
+    }
+
+    fn terms_hash_over(
+        &self,
+        trade_engine_specifics: serde_json::Value,
+    ) -> Result<String, N3xbError> {
+        let mut terms = serde_json::json!({
+            "trade_uuid": self.trade_uuid,
+            "maker_obligation": serde_json::to_value(&self.maker_obligation)?,
+            "taker_obligation": serde_json::to_value(&self.taker_obligation)?,
+            "trade_details": serde_json::to_value(&self.trade_details)?,
+            "trade_engine_specifics": trade_engine_specifics,
+        });
+        Self::canonicalize_terms(&mut terms);
+        let terms_string = serde_json::to_string(&terms)?;
+        Ok(Sha256Hash::hash(terms_string.as_bytes()).to_string())
+    }
+
+    // Sets and maps don't serialize in a stable order, so arrays and keys are sorted
+    fn canonicalize_terms(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Array(values) => {
+                values.iter_mut().for_each(Self::canonicalize_terms);
+                values.sort_by_cached_key(|value| value.to_string());
+            }
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<(String, serde_json::Value)> =
+                    std::mem::take(map).into_iter().collect();
+                entries.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+                for (key, mut value) in entries {
+                    Self::canonicalize_terms(&mut value);
+                    map.insert(key, value);
                 }
-                _ => {}
             }
+            _ => {}
         }
+    }
+
+    pub fn check_bitcoin_network(&self, expected_network: BitcoinNetwork) -> bool {
+        self.validate_bitcoin_network(&expected_network).is_ok()
+    }
 
-        for kind in &self.taker_obligation.kinds {
+    // All Bitcoin Obligation Kinds of an Order are to be on the same network
+    pub fn validate_bitcoin_network(
+        &self,
+        expected_network: &BitcoinNetwork,
+    ) -> Result<(), N3xbError> {
+        let kinds = self
+            .maker_obligation
+            .kinds
+            .iter()
+            .chain(self.taker_obligation.kinds.iter());
+
+        for kind in kinds {
             match kind {
                 ObligationKind::Bitcoin(network, _method) => {
-                    if network.to_owned() != expected_network {
-                        return false;
+                    if network != expected_network {
+                        return Err(N3xbError::BitcoinNetworkMismatch(
+                            expected_network.to_owned(),
+                            network.to_owned(),
+                        ));
                     }
                 }
                 _ => {}
             }
         }
+        Ok(())
+    }
 
-        return true;
+    // Network of the first Bitcoin Obligation Kind found. None if no Bitcoin is exchanged
+    pub fn bitcoin_network(&self) -> Option<BitcoinNetwork> {
+        self.maker_obligation
+            .kinds
+            .iter()
+            .chain(self.taker_obligation.kinds.iter())
+            .find_map(|kind| match kind {
+                ObligationKind::Bitcoin(network, _method) => Some(network.to_owned()),
+                _ => None,
+            })
     }
 
     pub fn validate(&self) -> Result<(), N3xbError> {
@@ -69,7 +322,9 @@ impl Order {
         self.validate_taker_obligation_specified()?;
         self.validate_taker_obligation_limit_rate_valid()?;
         self.validate_taker_obligation_market_offset_not_supported()?;
-        self.validate_trade_details_bonds_required()?;
+        self.trade_details.validate()?;
+        self.validate_custom_tags()?;
+        self.validate_geohash()?;
         Ok(())
     }
 
@@ -220,58 +475,301 @@ impl Order {
         Ok(())
     }
 
-    fn validate_trade_details_bonds_required(&self) -> Result<(), N3xbError> {
-        if self
-            .trade_details
-            .parameters
-            .contains(&TradeParameter::BondsRequired)
-        {
-            if self.trade_details.content.maker_bond_pct.is_none() {
+    fn validate_custom_tags(&self) -> Result<(), N3xbError> {
+        for (key, values) in &self.custom_tags {
+            if key.is_empty() || key.contains(CUSTOM_TAG_SEPARATOR) {
                 return Err(N3xbError::Simple(format!(
-                    "Trade Details Maker Bond Percentage should be specified"
+                    "Custom Tag key '{}' should be non-empty and not contain '{}'",
+                    key, CUSTOM_TAG_SEPARATOR
                 )));
             }
-
-            if self.trade_details.content.taker_bond_pct.is_none() {
+            if values.is_empty() {
                 return Err(N3xbError::Simple(format!(
-                    "Trade Details Taker Bond Percentage should be specified"
+                    "Custom Tag key '{}' should have at least one value",
+                    key
                 )));
             }
+        }
+        Ok(())
+    }
 
-            if let Some(maker_bond_pct) = self.trade_details.content.maker_bond_pct {
-                if let Some(taker_bond_pct) = self.trade_details.content.taker_bond_pct {
-                    if maker_bond_pct == 0 && taker_bond_pct == 0 {
-                        return Err(N3xbError::Simple(format!(
-                            "Trade Details Maker & Taker Bond Percentages should not be both zeros"
-                        )));
-                    }
-                }
+    fn validate_geohash(&self) -> Result<(), N3xbError> {
+        if let Some(geohash) = &self.geohash {
+            if geohash.is_empty()
+                || geohash.len() > GEOHASH_MAX_LEN
+                || !geohash.chars().all(|c| GEOHASH_ALPHABET.contains(c))
+            {
+                return Err(N3xbError::Simple(format!(
+                    "Geohash '{}' should be 1 to {} lowercase base32 geohash characters",
+                    geohash, GEOHASH_MAX_LEN
+                )));
             }
         }
         Ok(())
     }
+
+    // Relays match any value under the single reserved custom tag key. This narrows down to
+    // Orders matching every custom key filtered on
+    pub(crate) fn matches_custom_tags(
+        &self,
+        custom_tags: &HashMap<String, HashSet<String>>,
+    ) -> bool {
+        custom_tags.iter().all(|(key, values)| {
+            self.custom_tags
+                .get(key)
+                .map_or(false, |order_values| !order_values.is_disjoint(values))
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::{collections::HashSet, str::FromStr};
 
     use crate::{
-        common::types::{BitcoinNetwork, FiatPaymentMethod, ObligationKind},
+        common::{
+            error::N3xbError,
+            types::{BitcoinNetwork, EventIdString, FiatPaymentMethod, ObligationKind},
+        },
         order::{
-            MakerObligation, MakerObligationContent, TakerObligation, TakerObligationContent,
-            TradeDetails, TradeDetailsContent, TradeParameter, TradeTimeOutLimit,
+            EscrowDetails, FilterTag, MakerObligation, MakerObligationContent, OrderEnvelope,
+            TakerObligation, TakerObligationContent, TradeDetails, TradeDetailsContent,
+            TradeDirection, TradeParameter, TradeTimeOutLimit,
         },
-        testing::SomeTestOrderParams,
+        testing::{SomeTestOrderParams, SomeTestParams, SomeTradeEngineMakerOrderSpecifics},
     };
 
     use iso_currency::Currency;
+    use nostr_sdk::prelude::{EventBuilder, Keys, Kind};
+    use url::Url;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_validate_order() {
         _ = SomeTestOrderParams::default_buy_builder().build().unwrap();
     }
 
+    #[tokio::test]
+    async fn test_validate_order_bitcoin_network() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        assert_eq!(order.bitcoin_network(), Some(BitcoinNetwork::Regtest));
+        order
+            .validate_bitcoin_network(&BitcoinNetwork::Regtest)
+            .unwrap();
+        assert!(matches!(
+            order.validate_bitcoin_network(&BitcoinNetwork::Mainnet),
+            Err(N3xbError::BitcoinNetworkMismatch(
+                BitcoinNetwork::Mainnet,
+                BitcoinNetwork::Regtest
+            ))
+        ));
+    }
+
+    // Of 8 and 7 leading zero bits
+    const SOME_EVENT_ID_POW_8: &str =
+        "00a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f";
+    const SOME_EVENT_ID_POW_7: &str =
+        "01a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f";
+
+    #[tokio::test]
+    async fn test_verify_order_envelope() {
+        let event_id = EventIdString::from_str(SOME_EVENT_ID_POW_8).unwrap();
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            event_id,
+            0,
+        );
+        order_envelope.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_order_envelope_invalid_event_id() {
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        let mut json = serde_json::to_value(&order_envelope).unwrap();
+        json["event_id"] = "not-an-event-id".into();
+        assert!(serde_json::from_value::<OrderEnvelope>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_signature_without_signed_event() {
+        let event_id = EventIdString::from_str(SOME_EVENT_ID_POW_8).unwrap();
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            event_id,
+            0,
+        );
+        assert!(order_envelope.verify_signature().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_order_expiration() {
+        // Derived from the Four Days Trade Time Out of the test Order
+        let mut order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        assert_eq!(order.expiration_from(1000), Some(1000 + 4 * 24 * 60 * 60));
+
+        order.expiration = Some(2000);
+        assert_eq!(order.expiration_from(1000), Some(2000));
+
+        let mut order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        assert!(!order_envelope.is_expired(3000));
+        order_envelope.expiration = Some(2000);
+        assert!(!order_envelope.is_expired(1999));
+        assert!(order_envelope.is_expired(2000));
+    }
+
+    #[tokio::test]
+    async fn test_merge_order_envelopes() {
+        let mut older = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        older.created_at = 1;
+        older.urls = HashSet::from([Url::parse("wss://relay1.example.com").unwrap()]);
+
+        let mut newer = older.clone();
+        newer.event_id = SomeTestParams::some_event_id(2);
+        newer.created_at = 2;
+        newer.urls = HashSet::from([Url::parse("wss://relay2.example.com").unwrap()]);
+
+        let merged = older.merge(&newer).unwrap();
+        assert_eq!(merged.event_id, SomeTestParams::some_event_id(2));
+        assert_eq!(merged.created_at, 2);
+        assert_eq!(merged.urls.len(), 2);
+
+        let merged = newer.merge(&older).unwrap();
+        assert_eq!(merged.event_id, SomeTestParams::some_event_id(2));
+        assert_eq!(merged.urls.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_order_envelopes_different_orders() {
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        let mut other = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(2),
+            0,
+        );
+        other.order.trade_uuid = Uuid::new_v4();
+        assert!(order_envelope.merge(&other).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_order_terms_hash() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let terms_hash = order.terms_hash().unwrap();
+        print!("Order Terms Hash: {}", terms_hash);
+
+        // Same terms rebuilt hash the same, regardless of set ordering or what's not a term
+        let mut same_order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        same_order.expiration = Some(2000);
+        same_order.pow_difficulty += 1;
+        same_order.geohash = Some("u4pruyd".to_string());
+        same_order
+            .custom_tags
+            .insert("x".to_string(), HashSet::from(["y".to_string()]));
+        assert_eq!(same_order.terms_hash().unwrap(), terms_hash);
+
+        let mut changed_order = order.clone();
+        changed_order.trade_details.content.taker_bond_pct = Some(5);
+        assert_ne!(changed_order.terms_hash().unwrap(), terms_hash);
+
+        let mut changed_order = order.clone();
+        changed_order
+            .trade_details
+            .parameters
+            .remove(&TradeParameter::AcceptsPartialTake);
+        assert_ne!(changed_order.terms_hash().unwrap(), terms_hash);
+    }
+
+    #[tokio::test]
+    async fn test_order_envelope_terms_hash_of_defaulted_specifics() {
+        let mut order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        let terms_hash = order_envelope.order.terms_hash().unwrap();
+
+        // The Maker's specifics as signed, with a default standing in for them on receipt
+        let trade_engine_specifics =
+            serde_json::to_value(&order_envelope.order.trade_engine_specifics).unwrap();
+        let content = serde_json::json!({ "trade_engine_specifics": trade_engine_specifics });
+        let keys = Keys::new(SomeTestParams::maker_private_key());
+        let event = EventBuilder::new(Kind::Custom(61021), content.to_string(), &[])
+            .to_event(&keys)
+            .unwrap();
+        order_envelope.signed_event = Some(event);
+        order_envelope.order.trade_engine_specifics =
+            Box::new(SomeTradeEngineMakerOrderSpecifics {
+                test_specific_field: "default".to_string(),
+            });
+        order_envelope.trade_engine_specifics_defaulted = true;
+
+        assert_ne!(order_envelope.order.terms_hash().unwrap(), terms_hash);
+        assert_eq!(order_envelope.terms_hash().unwrap(), terms_hash);
+
+        // Without the signed Maker Order Note, the original specifics are not known
+        order_envelope.signed_event = None;
+        assert!(order_envelope.terms_hash().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_similarity_filter_tags() {
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            SomeTestParams::some_event_id(1),
+            0,
+        );
+        let filter_tags = order_envelope.similarity_filter_tags();
+        print!("Similarity Filter Tags: {:?}", filter_tags);
+
+        assert_eq!(filter_tags.len(), 3);
+        assert!(filter_tags.contains(&FilterTag::TradeDirection(TradeDirection::BuyBitcoin)));
+        assert!(filter_tags.contains(&FilterTag::MakerObligations(
+            SomeTestOrderParams::obligation_fiat_cny_kinds()
+        )));
+        assert!(filter_tags.contains(&FilterTag::TakerObligations(
+            order_envelope.order.taker_obligation.kinds.clone()
+        )));
+        assert!(!filter_tags
+            .iter()
+            .any(|filter_tag| matches!(filter_tag, FilterTag::TradeUuid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_order_envelope_pow() {
+        // 8 leading zero bits meets the test Order's PoW difficulty of 8
+        let event_id = EventIdString::from_str(SOME_EVENT_ID_POW_8).unwrap();
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            event_id,
+            0,
+        );
+        order_envelope.verify_pow().unwrap();
+
+        // 7 leading zero bits does not
+        let event_id = EventIdString::from_str(SOME_EVENT_ID_POW_7).unwrap();
+        let order_envelope = SomeTestOrderParams::some_order_envelope(
+            SomeTestOrderParams::default_buy_builder().build().unwrap(),
+            event_id,
+            0,
+        );
+        assert!(order_envelope.verify_pow().is_err());
+    }
+
     #[tokio::test]
     async fn test_validate_order_maker_obligation_kind_fiat_missing_settlement() {
         let maker_obligation_kinds = HashSet::from([
@@ -502,6 +1000,10 @@ mod tests {
             maker_bond_pct: None,
             taker_bond_pct: Some(10),
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: Some(SomeTestOrderParams::escrow_details()),
+            min_partial_amount: None,
+            max_partial_amount: None,
         };
 
         let trade_details = TradeDetails {
@@ -529,6 +1031,10 @@ mod tests {
             maker_bond_pct: Some(10),
             taker_bond_pct: None,
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: Some(SomeTestOrderParams::escrow_details()),
+            min_partial_amount: None,
+            max_partial_amount: None,
         };
 
         let trade_details = TradeDetails {
@@ -556,16 +1062,112 @@ mod tests {
             maker_bond_pct: Some(0),
             taker_bond_pct: Some(0),
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: Some(SomeTestOrderParams::escrow_details()),
+            min_partial_amount: None,
+            max_partial_amount: None,
+        };
+
+        let trade_details = TradeDetails {
+            parameters: trade_parameters,
+            content: trade_details_content,
+        };
+
+        let result = SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_order_trusted_pubkeys_empty() {
+        let mut trade_details_content = SomeTestOrderParams::trade_details_content();
+        trade_details_content.trusted_pubkeys = Some(HashSet::new());
+
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: trade_details_content,
         };
 
+        let result = SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_order_partial_amounts_min_above_max() {
+        let mut trade_details_content = SomeTestOrderParams::trade_details_content();
+        trade_details_content.min_partial_amount = Some(5000000);
+        trade_details_content.max_partial_amount = Some(1000000);
+
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: trade_details_content.clone(),
+        };
+        let result = SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build();
+        assert!(result.is_err());
+
+        // Bounds are not looked at without partial takes accepted
+        let mut trade_parameters = SomeTestOrderParams::trade_parameters();
+        trade_parameters.remove(&TradeParameter::AcceptsPartialTake);
         let trade_details = TradeDetails {
             parameters: trade_parameters,
             content: trade_details_content,
         };
+        SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_order_escrow_missing() {
+        let mut trade_details_content = SomeTestOrderParams::trade_details_content();
+        trade_details_content.escrow = None;
+
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: trade_details_content,
+        };
+
+        let result = SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_order_escrow_unidentified() {
+        let mut trade_details_content = SomeTestOrderParams::trade_details_content();
+        trade_details_content.escrow = Some(EscrowDetails {
+            pubkey: None,
+            endpoint: None,
+        });
+
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: trade_details_content,
+        };
 
         let result = SomeTestOrderParams::default_buy_builder()
             .trade_details(trade_details)
             .build();
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_validate_order_escrow_not_required() {
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters_empty(),
+            content: SomeTestOrderParams::trade_details_empty(),
+        };
+
+        let result = SomeTestOrderParams::default_buy_builder()
+            .trade_details(trade_details)
+            .build();
+        assert!(result.is_ok());
+    }
 }
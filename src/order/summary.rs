@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use iso_currency::Currency;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use uuid::Uuid;
+
+use super::order::Order;
+use crate::common::types::{
+    BitcoinNetwork, BitcoinSettlementMethod, FiatPaymentMethod, ObligationKind,
+};
+
+// Direction is from the Maker's point of view
+#[derive(
+    PartialEq, Eq, Hash, Clone, Debug, Default, Serialize, Deserialize, Display, EnumString,
+)]
+pub enum TradeDirection {
+    BuyBitcoin,  // Maker gives non-Bitcoin, Taker gives Bitcoin
+    SellBitcoin, // Maker gives Bitcoin, Taker gives non-Bitcoin
+    #[default]
+    Other, // Bitcoin on both or neither sides
+}
+
+// Flattened view of an Order for indexing and listing. Amounts are in the Maker Obligation's units
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct OrderSummary {
+    pub trade_uuid: Uuid,
+    pub direction: TradeDirection,
+    pub fiat_currency: Option<Currency>,
+    pub fiat_methods: HashSet<FiatPaymentMethod>,
+    pub bitcoin_network: Option<BitcoinNetwork>,
+    pub bitcoin_methods: HashSet<BitcoinSettlementMethod>,
+    pub custom_kinds: HashSet<String>,
+    pub amount: f64,
+    pub amount_min: Option<f64>,
+    pub limit_rate: Option<f64>,
+}
+
+impl Order {
+    pub fn direction(&self) -> TradeDirection {
+        let maker_gives_bitcoin = self.maker_obligation.kinds.iter().any(|k| k.is_bitcoin());
+        let taker_gives_bitcoin = self.taker_obligation.kinds.iter().any(|k| k.is_bitcoin());
+        match (maker_gives_bitcoin, taker_gives_bitcoin) {
+            (false, true) => TradeDirection::BuyBitcoin,
+            (true, false) => TradeDirection::SellBitcoin,
+            _ => TradeDirection::Other,
+        }
+    }
+
+    pub fn summary(&self) -> OrderSummary {
+        let mut summary = OrderSummary {
+            trade_uuid: self.trade_uuid,
+            direction: self.direction(),
+            fiat_currency: None,
+            fiat_methods: HashSet::new(),
+            bitcoin_network: None,
+            bitcoin_methods: HashSet::new(),
+            custom_kinds: HashSet::new(),
+            amount: self.maker_obligation.content.amount,
+            amount_min: self.maker_obligation.content.amount_min,
+            limit_rate: self.taker_obligation.content.limit_rate,
+        };
+
+        let kinds = self
+            .maker_obligation
+            .kinds
+            .iter()
+            .chain(self.taker_obligation.kinds.iter());
+
+        for kind in kinds {
+            match kind {
+                ObligationKind::Fiat(currency, method) => {
+                    summary.fiat_currency = Some(currency.to_owned());
+                    if let Some(method) = method {
+                        summary.fiat_methods.insert(method.to_owned());
+                    }
+                }
+                ObligationKind::Bitcoin(network, method) => {
+                    summary.bitcoin_network = Some(network.to_owned());
+                    if let Some(method) = method {
+                        summary.bitcoin_methods.insert(method.to_owned());
+                    }
+                }
+                ObligationKind::Custom(custom) => {
+                    summary.custom_kinds.insert(custom.to_owned());
+                }
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SomeTestOrderParams;
+
+    #[test]
+    fn test_buy_order_summary() {
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let summary = order.summary();
+        print!("Buy Order Summary: {:?}", summary);
+
+        assert_eq!(summary.trade_uuid, SomeTestOrderParams::some_uuid());
+        assert_eq!(summary.direction, TradeDirection::BuyBitcoin);
+        assert_eq!(summary.fiat_currency, Some(Currency::CNY));
+        assert_eq!(
+            summary.fiat_methods,
+            HashSet::from([FiatPaymentMethod::WeChatPay, FiatPaymentMethod::AliPay])
+        );
+        assert_eq!(summary.bitcoin_network, Some(BitcoinNetwork::Regtest));
+        assert_eq!(
+            summary.bitcoin_methods,
+            HashSet::from([BitcoinSettlementMethod::Lightning])
+        );
+        assert_eq!(summary.amount, order.maker_obligation.content.amount);
+    }
+
+    #[test]
+    fn test_sell_order_summary() {
+        let order = SomeTestOrderParams::default_sell_builder().build().unwrap();
+        let summary = order.summary();
+        print!("Sell Order Summary: {:?}", summary);
+
+        assert_eq!(summary.direction, TradeDirection::SellBitcoin);
+        assert_eq!(summary.fiat_currency, Some(Currency::EUR));
+        assert_eq!(
+            summary.bitcoin_methods,
+            HashSet::from([
+                BitcoinSettlementMethod::Onchain,
+                BitcoinSettlementMethod::Lightning
+            ])
+        );
+    }
+}
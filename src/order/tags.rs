@@ -1,11 +1,14 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use strum_macros::{Display, EnumString, IntoStaticStr};
 use uuid::Uuid;
 
 use crate::common::{error::N3xbError, types::ObligationKind};
 
-use super::{Order, TradeDetails, TradeParameter};
+use super::{Order, TradeDetails, TradeDirection, TradeParameter};
 
 #[derive(Clone, Debug, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
 
@@ -14,9 +17,17 @@ pub enum FilterTag {
     MakerObligations(HashSet<ObligationKind>),
     TakerObligations(HashSet<ObligationKind>),
     TradeDetailParameters(HashSet<TradeParameter>),
+    CustomTag(String, HashSet<String>), // Key, and any of the values to match
+    TradeDirection(TradeDirection),
+    Geohash(String), // Prefix of the area to match, see FilterTag::near
 }
 
 impl FilterTag {
+    // Matches Orders with a geohash within the area of the prefix. Shorter prefixes cover larger areas
+    pub fn near(geohash_prefix: impl Into<String>) -> FilterTag {
+        FilterTag::Geohash(geohash_prefix.into())
+    }
+
     pub(crate) fn to_order_tag(self) -> OrderTag {
         match self {
             Self::TradeUuid(uuid) => OrderTag::TradeUUID(uuid),
@@ -25,10 +36,56 @@ impl FilterTag {
             Self::TradeDetailParameters(parameters) => {
                 OrderTag::TradeDetailParameters(parameters.clone())
             }
+            Self::CustomTag(key, values) => OrderTag::CustomTags(HashMap::from([(key, values)])),
+            Self::TradeDirection(direction) => OrderTag::TradeDirection(direction),
+            Self::Geohash(geohash) => OrderTag::Geohash(geohash),
         }
     }
 }
 
+// Custom tags of Trade Engines are all carried under a single reserved tag key,
+// with each value namespaced by the custom key as in "key:value"
+pub(crate) const CUSTOM_TAG_SEPARATOR: char = ':';
+
+pub(crate) fn custom_tags_to_strings(
+    custom_tags: &HashMap<String, HashSet<String>>,
+) -> Vec<String> {
+    custom_tags
+        .iter()
+        .flat_map(|(key, values)| {
+            values
+                .iter()
+                .map(move |value| format!("{}{}{}", key, CUSTOM_TAG_SEPARATOR, value))
+        })
+        .collect()
+}
+
+pub(crate) fn custom_tags_from_strings(
+    strings: Vec<String>,
+) -> Result<HashMap<String, HashSet<String>>, N3xbError> {
+    let mut custom_tags: HashMap<String, HashSet<String>> = HashMap::new();
+    for string in strings {
+        let Some((key, value)) = string.split_once(CUSTOM_TAG_SEPARATOR) else {
+            return Err(N3xbError::TagParsing(string));
+        };
+        custom_tags
+            .entry(key.to_string())
+            .or_default()
+            .insert(value.to_string());
+    }
+    Ok(custom_tags)
+}
+
+// Geohashes go in NIP-52 style 'g' tags, one for each prefix so a filter on any prefix matches
+pub(crate) const GEOHASH_ALPHABET: &str = "0123456789bcdefghjkmnpqrstuvwxyz";
+pub(crate) const GEOHASH_MAX_LEN: usize = 12;
+
+pub(crate) fn geohash_prefixes(geohash: &str) -> Vec<String> {
+    (1..=geohash.len())
+        .map(|len| geohash[..len].to_string())
+        .collect()
+}
+
 pub(crate) static N3XB_APPLICATION_TAG: &str = "n3xb";
 
 #[derive(Clone, Debug, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
@@ -45,6 +102,9 @@ pub(crate) enum OrderTag {
     TradeEngineName(String),
     EventKind(EventKind),
     ApplicationTag(String),
+    CustomTags(HashMap<String, HashSet<String>>),
+    TradeDirection(TradeDirection),
+    Geohash(String),
 }
 
 const ORDER_TAG_TRADE_UUID_KEY: char = 'i';
@@ -54,6 +114,9 @@ const ORDER_TAG_TRADE_DETAIL_PARAMETERS_KEY: char = 'p';
 const ORDER_TAG_TRADE_ENGINE_NAME_KEY: char = 'n';
 const ORDER_TAG_EVENT_KIND_KEY: char = 'k';
 const ORDER_TAG_APPLICATION_TAG_KEY: char = 'd';
+const ORDER_TAG_CUSTOM_TAGS_KEY: char = 'c';
+const ORDER_TAG_TRADE_DIRECTION_KEY: char = 'b';
+const ORDER_TAG_GEOHASH_KEY: char = 'g';
 
 impl OrderTag {
     pub(crate) fn key(&self) -> char {
@@ -65,16 +128,30 @@ impl OrderTag {
             OrderTag::TradeEngineName(_) => ORDER_TAG_TRADE_ENGINE_NAME_KEY,
             OrderTag::EventKind(_) => ORDER_TAG_EVENT_KIND_KEY,
             OrderTag::ApplicationTag(_) => ORDER_TAG_APPLICATION_TAG_KEY,
+            OrderTag::CustomTags(_) => ORDER_TAG_CUSTOM_TAGS_KEY,
+            OrderTag::TradeDirection(_) => ORDER_TAG_TRADE_DIRECTION_KEY,
+            OrderTag::Geohash(_) => ORDER_TAG_GEOHASH_KEY,
         }
     }
 
+    // Tags of Events received might come with no value at all
+    fn first_value(key: &str, value: &[String]) -> Result<String, N3xbError> {
+        value
+            .first()
+            .cloned()
+            .ok_or_else(|| N3xbError::TagParsing(key.to_string()))
+    }
+
     pub(crate) fn from_key_value(
         key: impl AsRef<str>,
         value: Vec<String>,
     ) -> Result<OrderTag, N3xbError> {
-        match key.as_ref().chars().next().unwrap() {
+        let Some(key_char) = key.as_ref().chars().next() else {
+            return Err(N3xbError::TagParsing(key.as_ref().to_string()));
+        };
+        match key_char {
             ORDER_TAG_TRADE_UUID_KEY => {
-                let uuid_string = value[0].clone();
+                let uuid_string = Self::first_value(key.as_ref(), &value)?;
                 match Uuid::from_str(uuid_string.as_str()) {
                     Ok(uuid) => Ok(OrderTag::TradeUUID(uuid)),
                     Err(error) => Err(N3xbError::Simple(format!(
@@ -98,12 +175,31 @@ impl OrderTag {
                 let parameters_set = TradeDetails::tags_to_parameters(tag_set);
                 Ok(OrderTag::TradeDetailParameters(parameters_set))
             }
-            ORDER_TAG_TRADE_ENGINE_NAME_KEY => Ok(OrderTag::TradeEngineName(value[0].clone())),
+            ORDER_TAG_TRADE_ENGINE_NAME_KEY => Ok(OrderTag::TradeEngineName(Self::first_value(
+                key.as_ref(),
+                &value,
+            )?)),
             ORDER_TAG_EVENT_KIND_KEY => {
-                let event_kind = EventKind::from_str(value[0].as_str())?;
+                let event_kind = EventKind::from_str(&Self::first_value(key.as_ref(), &value)?)?;
                 Ok(OrderTag::EventKind(event_kind))
             }
-            ORDER_TAG_APPLICATION_TAG_KEY => Ok(OrderTag::ApplicationTag(value[0].clone())),
+            ORDER_TAG_APPLICATION_TAG_KEY => Ok(OrderTag::ApplicationTag(Self::first_value(
+                key.as_ref(),
+                &value,
+            )?)),
+            ORDER_TAG_CUSTOM_TAGS_KEY => Ok(OrderTag::CustomTags(custom_tags_from_strings(value)?)),
+            ORDER_TAG_TRADE_DIRECTION_KEY => {
+                let direction =
+                    TradeDirection::from_str(&Self::first_value(key.as_ref(), &value)?)?;
+                Ok(OrderTag::TradeDirection(direction))
+            }
+            ORDER_TAG_GEOHASH_KEY => {
+                // One prefix per tag, but takes the longest should a tag list several
+                let Some(geohash) = value.into_iter().max_by_key(|value| value.len()) else {
+                    return Err(N3xbError::TagParsing(key.as_ref().to_string()));
+                };
+                Ok(OrderTag::Geohash(geohash))
+            }
             _ => Err(N3xbError::Simple(format!(
                 "Unrecognized key '{}' for Order Tag",
                 key.as_ref()
@@ -113,6 +209,7 @@ impl OrderTag {
 
     pub(crate) fn from_order(order: Order, trade_engine_name: impl AsRef<str>) -> Vec<OrderTag> {
         let mut order_tags: Vec<OrderTag> = Vec::new();
+        order_tags.push(OrderTag::TradeDirection(order.direction()));
         order_tags.push(OrderTag::TradeUUID(order.trade_uuid));
         order_tags.push(OrderTag::MakerObligations(order.maker_obligation.kinds));
         order_tags.push(OrderTag::TakerObligations(order.taker_obligation.kinds));
@@ -124,6 +221,12 @@ impl OrderTag {
         ));
         order_tags.push(OrderTag::EventKind(EventKind::MakerOrder));
         order_tags.push(OrderTag::ApplicationTag(N3XB_APPLICATION_TAG.to_string()));
+        if !order.custom_tags.is_empty() {
+            order_tags.push(OrderTag::CustomTags(order.custom_tags));
+        }
+        if let Some(geohash) = order.geohash {
+            order_tags.push(OrderTag::Geohash(geohash));
+        }
         order_tags
     }
 
@@ -132,8 +235,20 @@ impl OrderTag {
         trade_engine_name: impl AsRef<str>,
     ) -> Vec<OrderTag> {
         let mut order_tags: Vec<OrderTag> = Vec::new();
+        let mut custom_tags: HashMap<String, HashSet<String>> = HashMap::new();
         for filter_tag in filter_tags {
-            order_tags.push(filter_tag.to_order_tag());
+            // All custom tags share one tag key, so they go into a single relay filter
+            match filter_tag.to_order_tag() {
+                OrderTag::CustomTags(tags) => {
+                    for (key, values) in tags {
+                        custom_tags.entry(key).or_default().extend(values);
+                    }
+                }
+                order_tag => order_tags.push(order_tag),
+            }
+        }
+        if !custom_tags.is_empty() {
+            order_tags.push(OrderTag::CustomTags(custom_tags));
         }
         order_tags.push(OrderTag::ApplicationTag(N3XB_APPLICATION_TAG.to_string()));
         order_tags.push(OrderTag::EventKind(EventKind::MakerOrder));
@@ -146,11 +261,13 @@ impl OrderTag {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
+
     use crate::common::types::BitcoinNetwork;
-    use crate::order::{EventKind, FilterTag, OrderTag, TradeDetails};
+    use crate::order::{EventKind, FilterTag, OrderTag, TradeDetails, TradeDirection};
     use crate::testing::{SomeTestOrderParams, SomeTestParams};
 
-    use super::N3XB_APPLICATION_TAG;
+    use super::{geohash_prefixes, N3XB_APPLICATION_TAG};
 
     #[tokio::test]
     async fn test_key_for_trade_uuid() {
@@ -291,6 +408,50 @@ mod tests {
         assert_eq!(order_tag, OrderTag::ApplicationTag(application_tag));
     }
 
+    #[tokio::test]
+    async fn test_order_tag_from_trade_direction_key_value() {
+        let key = "b";
+        let value = vec![TradeDirection::SellBitcoin.to_string()];
+        let order_tag = OrderTag::from_key_value(key, value).unwrap();
+        assert_eq!(
+            order_tag,
+            OrderTag::TradeDirection(TradeDirection::SellBitcoin)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_tag_from_custom_tags_key_value() {
+        let key = "c";
+        let value = vec![
+            "region:eu".to_string(),
+            "region:us".to_string(),
+            "lang:en".to_string(),
+        ];
+        let order_tag = OrderTag::from_key_value(key, value).unwrap();
+        assert_eq!(
+            order_tag,
+            OrderTag::CustomTags(HashMap::from([
+                (
+                    "region".to_string(),
+                    HashSet::from(["eu".to_string(), "us".to_string()])
+                ),
+                ("lang".to_string(), HashSet::from(["en".to_string()])),
+            ]))
+        );
+
+        let value = vec!["no-separator".to_string()];
+        assert!(OrderTag::from_key_value(key, value).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_order_tag_from_geohash_key_value() {
+        let key = "g";
+        let value = geohash_prefixes("u4pru");
+        assert_eq!(value, vec!["u", "u4", "u4p", "u4pr", "u4pru"]);
+        let order_tag = OrderTag::from_key_value(key, value).unwrap();
+        assert_eq!(order_tag, OrderTag::Geohash("u4pru".to_string()));
+    }
+
     #[tokio::test]
     async fn test_order_tag_from_invalid_key_value() {
         let key = "x";
@@ -299,12 +460,21 @@ mod tests {
         assert!(order_tag.is_err());
     }
 
+    #[tokio::test]
+    async fn test_order_tag_from_key_without_value() {
+        for key in ["i", "n", "k", "d", "b", ""] {
+            let order_tag = OrderTag::from_key_value(key, Vec::new());
+            assert!(order_tag.is_err());
+        }
+    }
+
     #[tokio::test]
     async fn test_order_tag_from_order() {
         let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
         let trade_engine_name = SomeTestParams::engine_name_str();
         let order_tags = OrderTag::from_order(order.clone(), trade_engine_name.clone());
-        assert_eq!(order_tags.len(), 7);
+        assert_eq!(order_tags.len(), 8);
+        assert!(order_tags.contains(&OrderTag::TradeDirection(TradeDirection::BuyBitcoin)));
         assert!(order_tags.contains(&OrderTag::TradeUUID(order.trade_uuid)));
         assert!(order_tags.contains(&OrderTag::MakerObligations(order.maker_obligation.kinds)));
         assert!(order_tags.contains(&OrderTag::TakerObligations(order.taker_obligation.kinds)));
@@ -316,6 +486,49 @@ mod tests {
         assert!(order_tags.contains(&OrderTag::ApplicationTag(N3XB_APPLICATION_TAG.to_string())));
     }
 
+    #[tokio::test]
+    async fn test_order_tag_from_order_with_custom_tags() {
+        let order = SomeTestOrderParams::default_buy_builder()
+            .custom_tag("region", ["eu", "us"])
+            .custom_tag("lang", ["en"])
+            .build()
+            .unwrap();
+        let trade_engine_name = SomeTestParams::engine_name_str();
+        let order_tags = OrderTag::from_order(order.clone(), trade_engine_name);
+        assert_eq!(order_tags.len(), 9);
+        assert!(order_tags.contains(&OrderTag::CustomTags(order.custom_tags)));
+    }
+
+    #[tokio::test]
+    async fn test_order_tag_from_order_with_geohash() {
+        let order = SomeTestOrderParams::default_buy_builder()
+            .geohash("u4pru")
+            .build()
+            .unwrap();
+        let trade_engine_name = SomeTestParams::engine_name_str();
+        let order_tags = OrderTag::from_order(order, trade_engine_name);
+        assert_eq!(order_tags.len(), 9);
+        assert!(order_tags.contains(&OrderTag::Geohash("u4pru".to_string())));
+
+        let order_tags = OrderTag::from_filter_tags(vec![FilterTag::near("u4")], "");
+        assert!(order_tags.contains(&OrderTag::Geohash("u4".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_order_tags_from_custom_filter_tags() {
+        let filter_tags = vec![
+            FilterTag::CustomTag("region".to_string(), HashSet::from(["eu".to_string()])),
+            FilterTag::CustomTag("lang".to_string(), HashSet::from(["en".to_string()])),
+        ];
+        let trade_engine_name = SomeTestParams::engine_name_str();
+        let order_tags = OrderTag::from_filter_tags(filter_tags, trade_engine_name);
+        assert_eq!(order_tags.len(), 4);
+        assert!(order_tags.contains(&OrderTag::CustomTags(HashMap::from([
+            ("region".to_string(), HashSet::from(["eu".to_string()])),
+            ("lang".to_string(), HashSet::from(["en".to_string()])),
+        ]))));
+    }
+
     #[tokio::test]
     async fn test_order_tags_from_filter_tags() {
         let mut filter_tags: Vec<FilterTag> = Vec::new();
@@ -1,9 +1,11 @@
 use crate::common::error::N3xbError;
+use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::result::Result;
 use std::str::FromStr;
+use std::time::Duration;
 use strum_macros::{Display, EnumString, IntoStaticStr};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -24,6 +26,22 @@ impl TradeDetails {
         tag_string_set
     }
 
+    pub fn trade_timeout_limit(&self) -> Option<TradeTimeOutLimit> {
+        self.parameters
+            .iter()
+            .find_map(|parameter| match parameter {
+                TradeParameter::TradeTimesOut(limit) => Some(limit.to_owned()),
+                _ => None,
+            })
+    }
+
+    pub fn is_taker_trusted(&self, pubkey: &XOnlyPublicKey) -> bool {
+        match &self.content.trusted_pubkeys {
+            Some(trusted_pubkeys) => trusted_pubkeys.contains(pubkey),
+            None => true,
+        }
+    }
+
     pub fn tags_to_parameters(tags: HashSet<String>) -> HashSet<TradeParameter> {
         let mut parameters_set: HashSet<TradeParameter> = HashSet::new();
         let mut parse_failed_tags: Vec<N3xbError> = Vec::new(); // TODO: What do we do with errors when parsing some tags?
@@ -44,13 +62,140 @@ impl TradeDetails {
         }
         parameters_set
     }
+
+    // Consistency of the Trade Parameters against the Trade Details Content. Also checked on
+    // received Orders, so rules that could reject Orders of older versions are for builders only
+    pub fn validate(&self) -> Result<(), N3xbError> {
+        self.validate_bonds_required()?;
+        self.validate_trusted_pubkeys_not_empty()?;
+        self.validate_escrow_specified()?;
+        self.validate_partial_amounts_valid()?;
+        Ok(())
+    }
+
+    fn validate_bonds_required(&self) -> Result<(), N3xbError> {
+        if self.parameters.contains(&TradeParameter::BondsRequired) {
+            if self.content.maker_bond_pct.is_none() {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Maker Bond Percentage should be specified"
+                )));
+            }
+
+            if self.content.taker_bond_pct.is_none() {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Taker Bond Percentage should be specified"
+                )));
+            }
+
+            if let Some(maker_bond_pct) = self.content.maker_bond_pct {
+                if let Some(taker_bond_pct) = self.content.taker_bond_pct {
+                    if maker_bond_pct == 0 && taker_bond_pct == 0 {
+                        return Err(N3xbError::Simple(format!(
+                            "Trade Details Maker & Taker Bond Percentages should not be both zeros"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_trusted_pubkeys_not_empty(&self) -> Result<(), N3xbError> {
+        if let Some(trusted_pubkeys) = &self.content.trusted_pubkeys {
+            if trusted_pubkeys.is_empty() {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Trusted Pubkeys should not be empty if specified"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_escrow_specified(&self) -> Result<(), N3xbError> {
+        if self.parameters.contains(&TradeParameter::TrustedEscrow) {
+            let Some(escrow) = &self.content.escrow else {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Escrow should be specified"
+                )));
+            };
+
+            if escrow.pubkey.is_none() && escrow.endpoint.is_none() {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Escrow should have a Pubkey or an Endpoint specified"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_partial_amounts_valid(&self) -> Result<(), N3xbError> {
+        if !self
+            .parameters
+            .contains(&TradeParameter::AcceptsPartialTake)
+        {
+            return Ok(());
+        }
+
+        let content = &self.content;
+        if content.max_partial_amount == Some(0) {
+            return Err(N3xbError::Simple(format!(
+                "Trade Details Max Partial Amount should not be zero"
+            )));
+        }
+        if let (Some(min), Some(max)) = (content.min_partial_amount, content.max_partial_amount) {
+            if min > max {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Min Partial Amount more than Max Partial Amount"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Only for Trade Details being built
+    pub(crate) fn validate_trade_timeout_consistent(&self) -> Result<(), N3xbError> {
+        let Some(trade_timeout) = self.content.trade_timeout else {
+            return Ok(());
+        };
+
+        let Some(limit) = self.trade_timeout_limit() else {
+            return Err(N3xbError::Simple(format!(
+                "Trade Details Trade Timeout should only be specified with the TradeTimesOut parameter"
+            )));
+        };
+
+        if let Some(duration) = limit.duration() {
+            if u64::from(trade_timeout) > duration.as_secs() {
+                return Err(N3xbError::Simple(format!(
+                    "Trade Details Trade Timeout of {} secs exceeds the TradeTimesOut limit of {}",
+                    trade_timeout, limit
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
 pub struct TradeDetailsContent {
     pub maker_bond_pct: Option<u32>,
     pub taker_bond_pct: Option<u32>,
-    pub trade_timeout: Option<u32>,
+    pub trade_timeout: Option<u32>, // Seconds, within the limit of the TradeTimesOut parameter
+    #[serde(default)]
+    pub trusted_pubkeys: Option<HashSet<XOnlyPublicKey>>,
+    #[serde(default)]
+    pub escrow: Option<EscrowDetails>, // Required when TrustedEscrow is a Trade Parameter
+    #[serde(default)]
+    pub min_partial_amount: Option<u64>, // Sats per partial take. Only honored with AcceptsPartialTake
+    #[serde(default)]
+    pub max_partial_amount: Option<u64>,
+}
+
+// Identifies the Trusted Escrow so Takers can verify it before Offering
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct EscrowDetails {
+    pub pubkey: Option<XOnlyPublicKey>,
+    pub endpoint: Option<String>,
 }
 
 #[derive(
@@ -63,7 +208,7 @@ pub enum TradeParameter {
     TrustedEscrow,
     TrustlessEscrow,
     TrustedArbitration,
-    AcceptsPartialTake,
+    AcceptsPartialTake, // The rest of the Order is not reoffered once an Offer is accepted
     TradeTimesOut(TradeTimeOutLimit),
 }
 
@@ -123,9 +268,21 @@ pub enum TradeTimeOutLimit {
     FourDays,
 }
 
+impl TradeTimeOutLimit {
+    // Trade Engine specific time outs are not known to n3xB
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            TradeTimeOutLimit::TradeEngineSpecific => None,
+            TradeTimeOutLimit::OneDay => Some(Duration::from_secs(24 * 60 * 60)),
+            TradeTimeOutLimit::FourDays => Some(Duration::from_secs(4 * 24 * 60 * 60)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::SomeTestOfferParams;
 
     #[test]
     fn trade_details_times_out_parameter_to_tag() {
@@ -265,11 +422,28 @@ mod tests {
         assert_eq!(parameters, expected_parameters);
     }
 
+    #[test]
+    fn trade_details_taker_trusted() {
+        let trusted_pubkey = SomeTestOfferParams::some_x_only_public_key();
+        let mut trade_details = test_details_for_(HashSet::new());
+        assert!(trade_details.is_taker_trusted(&trusted_pubkey));
+
+        trade_details.content.trusted_pubkeys = Some(HashSet::from([trusted_pubkey]));
+        assert!(trade_details.is_taker_trusted(&trusted_pubkey));
+
+        trade_details.content.trusted_pubkeys = Some(HashSet::new());
+        assert!(!trade_details.is_taker_trusted(&trusted_pubkey));
+    }
+
     fn test_details_for_(parameters: HashSet<TradeParameter>) -> TradeDetails {
         let content = TradeDetailsContent {
             maker_bond_pct: None,
             taker_bond_pct: None,
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: None,
+            min_partial_amount: None,
+            max_partial_amount: None,
         };
         TradeDetails {
             parameters,
@@ -1,4 +1,4 @@
 mod peer_messaging;
 
-pub use peer_messaging::PeerEnvelope;
 pub(crate) use peer_messaging::*;
+pub use peer_messaging::{PeerEnvelope, PeerMessageGap, PeerMessageLogEntry};
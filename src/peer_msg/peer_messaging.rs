@@ -1,30 +1,117 @@
-use std::{any::Any, collections::HashSet, fmt::Debug};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt::Debug,
+    time::Duration,
+};
 
 use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
+use tokio::{sync::oneshot, time::Instant};
 use url::Url;
 use uuid::Uuid;
 
-use crate::common::types::{EventIdString, SerdeGenericTrait, SerdeGenericType};
+use crate::common::{
+    error::N3xbError,
+    types::{EventIdString, SerdeGenericTrait, SerdeGenericType},
+};
 
 // Peer Messaging Data Structures
 
+pub(crate) const PEER_MESSAGE_TYPE: &str = "n3xb-peer-message";
+pub(crate) const PEER_MESSAGE_VERSION: u32 = 1; // Bump on changes older peers can't deserialize
+pub(crate) const PEER_MESSAGE_SEND_ATTEMPTS: u32 = 3; // Trade Engine specific messages only
+pub(crate) const PEER_MESSAGE_RESEND_DELAY: Duration = Duration::from_secs(2);
+pub(crate) const PEER_MESSAGE_RECEIVED_IDS_CAPACITY: usize = 100; // Resends land within seconds
+pub(crate) const PEER_MESSAGE_REORDER_WINDOW: Duration = Duration::from_secs(5);
+pub(crate) const PEER_MESSAGE_REORDER_CAPACITY: usize = 20;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PeerEnvelope {
     pub pubkey: XOnlyPublicKey,
     pub urls: HashSet<Url>,
     pub event_id: EventIdString,
+    pub created_at: u64,
     pub(crate) message_type: SerdeGenericType,
     pub message: Box<dyn SerdeGenericTrait>,
+    #[serde(default)]
+    pub(crate) sequence: Option<u64>,
+    #[serde(default)]
+    pub(crate) message_id: Option<Uuid>,
+    #[serde(default)]
+    pub trade_engine_specifics_defaulted: bool, // Offer came with absent or malformed specifics
+}
+
+impl PeerEnvelope {
+    pub(crate) fn new(
+        pubkey: XOnlyPublicKey,
+        urls: HashSet<Url>,
+        event_id: EventIdString,
+        created_at: u64,
+        peer_message: PeerMessage,
+    ) -> Self {
+        Self {
+            pubkey,
+            urls,
+            event_id,
+            created_at,
+            message_type: peer_message.message_type,
+            message: peer_message.message,
+            sequence: peer_message.sequence,
+            message_id: peer_message.message_id,
+            trade_engine_specifics_defaulted: peer_message.trade_engine_specifics_defaulted,
+        }
+    }
+
+    // Anyone can send a peer message tagged with a Trade UUID. Only the established counterparty is to be trusted
+    pub(crate) fn verify_sender(&self, expected_pubkey: &XOnlyPublicKey) -> Result<(), N3xbError> {
+        if self.pubkey != *expected_pubkey {
+            return Err(N3xbError::Simple(format!(
+                "Peer message of type {:?} w/ Event ID {} from unexpected pubkey. Expected pubkey: {}, Received pubkey: {}",
+                self.message_type, self.event_id, expected_pubkey, self.pubkey
+            )));
+        }
+        Ok(())
+    }
+
+    // A resend of a message that had actually landed carries the same message ID. Only the
+    // first one received is to be processed. Messages without an ID are never resends. Only the
+    // latest IDs are remembered, oldest dropped past the capacity
+    pub(crate) fn is_resend(
+        &self,
+        received_message_ids: &mut VecDeque<Uuid>,
+        capacity: usize,
+    ) -> bool {
+        let Some(message_id) = self.message_id else {
+            return false;
+        };
+        if received_message_ids.contains(&message_id) {
+            return true;
+        }
+        received_message_ids.push_back(message_id);
+        while received_message_ids.len() > capacity {
+            received_message_ids.pop_front();
+        }
+        false
+    }
 }
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct PeerMessage {
     pub(crate) r#type: String,
-    pub(crate) responding_to_id: Option<String>, // TODO: Is there a more specific type we can use here?
-    pub(crate) maker_order_note_id: String, // TODO: Is there a more specific type we can use here?
-    pub(crate) trade_uuid: Uuid,            // TODO: Change to UUID type?
+    #[serde(default = "PeerMessage::unversioned")]
+    pub(crate) version: u32,
+    #[serde(default)]
+    pub(crate) sequence: Option<u64>, // Per-trade sequence of Trade Engine specific messages, starting from 1
+    #[serde(default)]
+    pub(crate) message_id: Option<Uuid>, // Stays the same across resends of the same message
+    pub(crate) responding_to_id: Option<EventIdString>,
+    pub(crate) maker_order_note_id: EventIdString,
+    pub(crate) trade_uuid: Uuid, // TODO: Change to UUID type?
     pub(crate) message_type: SerdeGenericType,
     pub(crate) message: Box<dyn SerdeGenericTrait>,
+    #[serde(skip)]
+    pub(crate) trade_engine_specifics_defaulted: bool, // Set on receipt, never sent
 }
 
 #[typetag::serde(name = "n3xB-peer-message")]
@@ -33,3 +120,503 @@ impl SerdeGenericTrait for PeerMessage {
         self
     }
 }
+
+// Trade Engine specific messages skipped over in sequence, likely lost in transit
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct PeerMessageGap {
+    pub expected_sequence: u64,
+    pub received_sequence: u64,
+}
+
+impl PeerMessageGap {
+    pub(crate) fn detect(last_received_sequence: u64, sequence: u64) -> Option<Self> {
+        let expected_sequence = last_received_sequence + 1;
+        if sequence > expected_sequence {
+            Some(Self {
+                expected_sequence,
+                received_sequence: sequence,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+// Trade Engine specific messages received ahead of sequence. Held for a short window in case the
+// ones before are only delayed on another relay, rather than lost
+#[derive(Default)]
+pub(crate) struct PeerMessageReorderBuffer {
+    envelopes: BTreeMap<u64, PeerEnvelope>,
+    deadline: Option<Instant>,
+}
+
+impl PeerMessageReorderBuffer {
+    // Missing messages are given up on past the deadline. See flush()
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub(crate) fn is_duplicate(&self, last_received_sequence: u64, sequence: u64) -> bool {
+        sequence <= last_received_sequence || self.envelopes.contains_key(&sequence)
+    }
+
+    // Returns the messages now in sequence, in order, for delivery. Check is_duplicate() first
+    pub(crate) fn push(
+        &mut self,
+        last_received_sequence: u64,
+        sequence: u64,
+        envelope: PeerEnvelope,
+    ) -> Vec<PeerEnvelope> {
+        self.envelopes.insert(sequence, envelope);
+
+        let mut ready = Vec::new();
+        let mut next_sequence = last_received_sequence + 1;
+        while let Some(envelope) = self.envelopes.remove(&next_sequence) {
+            ready.push(envelope);
+            next_sequence += 1;
+        }
+
+        // The window restarts whenever the sequence moves forward. No point holding on past capacity
+        self.deadline = if self.envelopes.is_empty() {
+            None
+        } else if self.envelopes.len() > PEER_MESSAGE_REORDER_CAPACITY {
+            Some(Instant::now())
+        } else if !ready.is_empty() || self.deadline.is_none() {
+            Some(Instant::now() + PEER_MESSAGE_REORDER_WINDOW)
+        } else {
+            self.deadline
+        };
+        ready
+    }
+
+    // Gives up on the missing messages. Returns everything held, in order, for delivery past the gaps
+    pub(crate) fn flush(&mut self) -> Vec<PeerEnvelope> {
+        self.deadline = None;
+        std::mem::take(&mut self.envelopes).into_values().collect()
+    }
+}
+
+// Trade Engine specific message exchanged with the counterparty, persisted for rebuilding a restored Trade
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerMessageLogEntry {
+    pub outgoing: bool, // Sent to the counterparty, otherwise received from it
+    pub sequence: Option<u64>,
+    pub created_at: u64, // Unix seconds
+    pub message: Box<dyn SerdeGenericTrait>,
+}
+
+impl PeerMessageLogEntry {
+    // Oldest entries are dropped past the capacity. Nothing is logged with a capacity of 0
+    pub(crate) fn push_capped(log: &mut VecDeque<Self>, capacity: usize, entry: Self) {
+        if capacity == 0 {
+            return;
+        }
+        log.push_back(entry);
+        while log.len() > capacity {
+            log.pop_front();
+        }
+    }
+}
+
+// Trade Engine specific message awaiting a resend. Resent from the actor loop once due, so
+// requests keep being served in between attempts
+pub(crate) struct PendingPeerMessage {
+    pub(crate) message: Box<dyn SerdeGenericTrait>,
+    pub(crate) sequence: u64,
+    pub(crate) message_id: Uuid, // Stays the same across resends
+    pub(crate) attempt: u32,
+    pub(crate) resend_at: Instant,
+    pub(crate) log_entry: PeerMessageLogEntry,
+    pub(crate) rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+}
+
+#[derive(Deserialize)]
+struct PeerMessageHeader {
+    r#type: String,
+    #[serde(default = "PeerMessage::unversioned")]
+    version: u32,
+}
+
+impl PeerMessage {
+    // The canonical wire format of all Peer Messages. See the golden tests below before changing
+    // anything serialized, and bump PEER_MESSAGE_VERSION if older peers can't deserialize it
+    pub(crate) fn new(
+        responding_to_id: Option<EventIdString>,
+        maker_order_note_id: EventIdString,
+        trade_uuid: Uuid,
+        message_type: SerdeGenericType,
+        message: Box<dyn SerdeGenericTrait>,
+        sequence: Option<u64>,
+        message_id: Option<Uuid>,
+    ) -> Self {
+        Self {
+            r#type: PEER_MESSAGE_TYPE.to_string(),
+            version: PEER_MESSAGE_VERSION,
+            sequence,
+            message_id,
+            responding_to_id,
+            maker_order_note_id,
+            trade_uuid,
+            message_type,
+            message,
+            trade_engine_specifics_defaulted: false,
+        }
+    }
+
+    // JSON pointer to the Trade Engine specifics of a serialized Offer. None for any other message
+    pub(crate) fn offer_specifics_pointer(value: &serde_json::Value) -> Option<&'static str> {
+        (value["message_type"] == "TakerOffer").then_some("/message/trade_engine_specifics")
+    }
+
+    // Messages from before versioning was introduced are of the first version
+    fn unversioned() -> u32 {
+        1
+    }
+
+    pub(crate) fn check_header(json: &str) -> Result<(), N3xbError> {
+        let header: PeerMessageHeader = serde_json::from_str(json)?;
+        if header.r#type != PEER_MESSAGE_TYPE {
+            return Err(N3xbError::Simple(format!(
+                "Peer Message of unexpected type {}",
+                header.r#type
+            )));
+        }
+        if header.version > PEER_MESSAGE_VERSION {
+            return Err(N3xbError::Simple(format!(
+                "Peer Message version {} is newer than supported version {}",
+                header.version, PEER_MESSAGE_VERSION
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Final message of a trade, telling the counterparty this side considers the trade complete
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct TradeComplete {}
+
+#[typetag::serde(name = "n3xB-trade-complete")]
+impl SerdeGenericTrait for TradeComplete {
+    fn any_ref(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Tells the Maker a pending Offer is withdrawn and no longer to be considered for acceptance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct OfferWithdrawal {
+    pub(crate) offer_event_id: EventIdString,
+}
+
+#[typetag::serde(name = "n3xB-offer-withdrawal")]
+impl SerdeGenericTrait for OfferWithdrawal {
+    fn any_ref(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::Secp256k1;
+
+    use super::*;
+    use crate::testing::{SomeTestOrderParams, SomeTestParams};
+
+    const SOME_EVENT_ID: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+    const SOME_RESPONDING_TO_ID: &str =
+        "0000000000000000000000000000000000000000000000000000000000000002";
+    const SOME_MESSAGE_ID: &str = "a1b2c3d4-0000-4000-8000-000000000001";
+
+    // Golden wire format. Peers on other crate versions parse exactly this
+    fn golden_peer_message_json() -> String {
+        format!(
+            "{{\"type\":\"n3xb-peer-message\",\"version\":1,\"sequence\":3,\"message_id\":\"{}\",\"responding_to_id\":\"{}\",\"maker_order_note_id\":\"{}\",\"trade_uuid\":\"{}\",\"message_type\":\"TradeComplete\",\"message\":{{\"type\":\"n3xB-trade-complete\"}}}}",
+            SOME_MESSAGE_ID,
+            SOME_RESPONDING_TO_ID,
+            SOME_EVENT_ID,
+            SomeTestOrderParams::some_uuid()
+        )
+    }
+
+    #[test]
+    fn test_peer_message_serialize_golden() {
+        let peer_message = PeerMessage::new(
+            Some(EventIdString::from_str(SOME_RESPONDING_TO_ID).unwrap()),
+            EventIdString::from_str(SOME_EVENT_ID).unwrap(),
+            SomeTestOrderParams::some_uuid(),
+            SerdeGenericType::TradeComplete,
+            Box::new(TradeComplete {}),
+            Some(3),
+            Some(Uuid::parse_str(SOME_MESSAGE_ID).unwrap()),
+        );
+        let json = serde_json::to_string(&peer_message).unwrap();
+        print!("Peer Message JSON: {}", json);
+        assert_eq!(json, golden_peer_message_json());
+    }
+
+    #[test]
+    fn test_peer_message_deserialize_golden() {
+        let json = golden_peer_message_json();
+        assert!(PeerMessage::check_header(&json).is_ok());
+
+        let peer_message: PeerMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(peer_message.r#type, PEER_MESSAGE_TYPE);
+        assert_eq!(peer_message.version, PEER_MESSAGE_VERSION);
+        assert_eq!(peer_message.sequence, Some(3));
+        assert_eq!(
+            peer_message.message_id,
+            Some(Uuid::parse_str(SOME_MESSAGE_ID).unwrap())
+        );
+        assert_eq!(
+            peer_message.responding_to_id,
+            Some(EventIdString::from_str(SOME_RESPONDING_TO_ID).unwrap())
+        );
+        assert_eq!(peer_message.maker_order_note_id.as_str(), SOME_EVENT_ID);
+        assert_eq!(peer_message.trade_uuid, SomeTestOrderParams::some_uuid());
+        assert!(matches!(
+            peer_message.message_type,
+            SerdeGenericType::TradeComplete
+        ));
+        assert!(peer_message
+            .message
+            .any_ref()
+            .downcast_ref::<TradeComplete>()
+            .is_some());
+        assert_eq!(serde_json::to_string(&peer_message).unwrap(), json);
+    }
+
+    #[test]
+    fn test_peer_message_deserialize_optional_fields_absent() {
+        // Unversioned messages without sequences, and not responding to any event
+        let json = format!(
+            "{{\"type\":\"n3xb-peer-message\",\"maker_order_note_id\":\"{}\",\"trade_uuid\":\"{}\",\"message_type\":\"TradeComplete\",\"message\":{{\"type\":\"n3xB-trade-complete\"}}}}",
+            SOME_EVENT_ID,
+            SomeTestOrderParams::some_uuid()
+        );
+        let peer_message: PeerMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(peer_message.version, 1);
+        assert_eq!(peer_message.sequence, None);
+        assert_eq!(peer_message.message_id, None);
+        assert_eq!(peer_message.responding_to_id, None);
+    }
+
+    #[test]
+    fn test_peer_message_deserialize_malformed_event_id() {
+        let json = format!(
+            "{{\"type\":\"n3xb-peer-message\",\"maker_order_note_id\":\"not-an-event-id\",\"trade_uuid\":\"{}\",\"message_type\":\"TradeComplete\",\"message\":{{\"type\":\"n3xB-trade-complete\"}}}}",
+            SomeTestOrderParams::some_uuid()
+        );
+        assert!(serde_json::from_str::<PeerMessage>(&json).is_err());
+    }
+
+    #[test]
+    fn test_check_header_current_version() {
+        let json = format!(
+            "{{\"type\":\"{}\",\"version\":{},\"message\":{{}}}}",
+            PEER_MESSAGE_TYPE, PEER_MESSAGE_VERSION
+        );
+        assert!(PeerMessage::check_header(&json).is_ok());
+    }
+
+    #[test]
+    fn test_check_header_unversioned() {
+        let json = format!("{{\"type\":\"{}\"}}", PEER_MESSAGE_TYPE);
+        assert!(PeerMessage::check_header(&json).is_ok());
+    }
+
+    #[test]
+    fn test_check_header_newer_version() {
+        let json = format!(
+            "{{\"type\":\"{}\",\"version\":{}}}",
+            PEER_MESSAGE_TYPE,
+            PEER_MESSAGE_VERSION + 1
+        );
+        assert!(PeerMessage::check_header(&json).is_err());
+    }
+
+    #[test]
+    fn test_peer_message_gap_detect() {
+        assert_eq!(PeerMessageGap::detect(0, 1), None);
+        assert_eq!(PeerMessageGap::detect(3, 4), None);
+        assert_eq!(
+            PeerMessageGap::detect(3, 6),
+            Some(PeerMessageGap {
+                expected_sequence: 4,
+                received_sequence: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_peer_message_gap_duplicate_not_gap() {
+        assert_eq!(PeerMessageGap::detect(3, 3), None);
+        assert_eq!(PeerMessageGap::detect(3, 1), None);
+    }
+
+    fn some_sequenced_envelope(sequence: u64) -> PeerEnvelope {
+        PeerEnvelope {
+            pubkey: SomeTestParams::maker_private_key()
+                .x_only_public_key(&Secp256k1::new())
+                .0,
+            urls: HashSet::new(),
+            event_id: SomeTestParams::some_event_id(sequence as u8),
+            created_at: 0,
+            message_type: SerdeGenericType::TradeEngineSpecific,
+            message: Box::new(TradeComplete {}),
+            sequence: Some(sequence),
+            message_id: Some(Uuid::new_v4()),
+            trade_engine_specifics_defaulted: false,
+        }
+    }
+
+    fn sequences_of(envelopes: Vec<PeerEnvelope>) -> Vec<u64> {
+        envelopes
+            .into_iter()
+            .map(|envelope| envelope.sequence.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_buffer_holds_until_in_sequence() {
+        let mut buffer = PeerMessageReorderBuffer::default();
+        assert!(sequences_of(buffer.push(0, 2, some_sequenced_envelope(2))).is_empty());
+        assert!(sequences_of(buffer.push(0, 3, some_sequenced_envelope(3))).is_empty());
+        assert!(buffer.deadline().is_some());
+        assert!(buffer.is_duplicate(0, 3));
+
+        let ready = buffer.push(0, 1, some_sequenced_envelope(1));
+        assert_eq!(sequences_of(ready), vec![1, 2, 3]);
+        assert!(buffer.deadline().is_none());
+        assert!(buffer.is_duplicate(3, 2));
+        assert!(!buffer.is_duplicate(3, 4));
+    }
+
+    #[test]
+    fn test_reorder_buffer_flush_past_gap() {
+        let mut buffer = PeerMessageReorderBuffer::default();
+        assert!(buffer.push(1, 4, some_sequenced_envelope(4)).is_empty());
+        assert!(buffer.push(1, 3, some_sequenced_envelope(3)).is_empty());
+        assert_eq!(sequences_of(buffer.flush()), vec![3, 4]);
+        assert!(buffer.deadline().is_none());
+        assert!(buffer.flush().is_empty());
+    }
+
+    #[test]
+    fn test_reorder_buffer_due_past_capacity() {
+        let mut buffer = PeerMessageReorderBuffer::default();
+        let first_sequence = 2;
+        let last_sequence = first_sequence + PEER_MESSAGE_REORDER_CAPACITY as u64;
+        for sequence in first_sequence..=last_sequence {
+            assert!(buffer
+                .push(0, sequence, some_sequenced_envelope(sequence))
+                .is_empty());
+        }
+        assert!(buffer.deadline().unwrap() <= Instant::now());
+    }
+
+    #[test]
+    fn test_peer_message_log_push_capped() {
+        let some_entry = |sequence: u64| PeerMessageLogEntry {
+            outgoing: true,
+            sequence: Some(sequence),
+            created_at: 0,
+            message: Box::new(TradeComplete {}),
+        };
+
+        let mut log: VecDeque<PeerMessageLogEntry> = VecDeque::new();
+        PeerMessageLogEntry::push_capped(&mut log, 0, some_entry(1));
+        assert!(log.is_empty());
+
+        for sequence in 1..=3 {
+            PeerMessageLogEntry::push_capped(&mut log, 2, some_entry(sequence));
+        }
+        let sequences: Vec<Option<u64>> = log.iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_check_header_unexpected_type() {
+        let json = "{\"type\":\"some-other-message\",\"version\":1}";
+        assert!(PeerMessage::check_header(json).is_err());
+    }
+
+    #[test]
+    fn test_verify_sender_rejects_spoofed_pubkey() {
+        let secp = Secp256k1::new();
+        let maker_pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&secp)
+            .0;
+        let taker_pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&secp)
+            .0;
+
+        let envelope = PeerEnvelope {
+            pubkey: taker_pubkey,
+            urls: HashSet::new(),
+            event_id: SomeTestParams::some_event_id(0),
+            created_at: 0,
+            message_type: SerdeGenericType::TradeComplete,
+            message: Box::new(TradeComplete {}),
+            sequence: None,
+            message_id: None,
+            trade_engine_specifics_defaulted: false,
+        };
+        assert!(envelope.verify_sender(&taker_pubkey).is_ok());
+        assert!(envelope.verify_sender(&maker_pubkey).is_err());
+    }
+
+    #[test]
+    fn test_resent_peer_message_processed_once() {
+        let message_id = Uuid::new_v4();
+        let some_envelope = |event_id: EventIdString, message_id: Option<Uuid>| {
+            let peer_message = PeerMessage::new(
+                None,
+                EventIdString::from_str(SOME_EVENT_ID).unwrap(),
+                SomeTestOrderParams::some_uuid(),
+                SerdeGenericType::TradeEngineSpecific,
+                Box::new(TradeComplete {}),
+                Some(1),
+                message_id,
+            );
+            PeerEnvelope {
+                pubkey: SomeTestParams::maker_private_key()
+                    .x_only_public_key(&Secp256k1::new())
+                    .0,
+                urls: HashSet::new(),
+                event_id,
+                created_at: 0,
+                message_type: peer_message.message_type,
+                message: peer_message.message,
+                sequence: peer_message.sequence,
+                message_id: peer_message.message_id,
+                trade_engine_specifics_defaulted: peer_message.trade_engine_specifics_defaulted,
+            }
+        };
+
+        // First attempt landed but was reported as failed, so the same message was sent again
+        let mut received_message_ids: VecDeque<Uuid> = VecDeque::new();
+        let first_attempt = some_envelope(SomeTestParams::some_event_id(1), Some(message_id));
+        let second_attempt = some_envelope(SomeTestParams::some_event_id(2), Some(message_id));
+        assert!(!first_attempt.is_resend(&mut received_message_ids, 2));
+        assert!(second_attempt.is_resend(&mut received_message_ids, 2));
+        assert_eq!(received_message_ids.len(), 1);
+
+        // Messages from peers not sending message IDs are always processed
+        let unidentified = some_envelope(SomeTestParams::some_event_id(3), None);
+        assert!(!unidentified.is_resend(&mut received_message_ids, 2));
+        assert!(!unidentified.is_resend(&mut received_message_ids, 2));
+
+        // Oldest IDs are forgotten past the capacity
+        for event_index in 4..6 {
+            let other = some_envelope(
+                SomeTestParams::some_event_id(event_index),
+                Some(Uuid::new_v4()),
+            );
+            assert!(!other.is_resend(&mut received_message_ids, 2));
+        }
+        assert_eq!(received_message_ids.len(), 2);
+        assert!(!received_message_ids.contains(&message_id));
+    }
+}
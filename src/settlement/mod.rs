@@ -0,0 +1,3 @@
+mod settlement;
+
+pub use settlement::*;
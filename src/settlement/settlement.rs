@@ -0,0 +1,237 @@
+use std::{any::Any, collections::HashSet, fmt::Debug};
+
+use secp256k1::XOnlyPublicKey;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use url::Url;
+
+use crate::{
+    common::{error::N3xbError, types::*},
+    order::Order,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SettlementInfoEnvelope {
+    pub pubkey: XOnlyPublicKey,
+    pub urls: HashSet<Url>,
+    pub event_id: EventIdString,
+    pub settlement_info: SettlementInfo,
+    pub(crate) _private: (),
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct SettlementInfo {
+    pub address: String,
+    pub amount_sats: u64,
+    pub network: BitcoinNetwork,
+}
+
+#[typetag::serde(name = "n3xB-settlement-info")]
+impl SerdeGenericTrait for SettlementInfo {
+    fn any_ref(&self) -> &dyn Any {
+        self
+    }
+}
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BASE58_CHARSET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+impl SettlementInfo {
+    pub fn new(address: impl Into<String>, amount_sats: u64, network: BitcoinNetwork) -> Self {
+        Self {
+            address: address.into(),
+            amount_sats,
+            network,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), N3xbError> {
+        if self.amount_sats == 0 {
+            return Err(N3xbError::Simple(format!(
+                "Settlement Info amount should be greater than 0 sats"
+            )));
+        }
+        Self::validate_address(&self.address, &self.network)
+    }
+
+    // Settlement Info exchanged for a trade is to be on the Bitcoin network of the Order
+    pub fn validate_for_order(&self, order: &Order) -> Result<(), N3xbError> {
+        match order.bitcoin_network() {
+            Some(network) => self.validate_for_network(&network),
+            None => self.validate(),
+        }
+    }
+
+    pub fn validate_for_network(&self, expected_network: &BitcoinNetwork) -> Result<(), N3xbError> {
+        if &self.network != expected_network {
+            return Err(N3xbError::BitcoinNetworkMismatch(
+                expected_network.to_owned(),
+                self.network.to_owned(),
+            ));
+        }
+        self.validate()
+    }
+
+    // An address of another network is reported as a mismatch rather than as malformed
+    fn validate_address(address: &str, network: &BitcoinNetwork) -> Result<(), N3xbError> {
+        if Self::address_valid(address, network) {
+            return Ok(());
+        }
+
+        if let Some(address_network) =
+            BitcoinNetwork::iter().find(|other| Self::address_valid(address, other))
+        {
+            return Err(N3xbError::BitcoinNetworkMismatch(
+                network.to_owned(),
+                address_network,
+            ));
+        }
+
+        Err(N3xbError::Simple(format!(
+            "Settlement Info address {} is not a valid {} address",
+            address, network
+        )))
+    }
+
+    // Format check only. Checksums are not verified
+    fn address_valid(address: &str, network: &BitcoinNetwork) -> bool {
+        let (bech32_hrp, base58_prefixes) = match network {
+            BitcoinNetwork::Mainnet => ("bc", vec!['1', '3']),
+            BitcoinNetwork::Testnet | BitcoinNetwork::Signet => ("tb", vec!['m', 'n', '2']),
+            BitcoinNetwork::Regtest => ("bcrt", vec!['m', 'n', '2']),
+        };
+
+        let lowercased = address.to_lowercase();
+        let bech32_prefix = format!("{}1", bech32_hrp);
+
+        if lowercased.starts_with(&bech32_prefix) {
+            let mixed_case = address != lowercased && address != address.to_uppercase();
+            let data = &lowercased[bech32_prefix.len()..];
+            !mixed_case
+                && (14..=90).contains(&address.len())
+                && data.len() >= 6
+                && data.chars().all(|c| BECH32_CHARSET.contains(c))
+        } else if let Some(first_char) = address.chars().next() {
+            base58_prefixes.contains(&first_char)
+                && (26..=35).contains(&address.len())
+                && address.chars().all(|c| BASE58_CHARSET.contains(c))
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SomeTestOrderParams;
+
+    #[test]
+    fn test_settlement_info_mainnet_addresses() {
+        let addresses = [
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            "BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ",
+            "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+            "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+        ];
+        for address in addresses {
+            let settlement_info = SettlementInfo::new(address, 100000, BitcoinNetwork::Mainnet);
+            assert!(settlement_info.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_settlement_info_testnet_address() {
+        let settlement_info = SettlementInfo::new(
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            100000,
+            BitcoinNetwork::Testnet,
+        );
+        assert!(settlement_info.validate().is_ok());
+    }
+
+    #[test]
+    fn test_settlement_info_regtest_address() {
+        let settlement_info = SettlementInfo::new(
+            "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+            100000,
+            BitcoinNetwork::Regtest,
+        );
+        assert!(settlement_info.validate().is_ok());
+    }
+
+    #[test]
+    fn test_settlement_info_network_mismatch() {
+        let settlement_info = SettlementInfo::new(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            100000,
+            BitcoinNetwork::Testnet,
+        );
+        assert!(matches!(
+            settlement_info.validate(),
+            Err(N3xbError::BitcoinNetworkMismatch(
+                BitcoinNetwork::Testnet,
+                BitcoinNetwork::Mainnet
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_settlement_info_trade_network_mismatch() {
+        let settlement_info = SettlementInfo::new(
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            100000,
+            BitcoinNetwork::Testnet,
+        );
+        assert!(settlement_info
+            .validate_for_network(&BitcoinNetwork::Testnet)
+            .is_ok());
+        assert!(matches!(
+            settlement_info.validate_for_network(&BitcoinNetwork::Mainnet),
+            Err(N3xbError::BitcoinNetworkMismatch(
+                BitcoinNetwork::Mainnet,
+                BitcoinNetwork::Testnet
+            ))
+        ));
+
+        // Test Orders exchange Bitcoin on Regtest
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        assert!(matches!(
+            settlement_info.validate_for_order(&order),
+            Err(N3xbError::BitcoinNetworkMismatch(
+                BitcoinNetwork::Regtest,
+                BitcoinNetwork::Testnet
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_settlement_info_mixed_case_bech32() {
+        let settlement_info = SettlementInfo::new(
+            "bc1Qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            100000,
+            BitcoinNetwork::Mainnet,
+        );
+        assert!(settlement_info.validate().is_err());
+    }
+
+    #[test]
+    fn test_settlement_info_invalid_base58_char() {
+        let settlement_info = SettlementInfo::new(
+            "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN0",
+            100000,
+            BitcoinNetwork::Mainnet,
+        );
+        assert!(settlement_info.validate().is_err());
+    }
+
+    #[test]
+    fn test_settlement_info_zero_amount() {
+        let settlement_info = SettlementInfo::new(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            0,
+            BitcoinNetwork::Mainnet,
+        );
+        assert!(settlement_info.validate().is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::taker::{TakerAccess, TakerNotif};
+use crate::{common::error::N3xbError, trade_rsp::TradeResponseStatus};
+
+type TakerBatchNotif = (Uuid, Result<TakerNotif, N3xbError>);
+type TakerBatchNotifTx = mpsc::Sender<TakerBatchNotif>;
+
+// Notifications are buffered until a notif_tx is registered, so none are lost in between
+struct TakerBatchNotifs {
+    tx: Option<TakerBatchNotifTx>,
+    buffered: VecDeque<TakerBatchNotif>,
+}
+
+// Takers created together against different Orders, for sending Offers to several Orders at once
+// and going with whichever gets accepted first. Once one is accepted, the Offers of all other
+// Takers in the batch are withdrawn so the Taker doesn't end up committed to multiple trades.
+// Makers accepting at the same time can still beat the withdrawal. Any Taker accepted after the
+// first is reported with an error notification, for the user to settle or cancel the extra trade.
+// Notifications of Takers in the batch are to be received through the batch
+#[derive(Clone)]
+pub struct TakerBatch {
+    takers: HashMap<Uuid, TakerAccess>,
+    notifs: Arc<Mutex<TakerBatchNotifs>>,
+    accepted_trade_uuid: Arc<Mutex<Option<Uuid>>>,
+}
+
+impl TakerBatch {
+    const TAKER_NOTIF_CHANNEL_SIZE: usize = 10;
+    const MAX_BUFFERED_NOTIFS: usize = 100;
+
+    pub(crate) async fn new(takers: HashMap<Uuid, TakerAccess>) -> Result<Self, N3xbError> {
+        let batch = Self {
+            takers,
+            notifs: Arc::new(Mutex::new(TakerBatchNotifs {
+                tx: None,
+                buffered: VecDeque::new(),
+            })),
+            accepted_trade_uuid: Arc::new(Mutex::new(None)),
+        };
+
+        for (trade_uuid, taker) in &batch.takers {
+            let (taker_tx, taker_rx) =
+                mpsc::channel::<Result<TakerNotif, N3xbError>>(Self::TAKER_NOTIF_CHANNEL_SIZE);
+            taker.register_notif_tx(taker_tx).await?;
+
+            let siblings: Vec<TakerAccess> = batch
+                .takers
+                .iter()
+                .filter(|(sibling_trade_uuid, _)| *sibling_trade_uuid != trade_uuid)
+                .map(|(_, sibling)| sibling.clone())
+                .collect();
+            tokio::spawn(Self::forward_notifs(
+                *trade_uuid,
+                taker_rx,
+                siblings,
+                batch.notifs.clone(),
+                batch.accepted_trade_uuid.clone(),
+            ));
+        }
+        Ok(batch)
+    }
+
+    async fn forward_notifs(
+        trade_uuid: Uuid,
+        mut taker_rx: mpsc::Receiver<Result<TakerNotif, N3xbError>>,
+        siblings: Vec<TakerAccess>,
+        notifs: Arc<Mutex<TakerBatchNotifs>>,
+        accepted_trade_uuid: Arc<Mutex<Option<Uuid>>>,
+    ) {
+        while let Some(notif) = taker_rx.recv().await {
+            let accepted = matches!(
+                &notif,
+                Ok(TakerNotif::TradeRsp(trade_rsp_envelope))
+                    if trade_rsp_envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted
+            );
+            if !accepted {
+                Self::forward_notif(&notifs, (trade_uuid, notif)).await;
+                continue;
+            }
+
+            let first_accepted_trade_uuid = {
+                let mut accepted_trade_uuid = accepted_trade_uuid.lock().await;
+                *accepted_trade_uuid.get_or_insert(trade_uuid)
+            };
+            if first_accepted_trade_uuid != trade_uuid {
+                let error = N3xbError::Simple(format!(
+                    "Taker Batch had Taker w/ TradeUUID {} accepted after Taker w/ TradeUUID {} was already accepted",
+                    trade_uuid, first_accepted_trade_uuid
+                ));
+                error!("{}", error);
+                Self::forward_notif(&notifs, (trade_uuid, notif)).await;
+                Self::forward_notif(&notifs, (trade_uuid, Err(error))).await;
+                continue;
+            }
+
+            for sibling in &siblings {
+                if let Some(error) = sibling.withdraw_offer().await.err() {
+                    warn!(
+                        "Taker Batch failed to withdraw Offer of sibling of accepted Taker w/ TradeUUID {} - {}",
+                        trade_uuid, error
+                    );
+                }
+            }
+            Self::forward_notif(&notifs, (trade_uuid, notif)).await;
+        }
+    }
+
+    async fn forward_notif(notifs: &Mutex<TakerBatchNotifs>, notif: TakerBatchNotif) {
+        let mut notifs = notifs.lock().await;
+        let Some(notif_tx) = notifs.tx.clone() else {
+            if notifs.buffered.len() >= Self::MAX_BUFFERED_NOTIFS {
+                warn!(
+                    "Taker Batch dropping oldest buffered notification, no notif_tx registered yet"
+                );
+                notifs.buffered.pop_front();
+            }
+            notifs.buffered.push_back(notif);
+            return;
+        };
+
+        let trade_uuid = notif.0;
+        if let Some(error) = notif_tx.send(notif).await.err() {
+            warn!(
+                "Taker Batch failed to forward notification from Taker w/ TradeUUID {} - {}",
+                trade_uuid, error
+            );
+        }
+    }
+
+    // Taker of the batch accepted first, if any
+    pub async fn accepted_trade_uuid(&self) -> Option<Uuid> {
+        *self.accepted_trade_uuid.lock().await
+    }
+
+    pub fn takers(&self) -> HashMap<Uuid, TakerAccess> {
+        self.takers.clone()
+    }
+
+    pub fn trade_uuids(&self) -> Vec<Uuid> {
+        self.takers.keys().copied().collect()
+    }
+
+    // Sends the Offers of all Takers in the batch. Returns the result of each by TradeUUID
+    pub async fn take_orders(&self) -> HashMap<Uuid, Result<(), N3xbError>> {
+        let mut results = HashMap::new();
+        for (trade_uuid, taker) in &self.takers {
+            results.insert(*trade_uuid, taker.take_order().await);
+        }
+        results
+    }
+
+    // Notifications from all Takers in the batch, tagged with the TradeUUID of the originating Taker.
+    // Notifications buffered before registering are delivered first, in the order received
+    pub async fn register_notif_tx(&self, tx: TakerBatchNotifTx) -> Result<(), N3xbError> {
+        let mut notifs = self.notifs.clone().lock_owned().await;
+        let mut result = Ok(());
+        if notifs.tx.is_some() {
+            result = Err(N3xbError::Simple(
+                "Taker Batch already have notif_tx registered".to_string(),
+            ));
+        }
+        notifs.tx = Some(tx.clone());
+
+        // Delivered with the lock held, so notifications forwarded meanwhile queue up behind
+        let buffered: Vec<TakerBatchNotif> = notifs.buffered.drain(..).collect();
+        tokio::spawn(async move {
+            for notif in buffered {
+                if let Some(error) = tx.send(notif).await.err() {
+                    warn!(
+                        "Taker Batch failed to deliver buffered notification - {}",
+                        error
+                    );
+                    break;
+                }
+            }
+            drop(notifs);
+        });
+        result
+    }
+
+    pub async fn unregister_notif_tx(&self) -> Result<(), N3xbError> {
+        let mut notifs = self.notifs.lock().await;
+        let mut result = Ok(());
+        if notifs.tx.is_none() {
+            result = Err(N3xbError::Simple(
+                "Taker Batch expected to already have notif_tx registered".to_string(),
+            ));
+        }
+        notifs.tx = None;
+        result
+    }
+}
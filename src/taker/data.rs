@@ -1,29 +1,62 @@
 use std::{
-    path::Path,
+    collections::{HashSet, VecDeque},
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 use crate::{
     common::{
         error::N3xbError,
         persist::Persister,
+        store::N3xbStore,
         types::{EventIdString, SerdeGenericTrait},
     },
+    dispute::DisputeRequest,
     offer::Offer,
     order::OrderEnvelope,
+    peer_msg::{PeerEnvelope, PeerMessageLogEntry, PEER_MESSAGE_RECEIVED_IDS_CAPACITY},
+    settlement::SettlementInfo,
+    trade_record::TradeRecordEntry,
     trade_rsp::TradeResponseEnvelope,
 };
 
+const TAKER_DATA_VERSION: u32 = 8;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct TakerDataStore {
+    version: u32,
     order_envelope: OrderEnvelope,
     offer: Offer,
     offer_event_id: Option<EventIdString>,
+    #[serde(default)]
+    offer_sent_at: Option<u64>, // Unix seconds
     trade_rsp_envelope: Option<TradeResponseEnvelope>,
     trade_completed: bool,
+    #[serde(default)]
+    offer_withdrawn: bool,
+    #[serde(default)]
+    peer_msg_seq_sent: u64, // Sequence of the last Trade Engine specific message sent
+    #[serde(default)]
+    peer_msg_seq_received: u64, // Highest sequence of Trade Engine specific message received
+    #[serde(default)]
+    peer_message_log: VecDeque<PeerMessageLogEntry>,
+    #[serde(default)]
+    settlement_infos: Vec<TradeRecordEntry<SettlementInfo>>, // Sent and received, never dropped
+    #[serde(default)]
+    disputes: Vec<TradeRecordEntry<DisputeRequest>>, // Raised and received, never dropped
+    #[serde(default)]
+    received_peer_message_ids: VecDeque<Uuid>, // For dropping resends that had actually landed
+    #[serde(default)]
+    encryption_secret_key: Option<SecretKey>, // Of the per-trade pubkey advertised in the Offer
+
+    // Trade specific settings
+    #[serde(default)]
+    peer_message_log_capacity: usize,
 }
 
 #[typetag::serde(name = "n3xb_taker_data")]
@@ -41,24 +74,38 @@ pub(crate) struct TakerData {
 
 impl TakerData {
     pub(crate) fn new(
-        dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        dir_key: impl AsRef<str>,
         order_envelope: OrderEnvelope,
         offer: Offer,
+        encryption_secret_key: Option<SecretKey>,
+        peer_message_log_capacity: usize,
     ) -> Self {
         let trade_uuid = order_envelope.order.trade_uuid;
-        let data_path = dir_path.as_ref().join(format!("{}-taker.json", trade_uuid));
+        let data_key = format!("{}/{}-taker.json", dir_key.as_ref(), trade_uuid);
 
         let store = TakerDataStore {
+            version: TAKER_DATA_VERSION,
             order_envelope,
             offer,
             offer_event_id: None,
+            offer_sent_at: None,
             trade_rsp_envelope: None,
             trade_completed: false,
+            offer_withdrawn: false,
+            peer_msg_seq_sent: 0,
+            peer_msg_seq_received: 0,
+            peer_message_log: VecDeque::new(),
+            settlement_infos: Vec::new(),
+            disputes: Vec::new(),
+            received_peer_message_ids: VecDeque::new(),
+            encryption_secret_key,
+            peer_message_log_capacity,
         };
 
         let store = Arc::new(RwLock::new(store));
         let generic_store: Arc<RwLock<dyn SerdeGenericTrait + 'static>> = store.clone();
-        let persister = Persister::new(generic_store, data_path);
+        let persister = Persister::new(generic_store, backend, data_key);
         persister.queue();
 
         Self {
@@ -68,15 +115,23 @@ impl TakerData {
         }
     }
 
-    pub(crate) fn restore(data_path: impl AsRef<Path>) -> Result<(Uuid, Self), N3xbError> {
-        let json = Persister::restore(&data_path)?;
+    pub(crate) fn restore(
+        backend: Arc<dyn N3xbStore>,
+        data_key: impl AsRef<str>,
+    ) -> Result<(Uuid, Self), N3xbError> {
+        let json = Persister::restore_migrated(
+            backend.as_ref(),
+            data_key.as_ref(),
+            TAKER_DATA_VERSION,
+            Self::migrate,
+        )?;
         let store: TakerDataStore = serde_json::from_str(&json)?;
 
         let trade_uuid = store.order_envelope.order.trade_uuid;
 
         let store = Arc::new(RwLock::new(store));
         let generic_store: Arc<RwLock<dyn SerdeGenericTrait + 'static>> = store.clone();
-        let persister = Persister::new(generic_store, &data_path);
+        let persister = Persister::new(generic_store, backend, data_key.as_ref());
         persister.queue();
 
         let data = Self {
@@ -88,6 +143,49 @@ impl TakerData {
         Ok((trade_uuid, data))
     }
 
+    fn migrate(version: u32, store: &mut Map<String, Value>) -> Result<(), N3xbError> {
+        match version {
+            // Fill in fields added before persisted data was versioned
+            1 => {
+                store.entry("peer_msg_seq_sent").or_insert(0.into());
+                store.entry("peer_msg_seq_received").or_insert(0.into());
+            }
+            2 => {
+                store
+                    .entry("peer_message_log")
+                    .or_insert(Value::Array(Vec::new()));
+                store.entry("peer_message_log_capacity").or_insert(0.into());
+            }
+            3 => {
+                store
+                    .entry("received_peer_message_ids")
+                    .or_insert(Value::Array(Vec::new()));
+            }
+            4 => {
+                store.entry("offer_sent_at").or_insert(Value::Null);
+            }
+            5 => {
+                store.entry("offer_withdrawn").or_insert(false.into());
+            }
+            6 => {
+                store.entry("encryption_secret_key").or_insert(Value::Null);
+            }
+            7 => {
+                store
+                    .entry("settlement_infos")
+                    .or_insert(Value::Array(Vec::new()));
+                store.entry("disputes").or_insert(Value::Array(Vec::new()));
+            }
+            _ => {
+                return Err(N3xbError::Simple(format!(
+                    "Taker Data has no migration from version {}",
+                    version
+                )));
+            }
+        }
+        Ok(())
+    }
+
     fn read_store(&self) -> RwLockReadGuard<'_, TakerDataStore> {
         match self.store.read() {
             Ok(store) => store,
@@ -120,6 +218,10 @@ impl TakerData {
         self.read_store().offer_event_id.clone()
     }
 
+    pub(crate) fn offer_sent_at(&self) -> Option<u64> {
+        self.read_store().offer_sent_at
+    }
+
     pub(crate) fn trade_rsp_envelope(&self) -> Option<TradeResponseEnvelope> {
         self.read_store().trade_rsp_envelope.clone()
     }
@@ -128,10 +230,46 @@ impl TakerData {
         self.read_store().trade_completed
     }
 
+    pub(crate) fn offer_withdrawn(&self) -> bool {
+        self.read_store().offer_withdrawn
+    }
+
+    pub(crate) fn peer_msg_seq_sent(&self) -> u64 {
+        self.read_store().peer_msg_seq_sent
+    }
+
+    pub(crate) fn peer_msg_seq_received(&self) -> u64 {
+        self.read_store().peer_msg_seq_received
+    }
+
+    pub(crate) fn peer_message_log(&self) -> Vec<PeerMessageLogEntry> {
+        self.read_store().peer_message_log.iter().cloned().collect()
+    }
+
+    pub(crate) fn settlement_infos(&self) -> Vec<TradeRecordEntry<SettlementInfo>> {
+        self.read_store().settlement_infos.clone()
+    }
+
+    pub(crate) fn disputes(&self) -> Vec<TradeRecordEntry<DisputeRequest>> {
+        self.read_store().disputes.clone()
+    }
+
+    pub(crate) fn encryption_secret_key(&self) -> Option<SecretKey> {
+        self.read_store().encryption_secret_key
+    }
+
     // Setter methods
 
+    // The Offer is taken as sent when its Event ID is known
     pub(crate) fn set_offer_event_id(&self, offer_event_id: EventIdString) {
-        self.write_store().offer_event_id = Some(offer_event_id);
+        let offer_sent_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut store = self.write_store();
+        store.offer_event_id = Some(offer_event_id);
+        store.offer_sent_at = Some(offer_sent_at);
+        drop(store);
         self.persister.queue();
     }
 
@@ -145,6 +283,60 @@ impl TakerData {
         self.persister.queue();
     }
 
+    // Wiped once the trade ends, so the per-trade key doesn't linger on disk
+    pub(crate) fn clear_encryption_secret_key(&self) {
+        self.write_store().encryption_secret_key = None;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_offer_withdrawn(&self, offer_withdrawn: bool) {
+        self.write_store().offer_withdrawn = offer_withdrawn;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_peer_msg_seq_sent(&self, peer_msg_seq_sent: u64) {
+        self.write_store().peer_msg_seq_sent = peer_msg_seq_sent;
+        self.persister.queue();
+    }
+
+    pub(crate) fn set_peer_msg_seq_received(&self, peer_msg_seq_received: u64) {
+        self.write_store().peer_msg_seq_received = peer_msg_seq_received;
+        self.persister.queue();
+    }
+
+    // Returns true if a message of the same message ID was already received
+    pub(crate) fn record_peer_message_received(&self, envelope: &PeerEnvelope) -> bool {
+        let resend = envelope.is_resend(
+            &mut self.write_store().received_peer_message_ids,
+            PEER_MESSAGE_RECEIVED_IDS_CAPACITY,
+        );
+        if !resend {
+            self.persister.queue();
+        }
+        resend
+    }
+
+    pub(crate) fn push_settlement_info(&self, entry: TradeRecordEntry<SettlementInfo>) {
+        self.write_store().settlement_infos.push(entry);
+        self.persister.queue();
+    }
+
+    pub(crate) fn push_dispute(&self, entry: TradeRecordEntry<DisputeRequest>) {
+        self.write_store().disputes.push(entry);
+        self.persister.queue();
+    }
+
+    pub(crate) fn push_peer_message_log(&self, entry: PeerMessageLogEntry) {
+        let mut store = self.write_store();
+        let capacity = store.peer_message_log_capacity;
+        if capacity == 0 {
+            return;
+        }
+        PeerMessageLogEntry::push_capped(&mut store.peer_message_log, capacity, entry);
+        drop(store);
+        self.persister.queue();
+    }
+
     pub(crate) fn terminate(self) {
         self.persister.terminate()
     }
@@ -1,5 +1,7 @@
+mod batch;
 mod data;
 mod taker;
 
+pub use batch::TakerBatch;
 pub(crate) use taker::Taker;
-pub use taker::{TakerAccess, TakerNotif};
+pub use taker::{TakerAccess, TakerNotif, TakerOpts, TakerTradeStatus};
@@ -1,10 +1,17 @@
-use std::path::Path;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tracing::{debug, error, info, warn};
 
+use nostr_sdk::prelude::{Filter, Kind};
+use secp256k1::{rand::rngs::OsRng, Secp256k1, XOnlyPublicKey};
 use strum_macros::{Display, IntoStaticStr};
 use tokio::{
     select,
     sync::{mpsc, oneshot},
+    time::{sleep, sleep_until, Instant},
 };
 use uuid::Uuid;
 
@@ -12,21 +19,52 @@ use super::data::TakerData;
 
 use crate::{
     common::{
-        error::N3xbError,
-        types::{SerdeGenericTrait, SerdeGenericType},
+        error::{N3xbError, OfferInvalidReason},
+        store::N3xbStore,
+        types::{EventIdString, SerdeGenericTrait, SerdeGenericType},
+        utils::send_rsp,
     },
     comms::CommsAccess,
+    dispute::{DisputeRequest, DisputeRequestEnvelope},
     offer::Offer,
-    order::OrderEnvelope,
-    peer_msg::PeerEnvelope,
-    trade_rsp::{TradeResponse, TradeResponseEnvelope},
+    order::{OrderEnvelope, TradeDetails},
+    peer_msg::{
+        PeerEnvelope, PeerMessageGap, PeerMessageLogEntry, PeerMessageReorderBuffer,
+        PendingPeerMessage, PEER_MESSAGE_RESEND_DELAY, PEER_MESSAGE_SEND_ATTEMPTS,
+    },
+    settlement::{SettlementInfo, SettlementInfoEnvelope},
+    trade_record::{TradeRecord, TradeRecordEntry},
+    trade_rsp::{TradeResponse, TradeResponseEnvelope, TradeResponseStatus},
 };
 
 pub enum TakerNotif {
     TradeRsp(TradeResponseEnvelope),
+    SettlementInfo(SettlementInfoEnvelope),
+    TradeComplete,
+    Dispute(DisputeRequestEnvelope),
+    PeerMessageGap(PeerMessageGap),
     Peer(PeerEnvelope),
 }
 
+// Lifecycle of a Taker's trade, for presenting to users
+#[derive(Clone, Copy, Debug, PartialEq, Display, IntoStaticStr)]
+pub enum TakerTradeStatus {
+    Draft,     // Offer not yet sent
+    OfferSent, // Offer sent, no Trade Response yet
+    Accepted,  // Offer accepted, no peer messages exchanged yet
+    Rejected,  // Offer rejected, or the Order no longer available
+    Trading,   // Offer accepted, peer messages exchanged
+    Complete,  // Trade marked as complete
+    Cancelled, // Order cancelled by the Maker
+    Withdrawn, // Offer withdrawn by the Taker before being accepted
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TakerOpts {
+    pub peer_message_log_capacity: usize, // Peer messages persisted for rebuilding a restored Trade
+    pub trade_encryption_key: bool, // Offer a per-trade key for peer messages, for forward secrecy
+}
+
 #[derive(Clone)]
 pub struct TakerAccess {
     tx: mpsc::Sender<TakerRequest>,
@@ -44,6 +82,21 @@ impl TakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    // The Offer may still be sent after timing out, as the Taker keeps working on the request
+    pub async fn take_order_with_timeout(&self, timeout: Duration) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::SendTakerOffer { rsp_tx };
+        // Queueing counts towards the timeout too, for when requests are backed up
+        let send_and_rsp = async {
+            self.tx.send(request).await?;
+            rsp_rx.await?
+        };
+        select! {
+            result = send_and_rsp => result,
+            _ = sleep(timeout) => Err(N3xbError::Timeout("Take Order".to_string())),
+        }
+    }
+
     pub async fn query_trade_rsp(&self) -> Result<Option<TradeResponseEnvelope>, N3xbError> {
         let (rsp_tx, rsp_rx) =
             oneshot::channel::<Result<Option<TradeResponseEnvelope>, N3xbError>>();
@@ -52,6 +105,72 @@ impl TakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    // Returns the Trade Details agreed to in an accepted Trade Response, if any
+    pub async fn query_agreed_trade_details(&self) -> Result<Option<TradeDetails>, N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<Option<TradeDetails>, N3xbError>>();
+        let request = TakerRequest::QueryAgreedTradeDetails { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Returns the Offer along with its Event ID, only after the Offer have been sent
+    pub async fn status(&self) -> TakerTradeStatus {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<TakerTradeStatus>();
+        let request = TakerRequest::QueryStatus { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Pubkey of the Maker of the Order being taken
+    pub async fn maker_pubkey(&self) -> XOnlyPublicKey {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<XOnlyPublicKey>();
+        let request = TakerRequest::QueryMakerPubkey { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Latest Trade Engine specific messages exchanged, if logging was enabled through TakerOpts
+    pub async fn peer_message_log(&self) -> Vec<PeerMessageLogEntry> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Vec<PeerMessageLogEntry>>();
+        let request = TakerRequest::QueryPeerMessageLog { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Everything exchanged for the trade so far, for archiving once the trade completes
+    pub async fn trade_record(&self) -> TradeRecord {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<TradeRecord>();
+        let request = TakerRequest::QueryTradeRecord { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Time since the Offer was sent, while still waiting on a Trade Response
+    pub async fn offer_pending_duration(&self) -> Option<Duration> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Option<Duration>>();
+        let request = TakerRequest::QueryOfferPendingDuration { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn query_sent_offer(&self) -> Result<Option<(Offer, EventIdString)>, N3xbError> {
+        let (rsp_tx, rsp_rx) =
+            oneshot::channel::<Result<Option<(Offer, EventIdString)>, N3xbError>>();
+        let request = TakerRequest::QuerySentOffer { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    // Stops following up on the Offer and tells the Maker, so it is no longer considered. Trade
+    // Responses received afterwards are ignored. Withdrawal is allowed to fail if already shutdown,
+    // and once the Offer is accepted. Failing to tell the Maker still leaves the Offer withdrawn
+    pub async fn withdraw_offer(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::WithdrawOffer { rsp_tx };
+        self.tx.send(request).await?;
+        rsp_rx.await?
+    }
+
     pub async fn send_peer_message(
         &self,
         content: Box<dyn SerdeGenericTrait>,
@@ -65,6 +184,29 @@ impl TakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub async fn send_settlement_info(
+        &self,
+        settlement_info: SettlementInfo,
+    ) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::SendSettlementInfo {
+            settlement_info,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn raise_dispute(&self, dispute_request: DisputeRequest) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::RaiseDispute {
+            dispute_request,
+            rsp_tx,
+        };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn trade_complete(&self) -> Result<(), N3xbError> {
         let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
         let request = TakerRequest::TradeComplete { rsp_tx };
@@ -72,6 +214,20 @@ impl TakerAccess {
         rsp_rx.await.unwrap()
     }
 
+    pub async fn pause(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::Pause { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
+    pub async fn resume(&self) -> Result<(), N3xbError> {
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        let request = TakerRequest::Resume { rsp_tx };
+        self.tx.send(request).await.unwrap();
+        rsp_rx.await.unwrap()
+    }
+
     pub async fn register_notif_tx(
         &self,
         tx: mpsc::Sender<Result<TakerNotif, N3xbError>>,
@@ -109,20 +265,31 @@ impl Taker {
         comms_accessor: CommsAccess,
         order_envelope: OrderEnvelope,
         offer: Offer,
-        taker_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        taker_dir_key: impl AsRef<str>,
+        opts: TakerOpts,
     ) -> Self {
         let (tx, rx) = mpsc::channel::<TakerRequest>(Self::TAKER_REQUEST_CHANNEL_SIZE);
-        let actor = TakerActor::new(rx, comms_accessor, order_envelope, offer, taker_dir_path);
+        let actor = TakerActor::new(
+            rx,
+            comms_accessor,
+            order_envelope,
+            offer,
+            backend,
+            taker_dir_key,
+            opts,
+        );
         let task_handle = tokio::spawn(async move { actor.run().await });
         Self { tx, task_handle }
     }
 
     pub(crate) fn restore(
         comms_accessor: CommsAccess,
-        taker_data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        taker_data_key: impl AsRef<str>,
     ) -> Result<(Uuid, Self), N3xbError> {
         let (tx, rx) = mpsc::channel::<TakerRequest>(Self::TAKER_REQUEST_CHANNEL_SIZE);
-        let (trade_uuid, actor) = TakerActor::restore(rx, comms_accessor, taker_data_path)?;
+        let (trade_uuid, actor) = TakerActor::restore(rx, comms_accessor, backend, taker_data_key)?;
         let task_handle = tokio::spawn(async move { actor.run().await });
         let taker = Self { tx, task_handle };
         Ok((trade_uuid, taker))
@@ -141,13 +308,51 @@ pub(super) enum TakerRequest {
     QueryTradeRsp {
         rsp_tx: oneshot::Sender<Result<Option<TradeResponseEnvelope>, N3xbError>>,
     },
+    QueryAgreedTradeDetails {
+        rsp_tx: oneshot::Sender<Result<Option<TradeDetails>, N3xbError>>,
+    },
+    QuerySentOffer {
+        rsp_tx: oneshot::Sender<Result<Option<(Offer, EventIdString)>, N3xbError>>,
+    },
+    QueryOfferPendingDuration {
+        rsp_tx: oneshot::Sender<Option<Duration>>,
+    },
+    WithdrawOffer {
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    QueryStatus {
+        rsp_tx: oneshot::Sender<TakerTradeStatus>,
+    },
+    QueryMakerPubkey {
+        rsp_tx: oneshot::Sender<XOnlyPublicKey>,
+    },
+    QueryPeerMessageLog {
+        rsp_tx: oneshot::Sender<Vec<PeerMessageLogEntry>>,
+    },
+    QueryTradeRecord {
+        rsp_tx: oneshot::Sender<TradeRecord>,
+    },
     PeerMessage {
         message: Box<dyn SerdeGenericTrait>,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    SendSettlementInfo {
+        settlement_info: SettlementInfo,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    RaiseDispute {
+        dispute_request: DisputeRequest,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     TradeComplete {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     },
+    Pause {
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
+    Resume {
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    },
     RegisterNotifTx {
         tx: mpsc::Sender<Result<TakerNotif, N3xbError>>,
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
@@ -165,38 +370,70 @@ struct TakerActor {
     comms_accessor: CommsAccess,
     data: TakerData,
     notif_tx: Option<mpsc::Sender<Result<TakerNotif, N3xbError>>>,
+    paused: bool,
+    paused_envelopes: VecDeque<PeerEnvelope>,
+    pending_peer_message: Option<PendingPeerMessage>,
+    reorder_buffer: PeerMessageReorderBuffer,
 }
 
 impl TakerActor {
+    const PAUSED_PEER_MESSAGE_BUFFER_SIZE: usize = 50;
+
     pub(crate) fn new(
         rx: mpsc::Receiver<TakerRequest>,
         comms_accessor: CommsAccess,
         order_envelope: OrderEnvelope,
         offer: Offer,
-        taker_dir_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        taker_dir_key: impl AsRef<str>,
+        opts: TakerOpts,
     ) -> Self {
-        let data = TakerData::new(taker_dir_path, order_envelope, offer);
+        // Peer messages stay encrypted with the identity keys unless the Maker responds with its own
+        let mut offer = offer;
+        let encryption_secret_key = opts.trade_encryption_key.then(|| {
+            let (secret_key, public_key) = Secp256k1::new().generate_keypair(&mut OsRng);
+            offer.encryption_pubkey = Some(public_key.x_only_public_key().0);
+            secret_key
+        });
+
+        let data = TakerData::new(
+            backend,
+            taker_dir_key,
+            order_envelope,
+            offer,
+            encryption_secret_key,
+            opts.peer_message_log_capacity,
+        );
 
         TakerActor {
             rx,
             comms_accessor,
             data,
             notif_tx: None,
+            paused: false,
+            paused_envelopes: VecDeque::new(),
+            pending_peer_message: None,
+            reorder_buffer: PeerMessageReorderBuffer::default(),
         }
     }
 
     pub(crate) fn restore(
         rx: mpsc::Receiver<TakerRequest>,
         comms_accessor: CommsAccess,
-        taker_data_path: impl AsRef<Path>,
+        backend: Arc<dyn N3xbStore>,
+        taker_data_key: impl AsRef<str>,
     ) -> Result<(Uuid, Self), N3xbError> {
-        let (trade_uuid, data) = TakerData::restore(taker_data_path)?;
+        let (trade_uuid, data) = TakerData::restore(backend, taker_data_key)?;
 
         let actor = TakerActor {
             rx,
             comms_accessor,
             data,
             notif_tx: None,
+            paused: false,
+            paused_envelopes: VecDeque::new(),
+            pending_peer_message: None,
+            reorder_buffer: PeerMessageReorderBuffer::default(),
         };
 
         Ok((trade_uuid, actor))
@@ -218,7 +455,34 @@ impl TakerActor {
             );
         }
 
+        // Only the Maker of the Order is expected to DM this Taker for this trade
+        let maker_dm_filter = Filter::new()
+            .author(self.data.order_envelope().pubkey)
+            .kind(Kind::EncryptedDirectMessage);
+
+        if let Some(error) = self
+            .comms_accessor
+            .register_trade_filter(self.data.trade_uuid, maker_dm_filter)
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to register trade specific subscription filter - {}",
+                self.data.trade_uuid, error
+            );
+        }
+        self.register_trade_encryption().await;
+
+        let (decrypt_failure_tx, mut decrypt_failure_rx) = mpsc::channel::<N3xbError>(20);
+        self.watch_counterparty(decrypt_failure_tx).await;
+
         loop {
+            let next_resend = self
+                .pending_peer_message
+                .as_ref()
+                .map(|pending| pending.resend_at);
+            let reorder_deadline = self.reorder_buffer.deadline();
+
             select! {
                 Some(request) = self.rx.recv() => {
                     if self.handle_request(request).await {
@@ -226,13 +490,27 @@ impl TakerActor {
                     }
                 },
                 Some(envelope) = rx.recv() => {
-                    self.handle_peer_message(envelope).await;
+                    if self.paused {
+                        self.buffer_peer_message(envelope);
+                    } else {
+                        self.handle_peer_message(envelope).await;
+                    }
+                },
+                Some(error) = decrypt_failure_rx.recv() => {
+                    self.notify_decrypt_failure(error).await;
+                },
+                _ = sleep_until(next_resend.unwrap_or_else(Instant::now)), if next_resend.is_some() => {
+                    self.resend_peer_message().await;
+                },
+                _ = sleep_until(reorder_deadline.unwrap_or_else(Instant::now)), if reorder_deadline.is_some() => {
+                    self.flush_reordered_peer_messages().await;
                 },
                 else => break,
 
             }
         }
         info!("Taker w/ TradeUUID {} terminating", self.data.trade_uuid);
+        self.abandon_peer_message();
         self.data.terminate();
     }
 
@@ -250,11 +528,53 @@ impl TakerActor {
             TakerRequest::QueryTradeRsp { rsp_tx } => {
                 self.query_trade_rsp(rsp_tx);
             }
+            TakerRequest::QueryAgreedTradeDetails { rsp_tx } => {
+                self.query_agreed_trade_details(rsp_tx);
+            }
+            TakerRequest::QuerySentOffer { rsp_tx } => {
+                self.query_sent_offer(rsp_tx);
+            }
+            TakerRequest::QueryStatus { rsp_tx } => {
+                self.query_status(rsp_tx);
+            }
+            TakerRequest::QueryOfferPendingDuration { rsp_tx } => {
+                self.query_offer_pending_duration(rsp_tx);
+            }
+            TakerRequest::WithdrawOffer { rsp_tx } => {
+                self.withdraw_offer(rsp_tx).await;
+            }
+            TakerRequest::QueryMakerPubkey { rsp_tx } => {
+                send_rsp(rsp_tx, self.data.order_envelope().pubkey);
+            }
+            TakerRequest::QueryPeerMessageLog { rsp_tx } => {
+                send_rsp(rsp_tx, self.data.peer_message_log());
+            }
+            TakerRequest::QueryTradeRecord { rsp_tx } => {
+                self.query_trade_record(rsp_tx).await;
+            }
             TakerRequest::PeerMessage { message, rsp_tx } => {
                 self.send_peer_message(message, rsp_tx).await;
             }
+            TakerRequest::SendSettlementInfo {
+                settlement_info,
+                rsp_tx,
+            } => {
+                self.send_settlement_info(settlement_info, rsp_tx).await;
+            }
+            TakerRequest::RaiseDispute {
+                dispute_request,
+                rsp_tx,
+            } => {
+                self.raise_dispute(dispute_request, rsp_tx).await;
+            }
             TakerRequest::TradeComplete { rsp_tx } => {
-                self.trade_complete(rsp_tx);
+                self.trade_complete(rsp_tx).await;
+            }
+            TakerRequest::Pause { rsp_tx } => {
+                self.pause(rsp_tx);
+            }
+            TakerRequest::Resume { rsp_tx } => {
+                self.resume(rsp_tx).await;
             }
             TakerRequest::RegisterNotifTx { tx, rsp_tx } => {
                 self.register_notif_tx(tx, rsp_tx);
@@ -272,7 +592,16 @@ impl TakerActor {
 
     async fn send_taker_offer(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        if self.data.offer_withdrawn() {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} already withdrew the Offer",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
@@ -293,10 +622,10 @@ impl TakerActor {
         match result {
             Ok(event_id) => {
                 self.data.set_offer_event_id(event_id);
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Ok(()));
             }
             Err(err) => {
-                rsp_tx.send(Err(err)).unwrap(); // oneshot should not fail
+                send_rsp(rsp_tx, Err(err));
             }
         }
     }
@@ -306,7 +635,155 @@ impl TakerActor {
         rsp_tx: oneshot::Sender<Result<Option<TradeResponseEnvelope>, N3xbError>>,
     ) {
         let trade_rsp = self.data.trade_rsp_envelope();
-        rsp_tx.send(Ok(trade_rsp)).unwrap(); // oneshot should not fail
+        send_rsp(rsp_tx, Ok(trade_rsp));
+    }
+
+    fn query_agreed_trade_details(
+        &mut self,
+        rsp_tx: oneshot::Sender<Result<Option<TradeDetails>, N3xbError>>,
+    ) {
+        let agreed_trade_details = self
+            .data
+            .trade_rsp_envelope()
+            .filter(|envelope| envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted)
+            .and_then(|envelope| envelope.trade_rsp.agreed_trade_details);
+        send_rsp(rsp_tx, Ok(agreed_trade_details));
+    }
+
+    fn offer_accepted(&self) -> bool {
+        matches!(
+            self.data.trade_rsp_envelope(),
+            Some(trade_rsp_envelope)
+                if trade_rsp_envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted
+        )
+    }
+
+    async fn withdraw_offer(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        if self.offer_accepted() {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} cannot withdraw an already accepted Offer",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        self.data.set_offer_withdrawn(true);
+        self.unregister_trade_encryption().await;
+
+        // Nothing for the Maker to forget if the Offer was never sent
+        let Some(offer_event_id) = self.data.offer_event_id() else {
+            send_rsp(rsp_tx, Ok(()));
+            return;
+        };
+
+        let order_envelope = self.data.order_envelope();
+        let result = self
+            .comms_accessor
+            .send_offer_withdrawal(
+                order_envelope.pubkey,
+                order_envelope.event_id,
+                self.data.trade_uuid,
+                offer_event_id,
+            )
+            .await;
+        send_rsp(rsp_tx, result.map(|_| ()));
+    }
+
+    fn query_status(&mut self, rsp_tx: oneshot::Sender<TakerTradeStatus>) {
+        // An Offer can only be withdrawn before being accepted
+        let status = if self.data.offer_withdrawn() {
+            TakerTradeStatus::Withdrawn
+        } else if let Some(trade_rsp_envelope) = self.data.trade_rsp_envelope() {
+            let trade_rsp = trade_rsp_envelope.trade_rsp;
+            match trade_rsp.trade_response {
+                TradeResponseStatus::Accepted => {
+                    if self.data.trade_completed() {
+                        TakerTradeStatus::Complete
+                    } else if self.data.peer_msg_seq_sent() == 0
+                        && self.data.peer_msg_seq_received() == 0
+                    {
+                        TakerTradeStatus::Accepted
+                    } else {
+                        TakerTradeStatus::Trading
+                    }
+                }
+                TradeResponseStatus::Rejected | TradeResponseStatus::NotAvailable => {
+                    if trade_rsp
+                        .reject_reason
+                        .contains(&OfferInvalidReason::Cancelled)
+                    {
+                        TakerTradeStatus::Cancelled
+                    } else {
+                        TakerTradeStatus::Rejected
+                    }
+                }
+            }
+        } else if self.data.offer_event_id().is_some() {
+            TakerTradeStatus::OfferSent
+        } else {
+            TakerTradeStatus::Draft
+        };
+        send_rsp(rsp_tx, status);
+    }
+
+    fn query_offer_pending_duration(&mut self, rsp_tx: oneshot::Sender<Option<Duration>>) {
+        let pending_duration = match (self.data.offer_sent_at(), self.data.trade_rsp_envelope()) {
+            (Some(offer_sent_at), None) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                Some(Duration::from_secs(now.saturating_sub(offer_sent_at)))
+            }
+            _ => None,
+        };
+        send_rsp(rsp_tx, pending_duration);
+    }
+
+    // Taker only knows the Maker accepted once the Trade Response arrives
+    async fn query_trade_record(&mut self, rsp_tx: oneshot::Sender<TradeRecord>) {
+        let order_envelope = self.data.order_envelope();
+        let trade_rsp_envelope = self.data.trade_rsp_envelope();
+        let accepted = matches!(
+            &trade_rsp_envelope,
+            Some(envelope) if envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted
+        );
+        let taker_pubkey = if accepted {
+            Some(self.comms_accessor.get_pubkey().await)
+        } else {
+            None
+        };
+
+        let trade_record = TradeRecord {
+            trade_uuid: self.data.trade_uuid,
+            maker_pubkey: order_envelope.pubkey,
+            taker_pubkey,
+            order: order_envelope.order,
+            order_event_id: Some(order_envelope.event_id),
+            offer: Some(self.data.offer()),
+            offer_event_id: self.data.offer_event_id(),
+            trade_rsp_event_id: trade_rsp_envelope
+                .as_ref()
+                .map(|envelope| envelope.event_id.clone()),
+            trade_rsp: trade_rsp_envelope.map(|envelope| envelope.trade_rsp),
+            settlement_infos: self.data.settlement_infos(),
+            disputes: self.data.disputes(),
+            peer_messages: self.data.peer_message_log(),
+            trade_completed: self.data.trade_completed(),
+        };
+        send_rsp(rsp_tx, trade_record);
+    }
+
+    fn query_sent_offer(
+        &mut self,
+        rsp_tx: oneshot::Sender<Result<Option<(Offer, EventIdString)>, N3xbError>>,
+    ) {
+        let sent_offer = self
+            .data
+            .offer_event_id()
+            .map(|offer_event_id| (self.data.offer(), offer_event_id));
+        send_rsp(rsp_tx, Ok(sent_offer));
     }
 
     async fn send_peer_message(
@@ -315,32 +792,267 @@ impl TakerActor {
         rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
     ) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Sequences are to be sent in order. The next message has to wait for the pending one
+        if let Some(pending) = &self.pending_peer_message {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} still resending peer message {}",
+                self.data.trade_uuid, pending.message_id
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        // Only bump the sequence once actually sent, so a failed send doesn't show up as a gap
+        let sequence = self.data.peer_msg_seq_sent() + 1;
+        let log_entry = PeerMessageLogEntry {
+            outgoing: true,
+            sequence: Some(sequence),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            message: message.clone(),
+        };
+
+        // Resends carry the same message ID, so the Maker only processes whichever lands first
+        let pending = PendingPeerMessage {
+            message,
+            sequence,
+            message_id: Uuid::new_v4(),
+            attempt: 1,
+            resend_at: Instant::now(),
+            log_entry,
+            rsp_tx,
+        };
+        self.attempt_peer_message(pending).await;
+    }
+
+    async fn resend_peer_message(&mut self) {
+        let Some(pending) = self.pending_peer_message.take() else {
+            return;
+        };
+
+        // Trade might have been completed or cancelled in between attempts
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(pending.rsp_tx, Err(error));
             return;
         }
+        self.attempt_peer_message(pending).await;
+    }
 
+    async fn attempt_peer_message(&mut self, mut pending: PendingPeerMessage) {
         let order_envelope = self.data.order_envelope();
         let result = self
             .comms_accessor
             .send_trade_engine_specific_message(
                 order_envelope.pubkey,
                 None,
-                order_envelope.event_id,
+                order_envelope.event_id.clone(),
                 order_envelope.order.trade_uuid,
-                message,
+                pending.message.clone(),
+                pending.sequence,
+                pending.message_id,
+                HashSet::new(),
             )
             .await;
 
         match result {
             Ok(_) => {
-                rsp_tx.send(Ok(())).unwrap(); // oneshot should not fail
+                self.data.set_peer_msg_seq_sent(pending.sequence);
+                self.data.push_peer_message_log(pending.log_entry);
+                send_rsp(pending.rsp_tx, Ok(()));
+            }
+            Err(error) if pending.attempt < PEER_MESSAGE_SEND_ATTEMPTS => {
+                warn!(
+                    "Taker w/ TradeUUID {} failed to send peer message {} on attempt {} - {}",
+                    self.data.trade_uuid, pending.message_id, pending.attempt, error
+                );
+                pending.attempt += 1;
+                pending.resend_at = Instant::now() + PEER_MESSAGE_RESEND_DELAY;
+                self.pending_peer_message = Some(pending);
             }
             Err(err) => {
-                rsp_tx.send(Err(err)).unwrap(); // oneshot should not fail
+                send_rsp(pending.rsp_tx, Err(err));
             }
         }
     }
 
+    fn abandon_peer_message(&mut self) {
+        if let Some(pending) = self.pending_peer_message.take() {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} terminated before peer message {} could be sent",
+                self.data.trade_uuid, pending.message_id
+            ));
+            send_rsp(pending.rsp_tx, Err(error));
+        }
+    }
+
+    async fn send_settlement_info(
+        &mut self,
+        settlement_info: SettlementInfo,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let order_envelope = self.data.order_envelope();
+        if let Some(error) = settlement_info
+            .validate_for_order(&order_envelope.order)
+            .err()
+        {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let result = self
+            .comms_accessor
+            .send_settlement_info(
+                order_envelope.pubkey,
+                None,
+                order_envelope.event_id,
+                order_envelope.order.trade_uuid,
+                settlement_info.clone(),
+                HashSet::new(),
+            )
+            .await;
+
+        match result {
+            Ok(event_id) => {
+                self.data.push_settlement_info(TradeRecordEntry {
+                    outgoing: true,
+                    pubkey: order_envelope.pubkey,
+                    event_id,
+                    created_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    content: settlement_info,
+                });
+                send_rsp(rsp_tx, Ok(()));
+            }
+            Err(err) => {
+                send_rsp(rsp_tx, Err(err));
+            }
+        }
+    }
+
+    // Sent to the Maker, and to the Order's trusted pubkeys for arbitration if any
+    async fn raise_dispute(
+        &mut self,
+        dispute_request: DisputeRequest,
+        rsp_tx: oneshot::Sender<Result<(), N3xbError>>,
+    ) {
+        if let Some(error) = self.check_trade_completed().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        if let Some(error) = dispute_request.validate().err() {
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        let order_envelope = self.data.order_envelope();
+        let mut recipients = vec![order_envelope.pubkey];
+        let arbiter_pubkeys = order_envelope.order.trade_details.content.trusted_pubkeys;
+        recipients.extend(arbiter_pubkeys.unwrap_or_default());
+
+        let mut undelivered_pubkeys = Vec::new();
+        for pubkey in recipients {
+            let result = self
+                .comms_accessor
+                .send_dispute_request(
+                    pubkey,
+                    None,
+                    order_envelope.event_id.clone(),
+                    order_envelope.order.trade_uuid,
+                    dispute_request.clone(),
+                    HashSet::new(),
+                )
+                .await;
+
+            match result {
+                Ok(event_id) => {
+                    self.data.push_dispute(TradeRecordEntry {
+                        outgoing: true,
+                        pubkey,
+                        event_id,
+                        created_at: SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                        content: dispute_request.clone(),
+                    });
+                }
+                Err(error) => {
+                    warn!(
+                        "Taker w/ TradeUUID {} failed to send Dispute Request to pubkey {} - {}",
+                        self.data.trade_uuid, pubkey, error
+                    );
+                    undelivered_pubkeys.push(pubkey);
+                }
+            }
+        }
+
+        // Every recipient is still attempted. Those sent to are in the Trade Record
+        if undelivered_pubkeys.is_empty() {
+            send_rsp(rsp_tx, Ok(()));
+        } else {
+            send_rsp(
+                rsp_tx,
+                Err(N3xbError::DisputeUndelivered(undelivered_pubkeys)),
+            );
+        }
+    }
+
+    fn pause(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        let mut result = Ok(());
+        if self.paused {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} already paused",
+                self.data.trade_uuid
+            ));
+            result = Err(error);
+        }
+        self.paused = true;
+        send_rsp(rsp_tx, result);
+    }
+
+    async fn resume(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+        if !self.paused {
+            let error = N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} expected to be paused",
+                self.data.trade_uuid
+            ));
+            send_rsp(rsp_tx, Err(error));
+            return;
+        }
+
+        self.paused = false;
+        while let Some(envelope) = self.paused_envelopes.pop_front() {
+            self.handle_peer_message(envelope).await;
+        }
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    fn buffer_peer_message(&mut self, envelope: PeerEnvelope) {
+        if self.paused_envelopes.len() >= Self::PAUSED_PEER_MESSAGE_BUFFER_SIZE {
+            if let Some(dropped) = self.paused_envelopes.pop_front() {
+                warn!(
+                    "Taker w/ TradeUUID {} paused buffer full, dropping oldest Peer Message w/ Event ID {}",
+                    self.data.trade_uuid, dropped.event_id
+                );
+            }
+        }
+        self.paused_envelopes.push_back(envelope);
+    }
+
     fn register_notif_tx(
         &mut self,
         tx: mpsc::Sender<Result<TakerNotif, N3xbError>>,
@@ -355,7 +1067,7 @@ impl TakerActor {
             result = Err(error);
         }
         self.notif_tx = Some(tx);
-        rsp_tx.send(result).unwrap();
+        send_rsp(rsp_tx, result);
     }
 
     fn unregister_notif_tx(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
@@ -368,7 +1080,7 @@ impl TakerActor {
             result = Err(error);
         }
         self.notif_tx = None;
-        rsp_tx.send(result).unwrap();
+        send_rsp(rsp_tx, result);
     }
 
     fn check_trade_completed(&self) -> Result<(), N3xbError> {
@@ -383,23 +1095,157 @@ impl TakerActor {
         }
     }
 
-    fn trade_complete(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
+    async fn trade_complete(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
         if let Some(error) = self.check_trade_completed().err() {
-            rsp_tx.send(Err(error)).unwrap(); // oneshot should not fail
+            send_rsp(rsp_tx, Err(error));
             return;
         }
 
-        // TODO: What else to do for Trade Complete?
+        if let Some(error) = self
+            .comms_accessor
+            .unregister_trade_filter(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to unregister trade specific subscription filter - {}",
+                self.data.trade_uuid, error
+            );
+        }
+
+        // Let the Maker know, if it accepted the Offer
+        if self.offer_accepted() {
+            let order_envelope = self.data.order_envelope();
+            if let Some(error) = self
+                .comms_accessor
+                .send_trade_complete(
+                    order_envelope.pubkey,
+                    None,
+                    order_envelope.event_id,
+                    order_envelope.order.trade_uuid,
+                    HashSet::new(),
+                )
+                .await
+                .err()
+            {
+                send_rsp(rsp_tx, Err(error));
+                return;
+            }
+        }
+
         self.data.set_trade_completed(true);
-        rsp_tx.send(Ok(())).unwrap();
+        if let Some(error) = self
+            .comms_accessor
+            .unwatch_counterparty(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to unwatch counterparty - {}",
+                self.data.trade_uuid, error
+            );
+        }
+        self.unregister_trade_encryption().await;
+        send_rsp(rsp_tx, Ok(()));
     }
 
     fn shutdown(&mut self, rsp_tx: oneshot::Sender<Result<(), N3xbError>>) {
-        rsp_tx.send(Ok(())).unwrap();
+        send_rsp(rsp_tx, Ok(()));
+    }
+
+    // The Maker of the Order is the counterparty from the start, as the Offer is DMed to it
+    async fn watch_counterparty(&self, decrypt_failure_tx: mpsc::Sender<N3xbError>) {
+        if self.data.trade_completed() {
+            return;
+        }
+
+        if let Some(error) = self
+            .comms_accessor
+            .watch_counterparty(
+                self.data.trade_uuid,
+                self.data.order_envelope().pubkey,
+                decrypt_failure_tx,
+            )
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to watch counterparty for decrypt failures - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    // Likely the keys got out of sync with the Maker, which the Trade Engine should know of
+    async fn notify_decrypt_failure(&self, error: N3xbError) {
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Err(error)).await.err() {
+                error!(
+                    "Taker w/ TradeUUID {} failed in notifying user with notify_decrypt_failure - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Taker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    // Only once the Maker accepted with a per-trade pubkey of its own
+    async fn register_trade_encryption(&self) {
+        let Some(secret_key) = self.data.encryption_secret_key() else {
+            return;
+        };
+        let Some(peer_pubkey) = self
+            .data
+            .trade_rsp_envelope()
+            .filter(|envelope| envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted)
+            .and_then(|envelope| envelope.trade_rsp.encryption_pubkey)
+        else {
+            return;
+        };
+
+        if let Some(error) = self
+            .comms_accessor
+            .register_trade_encryption(self.data.trade_uuid, secret_key, peer_pubkey)
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to register per-trade encryption keys - {}",
+                self.data.trade_uuid, error
+            );
+        }
+    }
+
+    // Per-trade keys are of no use once the trade ends, and are not to be kept around
+    async fn unregister_trade_encryption(&self) {
+        if self.data.encryption_secret_key().is_none() {
+            return;
+        }
+        self.data.clear_encryption_secret_key();
+
+        if let Some(error) = self
+            .comms_accessor
+            .unregister_trade_encryption(self.data.trade_uuid)
+            .await
+            .err()
+        {
+            warn!(
+                "Taker w/ TradeUUID {} failed to unregister per-trade encryption keys - {}",
+                self.data.trade_uuid, error
+            );
+        }
     }
 
     // Bottom-up Peer Message Handling
 
+    fn verify_counterparty(&self, envelope: &PeerEnvelope) -> Result<(), N3xbError> {
+        envelope.verify_sender(&self.data.order_envelope().pubkey)
+    }
+
     async fn handle_peer_message(&mut self, peer_envelope: PeerEnvelope) {
         debug!(
             "Taker w/ TradeUUID {} handle_peer_message() from pubkey {}, of event id {}, type {:?}",
@@ -409,17 +1255,44 @@ impl TakerActor {
             peer_envelope.message_type
         );
 
+        // Only the Maker of the Order taken is to be trusted
+        if !matches!(peer_envelope.message_type, SerdeGenericType::TakerOffer) {
+            if let Err(error) = self.verify_counterparty(&peer_envelope) {
+                error!(
+                    "Taker w/ TradeUUID {} rejected peer message - {}",
+                    self.data.trade_uuid, error
+                );
+                if let Some(tx) = &self.notif_tx {
+                    if let Some(error) = tx.send(Err(error)).await.err() {
+                        error!(
+                            "Taker w/ TradeUUID {} failed in notifying user with handle_peer_message - {}",
+                            self.data.trade_uuid, error
+                        );
+                    }
+                }
+                return;
+            }
+        }
+
         match peer_envelope.message_type {
             SerdeGenericType::TradeResponse => {
-                let trade_rsp = peer_envelope.message
-                    .downcast_ref::<TradeResponse>()
-                    .expect(
-                        &format!(
-                            "Taker w/ TradeUUID {} received peer message of SerdeGenericType::TakerOffer, but failed to downcast message into Offer",
-                            self.data.trade_uuid
-                        )
-                    )
-                    .to_owned();
+                let Some(trade_rsp) = peer_envelope.message.downcast_ref::<TradeResponse>() else {
+                    let error = N3xbError::Simple(format!(
+                        "Taker w/ TradeUUID {} received peer message of SerdeGenericType::TradeResponse, but failed to downcast message into TradeResponse",
+                        self.data.trade_uuid
+                    ));
+                    error!("{}", error);
+                    if let Some(tx) = &self.notif_tx {
+                        if let Some(error) = tx.send(Err(error)).await.err() {
+                            error!(
+                                "Taker w/ TradeUUID {} failed in notifying user with handle_peer_message - {}",
+                                self.data.trade_uuid, error
+                            );
+                        }
+                    }
+                    return;
+                };
+                let trade_rsp = trade_rsp.to_owned();
                 let trade_rsp_envelope = TradeResponseEnvelope {
                     pubkey: peer_envelope.pubkey,
                     urls: peer_envelope.urls,
@@ -441,31 +1314,57 @@ impl TakerActor {
                 self.handle_engine_specific_peer_message(peer_envelope)
                     .await;
             }
+
+            SerdeGenericType::SettlementInfo => {
+                self.handle_settlement_info(peer_envelope).await;
+            }
+
+            SerdeGenericType::TradeComplete => {
+                self.handle_trade_complete().await;
+            }
+
+            SerdeGenericType::DisputeRequest => {
+                self.handle_dispute_request(peer_envelope).await;
+            }
+
+            SerdeGenericType::OfferWithdrawal => {
+                error!(
+                    "Taker w/ TradeUUID {} received unexpected OfferWithdrawal message",
+                    self.data.trade_uuid
+                );
+            }
         }
     }
 
     async fn handle_trade_response(&mut self, trade_rsp_envelope: TradeResponseEnvelope) {
+        if self.data.offer_withdrawn() {
+            warn!(
+                "Taker w/ TradeUUID {} ignoring TradeResponse w/ Event ID {} for withdrawn Offer",
+                self.data.trade_uuid, trade_rsp_envelope.event_id
+            );
+            return;
+        }
+
         let mut notif_result: Result<TakerNotif, N3xbError> =
             Ok(TakerNotif::TradeRsp(trade_rsp_envelope.clone()));
 
-        let order_envelope = self.data.order_envelope();
         let offer_event_id = self.data.offer_event_id().expect(&format!(
             "Taker w/ TradeUUID {} received TradeResponse message before Taker Offer has been sent",
             self.data.trade_uuid
         ));
 
-        if trade_rsp_envelope.pubkey != order_envelope.pubkey {
-            notif_result = Err(
-                N3xbError::Simple(
-                    format!(
-                        "Taker w/ TradeUUID {} received TradeResponse message with unexpected pubkey. Expected pubkey: {}, Received pubkey: {}",
-                        self.data.trade_uuid,
-                        order_envelope.pubkey,
-                        trade_rsp_envelope.pubkey
-                    )
-                )
-            );
-        } else if let Some(existing_trade_rsp_envelope) = &self.data.trade_rsp_envelope() {
+        // A Maker reconsidering an Offer can follow up a rejection with an acceptance
+        let existing_trade_rsp_envelope = self.data.trade_rsp_envelope();
+        let supersedes_rejection = matches!(
+            &existing_trade_rsp_envelope,
+            Some(existing) if existing.trade_rsp.trade_response == TradeResponseStatus::Rejected
+                && trade_rsp_envelope.trade_rsp.trade_response == TradeResponseStatus::Accepted
+        );
+
+        if let Some(existing_trade_rsp_envelope) = existing_trade_rsp_envelope
+            .as_ref()
+            .filter(|_| !supersedes_rejection)
+        {
             notif_result = Err(
                 N3xbError::Simple(
                     format!(
@@ -489,6 +1388,7 @@ impl TakerActor {
             );
         } else {
             self.data.set_trade_rsp_envelope(trade_rsp_envelope);
+            self.register_trade_encryption().await;
         }
 
         // Notify user of new Trade Response recieved
@@ -508,19 +1408,199 @@ impl TakerActor {
         }
     }
 
+    async fn handle_settlement_info(&mut self, envelope: PeerEnvelope) {
+        let notif_result = match envelope.message.downcast_ref::<SettlementInfo>() {
+            Some(settlement_info) => settlement_info.validate().map(|_| {
+                TakerNotif::SettlementInfo(SettlementInfoEnvelope {
+                    pubkey: envelope.pubkey,
+                    urls: envelope.urls.clone(),
+                    event_id: envelope.event_id.clone(),
+                    settlement_info: settlement_info.to_owned(),
+                    _private: (),
+                })
+            }),
+            None => Err(N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} received peer message of SerdeGenericType::SettlementInfo, but failed to downcast message into SettlementInfo",
+                self.data.trade_uuid
+            ))),
+        };
+
+        if let Ok(TakerNotif::SettlementInfo(received)) = &notif_result {
+            self.data.push_settlement_info(TradeRecordEntry {
+                outgoing: false,
+                pubkey: received.pubkey,
+                event_id: received.event_id.clone(),
+                created_at: envelope.created_at,
+                content: received.settlement_info.clone(),
+            });
+        }
+
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(notif_result).await.err() {
+                error!(
+                    "Taker w/ TradeUUID {} failed in notifying user with handle_settlement_info - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Taker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn handle_dispute_request(&mut self, envelope: PeerEnvelope) {
+        let notif_result = match envelope.message.downcast_ref::<DisputeRequest>() {
+            Some(dispute_request) => dispute_request.validate().map(|_| {
+                TakerNotif::Dispute(DisputeRequestEnvelope {
+                    pubkey: envelope.pubkey,
+                    urls: envelope.urls.clone(),
+                    event_id: envelope.event_id.clone(),
+                    dispute_request: dispute_request.to_owned(),
+                    _private: (),
+                })
+            }),
+            None => Err(N3xbError::Simple(format!(
+                "Taker w/ TradeUUID {} received peer message of SerdeGenericType::DisputeRequest, but failed to downcast message into DisputeRequest",
+                self.data.trade_uuid
+            ))),
+        };
+
+        if let Ok(TakerNotif::Dispute(received)) = &notif_result {
+            self.data.push_dispute(TradeRecordEntry {
+                outgoing: false,
+                pubkey: received.pubkey,
+                event_id: received.event_id.clone(),
+                created_at: envelope.created_at,
+                content: received.dispute_request.clone(),
+            });
+        }
+
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(notif_result).await.err() {
+                error!(
+                    "Taker w/ TradeUUID {} failed in notifying user with handle_dispute_request - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Taker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn handle_trade_complete(&mut self) {
+        // Counterparty completed after we did. Nothing left to converge on
+        if self.data.trade_completed() {
+            debug!(
+                "Taker w/ TradeUUID {} received TradeComplete message after already marked as Trade Complete",
+                self.data.trade_uuid
+            );
+            return;
+        }
+
+        // Nothing to complete with the Maker until it accepted the Offer
+        if !self.offer_accepted() {
+            warn!(
+                "Taker w/ TradeUUID {} ignoring TradeComplete message without an accepted Offer",
+                self.data.trade_uuid
+            );
+            return;
+        }
+
+        // User is expected to call trade_complete() in response to the notification
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(TakerNotif::TradeComplete)).await.err() {
+                error!(
+                    "Taker w/ TradeUUID {} failed in notifying user with handle_trade_complete - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        } else {
+            warn!(
+                "Taker w/ TradeUUID {} do not have notif_tx registered",
+                self.data.trade_uuid
+            );
+        }
+    }
+
+    async fn check_peer_message_sequence(&mut self, sequence: u64) {
+        let last_received_sequence = self.data.peer_msg_seq_received();
+        self.data.set_peer_msg_seq_received(sequence);
+
+        let Some(gap) = PeerMessageGap::detect(last_received_sequence, sequence) else {
+            return;
+        };
+
+        warn!(
+            "Taker w/ TradeUUID {} detected gap in TradeEngineSpecific messages - {:?}",
+            self.data.trade_uuid, gap
+        );
+        if let Some(tx) = &self.notif_tx {
+            if let Some(error) = tx.send(Ok(TakerNotif::PeerMessageGap(gap))).await.err() {
+                error!(
+                    "Taker w/ TradeUUID {} failed in notifying user with check_peer_message_sequence - {}",
+                    self.data.trade_uuid, error
+                );
+            }
+        }
+    }
+
     async fn handle_engine_specific_peer_message(&mut self, envelope: PeerEnvelope) {
-        let order_envelope = self.data.order_envelope();
+        if self.data.record_peer_message_received(&envelope) {
+            debug!(
+                "Taker w/ TradeUUID {} dropped resent peer message w/ Event ID {}",
+                self.data.trade_uuid, envelope.event_id
+            );
+            return;
+        }
 
-        // Verify peer message is signed by the expected pubkey before passing to Trade Engine
-        if envelope.pubkey != order_envelope.pubkey {
-            error!(
-                "Taker w/ TradeUUID {} received TradeEngineSpecific message with unexpected pubkey. Expected pubkey: {}, Received pubkey: {}",
-                self.data.trade_uuid,
-                order_envelope.pubkey,
-                envelope.pubkey
+        let Some(sequence) = envelope.sequence else {
+            self.deliver_engine_specific_peer_message(envelope).await;
+            return;
+        };
+
+        // Messages can arrive out of order across relays. Only delivered once back in sequence
+        let last_received_sequence = self.data.peer_msg_seq_received();
+        if self
+            .reorder_buffer
+            .is_duplicate(last_received_sequence, sequence)
+        {
+            warn!(
+                "Taker w/ TradeUUID {} dropped TradeEngineSpecific message with duplicate sequence {}, last received {}",
+                self.data.trade_uuid, sequence, last_received_sequence
             );
             return;
         }
+        let envelopes = self
+            .reorder_buffer
+            .push(last_received_sequence, sequence, envelope);
+        for envelope in envelopes {
+            self.deliver_engine_specific_peer_message(envelope).await;
+        }
+    }
+
+    // Missing messages are taken as lost. Whatever arrived after is delivered past the gap
+    async fn flush_reordered_peer_messages(&mut self) {
+        for envelope in self.reorder_buffer.flush() {
+            self.deliver_engine_specific_peer_message(envelope).await;
+        }
+    }
+
+    async fn deliver_engine_specific_peer_message(&mut self, envelope: PeerEnvelope) {
+        if let Some(sequence) = envelope.sequence {
+            self.check_peer_message_sequence(sequence).await;
+        }
+
+        self.data.push_peer_message_log(PeerMessageLogEntry {
+            outgoing: false,
+            sequence: envelope.sequence,
+            created_at: envelope.created_at,
+            message: envelope.message.clone(),
+        });
 
         // Let the Trade Engine / user to do the downcasting. Pass the SerdeGeneric message up as is
         if let Some(tx) = &self.notif_tx {
@@ -541,6 +1621,58 @@ impl TakerActor {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::{
+        common::store::FsStore,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_raise_dispute_reports_undelivered_recipients() {
+        let data_dir_path = "test_taker_raise_dispute_undelivered";
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::taker_private_key(), data_dir_path).await;
+        let maker_pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        let arbiter_pubkey = SomeTestOfferParams::some_x_only_public_key();
+
+        let mut order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        order.trade_details.content.trusted_pubkeys = Some(HashSet::from([arbiter_pubkey]));
+        let order_envelope =
+            SomeTestOrderParams::some_order_envelope(order, SomeTestParams::some_event_id(1), 0);
+        let (_tx, rx) = mpsc::channel::<TakerRequest>(TESTING_DEFAULT_CHANNEL_SIZE);
+        let mut actor = TakerActor::new(
+            rx,
+            comms.new_accessor(),
+            order_envelope,
+            SomeTestOfferParams::default_buy_builder().build().unwrap(),
+            Arc::new(FsStore::new(data_dir_path)),
+            "takers",
+            TakerOpts::default(),
+        );
+
+        // No relay to send to. Arbiter still attempted after the Maker fails
+        let (rsp_tx, rsp_rx) = oneshot::channel::<Result<(), N3xbError>>();
+        actor
+            .raise_dispute(DisputeRequest::new("Bitcoin not received", None), rsp_tx)
+            .await;
+        match rsp_rx.await.unwrap() {
+            Err(N3xbError::DisputeUndelivered(pubkeys)) => {
+                assert_eq!(pubkeys, vec![maker_pubkey, arbiter_pubkey]);
+            }
+            _ => panic!("Taker only expects DisputeUndelivered error at this point"),
+        }
+        assert!(actor.data.disputes().is_empty());
+
+        actor.comms_accessor.shutdown().await.unwrap();
+        actor.data.terminate();
+        let _ = tokio::fs::remove_dir_all(data_dir_path).await;
+    }
+
     // TODO: A lot to mock. Postponing this
 
     // #[tokio::test]
@@ -1,5 +1,7 @@
 mod offer;
 mod order;
+#[cfg(test)]
+mod relay;
 mod testing;
 mod trade_rsp;
 
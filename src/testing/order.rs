@@ -3,7 +3,7 @@ use std::collections::HashSet;
 use std::str::FromStr;
 
 use iso_currency::Currency;
-use secp256k1::{PublicKey, SecretKey, XOnlyPublicKey};
+use secp256k1::{PublicKey, Secp256k1, SecretKey, XOnlyPublicKey};
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
@@ -177,6 +177,10 @@ impl SomeTestOrderParams {
             maker_bond_pct: Some(10),
             taker_bond_pct: Some(10),
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: Some(Self::escrow_details()),
+            min_partial_amount: None,
+            max_partial_amount: None,
         }
     }
 
@@ -185,6 +189,17 @@ impl SomeTestOrderParams {
             maker_bond_pct: None,
             taker_bond_pct: None,
             trade_timeout: None,
+            trusted_pubkeys: None,
+            escrow: None,
+            min_partial_amount: None,
+            max_partial_amount: None,
+        }
+    }
+
+    pub fn escrow_details() -> EscrowDetails {
+        EscrowDetails {
+            pubkey: Some(Self::some_x_only_public_key()),
+            endpoint: Some("https://escrow.example.com".to_string()),
         }
     }
 
@@ -275,6 +290,28 @@ impl SomeTestOrderParams {
         builder
     }
 
+    // Order Envelope of the Order as posted by the Maker, without a signed event to verify against
+    pub fn some_order_envelope(
+        order: Order,
+        event_id: EventIdString,
+        created_at: u64,
+    ) -> OrderEnvelope {
+        OrderEnvelope {
+            pubkey: SomeTestParams::maker_private_key()
+                .x_only_public_key(&Secp256k1::new())
+                .0,
+            urls: HashSet::new(),
+            event_id,
+            order,
+            delegator_pubkey: None,
+            created_at,
+            expiration: None,
+            signed_event: None,
+            trade_engine_specifics_defaulted: false,
+            _private: (),
+        }
+    }
+
     pub fn check(order: &Order, expected: &Order) {
         assert_eq!(order.trade_uuid, expected.trade_uuid);
         assert_eq!(
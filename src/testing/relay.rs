@@ -0,0 +1,57 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use nostr_rs_relay::{config, server::start_server};
+use url::Url;
+
+// Relay run in-process, bound to loopback and backed by an in-memory database, for unit tests to
+// exchange events through without touching the network. Each has its own port and starts empty
+pub(crate) struct SomeTestRelay {
+    pub(crate) url: Url,
+    shutdown_tx: mpsc::Sender<()>,
+}
+
+impl SomeTestRelay {
+    const READY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+    const READY_MAX_POLLS: usize = 500;
+
+    pub(crate) async fn start() -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .unwrap();
+
+        let mut settings = config::Settings::default();
+        settings.network.address = "127.0.0.1".to_string();
+        settings.network.port = port;
+        settings.database.in_memory = true;
+        settings.database.min_conn = 4;
+        settings.database.max_conn = 8;
+
+        // Server blocks the thread it runs on
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
+        thread::spawn(move || {
+            let _ = start_server(&settings, shutdown_rx);
+        });
+
+        for _ in 0..Self::READY_MAX_POLLS {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+            tokio::time::sleep(Self::READY_POLL_INTERVAL).await;
+        }
+
+        SomeTestRelay {
+            url: Url::parse(&format!("ws://127.0.0.1:{}", port)).unwrap(),
+            shutdown_tx,
+        }
+    }
+
+    pub(crate) fn shutdown(self) {
+        let _ = self.shutdown_tx.send(());
+    }
+}
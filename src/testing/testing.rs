@@ -1,7 +1,20 @@
-use std::str::FromStr;
+use std::{path::Path, str::FromStr, sync::Arc};
 
 use secp256k1::SecretKey;
 
+use crate::{
+    common::{
+        store::FsStore,
+        types::{BitcoinNetwork, EventIdString},
+    },
+    comms::{Comms, CommsOptions},
+};
+
+#[cfg(test)]
+use super::relay::SomeTestRelay;
+#[cfg(test)]
+use crate::comms::RelayCapability;
+
 pub struct SomeTestParams {}
 
 impl SomeTestParams {
@@ -22,6 +35,132 @@ impl SomeTestParams {
         SecretKey::from_str("80e6f8e839135232972dfc16f2acdaeee9c0bcb4793a8a8249b7e384a51377e1")
             .unwrap()
     }
+
+    // Well-formed but made up Event ID, distinct for each seed
+    pub fn some_event_id(seed: u8) -> EventIdString {
+        EventIdString::from_str(&format!("{:064x}", seed)).unwrap()
+    }
+
+    // Comms with a fixed identity. No relays are added nor connected, so nothing touches the network
+    // until a test adds one, see some_comms_with_relay()
+    pub(crate) async fn some_comms(
+        secret_key: SecretKey,
+        data_dir_path: impl AsRef<Path>,
+    ) -> Comms {
+        Comms::new_with_key(
+            secret_key,
+            Self::engine_name_str(),
+            BitcoinNetwork::Regtest,
+            Arc::new(FsStore::new(data_dir_path)),
+            CommsOptions::default(),
+        )
+        .await
+    }
+
+    // Comms with a fixed identity, connected only to a relay of its own run in-process. Pass the
+    // relay on to further Comms for them to exchange events through
+    #[cfg(test)]
+    pub(crate) async fn some_comms_with_relay(
+        secret_key: SecretKey,
+        data_dir_path: impl AsRef<Path>,
+        relay: Option<&SomeTestRelay>,
+    ) -> (Comms, Option<SomeTestRelay>) {
+        let comms = Self::some_comms(secret_key, data_dir_path).await;
+        let (relay_url, started_relay) = match relay {
+            Some(relay) => (relay.url.clone(), None),
+            None => {
+                let relay = SomeTestRelay::start().await;
+                (relay.url.clone(), Some(relay))
+            }
+        };
+        comms
+            .new_accessor()
+            .add_relays(
+                vec![(relay_url, None, RelayCapability::ReadWrite)],
+                true,
+                Vec::new(),
+            )
+            .await
+            .unwrap();
+        (comms, started_relay)
+    }
 }
 
 pub const TESTING_DEFAULT_CHANNEL_SIZE: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::Secp256k1;
+    use std::time::Duration;
+    use tokio::{fs, time::sleep};
+
+    use super::*;
+    use crate::{comms::QueryOpts, order::FilterTag, testing::SomeTestOrderParams};
+
+    #[tokio::test]
+    async fn test_some_comms_deterministic_identity() {
+        let data_dir_path = "test_some_comms";
+        let _ = fs::remove_dir_all(data_dir_path).await;
+
+        let comms =
+            SomeTestParams::some_comms(SomeTestParams::maker_private_key(), data_dir_path).await;
+        let comms_accessor = comms.new_accessor();
+
+        let expected_pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        assert_eq!(comms_accessor.get_pubkey().await, expected_pubkey);
+        assert!(comms_accessor.get_relays().await.is_empty());
+
+        comms_accessor.shutdown().await.unwrap();
+        let _ = fs::remove_dir_all(data_dir_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_some_comms_exchange_through_relay() {
+        let maker_dir_path = "test_some_comms_relay_maker";
+        let taker_dir_path = "test_some_comms_relay_taker";
+        let _ = fs::remove_dir_all(maker_dir_path).await;
+        let _ = fs::remove_dir_all(taker_dir_path).await;
+
+        let (maker_comms, relay) = SomeTestParams::some_comms_with_relay(
+            SomeTestParams::maker_private_key(),
+            maker_dir_path,
+            None,
+        )
+        .await;
+        let relay = relay.unwrap();
+        let (taker_comms, _) = SomeTestParams::some_comms_with_relay(
+            SomeTestParams::taker_private_key(),
+            taker_dir_path,
+            Some(&relay),
+        )
+        .await;
+        let maker_comms_accessor = maker_comms.new_accessor();
+        let taker_comms_accessor = taker_comms.new_accessor();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        maker_comms_accessor
+            .send_maker_order_note(order)
+            .await
+            .unwrap();
+        sleep(Duration::from_secs(1)).await;
+
+        let filter_tags = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_comms_accessor
+            .query_orders(filter_tags, QueryOpts::default())
+            .await
+            .unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+        assert_eq!(
+            order_envelopes[0].pubkey,
+            maker_comms_accessor.get_pubkey().await
+        );
+
+        taker_comms_accessor.shutdown().await.unwrap();
+        maker_comms_accessor.shutdown().await.unwrap();
+        relay.shutdown();
+        let _ = fs::remove_dir_all(maker_dir_path).await;
+        let _ = fs::remove_dir_all(taker_dir_path).await;
+    }
+}
@@ -0,0 +1,3 @@
+mod trade_record;
+
+pub use trade_record::*;
@@ -0,0 +1,48 @@
+use secp256k1::XOnlyPublicKey;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    common::{error::N3xbError, types::EventIdString},
+    dispute::DisputeRequest,
+    offer::Offer,
+    order::Order,
+    peer_msg::PeerMessageLogEntry,
+    settlement::SettlementInfo,
+    trade_rsp::TradeResponse,
+};
+
+// Message exchanged with the counterparty or the arbiters, persisted in full for the Trade Record
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TradeRecordEntry<T> {
+    pub outgoing: bool,         // Sent by us, otherwise received
+    pub pubkey: XOnlyPublicKey, // Recipient if outgoing, otherwise sender
+    pub event_id: EventIdString,
+    pub created_at: u64, // Unix seconds
+    pub content: T,
+}
+
+// What was exchanged over the course of a trade, assembled from what the Maker or Taker persisted.
+// For apps to archive for accounting, or to hold up as evidence in a dispute
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub trade_uuid: Uuid,
+    pub maker_pubkey: XOnlyPublicKey,
+    pub taker_pubkey: Option<XOnlyPublicKey>, // None until the Maker accepts an Offer
+    pub order: Order,
+    pub order_event_id: Option<EventIdString>,
+    pub offer: Option<Offer>, // The accepted Offer for the Maker, the Offer sent for the Taker
+    pub offer_event_id: Option<EventIdString>,
+    pub trade_rsp: Option<TradeResponse>,
+    pub trade_rsp_event_id: Option<EventIdString>,
+    pub settlement_infos: Vec<TradeRecordEntry<SettlementInfo>>,
+    pub disputes: Vec<TradeRecordEntry<DisputeRequest>>,
+    pub peer_messages: Vec<PeerMessageLogEntry>, // Trade Engine specific, as many as the log keeps
+    pub trade_completed: bool,
+}
+
+impl TradeRecord {
+    pub fn to_json(&self) -> Result<String, N3xbError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -1,6 +1,9 @@
-use crate::common::{
-    error::{N3xbError, OfferInvalidReason},
-    types::{EventIdString, SerdeGenericTrait, SerdeGenericsPlaceholder},
+use crate::{
+    common::{
+        error::{N3xbError, OfferInvalidReason},
+        types::{EventIdString, SerdeGenericTrait, SerdeGenericsPlaceholder},
+    },
+    order::TradeDetails,
 };
 
 use super::{TradeResponse, TradeResponseStatus};
@@ -9,6 +12,7 @@ pub struct TradeResponseBuilder {
     offer_event_id: Option<EventIdString>,
     trade_response: Option<TradeResponseStatus>,
     reject_reason: Vec<OfferInvalidReason>,
+    agreed_trade_details: Option<TradeDetails>,
     trade_engine_specifics: Option<Box<dyn SerdeGenericTrait>>,
 }
 
@@ -18,6 +22,7 @@ impl TradeResponseBuilder {
             offer_event_id: None,
             trade_response: None,
             reject_reason: [].to_vec(),
+            agreed_trade_details: None,
             trade_engine_specifics: None,
         }
     }
@@ -37,6 +42,11 @@ impl TradeResponseBuilder {
         self
     }
 
+    pub fn agreed_trade_details(&mut self, trade_details: impl Into<TradeDetails>) -> &mut Self {
+        self.agreed_trade_details = Some(trade_details.into());
+        self
+    }
+
     pub fn trade_engine_specifics(
         &mut self,
         trade_engine_specifics: Box<dyn SerdeGenericTrait>,
@@ -73,6 +83,8 @@ impl TradeResponseBuilder {
             offer_event_id: offer_event_id.to_owned(),
             trade_response: trade_response,
             reject_reason: self.reject_reason.to_owned(),
+            agreed_trade_details: self.agreed_trade_details.to_owned(),
+            encryption_pubkey: None,
             trade_engine_specifics: trade_engine_specifics,
         };
 
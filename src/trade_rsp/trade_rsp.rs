@@ -4,7 +4,10 @@ use secp256k1::XOnlyPublicKey;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::common::{error::OfferInvalidReason, types::*};
+use crate::{
+    common::{error::OfferInvalidReason, types::*},
+    order::TradeDetails,
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TradeResponseEnvelope {
@@ -27,6 +30,10 @@ pub struct TradeResponse {
     pub offer_event_id: EventIdString,
     pub trade_response: TradeResponseStatus,
     pub reject_reason: Vec<OfferInvalidReason>,
+    #[serde(default)]
+    pub agreed_trade_details: Option<TradeDetails>, // Binding Trade Details once Accepted. Filled from the Order by the Maker if not set
+    #[serde(default)]
+    pub encryption_pubkey: Option<XOnlyPublicKey>, // Per-trade peer message key. Filled by the Maker if the Offer has one
     pub trade_engine_specifics: Box<dyn SerdeGenericTrait>,
 }
 
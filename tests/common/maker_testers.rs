@@ -56,7 +56,7 @@ impl MakerTesterActor {
     async fn run(self) {
         // Create and setup a Maker for a new Order
         let order = self.order.clone();
-        let maker = self.manager.new_maker(order).await;
+        let maker = self.manager.new_maker(order).await.unwrap();
 
         // Register Maker notificaitons
         let (notif_tx, mut notif_rx) =
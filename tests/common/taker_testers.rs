@@ -4,7 +4,7 @@ use crusty_n3xb::{
     order::{FilterTag, OrderEnvelope},
     taker::TakerNotif,
     testing::{
-        SomeTestOfferParams, SomeTestOrderParams, SomeTestTradeRspParams,
+        SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
         TESTING_DEFAULT_CHANNEL_SIZE,
     },
     trade_rsp::TradeResponseStatus,
@@ -118,7 +118,7 @@ impl TakerTesterActor {
         };
 
         let mut expected_trade_rsp_builder = SomeTestTradeRspParams::default_builder();
-        expected_trade_rsp_builder.offer_event_id("".to_string());
+        expected_trade_rsp_builder.offer_event_id(SomeTestParams::some_event_id(0));
 
         let expected_trade_rsp = expected_trade_rsp_builder.build().unwrap();
         SomeTestTradeRspParams::check(&trade_rsp_envelope.trade_rsp, &expected_trade_rsp);
@@ -0,0 +1,119 @@
+mod common;
+
+#[cfg(test)]
+mod test_agreed_trade_details {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_agreed_trade_details";
+
+    #[tokio::test]
+    async fn test_agreed_trade_details_default_to_order() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        // Nothing agreed on before the Trade Response
+        assert!(taker.query_agreed_trade_details().await.unwrap().is_none());
+
+        let offer_envelope = match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+
+        // Accepting without specifying Trade Details binds the Order's own
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::TradeRsp(_) => {}
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        }
+
+        let agreed_trade_details = taker.query_agreed_trade_details().await.unwrap().unwrap();
+        assert_eq!(
+            agreed_trade_details.content,
+            SomeTestOrderParams::trade_details_content()
+        );
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,128 @@
+mod common;
+
+#[cfg(test)]
+mod test_block_taker {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{
+            error::{N3xbError, OfferInvalidReason},
+            types::BitcoinNetwork,
+        },
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+        trade_rsp::TradeResponseStatus,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_block_taker";
+
+    #[tokio::test]
+    async fn test_block_taker_rejects_pending_offer() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        // Maker posts an Order
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Taker takes the Order
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        let notif_result = maker_notif_rx.recv().await.unwrap();
+        match notif_result.unwrap() {
+            MakerNotif::Offer(_) => {}
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+
+        // Maker blocks the Taker, rejecting the pending Offer
+        let rejected_count = maker
+            .block_taker(taker_manager.pubkey().await)
+            .await
+            .unwrap();
+        assert_eq!(rejected_count, 1);
+        assert!(maker.query_offers().await.is_empty());
+
+        let notif_result = taker_notif_rx.recv().await.unwrap();
+        let trade_rsp_envelope = match notif_result.unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+        assert_eq!(
+            trade_rsp_envelope.trade_rsp.trade_response,
+            TradeResponseStatus::Rejected
+        );
+        assert!(trade_rsp_envelope
+            .trade_rsp
+            .reject_reason
+            .contains(&OfferInvalidReason::Blocked));
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+#[cfg(test)]
+mod test_connect_on_start {
+    use std::{net::SocketAddr, str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork, manager::Manager, testing::SomeTestParams, CommsOptions,
+        RelayStatus,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_connect_on_start";
+
+    #[tokio::test]
+    async fn test_connect_on_start_lazy_and_eager() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!("Failed to remove {} directory: {}", ROOT_DIR_PATH_STR, error);
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+        let relay_addrs: Vec<(Url, Option<SocketAddr>)> = vec![(relay_url.clone(), None)];
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+        let test_private_key = SomeTestParams::maker_private_key();
+
+        // Add Relays so they get persisted
+        {
+            let manager = Manager::new_with_key(
+                test_private_key,
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            manager.add_relays(relay_addrs, true).await.unwrap();
+            manager.shutdown().await.unwrap();
+        }
+
+        // Lazy - restored Relays are added but left disconnected
+        {
+            let comms_opts = CommsOptions {
+                connect_on_start: false,
+                ..Default::default()
+            };
+            let manager = Manager::new_with_key_and_opts(
+                test_private_key,
+                comms_opts,
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            sleep(Duration::from_secs(1)).await;
+
+            let relays = manager.get_relays().await;
+            print!("Lazy Relays: {:?}\n", relays);
+            assert_eq!(relays.len(), 1);
+            assert_eq!(relays[0].url, relay_url);
+            assert_ne!(relays[0].status, RelayStatus::Connected);
+            assert!(!manager.is_connected().await);
+            manager.shutdown().await.unwrap();
+        }
+
+        // Eager - restored Relays are connected on construction
+        {
+            let comms_opts = CommsOptions {
+                connect_on_start: true,
+                ..Default::default()
+            };
+            let manager = Manager::new_with_key_and_opts(
+                test_private_key,
+                comms_opts,
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            sleep(Duration::from_secs(1)).await;
+
+            let relays = manager.get_relays().await;
+            print!("Eager Relays: {:?}\n", relays);
+            assert_eq!(relays.len(), 1);
+            assert_eq!(relays[0].url, relay_url);
+            assert_eq!(relays[0].status, RelayStatus::Connected);
+            assert!(manager.is_connected().await);
+            manager.shutdown().await.unwrap();
+        }
+
+        relay.shutdown().unwrap();
+    }
+}
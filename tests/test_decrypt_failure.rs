@@ -0,0 +1,141 @@
+mod common;
+
+#[cfg(test)]
+mod test_decrypt_failure {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use nostr_sdk::prelude::{Client, EventBuilder, Keys, Kind, Tag, TagKind};
+    use tokio::{
+        fs,
+        sync::mpsc,
+        time::{sleep, timeout},
+    };
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_decrypt_failure";
+
+    async fn send_undecryptable_dm(keys: &Keys, relay_url: &Url, receiver: &str) {
+        let client = Client::new(keys);
+        client
+            .add_relays(vec![(relay_url.to_string(), None)])
+            .await
+            .unwrap();
+        client.connect().await;
+
+        let tags = vec![Tag::Generic(TagKind::P, vec![receiver.to_string()])];
+        let event = EventBuilder::new(Kind::EncryptedDirectMessage, "not-encrypted", &tags)
+            .to_event(keys)
+            .unwrap();
+        client.send_event(event).await.unwrap();
+        client.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_counterparty_decrypt_failure_notified() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        taker.take_order().await.unwrap();
+
+        let offer_envelope = match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        let maker_pubkey = maker_manager.pubkey().await.to_string();
+
+        // Undecryptable DM from a stranger is only spam
+        let stranger_keys = Keys::generate();
+        send_undecryptable_dm(&stranger_keys, &relay_url, &maker_pubkey).await;
+        assert!(timeout(Duration::from_secs(2), maker_notif_rx.recv())
+            .await
+            .is_err());
+
+        // Undecryptable DM from the accepted Taker is surfaced to the Maker
+        let taker_keys = Keys::new(SomeTestParams::taker_private_key());
+        send_undecryptable_dm(&taker_keys, &relay_url, &maker_pubkey).await;
+        let notif_result = timeout(Duration::from_secs(5), maker_notif_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match notif_result {
+            Err(N3xbError::CounterpartyDecryptFailed(_, _)) => {}
+            _ => panic!("Maker only expects counterparty decrypt failure at this point"),
+        }
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
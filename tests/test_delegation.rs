@@ -0,0 +1,143 @@
+mod common;
+
+#[cfg(test)]
+mod test_delegation {
+    use std::{
+        str::FromStr,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+    use tracing::error;
+
+    use nostr_sdk::nostr::nips::nip26::{Conditions, DelegationTag};
+    use nostr_sdk::prelude::Keys;
+    use secp256k1::{Secp256k1, XOnlyPublicKey};
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::FilterTag,
+        testing::{SomeTestOrderParams, SomeTestParams},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_delegation";
+
+    fn delegation_tag_json(
+        delegator_keys: &Keys,
+        delegatee_pubkey: XOnlyPublicKey,
+        kind: u64,
+    ) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let conditions = Conditions::from_str(&format!(
+            "kind={}&created_at>{}&created_at<{}",
+            kind,
+            now - 60,
+            now + 3600
+        ))
+        .unwrap();
+        DelegationTag::new(delegator_keys, delegatee_pubkey, conditions)
+            .unwrap()
+            .as_json()
+    }
+
+    #[tokio::test]
+    async fn test_order_note_carries_delegator() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let secp = Secp256k1::new();
+        let maker_pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&secp)
+            .0;
+        let taker_pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&secp)
+            .0;
+        let root_keys = Keys::generate();
+
+        // Delegations for another pubkey, or not covering Maker Order Notes, are refused
+        let other_delegatee = delegation_tag_json(&root_keys, taker_pubkey, 61021);
+        assert!(maker_manager
+            .set_delegation_tag(Some(other_delegatee))
+            .await
+            .is_err());
+        let other_kind = delegation_tag_json(&root_keys, maker_pubkey, 1);
+        assert!(maker_manager
+            .set_delegation_tag(Some(other_kind))
+            .await
+            .is_err());
+        assert!(maker_manager
+            .set_delegation_tag(Some("not-a-delegation-tag".to_string()))
+            .await
+            .is_err());
+
+        let delegation_tag = delegation_tag_json(&root_keys, maker_pubkey, 61021);
+        maker_manager
+            .set_delegation_tag(Some(delegation_tag))
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Signed by the Maker, on behalf of the root identity
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+        let order_envelope = order_envelopes.first().unwrap();
+        assert_eq!(order_envelope.pubkey, maker_pubkey);
+        assert_eq!(
+            order_envelope.delegator_pubkey,
+            Some(root_keys.public_key())
+        );
+        order_envelope.verify_signature().unwrap();
+
+        maker_manager.set_delegation_tag(None).await.unwrap();
+
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod test_disk_usage {
+    use std::time::Duration;
+
+    use tokio::time::sleep;
+
+    use crusty_n3xb::common::types::BitcoinNetwork;
+    use crusty_n3xb::manager::Manager;
+    use crusty_n3xb::testing::*;
+
+    #[tokio::test]
+    async fn test_disk_usage_reports_maker_bytes() {
+        let test_engine_name = String::from("test-engine-disk-usage");
+        let manager = Manager::new(&test_engine_name, BitcoinNetwork::Regtest, "").await;
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let trade_uuid = order.trade_uuid;
+        manager.new_maker(order).await.unwrap();
+
+        // Give the Persister a moment to write the Maker data out
+        sleep(Duration::from_secs(1)).await;
+
+        let disk_usage = manager.disk_usage().await.unwrap();
+        let maker_bytes = disk_usage.maker_bytes.get(&trade_uuid).unwrap().to_owned();
+        print!("Disk Usage: {:?}\n", disk_usage);
+
+        assert!(maker_bytes > 0);
+        assert!(disk_usage.taker_bytes.is_empty());
+        assert!(disk_usage.total_bytes >= maker_bytes);
+
+        manager.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod test_duplicate_uuid {
+    use crusty_n3xb::common::error::N3xbError;
+    use crusty_n3xb::common::types::BitcoinNetwork;
+    use crusty_n3xb::manager::Manager;
+    use crusty_n3xb::testing::*;
+
+    #[tokio::test]
+    async fn test_new_maker_duplicate_trade_uuid() {
+        let test_engine_name = String::from("test-engine-dup-uuid");
+        let manager = Manager::new(&test_engine_name, BitcoinNetwork::Regtest, "").await;
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let trade_uuid = order.trade_uuid;
+
+        manager.new_maker(order.clone()).await.unwrap();
+        let result = manager.new_maker(order).await;
+
+        match result {
+            Err(N3xbError::DuplicateTradeUuid(uuid)) => assert_eq!(uuid, trade_uuid),
+            _ => panic!("Expected DuplicateTradeUuid error for second Maker"),
+        }
+
+        manager.shutdown().await.unwrap();
+    }
+}
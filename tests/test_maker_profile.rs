@@ -0,0 +1,98 @@
+mod common;
+
+#[cfg(test)]
+mod test_maker_profile {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use nostr_sdk::prelude::{Client, EventBuilder, Keys, Kind};
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork, manager::Manager, testing::SomeTestParams, Metadata,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_maker_profile";
+
+    #[tokio::test]
+    async fn test_get_maker_profile() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Maker publishes its profile Metadata through a plain Nostr client
+        let maker_keys = Keys::new(SomeTestParams::maker_private_key());
+        let maker_client = Client::new(&maker_keys);
+        maker_client
+            .add_relays(vec![(relay_url.to_string(), None)])
+            .await
+            .unwrap();
+        maker_client.connect().await;
+
+        let metadata = Metadata::new().name("maker").nip05("maker@n3xb.invalid");
+        let event = EventBuilder::new(
+            Kind::Metadata,
+            serde_json::to_string(&metadata).unwrap(),
+            &[],
+        )
+        .to_event(&maker_keys)
+        .unwrap();
+        maker_client.send_event(event).await.unwrap();
+
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let maker_pubkey = maker_keys.public_key();
+        let profile = taker_manager
+            .get_maker_profile(maker_pubkey, false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.name, Some("maker".to_string()));
+        assert_eq!(profile.nip05, Some("maker@n3xb.invalid".to_string()));
+
+        // NIP-05 identifier that can't be verified is left out
+        let profile = taker_manager
+            .get_maker_profile(maker_pubkey, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.name, Some("maker".to_string()));
+        assert!(profile.nip05.is_none());
+
+        // Pubkey without any profile Metadata
+        let taker_pubkey = taker_manager.pubkey().await;
+        assert!(taker_manager
+            .get_maker_profile(taker_pubkey, true)
+            .await
+            .unwrap()
+            .is_none());
+
+        maker_client.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+#[cfg(test)]
+mod test_max_relays {
+    use std::str::FromStr;
+    use tracing::error;
+
+    use tokio::fs;
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        manager::Manager,
+        testing::SomeTestParams,
+        CommsOptions,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_max_relays";
+
+    #[tokio::test]
+    async fn test_add_relays_over_max_relays() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let comms_opts = CommsOptions {
+            max_relays: 2,
+            ..Default::default()
+        };
+        let manager = Manager::new_with_key_and_opts(
+            SomeTestParams::maker_private_key(),
+            comms_opts,
+            &SomeTestParams::engine_name_str(),
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        // Two more Relays would make three, over the maximum of two
+        let more_relay_addrs = vec![
+            (Url::from_str("ws://localhost:1").unwrap(), None),
+            (Url::from_str("ws://localhost:2").unwrap(), None),
+        ];
+        let result = manager.add_relays(more_relay_addrs, false).await;
+        assert!(matches!(result, Err(N3xbError::TooManyRelays(2))));
+        assert_eq!(manager.get_relays().await.len(), 1);
+
+        // Relays already added don't count again
+        let relay_addrs = vec![
+            (relay_url.clone(), None),
+            (Url::from_str("ws://localhost:1").unwrap(), None),
+        ];
+        manager.add_relays(relay_addrs, false).await.unwrap();
+        assert_eq!(manager.get_relays().await.len(), 2);
+
+        manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
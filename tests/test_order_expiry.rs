@@ -0,0 +1,112 @@
+mod common;
+
+#[cfg(test)]
+mod test_order_expiry {
+    use std::{
+        str::FromStr,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::timeout};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::{MakerNotif, MakerOpts, MakerTradeStatus},
+        manager::Manager,
+        testing::{SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_order_expiry";
+
+    #[tokio::test]
+    async fn test_order_deleted_on_expiry_after_restore() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Maker posts an Order expiring shortly, then goes offline before it expires
+        {
+            let maker_manager = Manager::new_with_key(
+                SomeTestParams::maker_private_key(),
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            maker_manager
+                .add_relays(vec![(relay_url.clone(), None)], true)
+                .await
+                .unwrap();
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            let order = SomeTestOrderParams::default_buy_builder()
+                .expiration(now + 5)
+                .build()
+                .unwrap();
+            let opts = MakerOpts {
+                delete_on_expiry: true,
+                ..Default::default()
+            };
+            let maker = maker_manager
+                .new_maker_with_opts(order, opts)
+                .await
+                .unwrap();
+            maker.post_new_order().await.unwrap();
+            assert_eq!(maker.status().await, MakerTradeStatus::Posted);
+
+            maker.shutdown().await.unwrap();
+            maker_manager.shutdown().await.unwrap();
+        }
+
+        // Restored Maker still takes the Order down once it expires
+        {
+            let maker_manager = Manager::new_with_key(
+                SomeTestParams::maker_private_key(),
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+
+            let makers = maker_manager.get_makers().await;
+            let maker = makers.get(&SomeTestOrderParams::some_uuid()).unwrap();
+
+            let (maker_notif_tx, mut maker_notif_rx) =
+                mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+            maker.register_notif_tx(maker_notif_tx).await.unwrap();
+            maker_manager.connect_all_relays().await.unwrap();
+
+            let notif_result = timeout(Duration::from_secs(10), maker_notif_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            match notif_result.unwrap() {
+                MakerNotif::OrderExpired => {}
+                _ => panic!("Maker only expects Order Expired notification at this point"),
+            }
+            assert_eq!(maker.status().await, MakerTradeStatus::Cancelled);
+            assert!(maker.cancel_order().await.is_err());
+
+            maker.shutdown().await.unwrap();
+            maker_manager.shutdown().await.unwrap();
+        }
+
+        relay.shutdown().unwrap();
+    }
+}
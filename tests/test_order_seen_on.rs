@@ -0,0 +1,94 @@
+mod common;
+
+#[cfg(test)]
+mod test_order_seen_on {
+    use std::{collections::HashSet, str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::timeout};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::{MakerNotif, MakerOpts},
+        manager::Manager,
+        testing::{SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_order_seen_on";
+
+    #[tokio::test]
+    async fn test_order_seen_on_each_relay() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay1: Relay = Relay::start();
+        relay1.wait_for_healthy_relay().await.unwrap();
+        let relay1_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay1.port)).unwrap();
+
+        let relay2: Relay = Relay::start();
+        relay2.wait_for_healthy_relay().await.unwrap();
+        let relay2_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay2.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(
+                vec![(relay1_url.clone(), None), (relay2_url.clone(), None)],
+                true,
+            )
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let opts = MakerOpts {
+            notify_order_seen_on: true,
+            ..Default::default()
+        };
+        let maker = maker_manager
+            .new_maker_with_opts(order, opts)
+            .await
+            .unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        // Each relay is reported once, as it shows the Order Note back
+        let mut seen_on: HashSet<Url> = HashSet::new();
+        while seen_on.len() < 2 {
+            let notif_result = timeout(Duration::from_secs(5), maker_notif_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            match notif_result.unwrap() {
+                MakerNotif::OrderSeenOn(url) => {
+                    assert!(seen_on.insert(url));
+                }
+                _ => panic!("Maker only expects Order Seen On notification at this point"),
+            }
+        }
+        assert_eq!(seen_on, HashSet::from([relay1_url, relay2_url]));
+        assert!(timeout(Duration::from_secs(1), maker_notif_rx.recv())
+            .await
+            .is_err());
+
+        maker.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay2.shutdown().unwrap();
+        relay1.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+mod common;
+
+#[cfg(test)]
+mod test_pause_resume {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{
+        fs,
+        sync::mpsc,
+        time::{sleep, timeout},
+    };
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_pause_resume";
+
+    #[tokio::test]
+    async fn test_peer_messages_held_while_paused() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        // Only a paused Maker can resume, and only once
+        assert!(maker.resume().await.is_err());
+        maker.pause().await.unwrap();
+        assert!(maker.pause().await.is_err());
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        // Offer held until the Maker resumes
+        assert!(timeout(Duration::from_secs(2), maker_notif_rx.recv())
+            .await
+            .is_err());
+        maker.resume().await.unwrap();
+
+        let offer_envelope = match timeout(Duration::from_secs(5), maker_notif_rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+        {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+
+        // Same for the Taker, with the Trade Response
+        taker.pause().await.unwrap();
+
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        assert!(timeout(Duration::from_secs(2), taker_notif_rx.recv())
+            .await
+            .is_err());
+        taker.resume().await.unwrap();
+        assert!(taker.resume().await.is_err());
+
+        match timeout(Duration::from_secs(5), taker_notif_rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+        {
+            TakerNotif::TradeRsp(_) => {}
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        }
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+#[cfg(test)]
+mod test_peer_allowlist {
+    use std::{collections::HashSet, str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{
+        fs,
+        sync::mpsc,
+        time::{sleep, timeout},
+    };
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_peer_allowlist";
+
+    #[tokio::test]
+    async fn test_offer_outside_allowlist_dropped() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let outsider_taker_manager = Manager::new(
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+
+        for manager in [&maker_manager, &outsider_taker_manager, &taker_manager] {
+            manager
+                .add_relays(vec![(relay_url.clone(), None)], true)
+                .await
+                .unwrap();
+        }
+
+        // Maker only trades within a private group of the one Taker
+        let allowlist = HashSet::from([taker_manager.pubkey().await]);
+        maker_manager
+            .set_peer_allowlist(Some(allowlist))
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        // Offer from outside the allowlist never reaches the Maker
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let outsider_taker = outsider_taker_manager
+            .new_taker(order_envelope.clone(), offer)
+            .await
+            .unwrap();
+        outsider_taker.take_order().await.unwrap();
+        assert!(timeout(Duration::from_secs(2), maker_notif_rx.recv())
+            .await
+            .is_err());
+
+        // Offer from within the allowlist is routed to the Maker
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        taker.take_order().await.unwrap();
+        let notif_result = timeout(Duration::from_secs(5), maker_notif_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match notif_result.unwrap() {
+            MakerNotif::Offer(offer_envelope) => {
+                assert_eq!(offer_envelope.pubkey, taker_manager.pubkey().await);
+            }
+            _ => panic!("Maker only expects Offer notification at this point"),
+        }
+
+        taker.shutdown().await.unwrap();
+        outsider_taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        outsider_taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
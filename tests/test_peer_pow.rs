@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod test_peer_pow {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{
+        fs,
+        sync::mpsc,
+        time::{sleep, timeout},
+    };
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+        CommsOptions,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_peer_pow";
+
+    #[tokio::test]
+    async fn test_low_pow_peer_message_dropped() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Maker requires 16 bits of PoW on peer messages, which a DM without any has 1/65536 odds of
+        let maker_comms_opts = CommsOptions {
+            min_peer_pow: 16,
+            ..Default::default()
+        };
+        let maker_manager = Manager::new_with_key_and_opts(
+            SomeTestParams::maker_private_key(),
+            maker_comms_opts,
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let low_pow_taker_manager = Manager::new(
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_comms_opts = CommsOptions {
+            peer_pow: 16,
+            ..Default::default()
+        };
+        let taker_manager = Manager::new_with_key_and_opts(
+            SomeTestParams::taker_private_key(),
+            taker_comms_opts,
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+
+        for manager in [&maker_manager, &low_pow_taker_manager, &taker_manager] {
+            manager
+                .add_relays(vec![(relay_url.clone(), None)], true)
+                .await
+                .unwrap();
+        }
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        // Offer sent without PoW never reaches the Maker
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let low_pow_taker = low_pow_taker_manager
+            .new_taker(order_envelope.clone(), offer)
+            .await
+            .unwrap();
+        low_pow_taker.take_order().await.unwrap();
+        assert!(timeout(Duration::from_secs(2), maker_notif_rx.recv())
+            .await
+            .is_err());
+
+        // Offer sent with enough PoW is routed to the Maker
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        taker.take_order().await.unwrap();
+        let notif_result = timeout(Duration::from_secs(5), maker_notif_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match notif_result.unwrap() {
+            MakerNotif::Offer(offer_envelope) => {
+                assert_eq!(offer_envelope.pubkey, taker_manager.pubkey().await);
+            }
+            _ => panic!("Maker only expects Offer notification at this point"),
+        }
+
+        taker.shutdown().await.unwrap();
+        low_pow_taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        low_pow_taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+mod common;
+
+#[cfg(test)]
+mod test_query_paged {
+    use std::{collections::HashSet, str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+    use uuid::Uuid;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        manager::Manager,
+        order::OrderEnvelope,
+        testing::{SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_query_paged";
+
+    #[tokio::test]
+    async fn test_query_orders_paged_across_relays() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay1: Relay = Relay::start();
+        relay1.wait_for_healthy_relay().await.unwrap();
+        let relay1_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay1.port)).unwrap();
+
+        let relay2: Relay = Relay::start();
+        relay2.wait_for_healthy_relay().await.unwrap();
+        let relay2_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay2.port)).unwrap();
+
+        let relay_addrs = vec![(relay1_url.clone(), None), (relay2_url.clone(), None)];
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(relay_addrs.clone(), true)
+            .await
+            .unwrap();
+        taker_manager.add_relays(relay_addrs, true).await.unwrap();
+
+        // Maker posts Orders to both Relays, a second apart so each gets its own created_at
+        let mut makers = Vec::new();
+        let mut trade_uuids = HashSet::new();
+        for _ in 0..3 {
+            let trade_uuid = Uuid::new_v4();
+            let order = SomeTestOrderParams::default_buy_builder()
+                .trade_uuid(trade_uuid)
+                .build()
+                .unwrap();
+            let maker = maker_manager.new_maker(order).await.unwrap();
+            maker.post_new_order().await.unwrap();
+            makers.push(maker);
+            trade_uuids.insert(trade_uuid);
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        assert!(taker_manager
+            .query_orders_paged(Vec::new(), 0, mpsc::channel(1).0)
+            .await
+            .is_err());
+
+        // Pages of 2 walk back through the 3 Orders, each Order only once across both Relays
+        let (page_tx, mut page_rx) =
+            mpsc::channel::<Result<Vec<OrderEnvelope>, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker_manager
+            .query_orders_paged(Vec::new(), 2, page_tx)
+            .await
+            .unwrap();
+
+        let mut queried_trade_uuids = Vec::new();
+        while let Some(page_result) = page_rx.recv().await {
+            let order_envelopes = page_result.unwrap();
+            assert!(order_envelopes.len() <= 2);
+            for order_envelope in order_envelopes {
+                queried_trade_uuids.push(order_envelope.order.trade_uuid);
+            }
+        }
+        assert_eq!(queried_trade_uuids.len(), 3);
+        assert_eq!(
+            queried_trade_uuids.into_iter().collect::<HashSet<Uuid>>(),
+            trade_uuids
+        );
+
+        for maker in makers {
+            maker.shutdown().await.unwrap();
+        }
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay1.shutdown().unwrap();
+        relay2.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+mod common;
+
+#[cfg(test)]
+mod test_query_relays {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::FilterTag,
+        testing::{SomeTestOrderParams, SomeTestParams},
+        QueryOpts,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_query_relays";
+
+    #[tokio::test]
+    async fn test_query_orders_from_specific_relays() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!("Failed to remove {} directory: {}", ROOT_DIR_PATH_STR, error);
+        }
+
+        let relay_posted: Relay = Relay::start();
+        relay_posted.wait_for_healthy_relay().await.unwrap();
+        let relay_posted_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_posted.port)).unwrap();
+
+        let relay_other: Relay = Relay::start();
+        relay_other.wait_for_healthy_relay().await.unwrap();
+        let relay_other_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_other.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Maker posts an Order to only one of the relays
+        {
+            let maker_manager = Manager::new_with_key(
+                SomeTestParams::maker_private_key(),
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            maker_manager
+                .add_relays(vec![(relay_posted_url.clone(), None)], true)
+                .await
+                .unwrap();
+
+            let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+            let maker = maker_manager.new_maker(order).await.unwrap();
+            maker.post_new_order().await.unwrap();
+            maker.shutdown().await.unwrap();
+            maker_manager.shutdown().await.unwrap();
+        }
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Taker knows both relays, but only queries the ones asked for
+        let taker_manager = Manager::new(
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        taker_manager
+            .add_relays(
+                vec![
+                    (relay_posted_url.clone(), None),
+                    (relay_other_url.clone(), None),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+
+        let query_opts = QueryOpts {
+            relays: Some(vec![relay_other_url.clone()]),
+            ..Default::default()
+        };
+        let order_envelopes = taker_manager
+            .query_orders_with_opts(query_filter.clone(), query_opts)
+            .await
+            .unwrap();
+        assert!(order_envelopes.is_empty());
+
+        let query_opts = QueryOpts {
+            relays: Some(vec![relay_posted_url.clone()]),
+            ..Default::default()
+        };
+        let order_envelopes = taker_manager
+            .query_orders_with_opts(query_filter.clone(), query_opts)
+            .await
+            .unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        // Relays not added to the Manager cannot be queried
+        let query_opts = QueryOpts {
+            relays: Some(vec![Url::from_str("ws://localhost:1").unwrap()]),
+            ..Default::default()
+        };
+        assert!(taker_manager
+            .query_orders_with_opts(query_filter, query_opts)
+            .await
+            .is_err());
+
+        taker_manager.shutdown().await.unwrap();
+        relay_posted.shutdown().unwrap();
+        relay_other.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+#[cfg(test)]
+mod test_query_window {
+    use std::{
+        str::FromStr,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+    use tracing::error;
+
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::FilterTag,
+        testing::{SomeTestOrderParams, SomeTestParams},
+        QueryOpts,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_query_window";
+
+    #[tokio::test]
+    async fn test_query_orders_created_within() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        // Let the Order age past the window
+        sleep(Duration::from_secs(3)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let query_opts = QueryOpts {
+            created_within: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let order_envelopes = taker_manager
+            .query_orders_with_opts(query_filter.clone(), query_opts)
+            .await
+            .unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        let query_opts = QueryOpts {
+            created_within: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let order_envelopes = taker_manager
+            .query_orders_with_opts(query_filter.clone(), query_opts)
+            .await
+            .unwrap();
+        assert!(order_envelopes.is_empty());
+
+        // An explicit since later than the window start is the one that applies
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let query_opts = QueryOpts {
+            since: Some(now),
+            created_within: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let order_envelopes = taker_manager
+            .query_orders_with_opts(query_filter, query_opts)
+            .await
+            .unwrap();
+        assert!(order_envelopes.is_empty());
+
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+mod common;
+
+#[cfg(test)]
+mod test_reconsider_offer {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{
+            error::{N3xbError, OfferInvalidReason},
+            types::BitcoinNetwork,
+        },
+        maker::{MakerNotif, OfferStatus},
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+        trade_rsp::TradeResponseStatus,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_reconsider_offer";
+
+    #[tokio::test]
+    async fn test_reconsider_rejected_offer() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        // Maker posts an Order
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Taker takes the Order
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        let notif_result = maker_notif_rx.recv().await.unwrap();
+        let offer_event_id = match notif_result.unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope.event_id,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        assert_eq!(
+            maker
+                .query_offers_by_status(OfferStatus::Pending)
+                .await
+                .len(),
+            1
+        );
+
+        // Maker rejects the Offer
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder
+            .offer_event_id(offer_event_id.clone())
+            .trade_response(TradeResponseStatus::Rejected)
+            .reject_reason(OfferInvalidReason::PendingAnother);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.reject_offer(trade_rsp).await.unwrap();
+        assert!(maker.query_offers().await.is_empty());
+        assert!(maker
+            .query_offers_by_status(OfferStatus::Pending)
+            .await
+            .is_empty());
+        let rejected_offers = maker
+            .query_offers_by_status(OfferStatus::Rejected(OfferInvalidReason::PendingAnother))
+            .await;
+        assert_eq!(rejected_offers.len(), 1);
+        assert_eq!(rejected_offers.first().unwrap().event_id, offer_event_id);
+
+        let notif_result = taker_notif_rx.recv().await.unwrap();
+        let trade_rsp_envelope = match notif_result.unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+        assert_eq!(
+            trade_rsp_envelope.trade_rsp.trade_response,
+            TradeResponseStatus::Rejected
+        );
+
+        // Maker changes their mind and accepts the Offer after all
+        maker
+            .reconsider_offer(offer_event_id.clone())
+            .await
+            .unwrap();
+        assert!(maker.query_offer(offer_event_id.clone()).await.is_some());
+        assert!(maker
+            .reconsider_offer(offer_event_id.clone())
+            .await
+            .is_err());
+
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+        assert_eq!(
+            maker
+                .query_offers_by_status(OfferStatus::Accepted)
+                .await
+                .len(),
+            1
+        );
+        assert!(maker
+            .query_offers_by_status(OfferStatus::Rejected(OfferInvalidReason::PendingAnother))
+            .await
+            .is_empty());
+
+        let notif_result = taker_notif_rx.recv().await.unwrap();
+        let trade_rsp_envelope = match notif_result.unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+        assert_eq!(
+            trade_rsp_envelope.trade_rsp.trade_response,
+            TradeResponseStatus::Accepted
+        );
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
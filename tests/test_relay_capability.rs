@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod test_relay_capability {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::FilterTag,
+        testing::{SomeTestOrderParams, SomeTestParams},
+        RelayCapability,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_relay_capability";
+
+    #[tokio::test]
+    async fn test_read_only_relay_not_sent_to() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!("Failed to remove {} directory: {}", ROOT_DIR_PATH_STR, error);
+        }
+
+        let relay_rw: Relay = Relay::start();
+        relay_rw.wait_for_healthy_relay().await.unwrap();
+        let relay_rw_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_rw.port)).unwrap();
+
+        let relay_ro: Relay = Relay::start();
+        relay_ro.wait_for_healthy_relay().await.unwrap();
+        let relay_ro_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_ro.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+        let test_maker_private_key = SomeTestParams::maker_private_key();
+
+        // Maker posts an Order with one read-write and one read-only relay
+        {
+            let maker_manager = Manager::new_with_key(
+                test_maker_private_key,
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+            maker_manager
+                .add_relays_with_capability(
+                    vec![
+                        (relay_rw_url.clone(), None, RelayCapability::ReadWrite),
+                        (relay_ro_url.clone(), None, RelayCapability::ReadOnly),
+                    ],
+                    true,
+                )
+                .await
+                .unwrap();
+
+            let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+            let maker = maker_manager.new_maker(order).await.unwrap();
+            maker.post_new_order().await.unwrap();
+            maker.shutdown().await.unwrap();
+            maker_manager.shutdown().await.unwrap();
+        }
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+
+        // Taker on the read-write relay sees the Order
+        {
+            let taker_manager =
+                Manager::new(&test_engine_name, BitcoinNetwork::Regtest, ROOT_DIR_PATH_STR).await;
+            taker_manager
+                .add_relays(vec![(relay_rw_url.clone(), None)], true)
+                .await
+                .unwrap();
+
+            let order_envelopes = taker_manager
+                .query_orders(query_filter.clone())
+                .await
+                .unwrap();
+            assert_eq!(order_envelopes.len(), 1);
+            taker_manager.shutdown().await.unwrap();
+        }
+
+        // Taker on the read-only relay does not
+        {
+            let taker_manager =
+                Manager::new(&test_engine_name, BitcoinNetwork::Regtest, ROOT_DIR_PATH_STR).await;
+            taker_manager
+                .add_relays(vec![(relay_ro_url.clone(), None)], true)
+                .await
+                .unwrap();
+
+            let order_envelopes = taker_manager
+                .query_orders(query_filter.clone())
+                .await
+                .unwrap();
+            assert!(order_envelopes.is_empty());
+            taker_manager.shutdown().await.unwrap();
+        }
+
+        // Capabilities are restored along with the relays
+        {
+            let maker_manager = Manager::new_with_key(
+                test_maker_private_key,
+                &test_engine_name,
+                BitcoinNetwork::Regtest,
+                ROOT_DIR_PATH_STR,
+            )
+            .await;
+
+            let relays_info = maker_manager.get_relays().await;
+            print!("Restored Relays: {:?}\n", relays_info);
+            assert_eq!(relays_info.len(), 2);
+            for relay_info in relays_info {
+                if relay_info.url == relay_rw_url {
+                    assert_eq!(relay_info.capability, RelayCapability::ReadWrite);
+                } else {
+                    assert_eq!(relay_info.url, relay_ro_url);
+                    assert_eq!(relay_info.capability, RelayCapability::ReadOnly);
+                }
+            }
+            maker_manager.shutdown().await.unwrap();
+        }
+
+        relay_rw.shutdown().unwrap();
+        relay_ro.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+mod common;
+
+#[cfg(test)]
+mod test_relay_config {
+    use std::str::FromStr;
+    use tracing::error;
+
+    use tokio::fs;
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork, manager::Manager, testing::SomeTestParams, RelayCapability,
+        RelayConfig,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_relay_config";
+
+    #[tokio::test]
+    async fn test_relay_config_export_import() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay_rw: Relay = Relay::start();
+        relay_rw.wait_for_healthy_relay().await.unwrap();
+        let relay_rw_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_rw.port)).unwrap();
+
+        let relay_ro: Relay = Relay::start();
+        relay_ro.wait_for_healthy_relay().await.unwrap();
+        let relay_ro_url =
+            Url::from_str(&format!("{}:{}", "ws://localhost", relay_ro.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Relay setup exported from one device as JSON
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays_with_capability(
+                vec![
+                    (relay_rw_url.clone(), None, RelayCapability::ReadWrite),
+                    (relay_ro_url.clone(), None, RelayCapability::ReadOnly),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+        let relay_config = maker_manager.export_relay_config().await;
+        assert_eq!(relay_config.relays.len(), 2);
+        let json = relay_config.to_json().unwrap();
+
+        // And imported on another
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let imported_config = RelayConfig::from_json(json).unwrap();
+        assert_eq!(imported_config, relay_config);
+        taker_manager
+            .import_relay_config(imported_config)
+            .await
+            .unwrap();
+        assert_eq!(taker_manager.export_relay_config().await, relay_config);
+
+        let relays = taker_manager.get_relays().await;
+        assert_eq!(relays.len(), 2);
+        for relay_info in relays {
+            let expected_capability = if relay_info.url == relay_ro_url {
+                RelayCapability::ReadOnly
+            } else {
+                RelayCapability::ReadWrite
+            };
+            assert_eq!(relay_info.capability, expected_capability);
+        }
+
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay_ro.shutdown().unwrap();
+        relay_rw.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod test_relay_nips {
+    use std::str::FromStr;
+    use tracing::error;
+
+    use tokio::fs;
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork, manager::Manager, testing::SomeTestParams, AddRelayOpts,
+        RelayCapability,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_relay_nips";
+
+    #[tokio::test]
+    async fn test_relay_missing_required_nips_skipped() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        // Relay advertising NIP-01 is added
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let opts = AddRelayOpts {
+            require_nips: vec![1],
+            ..Default::default()
+        };
+        let skipped_relays = maker_manager
+            .add_relays_with_opts(
+                vec![(relay_url.clone(), None, RelayCapability::ReadWrite)],
+                opts,
+            )
+            .await
+            .unwrap();
+        assert!(skipped_relays.is_empty());
+        assert_eq!(maker_manager.get_relays().await.len(), 1);
+
+        // Same relay is skipped when also requiring a NIP it doesn't advertise
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let opts = AddRelayOpts {
+            require_nips: vec![1, 65535],
+            ..Default::default()
+        };
+        let skipped_relays = taker_manager
+            .add_relays_with_opts(
+                vec![(relay_url.clone(), None, RelayCapability::ReadWrite)],
+                opts,
+            )
+            .await
+            .unwrap();
+        assert_eq!(skipped_relays.len(), 1);
+        assert_eq!(skipped_relays[0].url, relay_url);
+        assert_eq!(skipped_relays[0].missing_nips, vec![65535]);
+        assert!(taker_manager.get_relays().await.is_empty());
+
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -112,7 +112,7 @@ mod tests {
             });
 
             let order = SomeTestOrderParams::default_sell_builder().build().unwrap();
-            let maker = maker_manager.new_maker(order).await;
+            let maker = maker_manager.new_maker(order).await.unwrap();
             maker.shutdown().await.unwrap();
             maker_manager.shutdown().await.unwrap();
             taker_manager.shutdown().await.unwrap();
@@ -326,7 +326,7 @@ mod tests {
             };
 
             let mut expected_trade_rsp_builder = SomeTestTradeRspParams::default_builder();
-            expected_trade_rsp_builder.offer_event_id("".to_string());
+            expected_trade_rsp_builder.offer_event_id(SomeTestParams::some_event_id(0));
 
             let expected_trade_rsp = expected_trade_rsp_builder.build().unwrap();
             SomeTestTradeRspParams::check(&trade_rsp_envelope.trade_rsp, &expected_trade_rsp);
@@ -362,7 +362,7 @@ mod tests {
             let trade_rsp_envelope = taker.query_trade_rsp().await.unwrap().unwrap();
 
             let mut expected_trade_rsp_builder = SomeTestTradeRspParams::default_builder();
-            expected_trade_rsp_builder.offer_event_id("".to_string());
+            expected_trade_rsp_builder.offer_event_id(SomeTestParams::some_event_id(0));
 
             let expected_trade_rsp = expected_trade_rsp_builder.build().unwrap();
             SomeTestTradeRspParams::check(&trade_rsp_envelope.trade_rsp, &expected_trade_rsp);
@@ -0,0 +1,106 @@
+mod common;
+
+#[cfg(test)]
+mod test_rotate_identity {
+    use std::str::FromStr;
+    use tracing::error;
+
+    use secp256k1::Secp256k1;
+    use tokio::fs;
+    use url::Url;
+    use uuid::Uuid;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        testing::{SomeTestOrderParams, SomeTestParams},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_rotate_identity";
+
+    #[tokio::test]
+    async fn test_rotate_identity_reports_disrupted_trades() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+        let old_secret_key = SomeTestParams::maker_private_key();
+        let new_secret_key = SomeTestParams::taker_private_key();
+        let new_pubkey = new_secret_key.x_only_public_key(&Secp256k1::new()).0;
+
+        let manager = Manager::new_with_key(
+            old_secret_key,
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        let old_pubkey = manager.pubkey().await;
+
+        // Order posted without per-trade keys can't survive the rotation
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        let identity_rotation = manager.rotate_identity(new_secret_key).await.unwrap();
+        assert_eq!(identity_rotation.old_pubkey, old_pubkey);
+        assert_eq!(identity_rotation.new_pubkey, new_pubkey);
+        assert!(identity_rotation.continuing_trades.is_empty());
+        assert_eq!(
+            identity_rotation.disrupted_trades,
+            vec![SomeTestOrderParams::some_uuid()]
+        );
+        // Old pubkey can't delete the Order Note it signed
+        assert_eq!(identity_rotation.undeletable_order_notes.len(), 1);
+        assert_eq!(manager.pubkey().await, new_pubkey);
+        assert_eq!(manager.get_relays().await.len(), 1);
+
+        // Rotating to the current identity is refused
+        assert!(manager.rotate_identity(new_secret_key).await.is_err());
+
+        // Makers created after the rotation are persisted under the new pubkey
+        let new_trade_uuid = Uuid::new_v4();
+        let order = SomeTestOrderParams::default_buy_builder()
+            .trade_uuid(new_trade_uuid)
+            .build()
+            .unwrap();
+        let new_maker = manager.new_maker(order).await.unwrap();
+
+        new_maker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        manager.shutdown().await.unwrap();
+
+        // Relays were carried over to the new identity
+        let manager = Manager::new_with_key(
+            new_secret_key,
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let relays = manager.get_relays().await;
+        assert_eq!(relays.len(), 1);
+        assert_eq!(relays[0].url, relay_url);
+
+        let makers = manager.get_makers().await;
+        assert_eq!(makers.len(), 1);
+        assert!(makers.contains_key(&new_trade_uuid));
+
+        manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+#[cfg(test)]
+mod test_sent_offer {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_sent_offer";
+
+    #[tokio::test]
+    async fn test_sent_offer_and_pending_duration() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+
+        // Nothing sent yet
+        assert!(taker.query_sent_offer().await.unwrap().is_none());
+        assert!(taker.offer_pending_duration().await.is_none());
+
+        taker.take_order().await.unwrap();
+        let (sent_offer, sent_offer_event_id) = taker.query_sent_offer().await.unwrap().unwrap();
+        SomeTestOfferParams::check(
+            &sent_offer,
+            &SomeTestOfferParams::default_buy_builder().build().unwrap(),
+        );
+        assert!(taker.offer_pending_duration().await.is_some());
+
+        taker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+
+        // Both are kept across a restore, with the Offer still pending from when it was sent
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let takers = taker_manager.get_takers().await;
+        let taker = takers.get(&SomeTestOrderParams::some_uuid()).unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker_manager.connect_all_relays().await.unwrap();
+
+        let (_, restored_offer_event_id) = taker.query_sent_offer().await.unwrap().unwrap();
+        assert_eq!(restored_offer_event_id, sent_offer_event_id);
+        assert!(taker.offer_pending_duration().await.is_some());
+
+        let offer_envelope = match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        assert_eq!(offer_envelope.event_id, sent_offer_event_id);
+
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        let trade_rsp_envelope = match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+
+        // No longer pending once responded to, but the sent Offer is still there
+        assert!(taker.offer_pending_duration().await.is_none());
+        let (_, sent_offer_event_id) = taker.query_sent_offer().await.unwrap().unwrap();
+        assert_eq!(
+            sent_offer_event_id,
+            trade_rsp_envelope.trade_rsp.offer_event_id
+        );
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
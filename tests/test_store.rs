@@ -0,0 +1,110 @@
+mod common;
+
+#[cfg(test)]
+mod test_store {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use secp256k1::Secp256k1;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, store::N3xbStore, types::BitcoinNetwork},
+        manager::Manager,
+        testing::{SomeTestOrderParams, SomeTestParams},
+        CommsOptions,
+    };
+
+    // Keeps everything in memory, as a stand-in for non-filesystem storage
+    #[derive(Default)]
+    struct MemStore {
+        blobs: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl N3xbStore for MemStore {
+        fn load(&self, key: &str) -> Result<Option<Vec<u8>>, N3xbError> {
+            Ok(self.blobs.lock().unwrap().get(key).cloned())
+        }
+
+        fn save(&self, key: &str, blob: &[u8]) -> Result<(), N3xbError> {
+            let mut blobs = self.blobs.lock().unwrap();
+            blobs.insert(key.to_string(), blob.to_vec());
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), N3xbError> {
+            self.blobs.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>, N3xbError> {
+            let dir_prefix = format!("{}/", prefix);
+            let blobs = self.blobs.lock().unwrap();
+            let mut keys: Vec<String> = blobs
+                .keys()
+                .filter(|key| match key.strip_prefix(&dir_prefix) {
+                    Some(name) => !name.contains('/'),
+                    None => false,
+                })
+                .cloned()
+                .collect();
+            keys.sort();
+            Ok(keys)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manager_restores_from_custom_store() {
+        let store = Arc::new(MemStore::default());
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_store(
+            SomeTestParams::maker_private_key(),
+            CommsOptions::default(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            store.clone(),
+        )
+        .await;
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+
+        let pubkey = SomeTestParams::maker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        let manager_dir_key = format!("{}/{}/regtest", pubkey, test_engine_name);
+        assert!(store
+            .exists(&format!("{}/comms.json", manager_dir_key))
+            .unwrap());
+        assert_eq!(
+            store
+                .list(&format!("{}/makers", manager_dir_key))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Maker comes back from the same store
+        let maker_manager = Manager::new_with_store(
+            SomeTestParams::maker_private_key(),
+            CommsOptions::default(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            store.clone(),
+        )
+        .await;
+        let makers = maker_manager.get_makers().await;
+        assert!(makers.contains_key(&SomeTestOrderParams::some_uuid()));
+
+        let disk_usage = maker_manager.disk_usage().await.unwrap();
+        assert!(disk_usage.maker_bytes[&SomeTestOrderParams::some_uuid()] > 0);
+
+        for (_, maker) in makers {
+            maker.shutdown().await.unwrap();
+        }
+        maker_manager.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+mod common;
+
+#[cfg(test)]
+mod test_taker_batch {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+    use uuid::Uuid;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::{TakerNotif, TakerTradeStatus},
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+        trade_rsp::TradeResponseStatus,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_taker_batch";
+
+    #[tokio::test]
+    async fn test_taker_batch_keep_first_accepted() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        // Maker posts two Orders
+        let accepting_trade_uuid = Uuid::new_v4();
+        let ignoring_trade_uuid = Uuid::new_v4();
+
+        let order = SomeTestOrderParams::default_buy_builder()
+            .trade_uuid(accepting_trade_uuid)
+            .build()
+            .unwrap();
+        let accepting_maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        accepting_maker
+            .register_notif_tx(maker_notif_tx)
+            .await
+            .unwrap();
+        accepting_maker.post_new_order().await.unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder()
+            .trade_uuid(ignoring_trade_uuid)
+            .build()
+            .unwrap();
+        let ignoring_maker = maker_manager.new_maker(order).await.unwrap();
+        let (ignoring_maker_notif_tx, mut ignoring_maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        ignoring_maker
+            .register_notif_tx(ignoring_maker_notif_tx)
+            .await
+            .unwrap();
+        ignoring_maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Taker sends Offers to both Orders
+        let mut offers = Vec::new();
+        for trade_uuid in [accepting_trade_uuid, ignoring_trade_uuid] {
+            let query_filter = vec![FilterTag::TradeUuid(trade_uuid)];
+            let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+            assert_eq!(order_envelopes.len(), 1);
+
+            let order_envelope = order_envelopes.first().unwrap().to_owned();
+            let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+            offers.push((order_envelope, offer));
+        }
+
+        let taker_batch = taker_manager.new_taker_batch(offers).await.unwrap();
+        assert_eq!(taker_batch.trade_uuids().len(), 2);
+
+        let results = taker_batch.take_orders().await;
+        assert_eq!(results.len(), 2);
+        assert!(results.values().all(|result| result.is_ok()));
+
+        // Only one of the Makers accepts
+        let notif_result = maker_notif_rx.recv().await.unwrap();
+        let offer_event_id = match notif_result.unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope.event_id,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        accepting_maker.accept_offer(trade_rsp).await.unwrap();
+
+        let ignored_offer_event_id = match ignoring_maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope.event_id,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+
+        // Notifications received before registering are buffered
+        sleep(Duration::from_secs(1)).await;
+        let (batch_notif_tx, mut batch_notif_rx) =
+            mpsc::channel::<(Uuid, Result<TakerNotif, N3xbError>)>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker_batch.register_notif_tx(batch_notif_tx).await.unwrap();
+
+        let (trade_uuid, notif_result) = batch_notif_rx.recv().await.unwrap();
+        assert_eq!(trade_uuid, accepting_trade_uuid);
+        match notif_result.unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => {
+                assert_eq!(
+                    trade_rsp_envelope.trade_rsp.trade_response,
+                    TradeResponseStatus::Accepted
+                );
+            }
+            _ => panic!("Taker Batch only expects Trade Response notification at this point"),
+        }
+
+        // Offers to the other Orders got withdrawn on acceptance
+        let batch_takers = taker_batch.takers();
+        assert_eq!(
+            batch_takers[&ignoring_trade_uuid].status().await,
+            TakerTradeStatus::Withdrawn
+        );
+        assert_eq!(
+            batch_takers[&accepting_trade_uuid].status().await,
+            TakerTradeStatus::Accepted
+        );
+        assert!(batch_takers[&accepting_trade_uuid]
+            .withdraw_offer()
+            .await
+            .is_err());
+        assert_eq!(
+            taker_batch.accepted_trade_uuid().await,
+            Some(accepting_trade_uuid)
+        );
+
+        // Maker of the other Order is told of the withdrawal
+        match ignoring_maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::OfferWithdrawn(offer_event_id) => {
+                assert_eq!(offer_event_id, ignored_offer_event_id);
+            }
+            _ => panic!("Maker only expects Offer Withdrawn notification at this point"),
+        }
+
+        // Taker goes with the accepted Offer and cancels the rest
+        taker_manager
+            .cancel_taker_batch(taker_batch, Some(accepting_trade_uuid))
+            .await
+            .unwrap();
+        let takers = taker_manager.get_takers().await;
+        assert_eq!(takers.len(), 1);
+        assert!(takers.contains_key(&accepting_trade_uuid));
+
+        // Accepted Taker is committed to the trade, and cannot be cancelled
+        assert!(taker_manager
+            .cancel_taker(accepting_trade_uuid)
+            .await
+            .is_err());
+        assert!(taker_manager
+            .get_takers()
+            .await
+            .contains_key(&accepting_trade_uuid));
+
+        for taker in takers.values() {
+            taker.shutdown().await.unwrap();
+        }
+        accepting_maker.shutdown().await.unwrap();
+        ignoring_maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+mod common;
+
+#[cfg(test)]
+mod test_trade_encryption {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        maker::{MakerNotif, MakerOpts},
+        manager::Manager,
+        order::FilterTag,
+        taker::{TakerNotif, TakerOpts},
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::{relay::Relay, test_trade_msgs::SomeTradeEngMsg};
+
+    const ROOT_DIR_PATH_STR: &str = "test_trade_encryption";
+
+    #[tokio::test]
+    async fn test_peer_messages_with_trade_encryption_keys() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        // Maker and Taker both opt into per-trade keys
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker_opts = MakerOpts {
+            trade_encryption_key: true,
+            ..Default::default()
+        };
+        let maker = maker_manager
+            .new_maker_with_opts(order, maker_opts)
+            .await
+            .unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker_opts = TakerOpts {
+            trade_encryption_key: true,
+            ..Default::default()
+        };
+        let taker = taker_manager
+            .new_taker_with_opts(order_envelope, offer, taker_opts)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        // Offer carries the Taker's per-trade pubkey, the Trade Response the Maker's
+        let offer_envelope = match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        let taker_encryption_pubkey = offer_envelope.offer.encryption_pubkey.unwrap();
+        assert_ne!(taker_encryption_pubkey, offer_envelope.pubkey);
+
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        let trade_rsp_envelope = match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+        let maker_encryption_pubkey = trade_rsp_envelope.trade_rsp.encryption_pubkey.unwrap();
+        assert_ne!(maker_encryption_pubkey, trade_rsp_envelope.pubkey);
+
+        // Peer messages go through in both directions
+        let some_trade_eng_msg = SomeTradeEngMsg {
+            some_trade_specific_field: SomeTradeEngMsg::some_trade_specific_string(),
+        };
+        maker
+            .send_peer_message(Box::new(some_trade_eng_msg.clone()))
+            .await
+            .unwrap();
+        match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::Peer(peer_envelope) => {
+                let message = peer_envelope
+                    .message
+                    .downcast_ref::<SomeTradeEngMsg>()
+                    .unwrap();
+                assert_eq!(
+                    message.some_trade_specific_field,
+                    SomeTradeEngMsg::some_trade_specific_string()
+                );
+            }
+            _ => panic!("Taker only expects Peer notification at this point"),
+        }
+
+        // Per-trade keys are picked up again by a restored Taker
+        taker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let takers = taker_manager.get_takers().await;
+        let taker = takers.get(&SomeTestOrderParams::some_uuid()).unwrap();
+        taker_manager.connect_all_relays().await.unwrap();
+
+        taker
+            .send_peer_message(Box::new(some_trade_eng_msg))
+            .await
+            .unwrap();
+        match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Peer(peer_envelope) => {
+                let message = peer_envelope
+                    .message
+                    .downcast_ref::<SomeTradeEngMsg>()
+                    .unwrap();
+                assert_eq!(
+                    message.some_trade_specific_field,
+                    SomeTradeEngMsg::some_trade_specific_string()
+                );
+            }
+            _ => panic!("Maker only expects Peer notification at this point"),
+        }
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
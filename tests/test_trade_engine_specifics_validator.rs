@@ -0,0 +1,143 @@
+mod common;
+
+#[cfg(test)]
+mod test_trade_engine_specifics_validator {
+    use std::{str::FromStr, sync::Arc, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{
+            error::{N3xbError, OfferInvalidReason},
+            types::{BitcoinNetwork, SerdeGenericTrait, TradeEngineSpecificsValidator},
+        },
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+        trade_rsp::TradeResponseStatus,
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_trade_engine_specifics_validator";
+
+    #[tokio::test]
+    async fn test_trade_engine_specifics_validator_refuses() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let refuse_all: TradeEngineSpecificsValidator =
+            Arc::new(|_: &dyn SerdeGenericTrait| Err("Refused by test engine".to_string()));
+
+        // Maker posts an Order
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Orders refused by the Taker's engine are left out of query results
+        taker_manager
+            .register_trade_engine_specifics_validator(Some(refuse_all.clone()))
+            .await
+            .unwrap();
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager
+            .query_orders(query_filter.clone())
+            .await
+            .unwrap();
+        assert!(order_envelopes.is_empty());
+
+        taker_manager
+            .register_trade_engine_specifics_validator(None)
+            .await
+            .unwrap();
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        // Offers refused by the Maker's engine are rejected
+        maker_manager
+            .register_trade_engine_specifics_validator(Some(refuse_all))
+            .await
+            .unwrap();
+
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        let notif_result = maker_notif_rx.recv().await.unwrap();
+        match notif_result {
+            Err(N3xbError::InvalidOffer(OfferInvalidReason::TradeEngineSpecific)) => {}
+            _ => panic!("Maker only expects Trade Engine Specific invalid Offer notification"),
+        }
+
+        let notif_result = taker_notif_rx.recv().await.unwrap();
+        let trade_rsp_envelope = match notif_result.unwrap() {
+            TakerNotif::TradeRsp(trade_rsp_envelope) => trade_rsp_envelope,
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        };
+        assert_eq!(
+            trade_rsp_envelope.trade_rsp.trade_response,
+            TradeResponseStatus::Rejected
+        );
+        assert_eq!(
+            trade_rsp_envelope.trade_rsp.reject_reason,
+            vec![OfferInvalidReason::TradeEngineSpecific]
+        );
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
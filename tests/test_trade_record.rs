@@ -0,0 +1,160 @@
+mod common;
+
+#[cfg(test)]
+mod test_trade_record {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::{error::N3xbError, types::BitcoinNetwork},
+        dispute::DisputeRequest,
+        maker::MakerNotif,
+        manager::Manager,
+        order::FilterTag,
+        settlement::SettlementInfo,
+        taker::TakerNotif,
+        testing::{
+            SomeTestOfferParams, SomeTestOrderParams, SomeTestParams, SomeTestTradeRspParams,
+            TESTING_DEFAULT_CHANNEL_SIZE,
+        },
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_trade_record";
+
+    #[tokio::test]
+    async fn test_trade_record_keeps_settlement_infos_and_disputes() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        let (maker_notif_tx, mut maker_notif_rx) =
+            mpsc::channel::<Result<MakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        maker.register_notif_tx(maker_notif_tx).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        let order_envelope = order_envelopes.first().unwrap().to_owned();
+
+        let offer = SomeTestOfferParams::default_buy_builder().build().unwrap();
+        let taker = taker_manager
+            .new_taker(order_envelope, offer)
+            .await
+            .unwrap();
+        let (taker_notif_tx, mut taker_notif_rx) =
+            mpsc::channel::<Result<TakerNotif, N3xbError>>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker.register_notif_tx(taker_notif_tx).await.unwrap();
+        taker.take_order().await.unwrap();
+
+        let offer_envelope = match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::Offer(offer_envelope) => offer_envelope,
+            _ => panic!("Maker only expects Offer notification at this point"),
+        };
+        let mut trade_rsp_builder = SomeTestTradeRspParams::default_builder();
+        trade_rsp_builder.offer_event_id(offer_envelope.event_id);
+        let trade_rsp = trade_rsp_builder.build().unwrap();
+        maker.accept_offer(trade_rsp).await.unwrap();
+
+        match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::TradeRsp(_) => {}
+            _ => panic!("Taker only expects Trade Response notification at this point"),
+        }
+
+        let maker_pubkey = maker_manager.pubkey().await;
+        let taker_pubkey = taker_manager.pubkey().await;
+
+        let settlement_info = SettlementInfo::new(
+            "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+            100000,
+            BitcoinNetwork::Regtest,
+        );
+        taker
+            .send_settlement_info(settlement_info.clone())
+            .await
+            .unwrap();
+        match maker_notif_rx.recv().await.unwrap().unwrap() {
+            MakerNotif::SettlementInfo(_) => {}
+            _ => panic!("Maker only expects Settlement Info notification at this point"),
+        }
+
+        let dispute_request = DisputeRequest::new("Fiat payment not received", None);
+        maker.raise_dispute(dispute_request.clone()).await.unwrap();
+        match taker_notif_rx.recv().await.unwrap().unwrap() {
+            TakerNotif::Dispute(_) => {}
+            _ => panic!("Taker only expects Dispute notification at this point"),
+        }
+
+        // Kept regardless of the peer message log capacity, which defaults to keeping nothing
+        let maker_record = maker.trade_record().await;
+        assert!(maker_record.peer_messages.is_empty());
+        assert_eq!(maker_record.settlement_infos.len(), 1);
+        let entry = &maker_record.settlement_infos[0];
+        assert!(!entry.outgoing);
+        assert_eq!(entry.pubkey, taker_pubkey);
+        assert_eq!(entry.content, settlement_info);
+        assert_eq!(maker_record.disputes.len(), 1);
+        let entry = &maker_record.disputes[0];
+        assert!(entry.outgoing);
+        assert_eq!(entry.pubkey, taker_pubkey);
+        assert_eq!(entry.content, dispute_request);
+
+        let taker_record = taker.trade_record().await;
+        assert_eq!(taker_record.settlement_infos.len(), 1);
+        let entry = &taker_record.settlement_infos[0];
+        assert!(entry.outgoing);
+        assert_eq!(entry.pubkey, maker_pubkey);
+        assert_eq!(entry.event_id, maker_record.settlement_infos[0].event_id);
+        assert_eq!(taker_record.disputes.len(), 1);
+        let entry = &taker_record.disputes[0];
+        assert!(!entry.outgoing);
+        assert_eq!(entry.pubkey, maker_pubkey);
+        assert_eq!(entry.event_id, maker_record.disputes[0].event_id);
+
+        taker.shutdown().await.unwrap();
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
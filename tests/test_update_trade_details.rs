@@ -0,0 +1,106 @@
+mod common;
+
+#[cfg(test)]
+mod test_update_trade_details {
+    use std::{collections::HashSet, str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::{FilterTag, TradeDetails, TradeDetailsContent, TradeParameter},
+        testing::{SomeTestOrderParams, SomeTestParams},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_update_trade_details";
+
+    #[tokio::test]
+    async fn test_update_trade_details_republishes_order() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        // Bonds required without bond percentages do not validate
+        let invalid_trade_details = TradeDetails {
+            parameters: HashSet::from([TradeParameter::BondsRequired]),
+            content: SomeTestOrderParams::trade_details_empty(),
+        };
+        assert!(maker
+            .update_trade_details(invalid_trade_details)
+            .await
+            .is_err());
+
+        // Maker lowers the bonds to attract Takers
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: TradeDetailsContent {
+                maker_bond_pct: Some(5),
+                taker_bond_pct: Some(5),
+                ..SomeTestOrderParams::trade_details_content()
+            },
+        };
+        maker.update_trade_details(trade_details).await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        let order_envelope = order_envelopes.first().unwrap();
+        assert_eq!(
+            order_envelope.order.trade_details.content.taker_bond_pct,
+            Some(5)
+        );
+        assert_eq!(
+            order_envelope.order.maker_obligation.kinds,
+            SomeTestOrderParams::obligation_fiat_cny_kinds()
+        );
+
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+#[cfg(test)]
+mod test_verify_order_signature {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use secp256k1::Secp256k1;
+    use tokio::{fs, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        manager::Manager,
+        order::{FilterTag, OrderEnvelope},
+        testing::{SomeTestOrderParams, SomeTestParams},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_verify_order_signature";
+
+    #[tokio::test]
+    async fn test_verify_order_signature_out_of_band() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        // Order Envelope handed over out-of-band, such as through a link or another app
+        let order_envelope_json = serde_json::to_string(order_envelopes.first().unwrap()).unwrap();
+        let order_envelope: OrderEnvelope = serde_json::from_str(&order_envelope_json).unwrap();
+        order_envelope.verify_signature().unwrap();
+
+        let mut tampered = order_envelope.clone();
+        tampered.order.maker_obligation.content.amount += 1.0;
+        assert!(tampered.verify_signature().is_err());
+
+        let mut tampered = order_envelope.clone();
+        tampered.order.trade_details.parameters.clear();
+        assert!(tampered.verify_signature().is_err());
+
+        let mut tampered = order_envelope.clone();
+        tampered.expiration = tampered
+            .expiration
+            .map_or(Some(1), |expiration| Some(expiration + 1));
+        assert!(tampered.verify_signature().is_err());
+
+        let mut tampered = order_envelope.clone();
+        tampered.delegator_pubkey = Some(
+            SomeTestParams::taker_private_key()
+                .x_only_public_key(&Secp256k1::new())
+                .0,
+        );
+        assert!(tampered.verify_signature().is_err());
+
+        let mut tampered = order_envelope.clone();
+        tampered.pubkey = SomeTestParams::taker_private_key()
+            .x_only_public_key(&Secp256k1::new())
+            .0;
+        assert!(tampered.verify_signature().is_err());
+
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+#[cfg(test)]
+mod test_watch_order {
+    use std::{str::FromStr, time::Duration};
+    use tracing::error;
+
+    use tokio::{fs, sync::mpsc, time::sleep};
+    use url::Url;
+
+    use crusty_n3xb::{
+        common::types::BitcoinNetwork,
+        comms::OrderUpdate,
+        manager::Manager,
+        order::{FilterTag, TradeDetails, TradeDetailsContent},
+        testing::{SomeTestOrderParams, SomeTestParams, TESTING_DEFAULT_CHANNEL_SIZE},
+    };
+
+    use super::common::relay::Relay;
+
+    const ROOT_DIR_PATH_STR: &str = "test_watch_order";
+
+    #[tokio::test]
+    async fn test_watch_order_updated_then_deleted() {
+        if let Some(error) = fs::remove_dir_all(ROOT_DIR_PATH_STR).await.err() {
+            error!(
+                "Failed to remove {} directory: {}",
+                ROOT_DIR_PATH_STR, error
+            );
+        }
+
+        let relay: Relay = Relay::start();
+        relay.wait_for_healthy_relay().await.unwrap();
+        let relay_url = Url::from_str(&format!("{}:{}", "ws://localhost", relay.port)).unwrap();
+
+        let test_engine_name = SomeTestParams::engine_name_str();
+
+        let maker_manager = Manager::new_with_key(
+            SomeTestParams::maker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        let taker_manager = Manager::new_with_key(
+            SomeTestParams::taker_private_key(),
+            &test_engine_name,
+            BitcoinNetwork::Regtest,
+            ROOT_DIR_PATH_STR,
+        )
+        .await;
+        maker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+        taker_manager
+            .add_relays(vec![(relay_url.clone(), None)], true)
+            .await
+            .unwrap();
+
+        let order = SomeTestOrderParams::default_buy_builder().build().unwrap();
+        let maker = maker_manager.new_maker(order).await.unwrap();
+        maker.post_new_order().await.unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Taker locks onto the Order
+        let query_filter = vec![FilterTag::TradeUuid(SomeTestOrderParams::some_uuid())];
+        let order_envelopes = taker_manager.query_orders(query_filter).await.unwrap();
+        assert_eq!(order_envelopes.len(), 1);
+
+        let unwatched_filters = taker_manager.get_active_filters().await;
+        let (update_tx, mut update_rx) = mpsc::channel::<OrderUpdate>(TESTING_DEFAULT_CHANNEL_SIZE);
+        taker_manager
+            .watch_order(order_envelopes.first().unwrap(), update_tx.clone())
+            .await
+            .unwrap();
+        let watched_filters = taker_manager.get_active_filters().await;
+        assert_eq!(watched_filters.len(), unwatched_filters.len() + 2);
+        assert!(watched_filters
+            .iter()
+            .any(|filter| filter.contains(&SomeTestOrderParams::some_uuid().to_string())));
+        assert!(taker_manager
+            .watch_order(order_envelopes.first().unwrap(), update_tx)
+            .await
+            .is_err());
+
+        sleep(Duration::from_secs(1)).await;
+
+        // Maker amends the Order, which republishes it
+        let trade_details = TradeDetails {
+            parameters: SomeTestOrderParams::trade_parameters(),
+            content: TradeDetailsContent {
+                maker_bond_pct: Some(5),
+                taker_bond_pct: Some(5),
+                ..SomeTestOrderParams::trade_details_content()
+            },
+        };
+        maker.update_trade_details(trade_details).await.unwrap();
+
+        match update_rx.recv().await.unwrap() {
+            OrderUpdate::Updated(order_envelope) => assert_eq!(
+                order_envelope.order.trade_details.content.taker_bond_pct,
+                Some(5)
+            ),
+            OrderUpdate::Deleted => panic!("Taker expects the amended Order first"),
+        }
+
+        // Maker cancels the Order, which deletes it
+        maker.cancel_order().await.unwrap();
+        match update_rx.recv().await.unwrap() {
+            OrderUpdate::Deleted => {}
+            OrderUpdate::Updated(_) => panic!("Taker expects the Order to be deleted"),
+        }
+        assert!(taker_manager
+            .unwatch_order(SomeTestOrderParams::some_uuid())
+            .await
+            .is_err());
+        assert_eq!(
+            taker_manager.get_active_filters().await.len(),
+            unwatched_filters.len()
+        );
+
+        maker.shutdown().await.unwrap();
+        taker_manager.shutdown().await.unwrap();
+        maker_manager.shutdown().await.unwrap();
+        relay.shutdown().unwrap();
+    }
+}